@@ -310,6 +310,40 @@ typedef enum lb_commission_free_status_t {
   CommissionFreeStatusReady,
 } lb_commission_free_status_t;
 
+/**
+ * Connection event kind
+ */
+typedef enum lb_connection_event_kind_t {
+  /**
+   * Connecting to the server
+   */
+  ConnectionEventConnecting,
+  /**
+   * The websocket connection has been established
+   */
+  ConnectionEventConnected,
+  /**
+   * The first session has been created
+   */
+  ConnectionEventAuthenticated,
+  /**
+   * The session has been restored after a reconnection
+   */
+  ConnectionEventReconnected,
+  /**
+   * The subscriptions have been restored after a reconnection
+   */
+  ConnectionEventResubscribed,
+  /**
+   * The connection was lost or a reconnection attempt failed
+   */
+  ConnectionEventDisconnected,
+  /**
+   * The reconnection retries ran out, the context is closed
+   */
+  ConnectionEventGaveUp,
+} lb_connection_event_kind_t;
+
 /**
  * Deduction status
  */
@@ -1294,6 +1328,38 @@ typedef struct lb_http_header_t {
 
 typedef void (*lb_free_userdata_func_t)(void*);
 
+/**
+ * Connection state change event
+ */
+typedef struct lb_connection_event_t {
+  /**
+   * Event kind
+   */
+  enum lb_connection_event_kind_t kind;
+  /**
+   * Whether the previous session was reused (`Reconnected` only)
+   */
+  bool session_reused;
+  /**
+   * Close code sent by the server, `0` if the server did not send a close
+   * frame (`Disconnected` only)
+   */
+  uint16_t close_code;
+  /**
+   * Close reason sent by the server (maybe null)
+   */
+  const char *close_message;
+  /**
+   * Error message (maybe null)
+   */
+  const char *error;
+} lb_connection_event_t;
+
+typedef void (*lb_quote_connection_event_callback_t)(const struct lb_quote_context_t*,
+                                                     const struct lb_connection_event_t*,
+                                                     void*);
+
+
 /**
  * Quote message
  */
@@ -1664,6 +1730,10 @@ typedef struct lb_update_watchlist_group_t {
   enum lb_securities_update_mode_t mode;
 } lb_update_watchlist_group_t;
 
+typedef void (*lb_trade_connection_event_callback_t)(const struct lb_trade_context_t*,
+                                                     const struct lb_connection_event_t*,
+                                                     void*);
+
 /**
  * Order changed message
  */
@@ -3717,6 +3787,24 @@ typedef struct lb_warrant_info_t {
   enum lb_warrant_status_t status;
 } lb_warrant_info_t;
 
+/**
+ * A page of the warrant screener
+ */
+typedef struct lb_warrant_list_t {
+  /**
+   * Warrants
+   */
+  const struct lb_warrant_info_t *warrants;
+  /**
+   * Number of warrants
+   */
+  uintptr_t num_warrants;
+  /**
+   * Total number of the warrants matching the filter
+   */
+  int32_t total_count;
+} lb_warrant_list_t;
+
 /**
  * Security
  */
@@ -3813,6 +3901,14 @@ extern "C" {
  * - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected,
  *   `true` or `false` (Default: `true`)
  * - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
+ * - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+ *   certificates
+ * - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the pinned
+ *   certificates
+ * - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+ *   certificate chain
+ * - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client private
+ *   key
  */
 struct lb_config_t *lb_config_from_env(struct lb_error_t **error);
 
@@ -3828,6 +3924,26 @@ struct lb_config_t *lb_config_new(const char *app_key,
                                   bool enable_print_quote_packages,
                                   const char *log_path);
 
+/**
+ * Set the TLS configuration of the HTTP requests and the websocket
+ * connections
+ *
+ * - `root_certificates` - PEM encoded root certificates to trust in addition
+ *   to the built-in root certificates (nullable)
+ * - `pins` - Hex encoded SHA-256 fingerprints of the pinned server (or
+ *   intermediate) certificates
+ * - `client_cert` - PEM encoded client certificate chain (nullable)
+ * - `client_key` - PEM encoded private key of the client certificate
+ *   (nullable)
+ */
+void lb_config_set_tls(struct lb_config_t *config,
+                       const char *root_certificates,
+                       const char *const *pins,
+                       uintptr_t num_pins,
+                       const char *client_cert,
+                       const char *client_key,
+                       struct lb_error_t **error);
+
 /**
  * Free the config object
  */
@@ -3921,6 +4037,15 @@ void lb_quote_context_quote_package_details(const struct lb_quote_context_t *ctx
                                             lb_async_callback_t callback,
                                             void *userdata);
 
+/**
+ * Set connection event callback, it will be called when the state of the
+ * connection changes.
+ */
+void lb_quote_context_set_on_connection_event(const struct lb_quote_context_t *ctx,
+                                              lb_quote_connection_event_callback_t callback,
+                                              void *userdata,
+                                              lb_free_userdata_func_t free_userdata);
+
 /**
  * Set quote callback, after receiving the quote data push, it will call back
  * to this function.
@@ -4170,6 +4295,31 @@ void lb_quote_context_warrant_list(const struct lb_quote_context_t *ctx,
                                    lb_async_callback_t callback,
                                    void *userdata);
 
+/**
+ * Query a page of the warrants matching the filter
+ *
+ * `count` is the number of warrants, `0` means the default page size of the
+ * server.
+ */
+void lb_quote_context_warrant_screener(const struct lb_quote_context_t *ctx,
+                                       const char *symbol,
+                                       enum lb_warrant_sort_by_t sort_by,
+                                       enum lb_sort_order_type_t sort_order,
+                                       int32_t offset,
+                                       int32_t count,
+                                       const enum lb_warrant_type_t *warrant_type,
+                                       uintptr_t num_warrant_type,
+                                       const int32_t *issuer,
+                                       uintptr_t num_issuer,
+                                       const enum lb_filter_warrant_expiry_date_t *expiry_date,
+                                       uintptr_t num_expiry_date,
+                                       const enum lb_filter_warrant_in_out_bounds_type_t *price_type,
+                                       uintptr_t num_price_type,
+                                       const enum lb_warrant_status_t *status,
+                                       uintptr_t num_status,
+                                       lb_async_callback_t callback,
+                                       void *userdata);
+
 /**
  * Get trading session of the day
  */
@@ -4349,6 +4499,15 @@ void *lb_trade_context_userdata(const struct lb_trade_context_t *ctx);
 void lb_trade_context_set_free_userdata_func(const struct lb_trade_context_t *ctx,
                                              lb_free_userdata_func_t f);
 
+/**
+ * Set connection event callback, it will be called when the state of the
+ * connection changes.
+ */
+void lb_trade_context_set_on_connection_event(const struct lb_trade_context_t *ctx,
+                                              lb_trade_connection_event_callback_t callback,
+                                              void *userdata,
+                                              lb_free_userdata_func_t free_userdata);
+
 /**
  * Set order changed callback, after receiving the order changed event, it will
  * call back to this function.
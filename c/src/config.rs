@@ -4,13 +4,13 @@ use std::{
     sync::Arc,
 };
 
-use longport::Config;
+use longport::{Config, Error, TlsConfig};
 use time::OffsetDateTime;
 
 use crate::{
     async_call::{CAsyncCallback, execute_async},
     error::{CError, set_error},
-    types::{CLanguage, CPushCandlestickMode, CString},
+    types::{CLanguage, CPushCandlestickMode, CString, cstr_array_to_rust, cstr_to_rust},
 };
 
 /// Configuration options for LongPort sdk
@@ -40,6 +40,14 @@ pub struct CConfig(pub(crate) Arc<Config>);
 /// - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected,
 ///   `true` or `false` (Default: `true`)
 /// - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
+/// - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+///   certificates
+/// - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the pinned
+///   certificates
+/// - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+///   certificate chain
+/// - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client private
+///   key
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_config_from_env(error: *mut *mut CError) -> *mut CConfig {
     match Config::from_env() {
@@ -120,6 +128,63 @@ pub unsafe extern "C" fn lb_config_new(
     Box::into_raw(Box::new(CConfig(Arc::new(config))))
 }
 
+/// Set the TLS configuration of the HTTP requests and the websocket
+/// connections
+///
+/// - `root_certificates` - PEM encoded root certificates to trust in addition
+///   to the built-in root certificates (nullable)
+/// - `pins` - Hex encoded SHA-256 fingerprints of the pinned server (or
+///   intermediate) certificates
+/// - `client_cert` - PEM encoded client certificate chain (nullable)
+/// - `client_key` - PEM encoded private key of the client certificate
+///   (nullable)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_config_set_tls(
+    config: *mut CConfig,
+    root_certificates: *const c_char,
+    pins: *const *const c_char,
+    num_pins: usize,
+    client_cert: *const c_char,
+    client_key: *const c_char,
+    error: *mut *mut CError,
+) {
+    let pins = if num_pins > 0 {
+        cstr_array_to_rust(pins, num_pins)
+    } else {
+        Vec::new()
+    };
+    let res = (|| {
+        let mut tls_config = TlsConfig::new();
+        if !root_certificates.is_null() {
+            tls_config = tls_config.add_root_certificates(cstr_to_rust(root_certificates))?;
+        }
+        for pin in pins {
+            tls_config = tls_config.pin_certificate(pin)?;
+        }
+        match (client_cert.is_null(), client_key.is_null()) {
+            (false, false) => {
+                tls_config = tls_config
+                    .client_identity(cstr_to_rust(client_cert), cstr_to_rust(client_key))?;
+            }
+            (true, true) => {}
+            _ => {
+                return Err(Error::Tls(
+                    "client_cert and client_key must be set together".to_string(),
+                ));
+            }
+        }
+        Config::clone(&(*config).0).tls_config(tls_config)
+    })();
+
+    match res {
+        Ok(new_config) => {
+            set_error(error, None);
+            (*config).0 = Arc::new(new_config);
+        }
+        Err(err) => set_error(error, Some(err)),
+    }
+}
+
 /// Free the config object
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_config_free(config: *mut CConfig) {
@@ -33,9 +33,12 @@ pub unsafe extern "C" fn lb_http_client_new(
         .to_str()
         .expect("invalid access token");
 
-    Box::leak(Box::new(CHttpClient(HttpClient::new(
-        HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
-    ))))
+    Box::leak(Box::new(CHttpClient(
+        HttpClient::new(
+            HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
+        )
+        .expect("failed to create http client"),
+    )))
 }
 
 /// Free the http client
@@ -1,4 +1,5 @@
 #![allow(unsafe_op_in_unsafe_fn)]
+#![allow(clippy::result_large_err)]
 
 mod async_call;
 mod callback;
@@ -9,7 +9,7 @@ use longport::{
     QuoteContext,
     quote::{
         PushEvent, PushEventDetail, RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup,
-        SubFlags,
+        SubFlags, WarrantFilter,
     },
 };
 use parking_lot::Mutex;
@@ -34,11 +34,14 @@ use crate::{
             CQuotePackageDetailOwned, CRealtimeQuoteOwned, CSecurityBrokersOwned,
             CSecurityCalcIndexOwned, CSecurityDepthOwned, CSecurityOwned, CSecurityQuoteOwned,
             CSecurityStaticInfoOwned, CStrikePriceInfoOwned, CSubscriptionOwned, CTradeOwned,
-            CUpdateWatchlistGroup, CWarrantInfoOwned, CWarrantQuoteOwned, CWatchlistGroupOwned,
-            LB_WATCHLIST_GROUP_NAME, LB_WATCHLIST_GROUP_SECURITIES,
+            CUpdateWatchlistGroup, CWarrantInfoOwned, CWarrantListOwned, CWarrantQuoteOwned,
+            CWatchlistGroupOwned, LB_WATCHLIST_GROUP_NAME, LB_WATCHLIST_GROUP_SECURITIES,
         },
     },
-    types::{CCow, CDate, CDateTime, CMarket, CVec, ToFFI, cstr_array_to_rust, cstr_to_rust},
+    types::{
+        CConnectionEvent, CConnectionEventOwned, CCow, CDate, CDateTime, CMarket, CVec, ToFFI,
+        cstr_array_to_rust, cstr_to_rust,
+    },
 };
 
 pub type COnQuoteCallback = extern "C" fn(*const CQuoteContext, *const CPushQuote, *mut c_void);
@@ -52,9 +55,13 @@ pub type COnTradesCallback = extern "C" fn(*const CQuoteContext, *const CPushTra
 pub type COnCandlestickCallback =
     extern "C" fn(*const CQuoteContext, *const CPushCandlestick, *mut c_void);
 
+pub type COnQuoteConnectionEventCallback =
+    extern "C" fn(*const CQuoteContext, *const CConnectionEvent, *mut c_void);
+
 #[derive(Default)]
 struct Callbacks {
     quote: Option<Callback<COnQuoteCallback>>,
+    connection: Option<Callback<COnQuoteConnectionEventCallback>>,
     depth: Option<Callback<COnDepthCallback>>,
     brokers: Option<Callback<COnBrokersCallback>>,
     trades: Option<Callback<COnTradesCallback>>,
@@ -111,8 +118,31 @@ pub unsafe extern "C" fn lb_quote_context_new(
                 state,
             });
             let weak_ctx = Arc::downgrade(&arc_ctx);
+            let mut connection_receiver = arc_ctx.ctx.connection_events();
             let ctx = Arc::into_raw(arc_ctx);
 
+            tokio::spawn({
+                let weak_ctx = weak_ctx.clone();
+                async move {
+                    while let Some(event) = connection_receiver.recv().await {
+                        let ctx = match weak_ctx.upgrade() {
+                            Some(ctx) => ctx,
+                            None => return,
+                        };
+
+                        let state = ctx.state.lock();
+                        if let Some(callback) = &state.callbacks.connection {
+                            let event_owned: CConnectionEventOwned = event.into();
+                            (callback.f)(
+                                Arc::as_ptr(&ctx),
+                                &event_owned.to_ffi_type(),
+                                callback.userdata,
+                            );
+                        }
+                    }
+                }
+            });
+
             tokio::spawn(async move {
                 while let Some(event) = receiver.recv().await {
                     let ctx = match weak_ctx.upgrade() {
@@ -333,6 +363,22 @@ pub unsafe extern "C" fn lb_quote_context_quote_package_details(
     });
 }
 
+/// Set connection event callback, it will be called when the state of the
+/// connection changes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_quote_context_set_on_connection_event(
+    ctx: *const CQuoteContext,
+    callback: COnQuoteConnectionEventCallback,
+    userdata: *mut c_void,
+    free_userdata: CFreeUserDataFunc,
+) {
+    (*ctx).state.lock().callbacks.connection = Some(Callback {
+        f: callback,
+        userdata,
+        free_userdata,
+    });
+}
+
 /// Set quote callback, after receiving the quote data push, it will call back
 /// to this function.
 #[unsafe(no_mangle)]
@@ -492,7 +538,7 @@ pub unsafe extern "C" fn lb_quote_context_unsubscribe_candlesticks(
     let symbol = cstr_to_rust(symbol);
     execute_async(callback, ctx, userdata, async move {
         ctx_inner
-            .unsubscribe_candlesticks(symbol, period.into())
+            .unsubscribe_candlesticks(symbol, longport::quote::Period::from(period))
             .await
     });
 }
@@ -897,6 +943,71 @@ pub unsafe extern "C" fn lb_quote_context_warrant_list(
     });
 }
 
+/// Query a page of the warrants matching the filter
+///
+/// `count` is the number of warrants, `0` means the default page size of the
+/// server.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_quote_context_warrant_screener(
+    ctx: *const CQuoteContext,
+    symbol: *const c_char,
+    sort_by: CWarrantSortBy,
+    sort_order: CSortOrderType,
+    offset: i32,
+    count: i32,
+    warrant_type: *const CWarrantType,
+    num_warrant_type: usize,
+    issuer: *const i32,
+    num_issuer: usize,
+    expiry_date: *const CFilterWarrantExpiryDate,
+    num_expiry_date: usize,
+    price_type: *const CFilterWarrantInOutBoundsType,
+    num_price_type: usize,
+    status: *const CWarrantStatus,
+    num_status: usize,
+    callback: CAsyncCallback,
+    userdata: *mut c_void,
+) {
+    let ctx_inner = (*ctx).ctx.clone();
+    let filter = WarrantFilter::new(cstr_to_rust(symbol), sort_by.into(), sort_order.into())
+        .offset(offset)
+        .count(count)
+        .warrant_type(
+            std::slice::from_raw_parts(warrant_type, num_warrant_type)
+                .iter()
+                .copied()
+                .map(Into::into),
+        )
+        .issuer(std::slice::from_raw_parts(issuer, num_issuer).to_vec())
+        .expiry_date(
+            std::slice::from_raw_parts(expiry_date, num_expiry_date)
+                .iter()
+                .copied()
+                .map(Into::into),
+        )
+        .price_type(
+            std::slice::from_raw_parts(price_type, num_price_type)
+                .iter()
+                .copied()
+                .map(Into::into),
+        )
+        .status(
+            std::slice::from_raw_parts(status, num_status)
+                .iter()
+                .copied()
+                .map(Into::into),
+        );
+    execute_async(callback, ctx, userdata, async move {
+        let resp: CCow<CWarrantListOwned> = CCow::new(
+            ctx_inner
+                .warrant_screener(filter)
+                .await
+                .map(CWarrantListOwned::from)?,
+        );
+        Ok(resp)
+    });
+}
+
 /// Get trading session of the day
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_quote_context_trading_session(
@@ -1175,7 +1286,7 @@ pub unsafe extern "C" fn lb_quote_context_realtime_candlesticks(
     let symbol = cstr_to_rust(symbol);
     execute_async(callback, ctx, userdata, async move {
         let rows: CVec<CCandlestickOwned> = ctx_inner
-            .realtime_candlesticks(symbol, period.into(), count)
+            .realtime_candlesticks(symbol, longport::quote::Period::from(period), count)
             .await?
             .into();
         Ok(rows)
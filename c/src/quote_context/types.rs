@@ -8,7 +8,7 @@ use longport::quote::{
     PushTrades, QuotePackageDetail, RealtimeQuote, Security, SecurityBoard, SecurityBrokers,
     SecurityCalcIndex, SecurityDepth, SecurityQuote, SecurityStaticInfo, StrikePriceInfo,
     Subscription, Trade, TradeDirection, TradeSession, TradeStatus, TradingSessionInfo,
-    WarrantInfo, WarrantQuote, WarrantType, WatchlistGroup, WatchlistSecurity,
+    WarrantInfo, WarrantList, WarrantQuote, WarrantType, WatchlistGroup, WatchlistSecurity,
 };
 
 use crate::{
@@ -2791,6 +2791,51 @@ impl From<WarrantInfo> for CWarrantInfoOwned {
     }
 }
 
+/// A page of the warrant screener
+#[repr(C)]
+pub struct CWarrantList {
+    /// Warrants
+    pub warrants: *const CWarrantInfo,
+    /// Number of warrants
+    pub num_warrants: usize,
+    /// Total number of the warrants matching the filter
+    pub total_count: i32,
+}
+
+pub(crate) struct CWarrantListOwned {
+    warrants: CVec<CWarrantInfoOwned>,
+    total_count: i32,
+}
+
+impl From<WarrantList> for CWarrantListOwned {
+    fn from(list: WarrantList) -> Self {
+        let WarrantList {
+            warrants,
+            total_count,
+        } = list;
+        CWarrantListOwned {
+            warrants: warrants.into(),
+            total_count,
+        }
+    }
+}
+
+impl ToFFI for CWarrantListOwned {
+    type FFIType = CWarrantList;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        let CWarrantListOwned {
+            warrants,
+            total_count,
+        } = self;
+        CWarrantList {
+            warrants: warrants.to_ffi_type(),
+            num_warrants: warrants.len(),
+            total_count: *total_count,
+        }
+    }
+}
+
 /// Security
 #[repr(C)]
 pub struct CSecurity {
@@ -29,15 +29,22 @@ use crate::{
             CSubmitOrderResponseOwned,
         },
     },
-    types::{CCow, CVec, ToFFI, cstr_array_to_rust, cstr_to_rust},
+    types::{
+        CConnectionEvent, CConnectionEventOwned, CCow, CVec, ToFFI, cstr_array_to_rust,
+        cstr_to_rust,
+    },
 };
 
 pub type COnOrderChangedCallback =
     extern "C" fn(*const CTradeContext, *const CPushOrderChanged, *mut c_void);
 
+pub type COnTradeConnectionEventCallback =
+    extern "C" fn(*const CTradeContext, *const CConnectionEvent, *mut c_void);
+
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<Callback<COnOrderChangedCallback>>,
+    connection: Option<Callback<COnTradeConnectionEventCallback>>,
 }
 
 pub struct CTradeContextState {
@@ -85,8 +92,31 @@ pub unsafe extern "C" fn lb_trade_context_new(
             });
             let arc_ctx = Arc::new(CTradeContext { ctx, state });
             let weak_ctx = Arc::downgrade(&arc_ctx);
+            let mut connection_receiver = arc_ctx.ctx.connection_events();
             let ctx = Arc::into_raw(arc_ctx);
 
+            tokio::spawn({
+                let weak_ctx = weak_ctx.clone();
+                async move {
+                    while let Some(event) = connection_receiver.recv().await {
+                        let ctx = match weak_ctx.upgrade() {
+                            Some(ctx) => ctx,
+                            None => return,
+                        };
+
+                        let state = ctx.state.lock();
+                        if let Some(callback) = &state.callbacks.connection {
+                            let event_owned: CConnectionEventOwned = event.into();
+                            (callback.f)(
+                                Arc::as_ptr(&ctx),
+                                &event_owned.to_ffi_type(),
+                                callback.userdata,
+                            );
+                        }
+                    }
+                }
+            });
+
             tokio::spawn(async move {
                 while let Some(event) = receiver.recv().await {
                     let ctx = match weak_ctx.upgrade() {
@@ -172,6 +202,22 @@ pub unsafe extern "C" fn lb_trade_context_set_free_userdata_func(
     (*ctx).state.lock().free_userdata = f;
 }
 
+/// Set connection event callback, it will be called when the state of the
+/// connection changes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_trade_context_set_on_connection_event(
+    ctx: *const CTradeContext,
+    callback: COnTradeConnectionEventCallback,
+    userdata: *mut c_void,
+    free_userdata: CFreeUserDataFunc,
+) {
+    (*ctx).state.lock().callbacks.connection = Some(Callback {
+        f: callback,
+        userdata,
+        free_userdata,
+    });
+}
+
 /// Set order changed callback, after receiving the order changed event, it will
 /// call back to this function.
 #[unsafe(no_mangle)]
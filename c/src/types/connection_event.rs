@@ -0,0 +1,111 @@
+use std::os::raw::c_char;
+
+use longport::ConnectionEvent;
+
+use crate::types::{CString, ToFFI};
+
+/// Connection event kind
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+#[repr(C)]
+pub enum CConnectionEventKind {
+    /// Connecting to the server
+    ConnectionEventConnecting,
+    /// The websocket connection has been established
+    ConnectionEventConnected,
+    /// The first session has been created
+    ConnectionEventAuthenticated,
+    /// The session has been restored after a reconnection
+    ConnectionEventReconnected,
+    /// The subscriptions have been restored after a reconnection
+    ConnectionEventResubscribed,
+    /// The connection was lost or a reconnection attempt failed
+    ConnectionEventDisconnected,
+    /// The reconnection retries ran out, the context is closed
+    ConnectionEventGaveUp,
+}
+
+/// Connection state change event
+#[repr(C)]
+pub struct CConnectionEvent {
+    /// Event kind
+    pub kind: CConnectionEventKind,
+    /// Whether the previous session was reused (`Reconnected` only)
+    pub session_reused: bool,
+    /// Close code sent by the server, `0` if the server did not send a close
+    /// frame (`Disconnected` only)
+    pub close_code: u16,
+    /// Close reason sent by the server (maybe null)
+    pub close_message: *const c_char,
+    /// Error message (maybe null)
+    pub error: *const c_char,
+}
+
+pub(crate) struct CConnectionEventOwned {
+    kind: CConnectionEventKind,
+    session_reused: bool,
+    close_code: u16,
+    close_message: Option<CString>,
+    error: Option<CString>,
+}
+
+impl From<ConnectionEvent> for CConnectionEventOwned {
+    fn from(event: ConnectionEvent) -> Self {
+        let mut res = CConnectionEventOwned {
+            kind: CConnectionEventKind::ConnectionEventConnecting,
+            session_reused: false,
+            close_code: 0,
+            close_message: None,
+            error: None,
+        };
+        match event {
+            ConnectionEvent::Connecting => {}
+            ConnectionEvent::Connected => res.kind = CConnectionEventKind::ConnectionEventConnected,
+            ConnectionEvent::Authenticated => {
+                res.kind = CConnectionEventKind::ConnectionEventAuthenticated
+            }
+            ConnectionEvent::Reconnected { session_reused } => {
+                res.kind = CConnectionEventKind::ConnectionEventReconnected;
+                res.session_reused = session_reused;
+            }
+            ConnectionEvent::Resubscribed => {
+                res.kind = CConnectionEventKind::ConnectionEventResubscribed
+            }
+            ConnectionEvent::Disconnected { reason, error } => {
+                res.kind = CConnectionEventKind::ConnectionEventDisconnected;
+                if let Some(reason) = reason {
+                    res.close_code = reason.code.into();
+                    res.close_message = Some(reason.message.into());
+                }
+                res.error = Some(error.into());
+            }
+            ConnectionEvent::GaveUp { error, .. } => {
+                res.kind = CConnectionEventKind::ConnectionEventGaveUp;
+                res.error = Some(error.into());
+            }
+        }
+        res
+    }
+}
+
+impl ToFFI for CConnectionEventOwned {
+    type FFIType = CConnectionEvent;
+
+    fn to_ffi_type(&self) -> Self::FFIType {
+        CConnectionEvent {
+            kind: self.kind,
+            session_reused: self.session_reused,
+            close_code: self.close_code,
+            close_message: self
+                .close_message
+                .as_ref()
+                .map(ToFFI::to_ffi_type)
+                .unwrap_or(std::ptr::null()),
+            error: self
+                .error
+                .as_ref()
+                .map(ToFFI::to_ffi_type)
+                .unwrap_or(std::ptr::null()),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod array;
+mod connection_event;
 mod cow;
 mod datetime;
 mod decimal;
@@ -11,6 +12,7 @@ mod string;
 use std::{ffi::CStr, os::raw::c_char};
 
 pub(crate) use array::CVec;
+pub(crate) use connection_event::{CConnectionEvent, CConnectionEventOwned};
 pub(crate) use cow::CCow;
 pub(crate) use datetime::{CDate, CDateTime, CTime};
 pub(crate) use decimal::CDecimal;
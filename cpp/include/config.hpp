@@ -3,6 +3,7 @@
 #include <optional>
 #include <string>
 #include <utility>
+#include <vector>
 
 #include "async_result.hpp"
 #include "status.hpp"
@@ -82,8 +83,32 @@ public:
   /// - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected,
   ///   `true` or `false` (Default: `true`)
   /// - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
+  /// - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+  ///   certificates
+  /// - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the
+  ///   pinned certificates
+  /// - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+  ///   certificate chain
+  /// - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client
+  ///   private key
   static Status from_env(Config& config);
 
+  /** Set the TLS configuration of the HTTP requests and the websocket
+   * connections
+   *
+   * @param root_certificates PEM encoded root certificates to trust in
+   * addition to the built-in root certificates
+   * @param pins Hex encoded SHA-256 fingerprints of the pinned server (or
+   * intermediate) certificates
+   * @param client_cert PEM encoded client certificate chain
+   * @param client_key PEM encoded private key of the client certificate
+   */
+  Status set_tls(
+    const std::optional<std::string>& root_certificates = std::nullopt,
+    const std::vector<std::string>& pins = {},
+    const std::optional<std::string>& client_cert = std::nullopt,
+    const std::optional<std::string>& client_key = std::nullopt);
+
   /// Gets a new `access_token`
   void refresh_access_token(int64_t expired_at,
                             AsyncCallback<void*, std::string> callback);
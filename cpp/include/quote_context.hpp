@@ -192,6 +192,21 @@ public:
     const std::vector<WarrantStatus>& status,
     AsyncCallback<QuoteContext, std::vector<WarrantInfo>> callback) const;
 
+  /// Query a page of the warrants matching the filter, `count` is the number
+  /// of warrants, `0` means the default page size of the server
+  void warrant_screener(
+    const std::string& symbol,
+    WarrantSortBy sort_by,
+    SortOrderType sort_order,
+    int32_t offset,
+    int32_t count,
+    const std::vector<WarrantType>& warrant_type,
+    const std::vector<int32_t>& issuer,
+    const std::vector<FilterWarrantExpiryDate>& expiry_date,
+    const std::vector<FilterWarrantInOutBoundsType>& price_type,
+    const std::vector<WarrantStatus>& status,
+    AsyncCallback<QuoteContext, WarrantList> callback) const;
+
   /// Get trading session of the day
   void trading_session(
     AsyncCallback<QuoteContext, std::vector<MarketTradingSession>> callback)
@@ -1174,6 +1174,15 @@ struct WarrantInfo
   WarrantStatus status;
 };
 
+/// A page of the warrant screener
+struct WarrantList
+{
+  /// Warrants
+  std::vector<WarrantInfo> warrants;
+  /// Total number of the warrants matching the filter
+  int32_t total_count;
+};
+
 /// Security list category
 enum class SecurityListCategory
 {
@@ -2,6 +2,7 @@
 #include "callback.hpp"
 #include "convert.hpp"
 #include "longport.h"
+#include "utils.hpp"
 
 namespace longport {
 
@@ -81,6 +82,24 @@ Config::from_env(Config& config)
   return status;
 }
 
+Status
+Config::set_tls(const std::optional<std::string>& root_certificates,
+                const std::vector<std::string>& pins,
+                const std::optional<std::string>& client_cert,
+                const std::optional<std::string>& client_key)
+{
+  auto c_pins = utils::get_cstring_vector(pins);
+  lb_error_t* err = nullptr;
+  lb_config_set_tls(config_,
+                    root_certificates ? root_certificates->c_str() : nullptr,
+                    c_pins.data(),
+                    c_pins.size(),
+                    client_cert ? client_cert->c_str() : nullptr,
+                    client_key ? client_key->c_str() : nullptr,
+                    &err);
+  return Status(err);
+}
+
 void
 Config::refresh_access_token(int64_t expired_at,
                              AsyncCallback<void*, std::string> callback)
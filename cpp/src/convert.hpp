@@ -60,6 +60,7 @@ using longport::quote::TradeSessions;
 using longport::quote::TradeStatus;
 using longport::quote::TradingSessionInfo;
 using longport::quote::WarrantInfo;
+using longport::quote::WarrantList;
 using longport::quote::WarrantQuote;
 using longport::quote::WarrantSortBy;
 using longport::quote::WarrantStatus;
@@ -2080,6 +2081,17 @@ convert(lb_warrant_info_t info)
   };
 }
 
+inline WarrantList
+convert(const lb_warrant_list_t* info)
+{
+  std::vector<WarrantInfo> warrants;
+  std::transform(info->warrants,
+                 info->warrants + info->num_warrants,
+                 std::back_inserter(warrants),
+                 [](auto warrant) { return convert(warrant); });
+  return WarrantList{ warrants, info->total_count };
+}
+
 inline lb_security_list_category_t
 convert(SecurityListCategory category)
 {
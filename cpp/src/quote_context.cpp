@@ -980,6 +980,79 @@ QuoteContext::warrant_list(
     new AsyncCallback<QuoteContext, std::vector<WarrantInfo>>(callback));
 }
 
+void
+QuoteContext::warrant_screener(
+  const std::string& symbol,
+  WarrantSortBy sort_by,
+  SortOrderType sort_order,
+  int32_t offset,
+  int32_t count,
+  const std::vector<WarrantType>& warrant_type,
+  const std::vector<int32_t>& issuer,
+  const std::vector<FilterWarrantExpiryDate>& expiry_date,
+  const std::vector<FilterWarrantInOutBoundsType>& price_type,
+  const std::vector<WarrantStatus>& status,
+  AsyncCallback<QuoteContext, WarrantList> callback) const
+{
+  std::vector<lb_warrant_type_t> c_warrant_type;
+  std::transform(warrant_type.cbegin(),
+                 warrant_type.cend(),
+                 std::back_inserter(c_warrant_type),
+                 [](auto row) { return convert(row); });
+
+  std::vector<lb_filter_warrant_expiry_date_t> c_expiry_date;
+  std::transform(expiry_date.cbegin(),
+                 expiry_date.cend(),
+                 std::back_inserter(c_expiry_date),
+                 [](auto row) { return convert(row); });
+
+  std::vector<lb_filter_warrant_in_out_bounds_type_t> c_price_type;
+  std::transform(price_type.cbegin(),
+                 price_type.cend(),
+                 std::back_inserter(c_price_type),
+                 [](auto row) { return convert(row); });
+
+  std::vector<lb_warrant_status_t> c_status;
+  std::transform(status.cbegin(),
+                 status.cend(),
+                 std::back_inserter(c_status),
+                 [](auto row) { return convert(row); });
+
+  lb_quote_context_warrant_screener(
+    ctx_,
+    symbol.c_str(),
+    convert(sort_by),
+    convert(sort_order),
+    offset,
+    count,
+    c_warrant_type.data(),
+    c_warrant_type.size(),
+    issuer.data(),
+    issuer.size(),
+    c_expiry_date.data(),
+    c_expiry_date.size(),
+    c_price_type.data(),
+    c_price_type.size(),
+    c_status.data(),
+    c_status.size(),
+    [](auto res) {
+      auto callback_ptr =
+        callback::get_async_callback<QuoteContext, WarrantList>(res->userdata);
+      QuoteContext ctx((const lb_quote_context_t*)res->ctx);
+      Status status(res->error);
+
+      if (status) {
+        WarrantList list = convert((const lb_warrant_list_t*)res->data);
+        (*callback_ptr)(AsyncResult<QuoteContext, WarrantList>(
+          ctx, std::move(status), &list));
+      } else {
+        (*callback_ptr)(AsyncResult<QuoteContext, WarrantList>(
+          ctx, std::move(status), nullptr));
+      }
+    },
+    new AsyncCallback<QuoteContext, WarrantList>(callback));
+}
+
 void
 QuoteContext::trading_session(
   AsyncCallback<QuoteContext, std::vector<MarketTradingSession>> callback) const
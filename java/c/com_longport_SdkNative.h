@@ -50,10 +50,10 @@ JNIEXPORT void JNICALL Java_com_longport_SdkNative_httpClientRequest
 /*
  * Class:     com_longport_SdkNative
  * Method:    newConfig
- * Signature: (Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Lcom/longport/Language;ZLcom/longport/PushCandlestickMode;ZLjava/lang/String;)J
+ * Signature: (Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Lcom/longport/Language;ZLcom/longport/PushCandlestickMode;ZLjava/lang/String;Ljava/lang/String;[Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)J
  */
 JNIEXPORT jlong JNICALL Java_com_longport_SdkNative_newConfig
-  (JNIEnv *, jclass, jstring, jstring, jstring, jstring, jstring, jstring, jobject, jboolean, jobject, jboolean, jstring);
+  (JNIEnv *, jclass, jstring, jstring, jstring, jstring, jstring, jstring, jobject, jboolean, jobject, jboolean, jstring, jstring, jobjectArray, jstring, jstring);
 
 /*
  * Class:     com_longport_SdkNative
@@ -327,6 +327,14 @@ JNIEXPORT void JNICALL Java_com_longport_SdkNative_quoteContextWarrantIssuers
 JNIEXPORT void JNICALL Java_com_longport_SdkNative_quoteContextWarrantList
   (JNIEnv *, jclass, jlong, jobject, jobject);
 
+/*
+ * Class:     com_longport_SdkNative
+ * Method:    quoteContextWarrantScreener
+ * Signature: (JLcom/longport/quote/QueryWarrantOptions;Lcom/longport/AsyncCallback;)V
+ */
+JNIEXPORT void JNICALL Java_com_longport_SdkNative_quoteContextWarrantScreener
+  (JNIEnv *, jclass, jlong, jobject, jobject);
+
 /*
  * Class:     com_longport_SdkNative
  * Method:    quoteContextTradingSession
@@ -15,6 +15,10 @@ public class ConfigBuilder {
     private PushCandlestickMode pushCandlestickMode;
     private boolean enablePrintQuotePackages;
     private String logPath;
+    private String tlsRootCertificates;
+    private String[] tlsPins;
+    private String tlsClientCert;
+    private String tlsClientKey;
 
     /**
      * Create a `Config` object builder
@@ -125,6 +129,43 @@ public class ConfigBuilder {
         return this;
     }
 
+    /**
+     * Trusts the root certificates in addition to the built-in root
+     * certificates.
+     * 
+     * @param pem PEM encoded root certificates
+     * @return this object
+     */
+    public ConfigBuilder tlsRootCertificates(String pem) {
+        this.tlsRootCertificates = pem;
+        return this;
+    }
+
+    /**
+     * Pins the certificates, the connection fails unless the server presents
+     * one of them as the server or an intermediate certificate.
+     * 
+     * @param fingerprints Hex encoded SHA-256 fingerprints of the certificates
+     * @return this object
+     */
+    public ConfigBuilder tlsPins(String... fingerprints) {
+        this.tlsPins = fingerprints;
+        return this;
+    }
+
+    /**
+     * Authenticates with the client certificate.
+     * 
+     * @param certChain  PEM encoded client certificate chain
+     * @param privateKey PEM encoded private key
+     * @return this object
+     */
+    public ConfigBuilder tlsClientIdentity(String certChain, String privateKey) {
+        this.tlsClientCert = certChain;
+        this.tlsClientKey = privateKey;
+        return this;
+    }
+
     /**
      * Build a Config object
      * 
@@ -134,6 +175,7 @@ public class ConfigBuilder {
     public Config build() throws OpenApiException {
         return new Config(
                 SdkNative.newConfig(appKey, appSecret, accessToken, httpUrl, quoteWsUrl, tradeWsUrl, language,
-                        enableOvernight, pushCandlestickMode, enablePrintQuotePackages, logPath));
+                        enableOvernight, pushCandlestickMode, enablePrintQuotePackages, logPath,
+                        tlsRootCertificates, tlsPins, tlsClientCert, tlsClientKey));
     }
 }
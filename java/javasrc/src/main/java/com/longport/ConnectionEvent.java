@@ -0,0 +1,38 @@
+package com.longport;
+
+/**
+ * Connection state change event
+ */
+public class ConnectionEvent {
+    private ConnectionEventKind kind;
+    private boolean sessionReused;
+    private int closeCode;
+    private String closeMessage;
+    private String error;
+
+    public ConnectionEventKind getKind() {
+        return kind;
+    }
+
+    public boolean getSessionReused() {
+        return sessionReused;
+    }
+
+    public int getCloseCode() {
+        return closeCode;
+    }
+
+    public String getCloseMessage() {
+        return closeMessage;
+    }
+
+    public String getError() {
+        return error;
+    }
+
+    @Override
+    public String toString() {
+        return "ConnectionEvent [kind=" + kind + ", sessionReused=" + sessionReused + ", closeCode=" + closeCode
+                + ", closeMessage=" + closeMessage + ", error=" + error + "]";
+    }
+}
@@ -0,0 +1,5 @@
+package com.longport;
+
+public interface ConnectionEventHandler {
+    void onConnectionEvent(ConnectionEvent event);
+}
@@ -0,0 +1,35 @@
+package com.longport;
+
+/**
+ * Connection event kind
+ */
+public enum ConnectionEventKind {
+    /**
+     * Connecting to the server
+     */
+    Connecting,
+    /**
+     * The websocket connection has been established
+     */
+    Connected,
+    /**
+     * The first session has been created
+     */
+    Authenticated,
+    /**
+     * The session has been restored after a reconnection
+     */
+    Reconnected,
+    /**
+     * The subscriptions have been restored after a reconnection
+     */
+    Resubscribed,
+    /**
+     * The connection was lost or a reconnection attempt failed
+     */
+    Disconnected,
+    /**
+     * The reconnection retries ran out, the context is closed
+     */
+    GaveUp,
+}
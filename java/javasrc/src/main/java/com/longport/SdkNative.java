@@ -26,7 +26,8 @@ public class SdkNative {
 
         public static native long newConfig(String appKey, String appSecret, String accessToken, String httpUrl,
                         String quoteWsUrl, String tradeWsUrl, Language language, boolean enableOvernight,
-                        PushCandlestickMode mode, boolean enablePrintQuotePackages, String logPath);
+                        PushCandlestickMode mode, boolean enablePrintQuotePackages, String logPath,
+                        String tlsRootCertificates, String[] tlsPins, String tlsClientCert, String tlsClientKey);
 
         public static native long newConfigFromEnv();
 
@@ -45,6 +46,8 @@ public class SdkNative {
 
         public static native QuotePackageDetail[] quoteContextGetQuotePackageDetails(long context);
 
+        public static native void quoteContextSetOnConnectionEvent(long context, ConnectionEventHandler handler);
+
         public static native void quoteContextSetOnQuote(long context, QuoteHandler handler);
 
         public static native void quoteContextSetOnDepth(long context, DepthHandler handler);
@@ -110,6 +113,9 @@ public class SdkNative {
         public static native void quoteContextWarrantList(long context, QueryWarrantOptions opts,
                         AsyncCallback callback);
 
+        public static native void quoteContextWarrantScreener(long context, QueryWarrantOptions opts,
+                        AsyncCallback callback);
+
         public static native void quoteContextTradingSession(long context, AsyncCallback callback);
 
         public static native void quoteContextTradingDays(long context, Market market, LocalDate begin, LocalDate end,
@@ -159,6 +165,8 @@ public class SdkNative {
 
         public static native void freeTradeContext(long config);
 
+        public static native void tradeContextSetOnConnectionEvent(long context, ConnectionEventHandler handler);
+
         public static native void tradeContextSetOnOrderChanged(long context, OrderChangedHandler handler);
 
         public static native void tradeContextSubscribe(long context, TopicType[] topics, AsyncCallback callback);
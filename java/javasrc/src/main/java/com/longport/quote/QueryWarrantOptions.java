@@ -5,6 +5,8 @@ public class QueryWarrantOptions {
     private String symbol;
     private WarrantSortBy sortBy;
     private SortOrderType sortType;
+    private int offset;
+    private int count;
     private WarrantType[] warrantType;
     private int[] issuer;
     private FilterWarrantExpiryDate[] expiryDate;
@@ -17,6 +19,16 @@ public class QueryWarrantOptions {
         this.sortType = sortType;
     }
 
+    public QueryWarrantOptions setOffset(int offset) {
+        this.offset = offset;
+        return this;
+    }
+
+    public QueryWarrantOptions setCount(int count) {
+        this.count = count;
+        return this;
+    }
+
     public QueryWarrantOptions setWarrantType(WarrantType[] warrantType) {
         this.warrantType = warrantType;
         return this;
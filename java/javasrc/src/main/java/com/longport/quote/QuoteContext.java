@@ -58,6 +58,16 @@ public class QuoteContext implements AutoCloseable {
         return SdkNative.quoteContextGetQuotePackageDetails(this.raw);
     }
 
+    /**
+     * Set connection event callback, it will call back to this handler when the
+     * state of the connection changes.
+     * 
+     * @param handler A connection event handler
+     */
+    public void setOnConnectionEvent(ConnectionEventHandler handler) {
+        SdkNative.quoteContextSetOnConnectionEvent(this.raw, handler);
+    }
+
     /**
      * Set quote callback, after receiving the quote data push, it will call back to
      * this handler.
@@ -709,6 +719,41 @@ public class QuoteContext implements AutoCloseable {
         });
     }
 
+    /**
+     * Query a page of the warrants matching the filter
+     * 
+     * <pre>
+     * {@code
+     * import com.longport.*;
+     * import com.longport.quote.*;
+     * 
+     * class Main {
+     *     public static void main(String[] args) throws Exception {
+     *         try (Config config = Config.fromEnv(); QuoteContext ctx = QuoteContext.create(config).get()) {
+     *             QueryWarrantOptions opts = new QueryWarrantOptions("700.HK", WarrantSortBy.Volume,
+     *                     SortOrderType.Descending).setOffset(0).setCount(100);
+     *             WarrantList resp = ctx.queryWarrantScreener(opts).get();
+     *             System.out.println(resp.getTotalCount());
+     *             for (WarrantInfo obj : resp.getWarrants()) {
+     *                 System.out.println(obj);
+     *             }
+     *         }
+     *     }
+     * }
+     * }
+     * </pre>
+     * 
+     * @param opts Query options
+     * @return A Future representing the result of the operation
+     * @throws OpenApiException If an error occurs
+     */
+    public CompletableFuture<WarrantList> queryWarrantScreener(QueryWarrantOptions opts)
+            throws OpenApiException {
+        return AsyncCallback.executeTask((callback) -> {
+            SdkNative.quoteContextWarrantScreener(this.raw, opts, callback);
+        });
+    }
+
     /**
      * Get trading session of the day
      * 
@@ -0,0 +1,21 @@
+package com.longport.quote;
+
+import java.util.Arrays;
+
+public class WarrantList {
+    private WarrantInfo[] warrants;
+    private int totalCount;
+
+    public WarrantInfo[] getWarrants() {
+        return warrants;
+    }
+
+    public int getTotalCount() {
+        return totalCount;
+    }
+
+    @Override
+    public String toString() {
+        return "WarrantList [warrants=" + Arrays.toString(warrants) + ", totalCount=" + totalCount + "]";
+    }
+}
@@ -29,6 +29,16 @@ public class TradeContext implements AutoCloseable {
         SdkNative.freeTradeContext(raw);
     }
 
+    /**
+     * Set connection event callback, it will call back to this handler when the
+     * state of the connection changes.
+     * 
+     * @param handler A connection event handler
+     */
+    public void setOnConnectionEvent(ConnectionEventHandler handler) {
+        SdkNative.tradeContextSetOnConnectionEvent(this.raw, handler);
+    }
+
     /**
      * Set order changed event callback, after receiving the order changed event, it
      * will call back to this handler.
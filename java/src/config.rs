@@ -3,10 +3,14 @@ use jni::{
     objects::{JClass, JObject, JString},
     sys::{jboolean, jlong},
 };
-use longport::{Config, Language, PushCandlestickMode};
+use longport::{Config, Language, PushCandlestickMode, TlsConfig};
 use time::OffsetDateTime;
 
-use crate::{async_util, error::jni_result, types::FromJValue};
+use crate::{
+    async_util,
+    error::jni_result,
+    types::{FromJValue, ObjectArray},
+};
 
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_com_longport_SdkNative_newConfig(
@@ -23,6 +27,10 @@ pub extern "system" fn Java_com_longport_SdkNative_newConfig(
     push_candlestick_mode: JObject,
     enable_print_quote_packages: jboolean,
     log_path: JString,
+    tls_root_certificates: JString,
+    tls_pins: JObject,
+    tls_client_cert: JString,
+    tls_client_key: JString,
 ) -> jlong {
     jni_result(&mut env, 0, |env| {
         let app_key = String::from_jvalue(env, app_key.into())?;
@@ -35,6 +43,11 @@ pub extern "system" fn Java_com_longport_SdkNative_newConfig(
         let push_candlestick_mode =
             <Option<PushCandlestickMode>>::from_jvalue(env, push_candlestick_mode.into())?;
         let log_path = <Option<String>>::from_jvalue(env, log_path.into())?;
+        let tls_root_certificates =
+            <Option<String>>::from_jvalue(env, tls_root_certificates.into())?;
+        let tls_pins = ObjectArray::<String>::from_jvalue(env, tls_pins.into())?.0;
+        let tls_client_cert = <Option<String>>::from_jvalue(env, tls_client_cert.into())?;
+        let tls_client_key = <Option<String>>::from_jvalue(env, tls_client_key.into())?;
 
         let mut config = Config::new(app_key, app_secret, access_token);
 
@@ -62,6 +75,19 @@ pub extern "system" fn Java_com_longport_SdkNative_newConfig(
         if let Some(log_path) = log_path {
             config = config.log_path(log_path);
         }
+        if tls_root_certificates.is_some() || !tls_pins.is_empty() || tls_client_cert.is_some() {
+            let mut tls_config = TlsConfig::new();
+            if let Some(pem) = tls_root_certificates {
+                tls_config = tls_config.add_root_certificates(pem)?;
+            }
+            for pin in tls_pins {
+                tls_config = tls_config.pin_certificate(pin)?;
+            }
+            if let (Some(cert), Some(key)) = (tls_client_cert, tls_client_key) {
+                tls_config = tls_config.client_identity(cert, key)?;
+            }
+            config = config.tls_config(tls_config)?;
+        }
 
         Ok(Box::into_raw(Box::new(config)) as jlong)
     })
@@ -31,7 +31,8 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newHttpClient(
         let access_token = String::from_jvalue(env, access_token.into())?;
         let http_cli = HttpClient::new(
             HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
-        );
+        )
+        .map_err(longport::Error::HttpClient)?;
         Ok(Box::into_raw(Box::new(http_cli)) as jlong)
     })
 }
@@ -81,6 +81,7 @@ pub extern "system" fn Java_com_longport_SdkNative_init<'a>(
         longport::Language,
         longport::PushCandlestickMode,
         longport::Market,
+        crate::types::ConnectionEventKind,
         longport::quote::TradeStatus,
         longport::quote::TradeSession,
         longport::quote::TradeDirection,
@@ -118,6 +119,7 @@ pub extern "system" fn Java_com_longport_SdkNative_init<'a>(
     // classes
     init_class_by_classloader!(
         env,
+        crate::types::ConnectionEvent,
         longport::quote::Trade,
         longport::quote::Brokers,
         longport::quote::Depth,
@@ -140,6 +142,7 @@ pub extern "system" fn Java_com_longport_SdkNative_init<'a>(
         longport::quote::StrikePriceInfo,
         longport::quote::IssuerInfo,
         longport::quote::WarrantInfo,
+        longport::quote::WarrantList,
         longport::quote::MarketTradingSession,
         longport::quote::TradingSessionInfo,
         longport::quote::MarketTradingDays,
@@ -7,12 +7,12 @@ use jni::{
     sys::{jboolean, jobjectArray},
 };
 use longport::{
-    Config, Market, QuoteContext,
+    Config, ConnectionEvent, Market, QuoteContext,
     quote::{
         AdjustType, CalcIndex, FilterWarrantExpiryDate, FilterWarrantInOutBoundsType, Period,
         PushEvent, PushEventDetail, RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup,
         SecuritiesUpdateMode, SecurityListCategory, SortOrderType, SubFlags, TradeSessions,
-        WarrantSortBy, WarrantStatus, WarrantType,
+        WarrantFilter, WarrantSortBy, WarrantStatus, WarrantType,
     },
 };
 use parking_lot::Mutex;
@@ -31,6 +31,7 @@ use crate::{
 #[derive(Default)]
 struct Callbacks {
     quote: Option<GlobalRef>,
+    connection: Option<GlobalRef>,
     depth: Option<GlobalRef>,
     brokers: Option<GlobalRef>,
     trades: Option<GlobalRef>,
@@ -111,6 +112,24 @@ fn send_push_event(jvm: &JavaVM, callbacks: &Callbacks, event: PushEvent) -> Res
     Ok(())
 }
 
+fn send_connection_event(
+    jvm: &JavaVM,
+    callbacks: &Callbacks,
+    event: ConnectionEvent,
+) -> Result<()> {
+    if let Some(handler) = &callbacks.connection {
+        let mut env = jvm.attach_current_thread().unwrap();
+        let event = crate::types::ConnectionEvent::from(event).into_jvalue(&mut env)?;
+        env.call_method(
+            handler,
+            "onConnectionEvent",
+            "(Lcom/longport/ConnectionEvent;)V",
+            &[event.borrow()],
+        )?;
+    }
+    Ok(())
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_newQuoteContext(
     mut env: JNIEnv,
@@ -130,7 +149,7 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newQuoteContext(
 
     jni_result(&mut env, (), |env| {
         let config = Arc::new((*(config as *const Config)).clone());
-        let jvm = env.get_java_vm()?;
+        let jvm = Arc::new(env.get_java_vm()?);
 
         async_util::execute(env, callback, async move {
             let (ctx, mut receiver) = QuoteContext::try_new(config).await?;
@@ -138,6 +157,7 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newQuoteContext(
 
             tokio::spawn({
                 let callbacks = callbacks.clone();
+                let jvm = jvm.clone();
                 async move {
                     while let Some(event) = receiver.recv().await {
                         let callbacks = callbacks.lock();
@@ -146,6 +166,17 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newQuoteContext(
                 }
             });
 
+            tokio::spawn({
+                let callbacks = callbacks.clone();
+                let mut receiver = ctx.connection_events();
+                async move {
+                    while let Some(event) = receiver.recv().await {
+                        let callbacks = callbacks.lock();
+                        let _ = send_connection_event(&jvm, &callbacks, event);
+                    }
+                }
+            });
+
             Ok(ContextObjRef(
                 Box::into_raw(Box::new(ContextObj { ctx, callbacks })) as i64,
             ))
@@ -205,6 +236,24 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextGetQuotePa
         .unwrap()
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextSetOnConnectionEvent(
+    mut env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    handler: JObject,
+) {
+    let context = &*(ctx as *const ContextObj);
+    jni_result(&mut env, (), |env| {
+        if !handler.is_null() {
+            context.callbacks.lock().connection = Some(env.new_global_ref(handler)?);
+        } else {
+            context.callbacks.lock().connection = None;
+        }
+        Ok(())
+    })
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextSetOnQuote(
     mut env: JNIEnv,
@@ -800,6 +849,44 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextWarrantLis
     })
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextWarrantScreener(
+    mut env: JNIEnv,
+    _class: JClass,
+    context: i64,
+    opts: JObject,
+    callback: JObject,
+) {
+    jni_result(&mut env, (), |env| {
+        let context = &*(context as *const ContextObj);
+        let symbol: String = get_field(env, &opts, "symbol")?;
+        let sort_by: WarrantSortBy = get_field(env, &opts, "sortBy")?;
+        let sort_type: SortOrderType = get_field(env, &opts, "sortType")?;
+        let offset: i32 = get_field(env, &opts, "offset")?;
+        let count: i32 = get_field(env, &opts, "count")?;
+        let warrant_type: ObjectArray<WarrantType> = get_field(env, &opts, "warrantType")?;
+        let issuer: PrimaryArray<i32> = get_field(env, &opts, "issuer")?;
+        let expiry_date: ObjectArray<FilterWarrantExpiryDate> =
+            get_field(env, &opts, "expiryDate")?;
+        let price_type: ObjectArray<FilterWarrantInOutBoundsType> =
+            get_field(env, &opts, "priceType")?;
+        let status: ObjectArray<WarrantStatus> = get_field(env, &opts, "status")?;
+        let filter = WarrantFilter::new(symbol, sort_by, sort_type)
+            .offset(offset)
+            .count(count)
+            .warrant_type(warrant_type.0)
+            .issuer(issuer.0)
+            .expiry_date(expiry_date.0)
+            .price_type(price_type.0)
+            .status(status.0);
+
+        async_util::execute(env, callback, async move {
+            Ok(context.ctx.warrant_screener(filter).await?)
+        })?;
+        Ok(())
+    })
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextTradingSession(
     mut env: JNIEnv,
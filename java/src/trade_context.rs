@@ -7,7 +7,7 @@ use jni::{
     sys::jobjectArray,
 };
 use longport::{
-    Config, Decimal, Market, TradeContext,
+    Config, ConnectionEvent, Decimal, Market, TradeContext,
     trade::{
         BalanceType, EstimateMaxPurchaseQuantityOptions, GetCashFlowOptions,
         GetFundPositionsOptions, GetHistoryExecutionsOptions, GetHistoryOrdersOptions,
@@ -29,6 +29,7 @@ use crate::{
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<GlobalRef>,
+    connection: Option<GlobalRef>,
 }
 
 struct ContextObj {
@@ -56,6 +57,24 @@ fn send_push_event(jvm: &JavaVM, callbacks: &Callbacks, event: PushEvent) -> Res
     Ok(())
 }
 
+fn send_connection_event(
+    jvm: &JavaVM,
+    callbacks: &Callbacks,
+    event: ConnectionEvent,
+) -> Result<()> {
+    if let Some(handler) = &callbacks.connection {
+        let mut env = jvm.attach_current_thread().unwrap();
+        let event = crate::types::ConnectionEvent::from(event).into_jvalue(&mut env)?;
+        env.call_method(
+            handler,
+            "onConnectionEvent",
+            "(Lcom/longport/ConnectionEvent;)V",
+            &[event.borrow()],
+        )?;
+    }
+    Ok(())
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_newTradeContext(
     mut env: JNIEnv,
@@ -75,7 +94,7 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newTradeContext(
 
     jni_result(&mut env, (), |env| {
         let config = Arc::new((*(config as *const Config)).clone());
-        let jvm = env.get_java_vm()?;
+        let jvm = Arc::new(env.get_java_vm()?);
 
         async_util::execute(env, callback, async move {
             let (ctx, mut receiver) = TradeContext::try_new(config).await?;
@@ -83,6 +102,7 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newTradeContext(
 
             tokio::spawn({
                 let callbacks = callbacks.clone();
+                let jvm = jvm.clone();
                 async move {
                     while let Some(event) = receiver.recv().await {
                         let callbacks = callbacks.lock();
@@ -91,6 +111,17 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_newTradeContext(
                 }
             });
 
+            tokio::spawn({
+                let callbacks = callbacks.clone();
+                let mut receiver = ctx.connection_events();
+                async move {
+                    while let Some(event) = receiver.recv().await {
+                        let callbacks = callbacks.lock();
+                        let _ = send_connection_event(&jvm, &callbacks, event);
+                    }
+                }
+            });
+
             Ok(ContextObjRef(
                 Box::into_raw(Box::new(ContextObj { ctx, callbacks })) as i64,
             ))
@@ -109,6 +140,24 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_freeTradeContext(
     let _ = Box::from_raw(ctx as *mut ContextObj);
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_tradeContextSetOnConnectionEvent(
+    mut env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    handler: JObject,
+) {
+    let context = &*(ctx as *const ContextObj);
+    jni_result(&mut env, (), |env| {
+        if !handler.is_null() {
+            context.callbacks.lock().connection = Some(env.new_global_ref(handler)?);
+        } else {
+            context.callbacks.lock().connection = None;
+        }
+        Ok(())
+    })
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_tradeContextSetOnOrderChanged(
     mut env: JNIEnv,
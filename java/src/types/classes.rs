@@ -4,6 +4,8 @@ use longport::{Decimal, Market};
 use longport_java_macros::impl_java_class;
 use time::Date;
 
+use crate::types::ConnectionEventKind;
+
 impl_java_class!(
     "com/longport/quote/Trade",
     longport::quote::Trade,
@@ -319,6 +321,57 @@ impl_java_class!(
     [large, medium, small]
 );
 
+pub(crate) struct ConnectionEvent {
+    pub(crate) kind: ConnectionEventKind,
+    pub(crate) session_reused: bool,
+    pub(crate) close_code: i32,
+    pub(crate) close_message: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+impl From<longport::ConnectionEvent> for ConnectionEvent {
+    fn from(event: longport::ConnectionEvent) -> Self {
+        let mut res = ConnectionEvent {
+            kind: ConnectionEventKind::Connecting,
+            session_reused: false,
+            close_code: 0,
+            close_message: None,
+            error: None,
+        };
+        match event {
+            longport::ConnectionEvent::Connecting => {}
+            longport::ConnectionEvent::Connected => res.kind = ConnectionEventKind::Connected,
+            longport::ConnectionEvent::Authenticated => {
+                res.kind = ConnectionEventKind::Authenticated
+            }
+            longport::ConnectionEvent::Reconnected { session_reused } => {
+                res.kind = ConnectionEventKind::Reconnected;
+                res.session_reused = session_reused;
+            }
+            longport::ConnectionEvent::Resubscribed => res.kind = ConnectionEventKind::Resubscribed,
+            longport::ConnectionEvent::Disconnected { reason, error } => {
+                res.kind = ConnectionEventKind::Disconnected;
+                if let Some(reason) = reason {
+                    res.close_code = u16::from(reason.code) as i32;
+                    res.close_message = Some(reason.message);
+                }
+                res.error = Some(error);
+            }
+            longport::ConnectionEvent::GaveUp { error, .. } => {
+                res.kind = ConnectionEventKind::GaveUp;
+                res.error = Some(error);
+            }
+        }
+        res
+    }
+}
+
+impl_java_class!(
+    "com/longport/ConnectionEvent",
+    ConnectionEvent,
+    [kind, session_reused, close_code, close_message, error]
+);
+
 pub(crate) struct SecurityCalcIndex {
     pub(crate) symbol: String,
     pub(crate) last_done: Option<Decimal>,
@@ -584,6 +637,16 @@ impl_java_class!(
     ]
 );
 
+impl_java_class!(
+    "com/longport/quote/WarrantList",
+    longport::quote::WarrantList,
+    [
+        #[java(objarray)]
+        warrants,
+        total_count
+    ]
+);
+
 impl_java_class!(
     "com/longport/trade/PushOrderChanged",
     longport::trade::PushOrderChanged,
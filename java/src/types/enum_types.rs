@@ -24,6 +24,32 @@ impl_java_enum!(
     [Realtime, Confirmed]
 );
 
+/// Kind of `longport::ConnectionEvent`
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum ConnectionEventKind {
+    Connecting,
+    Connected,
+    Authenticated,
+    Reconnected,
+    Resubscribed,
+    Disconnected,
+    GaveUp,
+}
+
+impl_java_enum!(
+    "com/longport/ConnectionEventKind",
+    ConnectionEventKind,
+    [
+        Connecting,
+        Connected,
+        Authenticated,
+        Reconnected,
+        Resubscribed,
+        Disconnected,
+        GaveUp
+    ]
+);
+
 impl_java_enum!(
     "com/longport/Market",
     longport::Market,
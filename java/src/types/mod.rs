@@ -20,9 +20,10 @@ use jni::{
 
 pub(crate) use self::{
     classes::{
-        CreateWatchlistGroupResponse, SecurityCalcIndex, StockPosition, StockPositionChannel,
-        StockPositionsResponse,
+        ConnectionEvent, CreateWatchlistGroupResponse, SecurityCalcIndex, StockPosition,
+        StockPositionChannel, StockPositionsResponse,
     },
+    enum_types::ConnectionEventKind,
     object_array::ObjectArray,
     primary_array::PrimaryArray,
 };
@@ -1,12 +1,19 @@
+mod resources;
 mod server;
+mod session;
+mod stdio;
+mod streamable_http;
+mod types;
 
 use std::{path::PathBuf, sync::Arc};
 
 use clap::Parser;
 use longport::{Config, QuoteContext, TradeContext};
 use poem::{EndpointExt, Route, Server, listener::TcpListener, middleware::Cors};
-use poem_mcpserver::{McpServer, stdio::stdio, streamable_http};
-use server::Longport;
+use poem_mcpserver::{McpServer, tool::Tools};
+use resources::Resources;
+use server::{Longport, READ_TOOLS};
+use session::Session;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 
 #[derive(Parser)]
@@ -22,9 +29,16 @@ struct Cli {
     log_dir: Option<PathBuf>,
     /// Read-only mode
     ///
-    /// This mode is used to prevent submitting orders to the exchange.
+    /// This mode disables the tools that submit, replace or cancel orders, or
+    /// change the watchlist.
     #[clap(long, default_value_t = false)]
     readonly: bool,
+    /// Paper trading mode
+    ///
+    /// Orders are simulated locally against the live quotes instead of being
+    /// submitted to the exchange.
+    #[clap(long, default_value_t = false)]
+    paper: bool,
 }
 
 #[tokio::main]
@@ -41,19 +55,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .init();
     }
 
-    let config = Arc::new(
-        Config::from_env()
-            .inspect_err(|err| tracing::error!(error = %err, "failed to load config"))?
-            .dont_print_quote_packages(),
+    let mut config = Config::from_env()
+        .inspect_err(|err| tracing::error!(error = %err, "failed to load config"))?
+        .dont_print_quote_packages();
+    if cli.paper {
+        config = config.paper_trading();
+    }
+    let config = Arc::new(config);
+    let (quote_context, quote_receiver) = QuoteContext::try_new(config.clone()).await?;
+    let (trade_context, trade_receiver) = TradeContext::try_new(config.clone()).await?;
+    let resources = Resources::new(
+        quote_context.clone(),
+        trade_context.clone(),
+        quote_receiver,
+        trade_receiver,
     );
-    let (quote_context, _) = QuoteContext::try_new(config.clone()).await?;
-    let (trade_context, _) = TradeContext::try_new(config.clone()).await?;
     let readonly = cli.readonly;
 
     if !cli.http {
         tracing::info!("Starting MCP server with stdio transport");
         let server = create_mcp_server(quote_context, trade_context, readonly);
-        stdio(server).await?;
+        stdio::stdio(Session::new(server, resources)).await?;
     } else {
         tracing::info!(
             "Starting MCP server with Streamable-HTTP transport, listening on {}",
@@ -63,8 +85,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let app = Route::new()
             .at(
                 "/",
-                streamable_http::endpoint(move |_| {
-                    create_mcp_server(quote_context.clone(), trade_context.clone(), readonly)
+                streamable_http::endpoint(move || {
+                    Session::new(
+                        create_mcp_server(quote_context.clone(), trade_context.clone(), readonly),
+                        resources.clone(),
+                    )
                 }),
             )
             .with(Cors::new());
@@ -81,7 +106,12 @@ fn create_mcp_server(
 ) -> McpServer<Longport> {
     let mut server = McpServer::new().tools(Longport::new(quote_context, trade_context));
     if readonly {
-        server = server.disable_tools(["submit_order"]);
+        server = server.disable_tools(
+            Longport::list()
+                .into_iter()
+                .map(|tool| tool.name)
+                .filter(|name| !READ_TOOLS.contains(name)),
+        );
     }
     server
 }
@@ -0,0 +1,319 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use longport::{
+    PushReceiver, QuoteContext, TradeContext,
+    quote::{self, PushEventDetail, SubFlags},
+    trade::{self, TopicType},
+};
+use poem_mcpserver::protocol::rpc::RpcError;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::sync::{Mutex, broadcast};
+
+/// The capacity of the channel of the updated resources
+const UPDATES_CAPACITY: usize = 1024;
+
+/// A resource of the server
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ResourceUri {
+    /// `quote://{symbol}`, the real-time quote of the security
+    Quote(String),
+    /// `depth://{symbol}`, the real-time depth of the security
+    Depth(String),
+    /// `orders://today`, the orders of the day
+    Orders,
+    /// `positions://`, the stock and fund positions
+    Positions,
+}
+
+impl ResourceUri {
+    /// Parses the uri of a resource, the symbols are converted to upper case
+    pub(crate) fn parse(uri: &str) -> Option<Self> {
+        let symbol = |symbol: &str| {
+            (!symbol.is_empty() && !symbol.contains('/')).then(|| symbol.to_uppercase())
+        };
+
+        if let Some(value) = uri.strip_prefix("quote://") {
+            symbol(value).map(Self::Quote)
+        } else if let Some(value) = uri.strip_prefix("depth://") {
+            symbol(value).map(Self::Depth)
+        } else if uri == "orders://today" {
+            Some(Self::Orders)
+        } else if uri == "positions://" {
+            Some(Self::Positions)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceUri::Quote(symbol) => write!(f, "quote://{symbol}"),
+            ResourceUri::Depth(symbol) => write!(f, "depth://{symbol}"),
+            ResourceUri::Orders => f.write_str("orders://today"),
+            ResourceUri::Positions => f.write_str("positions://"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Positions {
+    stock_positions: Vec<trade::StockPositionChannel>,
+    fund_positions: Vec<trade::FundPositionChannel>,
+}
+
+/// The resources of the server, shared by all the sessions
+///
+/// The push events of the quote and trade contexts are turned into the
+/// updated resources, and the quote subscriptions are counted across the
+/// sessions so that a security stays subscribed while a session watches it.
+pub(crate) struct Resources {
+    quote_context: QuoteContext,
+    trade_context: TradeContext,
+    subscriptions: Mutex<HashMap<ResourceUri, usize>>,
+    updates_tx: broadcast::Sender<ResourceUri>,
+}
+
+impl Resources {
+    pub(crate) fn new(
+        quote_context: QuoteContext,
+        trade_context: TradeContext,
+        mut quote_receiver: PushReceiver<quote::PushEvent>,
+        mut trade_receiver: PushReceiver<trade::PushEvent>,
+    ) -> Arc<Self> {
+        let (updates_tx, _) = broadcast::channel(UPDATES_CAPACITY);
+
+        tokio::spawn({
+            let updates_tx = updates_tx.clone();
+            async move {
+                loop {
+                    tokio::select! {
+                        Some(event) = quote_receiver.recv() => {
+                            let uri = match event.detail {
+                                PushEventDetail::Quote(_) => ResourceUri::Quote(event.symbol),
+                                PushEventDetail::Depth(_) => ResourceUri::Depth(event.symbol),
+                                _ => continue,
+                            };
+                            _ = updates_tx.send(uri);
+                        }
+                        Some(event) = trade_receiver.recv() => {
+                            let trade::PushEvent::OrderChanged(order) = event;
+                            _ = updates_tx.send(ResourceUri::Orders);
+                            if !order.executed_quantity.is_zero() {
+                                _ = updates_tx.send(ResourceUri::Positions);
+                            }
+                        }
+                        else => break,
+                    }
+                }
+            }
+        });
+
+        Arc::new(Self {
+            quote_context,
+            trade_context,
+            subscriptions: Default::default(),
+            updates_tx,
+        })
+    }
+
+    /// Returns a receiver of the updated resources
+    pub(crate) fn updates(&self) -> broadcast::Receiver<ResourceUri> {
+        self.updates_tx.subscribe()
+    }
+
+    /// Returns the result of `resources/list`
+    pub(crate) fn list() -> Value {
+        json!({
+            "resources": [
+                {
+                    "uri": ResourceUri::Orders.to_string(),
+                    "name": "orders",
+                    "description": "The orders of the day, updated when an order changes.",
+                    "mimeType": "application/json",
+                },
+                {
+                    "uri": ResourceUri::Positions.to_string(),
+                    "name": "positions",
+                    "description": "The stock and fund positions, updated when an order is filled.",
+                    "mimeType": "application/json",
+                },
+            ]
+        })
+    }
+
+    /// Returns the result of `resources/templates/list`
+    pub(crate) fn templates() -> Value {
+        json!({
+            "resourceTemplates": [
+                {
+                    "uriTemplate": "quote://{symbol}",
+                    "name": "quote",
+                    "description": "The real-time quote of the security (e.g. quote://700.HK), updated with the quote pushes.",
+                    "mimeType": "application/json",
+                },
+                {
+                    "uriTemplate": "depth://{symbol}",
+                    "name": "depth",
+                    "description": "The real-time depth of the security (e.g. depth://AAPL.US), updated with the depth pushes.",
+                    "mimeType": "application/json",
+                },
+            ]
+        })
+    }
+
+    /// Returns the result of `resources/read`
+    pub(crate) async fn read(&self, uri: &ResourceUri) -> Result<Value, RpcError> {
+        let value = match uri {
+            ResourceUri::Quote(symbol) => self.quote_context.quote([symbol]).await.map(to_value),
+            ResourceUri::Depth(symbol) => self.quote_context.depth(symbol).await.map(to_value),
+            ResourceUri::Orders => self.trade_context.today_orders(None).await.map(to_value),
+            ResourceUri::Positions => tokio::try_join!(
+                self.trade_context.stock_positions(None),
+                self.trade_context.fund_positions(None)
+            )
+            .map(|(stock, fund)| {
+                to_value(Positions {
+                    stock_positions: stock.channels,
+                    fund_positions: fund.channels,
+                })
+            }),
+        }
+        .map_err(|err| RpcError::internal_error(err.to_string()))?;
+
+        let value = match (uri, value) {
+            (ResourceUri::Quote(_), Value::Array(mut quotes)) if !quotes.is_empty() => {
+                quotes.swap_remove(0)
+            }
+            (ResourceUri::Quote(symbol), _) => {
+                return Err(RpcError::invalid_params(format!(
+                    "unknown security `{symbol}`"
+                )));
+            }
+            (_, value) => value,
+        };
+
+        Ok(json!({
+            "contents": [{
+                "uri": uri.to_string(),
+                "mimeType": "application/json",
+                "text": value.to_string(),
+            }]
+        }))
+    }
+
+    /// Starts watching the resource, subscribes the pushes it depends on if
+    /// no session watches it yet
+    pub(crate) async fn acquire(&self, uri: &ResourceUri) -> Result<(), RpcError> {
+        let mut subscriptions = self.subscriptions.lock().await;
+        if !subscriptions.contains_key(uri) {
+            let res = match uri {
+                ResourceUri::Quote(symbol) => {
+                    self.quote_context
+                        .subscribe([symbol], SubFlags::QUOTE, false)
+                        .await
+                }
+                ResourceUri::Depth(symbol) => {
+                    self.quote_context
+                        .subscribe([symbol], SubFlags::DEPTH, false)
+                        .await
+                }
+                ResourceUri::Orders | ResourceUri::Positions => {
+                    if watches_orders(&subscriptions) {
+                        Ok(())
+                    } else {
+                        self.trade_context.subscribe([TopicType::Private]).await
+                    }
+                }
+            };
+            res.map_err(|err| RpcError::internal_error(err.to_string()))?;
+        }
+        *subscriptions.entry(uri.clone()).or_default() += 1;
+        Ok(())
+    }
+
+    /// Stops watching the resource, unsubscribes the pushes it depends on if
+    /// no other session watches it
+    pub(crate) async fn release(&self, uri: &ResourceUri) {
+        let mut subscriptions = self.subscriptions.lock().await;
+        let Some(count) = subscriptions.get_mut(uri) else {
+            return;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        subscriptions.remove(uri);
+
+        let res = match uri {
+            ResourceUri::Quote(symbol) => {
+                self.quote_context
+                    .unsubscribe([symbol], SubFlags::QUOTE)
+                    .await
+            }
+            ResourceUri::Depth(symbol) => {
+                self.quote_context
+                    .unsubscribe([symbol], SubFlags::DEPTH)
+                    .await
+            }
+            ResourceUri::Orders | ResourceUri::Positions => {
+                if watches_orders(&subscriptions) {
+                    Ok(())
+                } else {
+                    self.trade_context.unsubscribe([TopicType::Private]).await
+                }
+            }
+        };
+        if let Err(err) = res {
+            tracing::error!(uri = %uri, error = %err, "failed to unsubscribe the resource");
+        }
+    }
+}
+
+fn watches_orders(subscriptions: &HashMap<ResourceUri, usize>) -> bool {
+    subscriptions.contains_key(&ResourceUri::Orders)
+        || subscriptions.contains_key(&ResourceUri::Positions)
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).expect("serialize resource")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uri() {
+        assert_eq!(
+            ResourceUri::parse("quote://700.hk"),
+            Some(ResourceUri::Quote("700.HK".to_string()))
+        );
+        assert_eq!(
+            ResourceUri::parse("depth://AAPL.US"),
+            Some(ResourceUri::Depth("AAPL.US".to_string()))
+        );
+        assert_eq!(
+            ResourceUri::parse("orders://today"),
+            Some(ResourceUri::Orders)
+        );
+        assert_eq!(
+            ResourceUri::parse("positions://"),
+            Some(ResourceUri::Positions)
+        );
+        assert_eq!(ResourceUri::parse("quote://"), None);
+        assert_eq!(ResourceUri::parse("quote://700.HK/depth"), None);
+        assert_eq!(ResourceUri::parse("orders://history"), None);
+
+        for uri in [
+            "quote://700.HK",
+            "depth://AAPL.US",
+            "orders://today",
+            "positions://",
+        ] {
+            assert_eq!(ResourceUri::parse(uri).unwrap().to_string(), uri);
+        }
+    }
+}
@@ -1,20 +1,89 @@
 use longport::{
-    Decimal, Error, Market, QuoteContext, TradeContext,
-    quote::{AdjustType, Period, TradeSessions},
+    Decimal, Error, QuoteContext, TradeContext,
+    quote::{
+        AdjustType, RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup, SubFlags,
+        WarrantFilter,
+    },
     trade::{
-        GetHistoryOrdersOptions, OrderSide, OrderType, OutsideRTH, SubmitOrderOptions,
-        TimeInForceType,
+        EstimateMaxPurchaseQuantityOptions, GetCashFlowOptions, GetHistoryExecutionsOptions,
+        GetHistoryOrdersOptions, GetTodayExecutionsOptions, ReplaceOrderOptions,
+        SubmitOrderOptions,
     },
 };
 use poem_mcpserver::{
     Tools,
     content::{IntoContent, IntoContents, Json, Text},
 };
+use serde::Serialize;
 use time::{
-    Date, OffsetDateTime, format_description::BorrowedFormatItem, macros::format_description,
+    Date, OffsetDateTime, PrimitiveDateTime, format_description::BorrowedFormatItem,
+    format_description::well_known::Rfc3339, macros::format_description,
+};
+
+use crate::types::{
+    BalanceType, CalcIndex, Market, OrderSide, OrderType, OutsideRTH, Period, SecuritiesUpdateMode,
+    SecurityListCategory, SortOrder, TimeInForce, TradeSessions, WarrantExpiryDate,
+    WarrantPriceType, WarrantSortBy, WarrantStatus, WarrantType,
 };
 
 const DATE_FORMAT: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]");
+const DATETIME_FORMAT: &[BorrowedFormatItem] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+/// The tools that only read the data, the others are disabled in the
+/// read-only mode
+pub(crate) const READ_TOOLS: &[&str] = &[
+    "now",
+    "static_info",
+    "quote",
+    "option_quote",
+    "warrant_quote",
+    "depth",
+    "trades",
+    "intraday",
+    "candlesticks",
+    "history_candlesticks_by_offset",
+    "history_candlesticks_by_date",
+    "trading_session",
+    "trading_days",
+    "broker_queue",
+    "broker_info",
+    "option_chain_list",
+    "option_chain_info",
+    "warrant_issuers",
+    "warrant_list",
+    "warrant_screener",
+    "capital_flow",
+    "capital_distribution",
+    "calc_indexes",
+    "security_list",
+    "current_market_temperature",
+    "history_market_temperature",
+    "watchlist",
+    "subscriptions",
+    "account_balance",
+    "cash_flow",
+    "stock_positions",
+    "fund_positions",
+    "magin_ratio",
+    "estimate_max_purchase_quantity",
+    "order_detail",
+    "today_orders",
+    "history_orders",
+    "today_executions",
+    "history_executions",
+];
+
+/// The tools that change the orders or the watchlist
+#[cfg(test)]
+const WRITE_TOOLS: &[&str] = &[
+    "submit_order",
+    "replace_order",
+    "cancel_order",
+    "create_watchlist_group",
+    "update_watchlist_group",
+    "delete_watchlist_group",
+];
 
 pub(crate) struct Longport {
     quote_context: QuoteContext,
@@ -31,16 +100,20 @@ impl Longport {
     }
 }
 
+/// A subscription of the quote context
+#[derive(Serialize)]
+struct Subscription {
+    symbol: String,
+    sub_types: Vec<&'static str>,
+    candlesticks: Vec<Period>,
+}
+
 /// LongPort OpenAPI SDK.
 #[Tools]
 impl Longport {
     /// Get current time.
     async fn now(&self) -> impl IntoContent {
-        Text(
-            OffsetDateTime::now_utc()
-                .format(&time::format_description::well_known::Rfc3339)
-                .unwrap(),
-        )
+        Text(OffsetDateTime::now_utc().format(&Rfc3339).unwrap())
     }
 
     /// Get basic information of the securities.
@@ -86,6 +159,21 @@ impl Longport {
             .collect::<Vec<_>>())
     }
 
+    /// Get the latest price of HK warrants.
+    async fn warrant_quote(
+        &self,
+        /// A list of warrant symbols. (e.g. ["21125.HK"])
+        symbols: Vec<String>,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .warrant_quote(symbols)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
     /// Get the latest depth of the securities.
     async fn depth(&self, symbol: String) -> Result<impl IntoContents, Error> {
         Ok(Json(self.quote_context.depth(symbol).await?))
@@ -101,106 +189,144 @@ impl Longport {
         Ok(Json(self.quote_context.trades(symbol, count).await?))
     }
 
+    /// Get the intraday lines of the security.
+    async fn intraday(
+        &self,
+        symbol: String,
+        trade_sessions: TradeSessions,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            self.quote_context
+                .intraday(symbol, trade_sessions.into())
+                .await?,
+        ))
+    }
+
     /// Get the latest n candlesticks of the security.
     async fn candlesticks(
         &self,
         symbol: String,
-        /// 1m, 2m, 3m, 5m, 10m, 15m, 20m, 30m, 45m, 60m, 120m, 180m, 240m, day,
-        /// week, month, quarter, year
-        period: String,
+        period: Period,
         /// last n candlesticks (max: 1000)
         count: usize,
         /// whether to adjust the historical data for splits, dividends, etc.
         /// (required)
         forward_adjust: bool,
-        ///  trade sessions (required)
-        /// - intraday: regular trading hours
-        /// - all: all trading hours (intraday, pre, post, overnight)
-        trade_sessions: String,
+        trade_sessions: TradeSessions,
     ) -> Result<impl IntoContents, Error> {
-        let period = match period.as_str() {
-            "1m" => Period::OneMinute,
-            "2m" => Period::TwoMinute,
-            "3m" => Period::ThreeMinute,
-            "5m" => Period::FiveMinute,
-            "10m" => Period::TenMinute,
-            "15m" => Period::FifteenMinute,
-            "20m" => Period::TwentyMinute,
-            "30m" => Period::ThirtyMinute,
-            "45m" => Period::FortyFiveMinute,
-            "60m" => Period::SixtyMinute,
-            "120m" => Period::TwoHour,
-            "180m" => Period::ThreeHour,
-            "240m" => Period::FourHour,
-            "day" => Period::Day,
-            "week" => Period::Week,
-            "month" => Period::Month,
-            "quarter" => Period::Quarter,
-            "year" => Period::Year,
-            _ => {
-                return Err(Error::ParseField {
-                    name: "market",
-                    error: "invalid period".to_string(),
-                });
-            }
-        };
-        let trade_sessions = match trade_sessions.as_str() {
-            "intraday" => TradeSessions::Intraday,
-            "all" => TradeSessions::All,
-            _ => {
-                return Err(Error::ParseField {
-                    name: "market",
-                    error: "invalid trade_sessions".to_string(),
-                });
-            }
-        };
-
         Ok(Json(
             self.quote_context
                 .candlesticks(
                     symbol,
-                    period,
+                    period.into(),
+                    count,
+                    adjust_type(forward_adjust),
+                    trade_sessions.into(),
+                )
+                .await?,
+        ))
+    }
+
+    /// Get the historical candlesticks of the security before or after a
+    /// time.
+    #[allow(clippy::too_many_arguments, clippy::result_large_err)]
+    async fn history_candlesticks_by_offset(
+        &self,
+        symbol: String,
+        period: Period,
+        /// whether to adjust the historical data for splits, dividends, etc.
+        forward_adjust: bool,
+        /// true to get the candlesticks after the time, false to get the
+        /// candlesticks before the time
+        forward: bool,
+        /// format: "yyyy-mm-dd HH:MM:SS", if not provided, default to the
+        /// latest time
+        time: Option<String>,
+        /// number of candlesticks (max: 1000)
+        count: usize,
+        trade_sessions: TradeSessions,
+    ) -> Result<impl IntoContents, Error> {
+        let time = time
+            .map(|time| {
+                PrimitiveDateTime::parse(&time, DATETIME_FORMAT).map_err(|err| Error::ParseField {
+                    name: "time",
+                    error: err.to_string(),
+                })
+            })
+            .transpose()?;
+        Ok(Json(
+            self.quote_context
+                .history_candlesticks_by_offset(
+                    symbol,
+                    period.into(),
+                    adjust_type(forward_adjust),
+                    forward,
+                    time,
                     count,
-                    if forward_adjust {
-                        AdjustType::ForwardAdjust
-                    } else {
-                        AdjustType::NoAdjust
-                    },
-                    trade_sessions,
+                    trade_sessions.into(),
                 )
                 .await?,
         ))
     }
 
+    /// Get the historical candlesticks of the security between the dates.
+    #[allow(clippy::result_large_err)]
+    async fn history_candlesticks_by_date(
+        &self,
+        symbol: String,
+        period: Period,
+        /// whether to adjust the historical data for splits, dividends, etc.
+        forward_adjust: bool,
+        /// format: "yyyy-mm-dd"
+        start: Option<String>,
+        /// format: "yyyy-mm-dd"
+        end: Option<String>,
+        trade_sessions: TradeSessions,
+    ) -> Result<impl IntoContents, Error> {
+        let start = start.map(|start| parse_date("start", &start)).transpose()?;
+        let end = end.map(|end| parse_date("end", &end)).transpose()?;
+        Ok(Json(
+            self.quote_context
+                .history_candlesticks_by_date(
+                    symbol,
+                    period.into(),
+                    adjust_type(forward_adjust),
+                    start,
+                    end,
+                    trade_sessions.into(),
+                )
+                .await?,
+        ))
+    }
+
+    /// Get the trading sessions of the markets.
+    async fn trading_session(&self) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .trading_session()
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
     /// Get the trading days between the specified dates.
     ///
     /// The results include the `start_date` and `end_date`.
     async fn trading_days(
         &self,
-        /// Market code. (e.g. "HK", "US", "CN", "SG")
-        market: String,
+        market: Market,
         /// Start date of the trading days. (Format: "yyyy-mm-dd")
         start_date: String,
         /// End date of the trading days. (Format: "yyyy-mm-dd")
         end_date: String,
     ) -> Result<impl IntoContents, Error> {
-        let market = market.parse::<Market>().map_err(|err| Error::ParseField {
-            name: "market",
-            error: err.to_string(),
-        })?;
-        let start_date =
-            Date::parse(&start_date, DATE_FORMAT).map_err(|err| Error::ParseField {
-                name: "start_date",
-                error: err.to_string(),
-            })?;
-        let end_date = Date::parse(&end_date, DATE_FORMAT).map_err(|err| Error::ParseField {
-            name: "end_date",
-            error: err.to_string(),
-        })?;
+        let start_date = parse_date("start_date", &start_date)?;
+        let end_date = parse_date("end_date", &end_date)?;
 
         Ok(Json(
             self.quote_context
-                .trading_days(market, start_date, end_date)
+                .trading_days(market.into(), start_date, end_date)
                 .await?,
         ))
     }
@@ -222,12 +348,7 @@ impl Longport {
             .option_chain_expiry_date_list(symbol)
             .await?
             .into_iter()
-            .map(|date| {
-                Text(
-                    date.format(format_description!("[year]-[month]-[day]"))
-                        .unwrap(),
-                )
-            })
+            .map(|date| Text(date.format(DATE_FORMAT).unwrap()))
             .collect::<Vec<_>>())
     }
 
@@ -238,11 +359,7 @@ impl Longport {
         /// format: "yyyy-mm-dd"
         expiry_date: String,
     ) -> Result<impl IntoContents, Error> {
-        let expiry_date = Date::parse(&expiry_date, format_description!("[year]-[month]-[day]"))
-            .map_err(|err| Error::ParseField {
-                name: "expiry_date",
-                error: err.to_string(),
-            })?;
+        let expiry_date = parse_date("expiry_date", &expiry_date)?;
         Ok(Json(
             self.quote_context
                 .option_chain_info_by_date(symbol, expiry_date)
@@ -250,6 +367,87 @@ impl Longport {
         ))
     }
 
+    /// Returns the HK warrant issuers.
+    async fn warrant_issuers(&self) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .warrant_issuers()
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Returns the HK warrants of the underlying security matching the
+    /// filter.
+    #[allow(clippy::too_many_arguments)]
+    async fn warrant_list(
+        &self,
+        /// Underlying security symbol. (e.g. "700.HK")
+        symbol: String,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrder,
+        warrant_type: Option<Vec<WarrantType>>,
+        /// Issuer IDs
+        issuer: Option<Vec<i32>>,
+        expiry_date: Option<Vec<WarrantExpiryDate>>,
+        price_type: Option<Vec<WarrantPriceType>>,
+        status: Option<Vec<WarrantStatus>>,
+    ) -> Result<impl IntoContents, Error> {
+        let warrant_type = warrant_type.map(convert_values);
+        let expiry_date = expiry_date.map(convert_values);
+        let price_type = price_type.map(convert_values);
+        let status = status.map(convert_values);
+        Ok(self
+            .quote_context
+            .warrant_list(
+                symbol,
+                sort_by.into(),
+                sort_order.into(),
+                warrant_type.as_deref(),
+                issuer.as_deref(),
+                expiry_date.as_deref(),
+                price_type.as_deref(),
+                status.as_deref(),
+            )
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Returns a page of the HK warrants of the underlying security matching
+    /// the filter, and the total number of the matching warrants.
+    #[allow(clippy::too_many_arguments)]
+    async fn warrant_screener(
+        &self,
+        /// Underlying security symbol. (e.g. "700.HK")
+        symbol: String,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrder,
+        /// Offset of the first warrant
+        offset: i32,
+        /// Number of warrants (max: 500)
+        count: i32,
+        warrant_type: Option<Vec<WarrantType>>,
+        /// Issuer IDs
+        issuer: Option<Vec<i32>>,
+        expiry_date: Option<Vec<WarrantExpiryDate>>,
+        price_type: Option<Vec<WarrantPriceType>>,
+        status: Option<Vec<WarrantStatus>>,
+    ) -> Result<impl IntoContents, Error> {
+        let filter = WarrantFilter::new(symbol, sort_by.into(), sort_order.into())
+            .offset(offset)
+            .count(count)
+            .warrant_type(convert_values(warrant_type.unwrap_or_default()))
+            .issuer(issuer.unwrap_or_default())
+            .expiry_date(convert_values(expiry_date.unwrap_or_default()))
+            .price_type(convert_values(price_type.unwrap_or_default()))
+            .status(convert_values(status.unwrap_or_default()));
+
+        Ok(Json(self.quote_context.warrant_screener(filter).await?))
+    }
+
     // Returns the capital flow of the security.
     async fn capital_flow(&self, symbol: String) -> Result<impl IntoContents, Error> {
         Ok(Json(self.quote_context.capital_flow(symbol).await?))
@@ -260,17 +458,41 @@ impl Longport {
         Ok(Json(self.quote_context.capital_distribution(symbol).await?))
     }
 
-    /// Returns the market temperature of the specified market.
-    async fn current_market_temperature(
+    /// Returns the calculated indexes of the securities.
+    async fn calc_indexes(
         &self,
-        /// Market code. (e.g. "HK", "US", "CN", "SG")
-        market: String,
+        symbols: Vec<String>,
+        indexes: Vec<CalcIndex>,
     ) -> Result<impl IntoContents, Error> {
-        let market = market.parse::<Market>().map_err(|err| Error::ParseField {
-            name: "market",
-            error: err.to_string(),
-        })?;
-        Ok(Json(self.quote_context.market_temperature(market).await?))
+        Ok(self
+            .quote_context
+            .calc_indexes(symbols, convert_values(indexes))
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Returns the securities of the market in the category.
+    async fn security_list(
+        &self,
+        market: Market,
+        category: Option<SecurityListCategory>,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .security_list(market.into(), category.map(Into::into))
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Returns the market temperature of the specified market.
+    async fn current_market_temperature(&self, market: Market) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            self.quote_context.market_temperature(market.into()).await?,
+        ))
     }
 
     /// Returns the historical market temperature of the specified market.
@@ -278,32 +500,112 @@ impl Longport {
     /// includes the `start` and `end` dates.
     async fn history_market_temperature(
         &self,
-        /// Market code. (e.g. "HK", "US", "CN", "SG")
-        market: String,
+        market: Market,
         /// format: "yyyy-mm-dd"
         start: String,
         /// format: "yyyy-mm-dd"
         end: String,
     ) -> Result<impl IntoContents, Error> {
-        let market = market.parse::<Market>().map_err(|err| Error::ParseField {
-            name: "market",
-            error: err.to_string(),
-        })?;
-        let start = Date::parse(&start, DATE_FORMAT).map_err(|err| Error::ParseField {
-            name: "start",
-            error: err.to_string(),
-        })?;
-        let end = Date::parse(&end, DATE_FORMAT).map_err(|err| Error::ParseField {
-            name: "end",
-            error: err.to_string(),
-        })?;
+        let start = parse_date("start", &start)?;
+        let end = parse_date("end", &end)?;
         Ok(Json(
             self.quote_context
-                .history_market_temperature(market, start, end)
+                .history_market_temperature(market.into(), start, end)
                 .await?,
         ))
     }
 
+    /// Returns the watchlist groups and their securities.
+    async fn watchlist(&self) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .watchlist()
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Create a watchlist group, returns the id of the group.
+    async fn create_watchlist_group(
+        &self,
+        name: String,
+        /// A list of security symbols. (e.g. ["700.HK", "AAPL.US"])
+        securities: Option<Vec<String>>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut req = RequestCreateWatchlistGroup::new(name);
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        Ok(Json(self.quote_context.create_watchlist_group(req).await?))
+    }
+
+    /// Update the name or the securities of a watchlist group.
+    async fn update_watchlist_group(
+        &self,
+        id: i64,
+        name: Option<String>,
+        /// A list of security symbols. (e.g. ["700.HK", "AAPL.US"])
+        securities: Option<Vec<String>>,
+        /// how to update the securities (default: replace)
+        mode: Option<SecuritiesUpdateMode>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut req = RequestUpdateWatchlistGroup::new(id);
+        if let Some(name) = name {
+            req = req.name(name);
+        }
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        if let Some(mode) = mode {
+            req = req.mode(mode.into());
+        }
+        Ok(Json(self.quote_context.update_watchlist_group(req).await?))
+    }
+
+    /// Delete a watchlist group.
+    async fn delete_watchlist_group(
+        &self,
+        id: i64,
+        /// whether to remove the securities of the group from all the other
+        /// groups
+        purge: bool,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            self.quote_context.delete_watchlist_group(id, purge).await?,
+        ))
+    }
+
+    /// Returns the subscriptions of the quote context.
+    async fn subscriptions(&self) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .subscriptions()
+            .await?
+            .into_iter()
+            .map(|subscription| {
+                Json(Subscription {
+                    symbol: subscription.symbol,
+                    sub_types: [
+                        (SubFlags::QUOTE, "quote"),
+                        (SubFlags::DEPTH, "depth"),
+                        (SubFlags::BROKER, "broker"),
+                        (SubFlags::TRADE, "trade"),
+                    ]
+                    .into_iter()
+                    .filter(|(flag, _)| subscription.sub_types.contains(*flag))
+                    .map(|(_, name)| name)
+                    .collect(),
+                    candlesticks: subscription
+                        .candlesticks
+                        .into_iter()
+                        .filter_map(Period::from_sdk)
+                        .collect(),
+                })
+            })
+            .collect::<Vec<_>>())
+    }
+
     /// Get the account balance.
     async fn account_balance(&self) -> Result<impl IntoContents, Error> {
         Ok(self
@@ -315,6 +617,45 @@ impl Longport {
             .collect::<Vec<_>>())
     }
 
+    /// Get the cash flow of the account.
+    async fn cash_flow(
+        &self,
+        /// format: RFC3339
+        start_at: String,
+        /// format: RFC3339
+        end_at: String,
+        business_type: Option<BalanceType>,
+        symbol: Option<String>,
+        /// page number (default: 1)
+        page: Option<usize>,
+        /// page size (default: 50)
+        size: Option<usize>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetCashFlowOptions::new(
+            parse_datetime("start_at", &start_at)?,
+            parse_datetime("end_at", &end_at)?,
+        );
+        if let Some(business_type) = business_type {
+            opts = opts.business_type(business_type.into());
+        }
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(page) = page {
+            opts = opts.page(page);
+        }
+        if let Some(size) = size {
+            opts = opts.size(size);
+        }
+        Ok(self
+            .trade_context
+            .cash_flow(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
     /// Returns the stock positions.
     async fn stock_positions(&self) -> Result<impl IntoContents, Error> {
         Ok(self
@@ -345,24 +686,49 @@ impl Longport {
         Ok(Json(self.trade_context.margin_ratio(symbol).await?))
     }
 
+    /// Estimate the maximum purchase quantity for cash and margin.
+    #[allow(clippy::too_many_arguments)]
+    async fn estimate_max_purchase_quantity(
+        &self,
+        symbol: String,
+        order_type: OrderType,
+        side: OrderSide,
+        /// estimated order price, required for the limit orders
+        price: Option<Decimal>,
+        /// settlement currency
+        currency: Option<String>,
+        /// the id of the order to replace
+        order_id: Option<String>,
+        /// whether to get the fractional shares quantity
+        fractional_shares: Option<bool>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts =
+            EstimateMaxPurchaseQuantityOptions::new(symbol, order_type.into(), side.into());
+        if let Some(price) = price {
+            opts = opts.price(price);
+        }
+        if let Some(currency) = currency {
+            opts = opts.currency(currency);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+        if fractional_shares == Some(true) {
+            opts = opts.fractional_shares();
+        }
+        Ok(Json(
+            self.trade_context
+                .estimate_max_purchase_quantity(opts)
+                .await?,
+        ))
+    }
+
     /// Submit an order.
     #[allow(clippy::too_many_arguments)]
     async fn submit_order(
         &self,
         symbol: String,
-        /// Order type
-        /// LO:	Limit Order
-        /// ELO: Enhanced Limit Order
-        /// MO: Market Order
-        /// AO: At-auction Order
-        /// ALO: At-auction Limit Order
-        /// ODD: Odd Lots Order
-        /// LIT: Limit If Touched
-        /// MIT: Market If Touched
-        /// TSLPAMT: Trailing Limit If Touched (Trailing Amount)
-        /// TSLPPCT: Trailing Limit If Touched (Trailing Percent)
-        /// SLO: Special Limit Order. Not Support Replace Order.
-        order_type: String,
+        order_type: OrderType,
         /// for LO, ELO, ALO, ODD, LIT
         submitted_price: Option<Decimal>,
         submitted_quantity: Decimal,
@@ -376,36 +742,16 @@ impl Longport {
         trailing_percent: Option<Decimal>,
         /// format: "yyyy-mm-dd"
         expire_date: Option<String>,
-        /// Side of the order (Buy or Sell)
-        side: String,
-        /// - RTH_ONLY: regular trading hour only
-        /// - ANY_TIME: any time
-        /// - OVERNIGHT: overnight
-        outside_rth: Option<String>,
-        /// - Day: Day Order
-        /// - GTC: Good Till Cancel
-        /// - GTD: Good Till Date
-        time_in_force: String,
+        side: OrderSide,
+        outside_rth: Option<OutsideRTH>,
+        time_in_force: TimeInForce,
     ) -> Result<impl IntoContents, Error> {
         let mut opts = SubmitOrderOptions::new(
             symbol,
-            order_type
-                .parse::<OrderType>()
-                .map_err(|err| Error::ParseField {
-                    name: "order_type",
-                    error: err.to_string(),
-                })?,
-            side.parse::<OrderSide>().map_err(|err| Error::ParseField {
-                name: "side",
-                error: err.to_string(),
-            })?,
+            order_type.into(),
+            side.into(),
             submitted_quantity,
-            time_in_force
-                .parse::<TimeInForceType>()
-                .map_err(|err| Error::ParseField {
-                    name: "time_in_force",
-                    error: err.to_string(),
-                })?,
+            time_in_force.into(),
         );
 
         if let Some(submitted_price) = submitted_price {
@@ -423,30 +769,56 @@ impl Longport {
         if let Some(trailing_percent) = trailing_percent {
             opts = opts.trailing_percent(trailing_percent);
         }
-
         if let Some(expire_date) = expire_date {
-            opts = opts.expire_date(
-                Date::parse(&expire_date, format_description!("[year]-[month]-[day]")).map_err(
-                    |err| Error::ParseField {
-                        name: "expire_date",
-                        error: err.to_string(),
-                    },
-                )?,
-            );
+            opts = opts.expire_date(parse_date("expire_date", &expire_date)?);
         }
-
         if let Some(outside_rth) = outside_rth {
-            opts = opts.outside_rth(outside_rth.parse::<OutsideRTH>().map_err(|err| {
-                Error::ParseField {
-                    name: "outside_rth",
-                    error: err.to_string(),
-                }
-            })?);
+            opts = opts.outside_rth(outside_rth.into());
         }
 
         self.trade_context.submit_order(opts).await.map(Json)
     }
 
+    /// Replace the quantity or the prices of an order.
+    #[allow(clippy::too_many_arguments)]
+    async fn replace_order(
+        &self,
+        order_id: String,
+        quantity: Decimal,
+        /// for LO, ELO, ALO, ODD, LIT
+        price: Option<Decimal>,
+        /// for LIT, MIT
+        trigger_price: Option<Decimal>,
+        /// for TSLPAMT, TSLPPCT
+        limit_offset: Option<Decimal>,
+        /// for TSLPAMT
+        trailing_amount: Option<Decimal>,
+        /// for TSLPPCT (0-1)
+        trailing_percent: Option<Decimal>,
+        remark: Option<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = ReplaceOrderOptions::new(order_id, quantity);
+        if let Some(price) = price {
+            opts = opts.price(price);
+        }
+        if let Some(trigger_price) = trigger_price {
+            opts = opts.trigger_price(trigger_price);
+        }
+        if let Some(limit_offset) = limit_offset {
+            opts = opts.limit_offset(limit_offset);
+        }
+        if let Some(trailing_amount) = trailing_amount {
+            opts = opts.trailing_amount(trailing_amount);
+        }
+        if let Some(trailing_percent) = trailing_percent {
+            opts = opts.trailing_percent(trailing_percent);
+        }
+        if let Some(remark) = remark {
+            opts = opts.remark(remark);
+        }
+        Ok(Json(self.trade_context.replace_order(opts).await?))
+    }
+
     async fn cancel_order(&self, order_id: String) -> Result<impl IntoContents, Error> {
         Ok(Json(self.trade_context.cancel_order(order_id).await?))
     }
@@ -480,20 +852,8 @@ impl Longport {
         end_at: String,
     ) -> Result<impl IntoContents, Error> {
         let mut opts = GetHistoryOrdersOptions::new()
-            .start_at(
-                OffsetDateTime::parse(&start_at, &time::format_description::well_known::Rfc3339)
-                    .map_err(|err| Error::ParseField {
-                        name: "start_at",
-                        error: err.to_string(),
-                    })?,
-            )
-            .end_at(
-                OffsetDateTime::parse(&end_at, &time::format_description::well_known::Rfc3339)
-                    .map_err(|err| Error::ParseField {
-                        name: "end_at",
-                        error: err.to_string(),
-                    })?,
-            );
+            .start_at(parse_datetime("start_at", &start_at)?)
+            .end_at(parse_datetime("end_at", &end_at)?);
 
         if let Some(symbol) = symbol {
             opts = opts.symbol(symbol);
@@ -507,4 +867,118 @@ impl Longport {
             .map(Json)
             .collect::<Vec<_>>())
     }
+
+    /// Get the current account's executions for the day.
+    async fn today_executions(
+        &self,
+        /// if not provided, default to all symbols
+        symbol: Option<String>,
+        /// if not provided, default to all orders
+        order_id: Option<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetTodayExecutionsOptions::new();
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+        Ok(self
+            .trade_context
+            .today_executions(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the historical executions of the current account.
+    ///
+    /// does not include today's executions
+    async fn history_executions(
+        &self,
+        /// if not provided, default to all symbols
+        symbol: Option<String>,
+        /// format: RFC3339
+        start_at: String,
+        /// format: RFC3339
+        end_at: String,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetHistoryExecutionsOptions::new()
+            .start_at(parse_datetime("start_at", &start_at)?)
+            .end_at(parse_datetime("end_at", &end_at)?);
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        Ok(self
+            .trade_context
+            .history_executions(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+}
+
+fn adjust_type(forward_adjust: bool) -> AdjustType {
+    if forward_adjust {
+        AdjustType::ForwardAdjust
+    } else {
+        AdjustType::NoAdjust
+    }
+}
+
+fn convert_values<T: Into<U>, U>(values: Vec<T>) -> Vec<U> {
+    values.into_iter().map(Into::into).collect()
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_date(name: &'static str, value: &str) -> Result<Date, Error> {
+    Date::parse(value, DATE_FORMAT).map_err(|err| Error::ParseField {
+        name,
+        error: err.to_string(),
+    })
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_datetime(name: &'static str, value: &str) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::parse(value, &Rfc3339).map_err(|err| Error::ParseField {
+        name,
+        error: err.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use poem_mcpserver::tool::Tools;
+
+    use super::*;
+
+    #[test]
+    fn classify_tools() {
+        for tool in Longport::list() {
+            assert!(
+                READ_TOOLS.contains(&tool.name) != WRITE_TOOLS.contains(&tool.name),
+                "tool `{}` must be either a read or a write tool",
+                tool.name
+            );
+        }
+    }
+
+    #[test]
+    fn enum_schemas() {
+        let tool = Longport::list()
+            .into_iter()
+            .find(|tool| tool.name == "candlesticks")
+            .unwrap();
+        let period = &tool.input_schema["$defs"]["Period"];
+        let values = period["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value["const"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert!(values.contains(&"1m"));
+        assert!(values.contains(&"day"));
+    }
 }
@@ -0,0 +1,358 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use futures_util::{Stream, stream};
+use poem_mcpserver::{
+    McpServer,
+    protocol::{
+        JSON_RPC_VERSION,
+        rpc::{Request, RpcError},
+    },
+};
+use serde_json::{Value, json};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    resources::{ResourceUri, Resources},
+    server::Longport,
+};
+
+/// A session of a client
+///
+/// The `resources/*` requests are handled by the [`Resources`] and the other
+/// requests are forwarded to the tools server.
+pub(crate) struct Session {
+    server: McpServer<Longport>,
+    resources: Arc<Resources>,
+    subscribed: Arc<Mutex<HashSet<ResourceUri>>>,
+}
+
+impl Session {
+    pub(crate) fn new(server: McpServer<Longport>, resources: Arc<Resources>) -> Self {
+        Self {
+            server,
+            resources,
+            subscribed: Default::default(),
+        }
+    }
+
+    /// Handles a request or a batch of requests, returns `None` if there is
+    /// nothing to respond
+    pub(crate) async fn handle_message(&mut self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(requests) => {
+                let mut responses = Vec::new();
+                for request in requests {
+                    responses.extend(self.handle_request(request).await);
+                }
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            request => self.handle_request(request).await,
+        }
+    }
+
+    async fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        if request.get("jsonrpc").and_then(Value::as_str) != Some(JSON_RPC_VERSION) {
+            return Some(error_response(
+                id,
+                RpcError::invalid_request("invalid JSON-RPC version, expected `2.0`"),
+            ));
+        }
+
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let uri = || {
+            let uri = request
+                .get("params")
+                .and_then(|params| params.get("uri"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            ResourceUri::parse(uri)
+                .ok_or_else(|| RpcError::invalid_params(format!("unknown resource `{uri}`")))
+        };
+
+        let res = match method.as_str() {
+            "resources/list" => Ok(Resources::list()),
+            "resources/templates/list" => Ok(Resources::templates()),
+            "resources/read" => match uri() {
+                Ok(uri) => self.resources.read(&uri).await,
+                Err(err) => Err(err),
+            },
+            "resources/subscribe" => match uri() {
+                Ok(uri) => self.subscribe(uri).await.map(|_| json!({})),
+                Err(err) => Err(err),
+            },
+            "resources/unsubscribe" => match uri() {
+                Ok(uri) => {
+                    self.unsubscribe(uri).await;
+                    Ok(json!({}))
+                }
+                Err(err) => Err(err),
+            },
+            "initialize" => return self.forward(request, true).await,
+            _ => return self.forward(request, false).await,
+        };
+
+        let id = id?;
+        Some(match res {
+            Ok(result) => json!({
+                "jsonrpc": JSON_RPC_VERSION,
+                "id": id,
+                "result": result,
+            }),
+            Err(err) => error_response(Some(id), err),
+        })
+    }
+
+    async fn forward(&mut self, request: Value, is_initialize: bool) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) => request,
+            Err(err) => {
+                tracing::error!(error = %err, "failed to parse request");
+                return id.map(|id| {
+                    error_response(Some(id), RpcError::invalid_request(err.to_string()))
+                });
+            }
+        };
+        let mut response = serde_json::to_value(self.server.handle_request(request).await?).ok()?;
+
+        // the tools server has no resources to subscribe, they are served here
+        if is_initialize
+            && let Some(resources) = response.pointer_mut("/result/capabilities/resources")
+        {
+            resources["subscribe"] = Value::Bool(true);
+        }
+        Some(response)
+    }
+
+    async fn subscribe(&mut self, uri: ResourceUri) -> Result<(), RpcError> {
+        if self.subscribed.lock().unwrap().contains(&uri) {
+            return Ok(());
+        }
+        self.resources.acquire(&uri).await?;
+        self.subscribed.lock().unwrap().insert(uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, uri: ResourceUri) {
+        let removed = self.subscribed.lock().unwrap().remove(&uri);
+        if removed {
+            self.resources.release(&uri).await;
+        }
+    }
+
+    /// Returns the `notifications/resources/updated` notifications of the
+    /// resources subscribed by the session
+    pub(crate) fn notifications(&self) -> impl Stream<Item = Value> + Send + 'static {
+        stream::unfold(
+            (self.resources.updates(), self.subscribed.clone()),
+            |(mut updates, subscribed)| async move {
+                loop {
+                    match updates.recv().await {
+                        Ok(uri) if subscribed.lock().unwrap().contains(&uri) => {
+                            let notification = json!({
+                                "jsonrpc": JSON_RPC_VERSION,
+                                "method": "notifications/resources/updated",
+                                "params": { "uri": uri.to_string() },
+                            });
+                            return Some((notification, (updates, subscribed)));
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(count)) => {
+                            tracing::warn!(count, "resource updates lagged");
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        )
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let subscribed = std::mem::take(&mut *self.subscribed.lock().unwrap());
+        if subscribed.is_empty() {
+            return;
+        }
+        let resources = self.resources.clone();
+        tokio::spawn(async move {
+            for uri in subscribed {
+                resources.release(&uri).await;
+            }
+        });
+    }
+}
+
+fn error_response(id: Option<Value>, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": JSON_RPC_VERSION,
+        "id": id,
+        "error": err,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{pin::pin, sync::Arc, time::Duration};
+
+    use futures_util::StreamExt;
+    use longport::{
+        Config, Decimal, QuoteContext, TradeContext,
+        trade::{OrderSide, OrderType, SubmitOrderOptions, TimeInForceType},
+    };
+    use longport_mock::MockServer;
+    use longport_proto::quote::{PushQuote, SubType};
+    use tokio::time::timeout;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    async fn request(session: &mut Session, method: &str, params: Value) -> Value {
+        session
+            .handle_message(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .await
+            .unwrap()
+    }
+
+    /// Creates a session connected to the mock server, returns the session
+    /// and its trade context
+    pub(crate) async fn create_session(server: &MockServer) -> (Session, TradeContext) {
+        let config = Arc::new(
+            Config::new("app_key", "app_secret", "access_token")
+                .http_url(server.http_url())
+                .quote_ws_url(server.quote_ws_url())
+                .trade_ws_url(server.trade_ws_url())
+                .dont_print_quote_packages(),
+        );
+        let (quote_context, quote_receiver) = QuoteContext::try_new(config.clone()).await.unwrap();
+        let (trade_context, trade_receiver) = TradeContext::try_new(config).await.unwrap();
+        let resources = Resources::new(
+            quote_context.clone(),
+            trade_context.clone(),
+            quote_receiver,
+            trade_receiver,
+        );
+        let session = Session::new(
+            McpServer::new().tools(Longport::new(quote_context, trade_context.clone())),
+            resources,
+        );
+        (session, trade_context)
+    }
+
+    #[tokio::test]
+    async fn resource_subscriptions() {
+        let server = MockServer::start().await.unwrap();
+        let (mut session, trade_context) = create_session(&server).await;
+        let mut notifications = pin!(session.notifications());
+
+        let resp = request(
+            &mut session,
+            "initialize",
+            json!({
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0.0" },
+            }),
+        )
+        .await;
+        assert_eq!(
+            resp["result"]["capabilities"]["resources"]["subscribe"],
+            true
+        );
+
+        let resp = request(&mut session, "resources/templates/list", json!({})).await;
+        assert_eq!(
+            resp["result"]["resourceTemplates"][0]["uriTemplate"],
+            "quote://{symbol}"
+        );
+
+        let resp = request(
+            &mut session,
+            "resources/subscribe",
+            json!({ "uri": "quote://700.hk" }),
+        )
+        .await;
+        assert_eq!(resp["result"], json!({}));
+        assert_eq!(
+            server.subscriptions().get("700.HK"),
+            Some(&vec![SubType::Quote])
+        );
+
+        server.push_quote(PushQuote {
+            symbol: "700.HK".to_string(),
+            sequence: 1,
+            last_done: "380.5".to_string(),
+            timestamp: 1700000000,
+            ..Default::default()
+        });
+        let notification = timeout(TIMEOUT, notifications.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "quote://700.HK");
+
+        request(
+            &mut session,
+            "resources/subscribe",
+            json!({ "uri": "orders://today" }),
+        )
+        .await;
+        trade_context
+            .submit_order(SubmitOrderOptions::new(
+                "700.HK",
+                OrderType::LO,
+                OrderSide::Buy,
+                Decimal::from(100),
+                TimeInForceType::Day,
+            ))
+            .await
+            .unwrap();
+        let notification = timeout(TIMEOUT, notifications.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification["params"]["uri"], "orders://today");
+
+        let resp = request(
+            &mut session,
+            "resources/read",
+            json!({ "uri": "orders://today" }),
+        )
+        .await;
+        let contents = &resp["result"]["contents"][0];
+        assert_eq!(contents["uri"], "orders://today");
+        let orders: Value = serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+        assert_eq!(orders.as_array().unwrap().len(), 1);
+
+        let resp = request(
+            &mut session,
+            "resources/read",
+            json!({ "uri": "orders://history" }),
+        )
+        .await;
+        assert_eq!(resp["error"]["code"], -32602);
+
+        request(
+            &mut session,
+            "resources/unsubscribe",
+            json!({ "uri": "quote://700.HK" }),
+        )
+        .await;
+        assert!(server.subscriptions().is_empty());
+    }
+}
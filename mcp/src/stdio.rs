@@ -0,0 +1,54 @@
+//! Standard input and output transport
+//!
+//! Adapted from `poem_mcpserver::stdio` (identical in 0.2.5 and 0.2.9), which
+//! can only write the responses. Keep it in sync with it when upgrading
+//! `poem-mcpserver`; the difference is that the notifications of the
+//! subscribed resources are written between the responses.
+
+use std::pin::pin;
+
+use futures_util::StreamExt;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::session::Session;
+
+fn print_message(message: &Value) {
+    println!("{}", serde_json::to_string(message).unwrap());
+}
+
+/// Runs the session with the standard input and output, the notifications of
+/// the subscribed resources are written between the responses
+pub(crate) async fn stdio(mut session: Session) -> std::io::Result<()> {
+    let mut input = BufReader::new(tokio::io::stdin()).lines();
+    let mut notifications = pin!(session.notifications());
+
+    tracing::info!("stdio server started");
+
+    loop {
+        tokio::select! {
+            line = input.next_line() => {
+                let Some(line) = line? else {
+                    break;
+                };
+                tracing::info!(request = &line, "received request");
+
+                let Ok(message) = serde_json::from_str::<Value>(&line).inspect_err(|err| {
+                    tracing::error!(error = ?err, "failed to parse request");
+                }) else {
+                    continue;
+                };
+
+                if let Some(resp) = session.handle_message(message).await {
+                    tracing::info!(response = ?resp, "sending response");
+                    print_message(&resp);
+                }
+            }
+            Some(notification) = notifications.next() => {
+                print_message(&notification);
+            }
+        }
+    }
+
+    Ok(())
+}
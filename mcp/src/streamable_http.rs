@@ -0,0 +1,258 @@
+//! Streamable-HTTP transport
+//!
+//! Adapted from `poem_mcpserver::streamable_http` (identical in 0.2.5 and
+//! 0.2.9), which has no way to send server notifications. Keep the session
+//! handling in sync with it when upgrading `poem-mcpserver`; the differences
+//! are the [`Session`] wrapping the tools server and the `GET` stream of the
+//! resource notifications.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use poem::{
+    EndpointExt, IntoEndpoint, IntoResponse, Request, handler,
+    http::{HeaderMap, StatusCode},
+    post,
+    web::{
+        Accept, Data, Json,
+        sse::{Event, SSE},
+    },
+};
+use serde_json::Value;
+use tokio::time::Instant;
+
+use crate::session::Session;
+
+const SESSION_TIMEOUT: Duration = Duration::from_secs(60 * 5);
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+type SessionFactoryFn = Box<dyn Fn() -> Session + Send + Sync>;
+
+struct SessionState {
+    session: Arc<tokio::sync::Mutex<Session>>,
+    last_active: Instant,
+    /// Number of the open notification streams, the session is not reaped
+    /// while it is not zero
+    streams: usize,
+}
+
+struct State {
+    session_factory: SessionFactoryFn,
+    sessions: Mutex<HashMap<String, SessionState>>,
+}
+
+impl State {
+    fn session(&self, headers: &HeaderMap) -> Result<Arc<tokio::sync::Mutex<Session>>, StatusCode> {
+        let session_id = session_id_header(headers).ok_or(StatusCode::BAD_REQUEST)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let state = sessions.get_mut(session_id).ok_or(StatusCode::NOT_FOUND)?;
+        state.last_active = Instant::now();
+        Ok(state.session.clone())
+    }
+
+    /// Removes the sessions inactive for [`SESSION_TIMEOUT`] without an open
+    /// notification stream
+    fn reap(&self, now: Instant) {
+        self.sessions.lock().unwrap().retain(|_, session| {
+            session.streams > 0
+                || now.saturating_duration_since(session.last_active) < SESSION_TIMEOUT
+        });
+    }
+
+    /// Marks a notification stream of the session as open until the returned
+    /// guard is dropped
+    fn open_stream(self: &Arc<Self>, session_id: &str) -> Option<StreamGuard> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get_mut(session_id)?;
+        session.streams += 1;
+        session.last_active = Instant::now();
+        Some(StreamGuard {
+            state: self.clone(),
+            session_id: session_id.to_string(),
+        })
+    }
+}
+
+/// Keeps the session alive while its notification stream is open
+struct StreamGuard {
+    state: Arc<State>,
+    session_id: String,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        let mut sessions = self.state.sessions.lock().unwrap();
+        if let Some(session) = sessions.get_mut(&self.session_id) {
+            session.streams -= 1;
+            session.last_active = Instant::now();
+        }
+    }
+}
+
+fn session_id_header(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Mcp-Session-Id")
+        .and_then(|value| value.to_str().ok())
+}
+
+fn is_initialize(message: &Value) -> bool {
+    message.get("method").and_then(Value::as_str) == Some("initialize")
+}
+
+#[handler]
+async fn post_handler(
+    data: Data<&Arc<State>>,
+    request: &Request,
+    message: Json<Value>,
+    accept: Accept,
+) -> poem::Response {
+    let Some(accept) = accept.0.first() else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    if is_initialize(&message.0) && !request.headers().contains_key("Mcp-Session-Id") {
+        let session_id = session_id();
+        let mut session = (data.0.session_factory)();
+        let resp = session.handle_message(message.0).await;
+        data.0.sessions.lock().unwrap().insert(
+            session_id.clone(),
+            SessionState {
+                session: Arc::new(tokio::sync::Mutex::new(session)),
+                last_active: Instant::now(),
+                streams: 0,
+            },
+        );
+
+        tracing::info!(session_id = session_id, "created new session");
+        return Json(resp)
+            .with_header("Mcp-Session-Id", session_id)
+            .into_response();
+    }
+
+    let session = match data.0.session(request.headers()) {
+        Ok(session) => session,
+        Err(status) => return status.into_response(),
+    };
+    tracing::info!(request = ?message.0, "received request");
+    let Some(resp) = session.lock().await.handle_message(message.0).await else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+    tracing::info!(response = ?resp, "sending response");
+
+    match accept.essence_str() {
+        "application/json" => Json(resp).into_response(),
+        "text/event-stream" => SSE::new(futures_util::stream::once(async move {
+            Event::message(resp.to_string()).event_type("message")
+        }))
+        .into_response(),
+        _ => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+/// Opens the stream of the notifications of the resources subscribed by the
+/// session, the session is kept alive while the stream is open
+#[handler]
+async fn get_handler(data: Data<&Arc<State>>, headers: &HeaderMap) -> poem::Response {
+    let session = match data.0.session(headers) {
+        Ok(session) => session,
+        Err(status) => return status.into_response(),
+    };
+    let Some(guard) = session_id_header(headers).and_then(|id| data.0.open_stream(id)) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let notifications = session.lock().await.notifications();
+    SSE::new(notifications.map(move |notification| {
+        let _guard = &guard;
+        Event::message(notification.to_string()).event_type("message")
+    }))
+    .keep_alive(KEEP_ALIVE)
+    .into_response()
+}
+
+#[handler]
+async fn delete_handler(data: Data<&Arc<State>>, headers: &HeaderMap) -> StatusCode {
+    let Some(session_id) = session_id_header(headers) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    if data.sessions.lock().unwrap().remove(session_id).is_none() {
+        return StatusCode::NOT_FOUND;
+    }
+
+    tracing::info!(session_id = session_id, "deleted session");
+    StatusCode::ACCEPTED
+}
+
+/// A Streamable-HTTP endpoint, the notifications of the subscribed resources
+/// are sent with the stream opened by a `GET` request
+pub(crate) fn endpoint<F>(session_factory: F) -> impl IntoEndpoint
+where
+    F: Fn() -> Session + Send + Sync + 'static,
+{
+    let state = Arc::new(State {
+        session_factory: Box::new(session_factory),
+        sessions: Default::default(),
+    });
+
+    tokio::spawn({
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                state.reap(interval.tick().await);
+            }
+        }
+    });
+
+    post(post_handler)
+        .get(get_handler)
+        .delete(delete_handler)
+        .data(state)
+}
+
+fn session_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use longport_mock::MockServer;
+
+    use super::*;
+    use crate::session::tests::create_session;
+
+    #[tokio::test]
+    async fn keep_session_with_open_stream() {
+        let server = MockServer::start().await.unwrap();
+        let (session, _) = create_session(&server).await;
+        let state = Arc::new(State {
+            session_factory: Box::new(|| unreachable!()),
+            sessions: Default::default(),
+        });
+        state.sessions.lock().unwrap().insert(
+            "session".to_string(),
+            SessionState {
+                session: Arc::new(tokio::sync::Mutex::new(session)),
+                last_active: Instant::now(),
+                streams: 0,
+            },
+        );
+        let expired = || Instant::now() + SESSION_TIMEOUT * 2;
+
+        let guard = state.open_stream("session").unwrap();
+        state.reap(expired());
+        assert!(state.sessions.lock().unwrap().contains_key("session"));
+
+        // closing the stream counts as an activity
+        drop(guard);
+        state.reap(Instant::now() + SESSION_TIMEOUT / 2);
+        assert!(state.sessions.lock().unwrap().contains_key("session"));
+
+        state.reap(expired());
+        assert!(state.sessions.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+use longport::{quote, trade};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Defines an enum of the tool parameters, with the JSON schema of its values
+/// and the conversion to the SDK type
+macro_rules! define_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident => $target:ty {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $target_variant:expr
+            ),* $(,)?
+        }
+    ) => {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+        $(#[$meta])*
+        pub(crate) enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+        }
+
+        impl From<$name> for $target {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $target_variant,)*
+                }
+            }
+        }
+    };
+}
+
+define_enum! {
+    /// Market
+    Market => longport::Market {
+        /// US market
+        US => longport::Market::US,
+        /// HK market
+        HK => longport::Market::HK,
+        /// CN market
+        CN => longport::Market::CN,
+        /// SG market
+        SG => longport::Market::SG,
+        /// Crypto market
+        Crypto => longport::Market::Crypto,
+    }
+}
+
+define_enum! {
+    /// Candlestick period
+    Period => quote::Period {
+        /// One minute
+        #[serde(rename = "1m")]
+        OneMinute => quote::Period::OneMinute,
+        /// Two minutes
+        #[serde(rename = "2m")]
+        TwoMinute => quote::Period::TwoMinute,
+        /// Three minutes
+        #[serde(rename = "3m")]
+        ThreeMinute => quote::Period::ThreeMinute,
+        /// Five minutes
+        #[serde(rename = "5m")]
+        FiveMinute => quote::Period::FiveMinute,
+        /// Ten minutes
+        #[serde(rename = "10m")]
+        TenMinute => quote::Period::TenMinute,
+        /// Fifteen minutes
+        #[serde(rename = "15m")]
+        FifteenMinute => quote::Period::FifteenMinute,
+        /// Twenty minutes
+        #[serde(rename = "20m")]
+        TwentyMinute => quote::Period::TwentyMinute,
+        /// Thirty minutes
+        #[serde(rename = "30m")]
+        ThirtyMinute => quote::Period::ThirtyMinute,
+        /// Forty-five minutes
+        #[serde(rename = "45m")]
+        FortyFiveMinute => quote::Period::FortyFiveMinute,
+        /// One hour
+        #[serde(rename = "60m")]
+        SixtyMinute => quote::Period::SixtyMinute,
+        /// Two hours
+        #[serde(rename = "120m")]
+        TwoHour => quote::Period::TwoHour,
+        /// Three hours
+        #[serde(rename = "180m")]
+        ThreeHour => quote::Period::ThreeHour,
+        /// Four hours
+        #[serde(rename = "240m")]
+        FourHour => quote::Period::FourHour,
+        /// Daily
+        #[serde(rename = "day")]
+        Day => quote::Period::Day,
+        /// Weekly
+        #[serde(rename = "week")]
+        Week => quote::Period::Week,
+        /// Monthly
+        #[serde(rename = "month")]
+        Month => quote::Period::Month,
+        /// Quarterly
+        #[serde(rename = "quarter")]
+        Quarter => quote::Period::Quarter,
+        /// Yearly
+        #[serde(rename = "year")]
+        Year => quote::Period::Year,
+    }
+}
+
+impl Period {
+    pub(crate) fn from_sdk(period: quote::Period) -> Option<Self> {
+        Some(match period {
+            quote::Period::UnknownPeriod => return None,
+            quote::Period::OneMinute => Period::OneMinute,
+            quote::Period::TwoMinute => Period::TwoMinute,
+            quote::Period::ThreeMinute => Period::ThreeMinute,
+            quote::Period::FiveMinute => Period::FiveMinute,
+            quote::Period::TenMinute => Period::TenMinute,
+            quote::Period::FifteenMinute => Period::FifteenMinute,
+            quote::Period::TwentyMinute => Period::TwentyMinute,
+            quote::Period::ThirtyMinute => Period::ThirtyMinute,
+            quote::Period::FortyFiveMinute => Period::FortyFiveMinute,
+            quote::Period::SixtyMinute => Period::SixtyMinute,
+            quote::Period::TwoHour => Period::TwoHour,
+            quote::Period::ThreeHour => Period::ThreeHour,
+            quote::Period::FourHour => Period::FourHour,
+            quote::Period::Day => Period::Day,
+            quote::Period::Week => Period::Week,
+            quote::Period::Month => Period::Month,
+            quote::Period::Quarter => Period::Quarter,
+            quote::Period::Year => Period::Year,
+        })
+    }
+}
+
+define_enum! {
+    /// Trade sessions
+    #[serde(rename_all = "snake_case")]
+    TradeSessions => quote::TradeSessions {
+        /// Regular trading hours
+        Intraday => quote::TradeSessions::Intraday,
+        /// All trading hours (intraday, pre, post, overnight)
+        All => quote::TradeSessions::All,
+    }
+}
+
+define_enum! {
+    /// Calc index
+    #[serde(rename_all = "snake_case")]
+    CalcIndex => quote::CalcIndex {
+        /// Latest price
+        LastDone => quote::CalcIndex::LastDone,
+        /// Change value
+        ChangeValue => quote::CalcIndex::ChangeValue,
+        /// Change rate
+        ChangeRate => quote::CalcIndex::ChangeRate,
+        /// Volume
+        Volume => quote::CalcIndex::Volume,
+        /// Turnover
+        Turnover => quote::CalcIndex::Turnover,
+        /// Year-to-date change ratio
+        YtdChangeRate => quote::CalcIndex::YtdChangeRate,
+        /// Turnover rate
+        TurnoverRate => quote::CalcIndex::TurnoverRate,
+        /// Total market value
+        TotalMarketValue => quote::CalcIndex::TotalMarketValue,
+        /// Capital flow
+        CapitalFlow => quote::CalcIndex::CapitalFlow,
+        /// Amplitude
+        Amplitude => quote::CalcIndex::Amplitude,
+        /// Volume ratio
+        VolumeRatio => quote::CalcIndex::VolumeRatio,
+        /// PE (TTM)
+        PeTtmRatio => quote::CalcIndex::PeTtmRatio,
+        /// PB
+        PbRatio => quote::CalcIndex::PbRatio,
+        /// Dividend ratio (TTM)
+        DividendRatioTtm => quote::CalcIndex::DividendRatioTtm,
+        /// Five days change ratio
+        FiveDayChangeRate => quote::CalcIndex::FiveDayChangeRate,
+        /// Ten days change ratio
+        TenDayChangeRate => quote::CalcIndex::TenDayChangeRate,
+        /// Half year change ratio
+        HalfYearChangeRate => quote::CalcIndex::HalfYearChangeRate,
+        /// Five minutes change ratio
+        FiveMinutesChangeRate => quote::CalcIndex::FiveMinutesChangeRate,
+        /// Expiry date
+        ExpiryDate => quote::CalcIndex::ExpiryDate,
+        /// Strike price
+        StrikePrice => quote::CalcIndex::StrikePrice,
+        /// Upper bound price
+        UpperStrikePrice => quote::CalcIndex::UpperStrikePrice,
+        /// Lower bound price
+        LowerStrikePrice => quote::CalcIndex::LowerStrikePrice,
+        /// Outstanding quantity
+        OutstandingQty => quote::CalcIndex::OutstandingQty,
+        /// Outstanding ratio
+        OutstandingRatio => quote::CalcIndex::OutstandingRatio,
+        /// Premium
+        Premium => quote::CalcIndex::Premium,
+        /// In/out of the bound
+        ItmOtm => quote::CalcIndex::ItmOtm,
+        /// Implied volatility
+        ImpliedVolatility => quote::CalcIndex::ImpliedVolatility,
+        /// Warrant delta
+        WarrantDelta => quote::CalcIndex::WarrantDelta,
+        /// Call price
+        CallPrice => quote::CalcIndex::CallPrice,
+        /// Price interval from the call price
+        ToCallPrice => quote::CalcIndex::ToCallPrice,
+        /// Effective leverage
+        EffectiveLeverage => quote::CalcIndex::EffectiveLeverage,
+        /// Leverage ratio
+        LeverageRatio => quote::CalcIndex::LeverageRatio,
+        /// Conversion ratio
+        ConversionRatio => quote::CalcIndex::ConversionRatio,
+        /// Breakeven point
+        BalancePoint => quote::CalcIndex::BalancePoint,
+        /// Open interest
+        OpenInterest => quote::CalcIndex::OpenInterest,
+        /// Delta
+        Delta => quote::CalcIndex::Delta,
+        /// Gamma
+        Gamma => quote::CalcIndex::Gamma,
+        /// Theta
+        Theta => quote::CalcIndex::Theta,
+        /// Vega
+        Vega => quote::CalcIndex::Vega,
+        /// Rho
+        Rho => quote::CalcIndex::Rho,
+    }
+}
+
+define_enum! {
+    /// Sort order
+    SortOrder => quote::SortOrderType {
+        /// Ascending
+        #[serde(rename = "asc")]
+        Ascending => quote::SortOrderType::Ascending,
+        /// Descending
+        #[serde(rename = "desc")]
+        Descending => quote::SortOrderType::Descending,
+    }
+}
+
+define_enum! {
+    /// Warrant sort by
+    #[serde(rename_all = "snake_case")]
+    WarrantSortBy => quote::WarrantSortBy {
+        /// Last done
+        LastDone => quote::WarrantSortBy::LastDone,
+        /// Change rate
+        ChangeRate => quote::WarrantSortBy::ChangeRate,
+        /// Change value
+        ChangeValue => quote::WarrantSortBy::ChangeValue,
+        /// Volume
+        Volume => quote::WarrantSortBy::Volume,
+        /// Turnover
+        Turnover => quote::WarrantSortBy::Turnover,
+        /// Expiry date
+        ExpiryDate => quote::WarrantSortBy::ExpiryDate,
+        /// Strike price
+        StrikePrice => quote::WarrantSortBy::StrikePrice,
+        /// Upper strike price
+        UpperStrikePrice => quote::WarrantSortBy::UpperStrikePrice,
+        /// Lower strike price
+        LowerStrikePrice => quote::WarrantSortBy::LowerStrikePrice,
+        /// Outstanding quantity
+        OutstandingQuantity => quote::WarrantSortBy::OutstandingQuantity,
+        /// Outstanding ratio
+        OutstandingRatio => quote::WarrantSortBy::OutstandingRatio,
+        /// Premium
+        Premium => quote::WarrantSortBy::Premium,
+        /// In/out of the bound
+        ItmOtm => quote::WarrantSortBy::ItmOtm,
+        /// Implied volatility
+        ImpliedVolatility => quote::WarrantSortBy::ImpliedVolatility,
+        /// Greek value Delta
+        Delta => quote::WarrantSortBy::Delta,
+        /// Call price
+        CallPrice => quote::WarrantSortBy::CallPrice,
+        /// Price interval from the call price
+        ToCallPrice => quote::WarrantSortBy::ToCallPrice,
+        /// Effective leverage
+        EffectiveLeverage => quote::WarrantSortBy::EffectiveLeverage,
+        /// Leverage ratio
+        LeverageRatio => quote::WarrantSortBy::LeverageRatio,
+        /// Conversion ratio
+        ConversionRatio => quote::WarrantSortBy::ConversionRatio,
+        /// Breakeven point
+        BalancePoint => quote::WarrantSortBy::BalancePoint,
+        /// Status
+        Status => quote::WarrantSortBy::Status,
+    }
+}
+
+define_enum! {
+    /// Warrant type
+    #[serde(rename_all = "snake_case")]
+    WarrantType => quote::WarrantType {
+        /// Call
+        Call => quote::WarrantType::Call,
+        /// Put
+        Put => quote::WarrantType::Put,
+        /// Bull
+        Bull => quote::WarrantType::Bull,
+        /// Bear
+        Bear => quote::WarrantType::Bear,
+        /// Inline
+        Inline => quote::WarrantType::Inline,
+    }
+}
+
+define_enum! {
+    /// Warrant expiry date
+    WarrantExpiryDate => quote::FilterWarrantExpiryDate {
+        /// Less than 3 months
+        #[serde(rename = "lt_3")]
+        LessThan3 => quote::FilterWarrantExpiryDate::LT_3,
+        /// 3 - 6 months
+        #[serde(rename = "between_3_6")]
+        Between3And6 => quote::FilterWarrantExpiryDate::Between_3_6,
+        /// 6 - 12 months
+        #[serde(rename = "between_6_12")]
+        Between6And12 => quote::FilterWarrantExpiryDate::Between_6_12,
+        /// Greater than 12 months
+        #[serde(rename = "gt_12")]
+        GreaterThan12 => quote::FilterWarrantExpiryDate::GT_12,
+    }
+}
+
+define_enum! {
+    /// Warrant price in/out of the bounds
+    #[serde(rename_all = "snake_case")]
+    WarrantPriceType => quote::FilterWarrantInOutBoundsType {
+        /// In bounds
+        In => quote::FilterWarrantInOutBoundsType::In,
+        /// Out bounds
+        Out => quote::FilterWarrantInOutBoundsType::Out,
+    }
+}
+
+define_enum! {
+    /// Warrant status
+    #[serde(rename_all = "snake_case")]
+    WarrantStatus => quote::WarrantStatus {
+        /// Suspend
+        Suspend => quote::WarrantStatus::Suspend,
+        /// Prepare list
+        PrepareList => quote::WarrantStatus::PrepareList,
+        /// Normal
+        Normal => quote::WarrantStatus::Normal,
+    }
+}
+
+define_enum! {
+    /// Security list category
+    #[serde(rename_all = "snake_case")]
+    SecurityListCategory => quote::SecurityListCategory {
+        /// Overnight
+        Overnight => quote::SecurityListCategory::Overnight,
+    }
+}
+
+define_enum! {
+    /// Securities update mode of a watchlist group
+    #[serde(rename_all = "snake_case")]
+    SecuritiesUpdateMode => quote::SecuritiesUpdateMode {
+        /// Add the securities
+        Add => quote::SecuritiesUpdateMode::Add,
+        /// Remove the securities
+        Remove => quote::SecuritiesUpdateMode::Remove,
+        /// Replace the securities
+        Replace => quote::SecuritiesUpdateMode::Replace,
+    }
+}
+
+define_enum! {
+    /// Order type
+    #[allow(clippy::upper_case_acronyms)]
+    OrderType => trade::OrderType {
+        /// Limit Order
+        LO => trade::OrderType::LO,
+        /// Enhanced Limit Order
+        ELO => trade::OrderType::ELO,
+        /// Market Order
+        MO => trade::OrderType::MO,
+        /// At-auction Order
+        AO => trade::OrderType::AO,
+        /// At-auction Limit Order
+        ALO => trade::OrderType::ALO,
+        /// Odd Lots Order
+        ODD => trade::OrderType::ODD,
+        /// Limit If Touched
+        LIT => trade::OrderType::LIT,
+        /// Market If Touched
+        MIT => trade::OrderType::MIT,
+        /// Trailing Limit If Touched (Trailing Amount)
+        TSLPAMT => trade::OrderType::TSLPAMT,
+        /// Trailing Limit If Touched (Trailing Percent)
+        TSLPPCT => trade::OrderType::TSLPPCT,
+        /// Special Limit Order, does not support replacing the order
+        SLO => trade::OrderType::SLO,
+    }
+}
+
+define_enum! {
+    /// Order side
+    OrderSide => trade::OrderSide {
+        /// Buy
+        Buy => trade::OrderSide::Buy,
+        /// Sell
+        Sell => trade::OrderSide::Sell,
+    }
+}
+
+define_enum! {
+    /// Time in force
+    #[allow(clippy::upper_case_acronyms)]
+    TimeInForce => trade::TimeInForceType {
+        /// Day Order
+        Day => trade::TimeInForceType::Day,
+        /// Good Till Cancel
+        GTC => trade::TimeInForceType::GoodTilCanceled,
+        /// Good Till Date
+        GTD => trade::TimeInForceType::GoodTilDate,
+    }
+}
+
+define_enum! {
+    /// Trading hours of the order
+    OutsideRTH => trade::OutsideRTH {
+        /// Regular trading hours only
+        #[serde(rename = "RTH_ONLY")]
+        RTHOnly => trade::OutsideRTH::RTHOnly,
+        /// Any time
+        #[serde(rename = "ANY_TIME")]
+        AnyTime => trade::OutsideRTH::AnyTime,
+        /// Overnight
+        #[serde(rename = "OVERNIGHT")]
+        Overnight => trade::OutsideRTH::Overnight,
+    }
+}
+
+define_enum! {
+    /// Cash flow business type
+    #[serde(rename_all = "snake_case")]
+    BalanceType => trade::BalanceType {
+        /// Cash
+        Cash => trade::BalanceType::Cash,
+        /// Stock
+        Stock => trade::BalanceType::Stock,
+        /// Fund
+        Fund => trade::BalanceType::Fund,
+    }
+}
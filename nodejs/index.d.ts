@@ -160,6 +160,16 @@ export declare class Config {
    *   `realtime`)
    * - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected,
    *   `true` or `false` (Default: `true`)
+   * - `LONGPORT_PAPER_TRADING` - Simulate the orders of the `TradeContext`
+   *   locally, `true` or `false` (Default: `false`)
+   * - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+   *   certificates
+   * - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the
+   *   pinned certificates
+   * - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+   *   certificate chain
+   * - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client
+   *   private key
    */
   static fromEnv(): Config
   /**
@@ -171,6 +181,21 @@ export declare class Config {
   refreshAccessToken(expiredAt?: Date | undefined | null): Promise<string>
 }
 
+/** Connection state change event */
+export declare class ConnectionEvent {
+  /** Event kind */
+  get kind(): ConnectionEventKind
+  /** Whether the previous session was reused (`Reconnected` only) */
+  get sessionReused(): boolean
+  /** Close code sent by the server (`Disconnected` only) */
+  get closeCode(): number | null
+  /** Close reason sent by the server (`Disconnected` only) */
+  get closeMessage(): string | null
+  /** Error message (`Disconnected` and `GaveUp` only) */
+  get error(): string | null
+  toString(): string
+}
+
 export declare class Decimal {
   static E(): Decimal
   static E_INVERSE(): Decimal
@@ -979,6 +1004,11 @@ export declare class QuoteContext {
   quoteLevel(): string
   /** Returns the quote package details */
   quotePackageDetails(): Array<QuotePackageDetail>
+  /**
+   * Set connection event callback, it will be called when the state of
+   * the connection changes.
+   */
+  setOnConnectionEvent(callback: (err: null | Error, event: ConnectionEvent) => void): void
   /**
    * Set quote callback, after receiving the quote data push, it will call
    * back to this function.
@@ -1321,6 +1351,24 @@ export declare class QuoteContext {
    * ```
    */
   warrantList(symbol: string, sortBy: WarrantSortBy, sortOrder: SortOrderType, warrantType?: Array<WarrantType> | undefined | null, issuer?: Array<number> | undefined | null, expiryDate?: Array<FilterWarrantExpiryDate> | undefined | null, priceType?: Array<FilterWarrantInOutBoundsType> | undefined | null, status?: Array<WarrantStatus> | undefined | null): Promise<Array<WarrantInfo>>
+  /**
+   * Query a page of the warrants matching the filter
+   *
+   * #### Example
+   * ```javascript
+   * const { Config, QuoteContext, WarrantSortBy, SortOrderType } = require("longport")
+   * let config = Config.fromEnv()
+   * QuoteContext.new(config)
+   *  .then((ctx) => ctx.warrantScreener({ symbol: "700.HK", sortBy: WarrantSortBy.Volume, sortOrder: SortOrderType.Descending, offset: 0, count: 100 }))
+   * .then((resp) => {
+   *  console.log(resp.totalCount)
+   *  for (let obj of resp.warrants) {
+   *   console.log(obj.toString())
+   * }
+   * })
+   * ```
+   */
+  warrantScreener(filter: WarrantFilter): Promise<WarrantList>
   /**
    * Get trading session of the day
    *
@@ -1949,6 +1997,11 @@ export declare class Trade {
 /** Trade context */
 export declare class TradeContext {
   static new(config: Config): Promise<TradeContext>
+  /**
+   * Set connection event callback, it will be called when the state of
+   * the connection changes.
+   */
+  setOnConnectionEvent(callback: (err: null | Error, event: ConnectionEvent) => void): void
   /**
    * Set order changed callback, after receiving the order changed event, it
    * will call back to this function.
@@ -2362,6 +2415,16 @@ export declare class WarrantInfo {
   get status(): WarrantStatus
 }
 
+/** A page of the warrant screener */
+export declare class WarrantList {
+  toString(): string
+  toJSON(): any
+  /** Warrants */
+  get warrants(): Array<WarrantInfo>
+  /** Total number of the warrants matching the filter */
+  get totalCount(): number
+}
+
 /** Quote of warrant */
 export declare class WarrantQuote {
   toString(): string
@@ -2608,9 +2671,46 @@ export interface ConfigParams {
   enablePrintQuotePackages: boolean
   /** Set the path of the log files (Default: `no logs`) */
   logPath?: string
+  /**
+   * Simulate the orders of the `TradeContext` locally against the live
+   * quotes instead of sending them to the exchange (default: false)
+   */
+  paperTrading?: boolean
+  /**
+   * PEM encoded root certificates to trust in addition to the built-in
+   * ones
+   */
+  tlsRootCertificates?: string
+  /**
+   * Hex encoded SHA-256 fingerprints of the pinned server (or
+   * intermediate) certificates
+   */
+  tlsPins?: Array<string>
+  /** PEM encoded client certificate chain */
+  tlsClientCert?: string
+  /** PEM encoded private key of the client certificate */
+  tlsClientKey?: string
 }
 
 /** An request to create a watchlist group */
+/** Connection event kind */
+export declare const enum ConnectionEventKind {
+  /** Connecting to the server */
+  Connecting = 0,
+  /** The websocket connection has been established */
+  Connected = 1,
+  /** The first session has been created */
+  Authenticated = 2,
+  /** The session has been restored after a reconnection */
+  Reconnected = 3,
+  /** The subscriptions have been restored after a reconnection */
+  Resubscribed = 4,
+  /** The connection was lost or a reconnection attempt failed */
+  Disconnected = 5,
+  /** The reconnection retries ran out, the context is closed */
+  GaveUp = 6
+}
+
 export interface CreateWatchlistGroup {
   /** Group name */
   name: string
@@ -3203,6 +3303,30 @@ export interface UpdateWatchlistGroup {
   mode: SecuritiesUpdateMode
 }
 
+/** Options for the warrant screener */
+export interface WarrantFilter {
+  /** Security code of the underlying security */
+  symbol: string
+  /** Sort by field */
+  sortBy: WarrantSortBy
+  /** Sort order */
+  sortOrder: SortOrderType
+  /** Offset of the first warrant */
+  offset?: number
+  /** Number of warrants, `0` means the default page size of the server */
+  count?: number
+  /** Filter by warrant type */
+  warrantType?: Array<WarrantType>
+  /** Filter by issuer */
+  issuer?: Array<number>
+  /** Filter by expiry date */
+  expiryDate?: Array<FilterWarrantExpiryDate>
+  /** Filter by price type */
+  priceType?: Array<FilterWarrantInOutBoundsType>
+  /** Filter by status */
+  status?: Array<WarrantStatus>
+}
+
 /** Warrant sort by */
 export declare const enum WarrantSortBy {
   /** Last done */
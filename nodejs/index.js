@@ -402,6 +402,7 @@ module.exports.CapitalFlowLine = nativeBinding.CapitalFlowLine
 module.exports.CashFlow = nativeBinding.CashFlow
 module.exports.CashInfo = nativeBinding.CashInfo
 module.exports.Config = nativeBinding.Config
+module.exports.ConnectionEvent = nativeBinding.ConnectionEvent
 module.exports.Decimal = nativeBinding.Decimal
 module.exports.Depth = nativeBinding.Depth
 module.exports.EstimateMaxPurchaseQuantityResponse = nativeBinding.EstimateMaxPurchaseQuantityResponse
@@ -460,6 +461,7 @@ module.exports.Trade = nativeBinding.Trade
 module.exports.TradeContext = nativeBinding.TradeContext
 module.exports.TradingSessionInfo = nativeBinding.TradingSessionInfo
 module.exports.WarrantInfo = nativeBinding.WarrantInfo
+module.exports.WarrantList = nativeBinding.WarrantList
 module.exports.WarrantQuote = nativeBinding.WarrantQuote
 module.exports.WatchlistGroup = nativeBinding.WatchlistGroup
 module.exports.WatchlistSecurity = nativeBinding.WatchlistSecurity
@@ -469,6 +471,7 @@ module.exports.CalcIndex = nativeBinding.CalcIndex
 module.exports.CashFlowDirection = nativeBinding.CashFlowDirection
 module.exports.ChargeCategoryCode = nativeBinding.ChargeCategoryCode
 module.exports.CommissionFreeStatus = nativeBinding.CommissionFreeStatus
+module.exports.ConnectionEventKind = nativeBinding.ConnectionEventKind
 module.exports.DeductionStatus = nativeBinding.DeductionStatus
 module.exports.DerivativeType = nativeBinding.DerivativeType
 module.exports.FilterWarrantExpiryDate = nativeBinding.FilterWarrantExpiryDate
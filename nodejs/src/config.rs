@@ -35,6 +35,19 @@ pub struct ConfigParams {
     pub enable_print_quote_packages: bool,
     /// Set the path of the log files (Default: `no logs`)
     pub log_path: Option<String>,
+    /// Simulate the orders of the `TradeContext` locally against the live
+    /// quotes instead of sending them to the exchange (default: false)
+    pub paper_trading: Option<bool>,
+    /// PEM encoded root certificates to trust in addition to the built-in
+    /// ones
+    pub tls_root_certificates: Option<String>,
+    /// Hex encoded SHA-256 fingerprints of the pinned server (or
+    /// intermediate) certificates
+    pub tls_pins: Option<Vec<String>>,
+    /// PEM encoded client certificate chain
+    pub tls_client_cert: Option<String>,
+    /// PEM encoded private key of the client certificate
+    pub tls_client_key: Option<String>,
 }
 
 /// Configuration for LongPort sdk
@@ -45,7 +58,7 @@ pub struct Config(pub(crate) longport::Config);
 impl Config {
     /// Create a new `Config`
     #[napi(constructor)]
-    pub fn new(params: ConfigParams) -> Self {
+    pub fn new(params: ConfigParams) -> Result<Self> {
         let mut config =
             longport::Config::new(params.app_key, params.app_secret, params.access_token);
 
@@ -81,7 +94,42 @@ impl Config {
             config = config.log_path(log_path);
         }
 
-        Self(config)
+        if let Some(true) = params.paper_trading {
+            config = config.paper_trading();
+        }
+
+        let tls_pins = params.tls_pins.unwrap_or_default();
+        if params.tls_root_certificates.is_some()
+            || !tls_pins.is_empty()
+            || params.tls_client_cert.is_some()
+            || params.tls_client_key.is_some()
+        {
+            let mut tls_config = longport::TlsConfig::new();
+            if let Some(pem) = params.tls_root_certificates {
+                tls_config = tls_config
+                    .add_root_certificates(pem)
+                    .map_err(ErrorNewType)?;
+            }
+            for pin in tls_pins {
+                tls_config = tls_config.pin_certificate(pin).map_err(ErrorNewType)?;
+            }
+            match (params.tls_client_cert, params.tls_client_key) {
+                (Some(cert), Some(key)) => {
+                    tls_config = tls_config
+                        .client_identity(cert, key)
+                        .map_err(ErrorNewType)?;
+                }
+                (None, None) => {}
+                _ => {
+                    return Err(napi::Error::from_reason(
+                        "tlsClientCert and tlsClientKey must be set together",
+                    ));
+                }
+            }
+            config = config.tls_config(tls_config).map_err(ErrorNewType)?;
+        }
+
+        Ok(Self(config))
     }
 
     /// Create a new `Config` from the given environment variables
@@ -105,6 +153,16 @@ impl Config {
     ///   `realtime`)
     /// - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected,
     ///   `true` or `false` (Default: `true`)
+    /// - `LONGPORT_PAPER_TRADING` - Simulate the orders of the `TradeContext`
+    ///   locally, `true` or `false` (Default: `false`)
+    /// - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+    ///   certificates
+    /// - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the
+    ///   pinned certificates
+    /// - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+    ///   certificate chain
+    /// - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client
+    ///   private key
     #[napi(factory)]
     pub fn from_env() -> Result<Self> {
         Ok(Self(longport::Config::from_env().map_err(ErrorNewType)?))
@@ -16,9 +16,12 @@ impl HttpClient {
         app_secret: String,
         access_token: String,
     ) -> Result<Self> {
-        Ok(Self(LbHttpClient::new(
-            HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
-        )))
+        Ok(Self(
+            LbHttpClient::new(
+                HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
+            )
+            .map_err(|err| Error::from_reason(err.to_string()))?,
+        ))
     }
 
     /// Create a new `HttpClient` from the given environment variables
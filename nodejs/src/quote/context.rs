@@ -11,7 +11,9 @@ use crate::{
         push::{
             PushBrokersEvent, PushCandlestickEvent, PushDepthEvent, PushQuoteEvent, PushTradesEvent,
         },
-        requests::{CreateWatchlistGroup, DeleteWatchlistGroup, UpdateWatchlistGroup},
+        requests::{
+            CreateWatchlistGroup, DeleteWatchlistGroup, UpdateWatchlistGroup, WarrantFilter,
+        },
         types::{
             AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
             FilterWarrantExpiryDate, FilterWarrantInOutBoundsType,
@@ -20,17 +22,18 @@ use crate::{
             QuotePackageDetail, RealtimeQuote, Security, SecurityBrokers, SecurityCalcIndex,
             SecurityDepth, SecurityListCategory, SecurityQuote, SecurityStaticInfo, SortOrderType,
             StrikePriceInfo, SubType, SubTypes, Subscription, Trade, TradeSessions, WarrantInfo,
-            WarrantQuote, WarrantSortBy, WarrantStatus, WarrantType, WatchlistGroup,
+            WarrantList, WarrantQuote, WarrantSortBy, WarrantStatus, WarrantType, WatchlistGroup,
         },
     },
     time::{NaiveDate, NaiveDatetime},
-    types::Market,
+    types::{ConnectionEvent, Market},
     utils::JsCallback,
 };
 
 #[derive(Default)]
 struct Callbacks {
     quote: Option<JsCallback<PushQuoteEvent>>,
+    connection: Option<JsCallback<ConnectionEvent>>,
     depth: Option<JsCallback<PushDepthEvent>>,
     brokers: Option<JsCallback<PushBrokersEvent>>,
     trades: Option<JsCallback<PushTradesEvent>>,
@@ -131,6 +134,18 @@ impl QuoteContext {
             }
         });
 
+        tokio::spawn({
+            let callbacks = callbacks.clone();
+            let mut receiver = ctx.connection_events();
+            async move {
+                while let Some(event) = receiver.recv().await {
+                    if let Some(callback) = &callbacks.lock().connection {
+                        callback.call(Ok(event.into()), ThreadsafeFunctionCallMode::Blocking);
+                    }
+                }
+            }
+        });
+
         Ok(QuoteContext { ctx, callbacks })
     }
 
@@ -157,6 +172,19 @@ impl QuoteContext {
             .collect()
     }
 
+    /// Set connection event callback, it will be called when the state of
+    /// the connection changes.
+    #[napi(ts_args_type = "callback: (err: null | Error, event: ConnectionEvent) => void")]
+    pub fn set_on_connection_event(&self, callback: Function<ConnectionEvent, ()>) -> Result<()> {
+        self.callbacks.lock().connection = Some(
+            callback
+                .build_threadsafe_function()
+                .callee_handled::<true>()
+                .build()?,
+        );
+        Ok(())
+    }
+
     /// Set quote callback, after receiving the quote data push, it will call
     /// back to this function.
     #[napi(ts_args_type = "callback: (err: null | Error, event: PushQuoteEvent) => void")]
@@ -294,7 +322,7 @@ impl QuoteContext {
     #[napi]
     pub async fn unsubscribe_candlesticks(&self, symbol: String, period: Period) -> Result<()> {
         self.ctx
-            .unsubscribe_candlesticks(symbol, period.into())
+            .unsubscribe_candlesticks(symbol, longport::quote::Period::from(period))
             .await
             .map_err(ErrorNewType)?;
         Ok(())
@@ -798,6 +826,30 @@ impl QuoteContext {
             .collect()
     }
 
+    /// Query a page of the warrants matching the filter
+    ///
+    /// #### Example
+    /// ```javascript
+    /// const { Config, QuoteContext, WarrantSortBy, SortOrderType } = require("longport")
+    /// let config = Config.fromEnv()
+    /// QuoteContext.new(config)
+    ///  .then((ctx) => ctx.warrantScreener({ symbol: "700.HK", sortBy: WarrantSortBy.Volume, sortOrder: SortOrderType.Descending, offset: 0, count: 100 }))
+    /// .then((resp) => {
+    ///  console.log(resp.totalCount)
+    ///  for (let obj of resp.warrants) {
+    ///   console.log(obj.toString())
+    /// }
+    /// })
+    /// ```
+    #[napi]
+    pub async fn warrant_screener(&self, filter: WarrantFilter) -> Result<WarrantList> {
+        self.ctx
+            .warrant_screener(filter.into())
+            .await
+            .map_err(ErrorNewType)?
+            .try_into()
+    }
+
     /// Get trading session of the day
     ///
     /// #### Example
@@ -1227,7 +1279,11 @@ impl QuoteContext {
         count: i32,
     ) -> Result<Vec<Candlestick>> {
         self.ctx
-            .realtime_candlesticks(symbol, period.into(), count.max(0) as usize)
+            .realtime_candlesticks(
+                symbol,
+                longport::quote::Period::from(period),
+                count.max(0) as usize,
+            )
             .await
             .map_err(ErrorNewType)?
             .into_iter()
@@ -1,4 +1,7 @@
-use crate::quote::types::SecuritiesUpdateMode;
+use crate::quote::types::{
+    FilterWarrantExpiryDate, FilterWarrantInOutBoundsType, SecuritiesUpdateMode, SortOrderType,
+    WarrantSortBy, WarrantStatus, WarrantType,
+};
 
 /// An request to create a watchlist group
 #[napi_derive::napi(object)]
@@ -59,3 +62,70 @@ impl From<UpdateWatchlistGroup> for longport::quote::RequestUpdateWatchlistGroup
         }
     }
 }
+
+/// Options for the warrant screener
+#[napi_derive::napi(object)]
+#[derive(Debug)]
+pub struct WarrantFilter {
+    /// Security code of the underlying security
+    pub symbol: String,
+    /// Sort by field
+    pub sort_by: WarrantSortBy,
+    /// Sort order
+    pub sort_order: SortOrderType,
+    /// Offset of the first warrant
+    pub offset: Option<i32>,
+    /// Number of warrants, `0` means the default page size of the server
+    pub count: Option<i32>,
+    /// Filter by warrant type
+    pub warrant_type: Option<Vec<WarrantType>>,
+    /// Filter by issuer
+    pub issuer: Option<Vec<i32>>,
+    /// Filter by expiry date
+    pub expiry_date: Option<Vec<FilterWarrantExpiryDate>>,
+    /// Filter by price type
+    pub price_type: Option<Vec<FilterWarrantInOutBoundsType>>,
+    /// Filter by status
+    pub status: Option<Vec<WarrantStatus>>,
+}
+
+impl From<WarrantFilter> for longport::quote::WarrantFilter {
+    fn from(filter: WarrantFilter) -> Self {
+        longport::quote::WarrantFilter::new(
+            filter.symbol,
+            filter.sort_by.into(),
+            filter.sort_order.into(),
+        )
+        .offset(filter.offset.unwrap_or_default())
+        .count(filter.count.unwrap_or_default())
+        .warrant_type(
+            filter
+                .warrant_type
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into),
+        )
+        .issuer(filter.issuer.unwrap_or_default())
+        .expiry_date(
+            filter
+                .expiry_date
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into),
+        )
+        .price_type(
+            filter
+                .price_type
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into),
+        )
+        .status(
+            filter
+                .status
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into),
+        )
+    }
+}
@@ -807,7 +807,7 @@ pub enum FilterWarrantInOutBoundsType {
 
 /// Warrant info
 #[napi_derive::napi]
-#[derive(Debug, JsObject)]
+#[derive(Debug, JsObject, Clone)]
 #[js(remote = "longport::quote::WarrantInfo")]
 pub struct WarrantInfo {
     /// Security code
@@ -873,6 +873,18 @@ pub struct WarrantInfo {
     status: WarrantStatus,
 }
 
+/// A page of the warrant screener
+#[napi_derive::napi]
+#[derive(Debug, JsObject)]
+#[js(remote = "longport::quote::WarrantList")]
+pub struct WarrantList {
+    /// Warrants
+    #[js(array)]
+    warrants: Vec<WarrantInfo>,
+    /// Total number of the warrants matching the filter
+    total_count: i32,
+}
+
 /// Warrant status
 #[napi_derive::napi]
 #[derive(Debug, JsEnum, Hash, Eq, PartialEq, Copy, Clone)]
@@ -19,12 +19,14 @@ use crate::{
             StockPositionsResponse, SubmitOrderResponse, TopicType,
         },
     },
+    types::ConnectionEvent,
     utils::JsCallback,
 };
 
 #[derive(Default)]
 struct Callbacks {
     order_changed: Option<JsCallback<PushOrderChanged>>,
+    connection: Option<JsCallback<ConnectionEvent>>,
 }
 
 /// Trade context
@@ -64,9 +66,34 @@ impl TradeContext {
             }
         });
 
+        tokio::spawn({
+            let callbacks = callbacks.clone();
+            let mut receiver = ctx.connection_events();
+            async move {
+                while let Some(event) = receiver.recv().await {
+                    if let Some(callback) = &callbacks.lock().connection {
+                        callback.call(Ok(event.into()), ThreadsafeFunctionCallMode::Blocking);
+                    }
+                }
+            }
+        });
+
         Ok(TradeContext { ctx, callbacks })
     }
 
+    /// Set connection event callback, it will be called when the state of
+    /// the connection changes.
+    #[napi(ts_args_type = "callback: (err: null | Error, event: ConnectionEvent) => void")]
+    pub fn set_on_connection_event(&self, callback: Function<ConnectionEvent, ()>) -> Result<()> {
+        self.callbacks.lock().connection = Some(
+            callback
+                .build_threadsafe_function()
+                .callee_handled::<true>()
+                .build()?,
+        );
+        Ok(())
+    }
+
     /// Set order changed callback, after receiving the order changed event, it
     /// will call back to this function.
     #[napi(ts_args_type = "callback: (err: null | Error, event: PushOrderChanged) => void")]
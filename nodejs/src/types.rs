@@ -40,3 +40,108 @@ pub enum PushCandlestickMode {
     /// Confirmed mode
     Confirmed,
 }
+
+/// Connection event kind
+#[napi_derive::napi]
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum ConnectionEventKind {
+    /// Connecting to the server
+    Connecting,
+    /// The websocket connection has been established
+    Connected,
+    /// The first session has been created
+    Authenticated,
+    /// The session has been restored after a reconnection
+    Reconnected,
+    /// The subscriptions have been restored after a reconnection
+    Resubscribed,
+    /// The connection was lost or a reconnection attempt failed
+    Disconnected,
+    /// The reconnection retries ran out, the context is closed
+    GaveUp,
+}
+
+/// Connection state change event
+#[napi_derive::napi]
+#[derive(Debug)]
+pub struct ConnectionEvent {
+    kind: ConnectionEventKind,
+    session_reused: bool,
+    close_code: Option<u16>,
+    close_message: Option<String>,
+    error: Option<String>,
+}
+
+#[napi_derive::napi]
+impl ConnectionEvent {
+    /// Event kind
+    #[napi(getter)]
+    pub fn kind(&self) -> ConnectionEventKind {
+        self.kind
+    }
+
+    /// Whether the previous session was reused (`Reconnected` only)
+    #[napi(getter)]
+    pub fn session_reused(&self) -> bool {
+        self.session_reused
+    }
+
+    /// Close code sent by the server (`Disconnected` only)
+    #[napi(getter)]
+    pub fn close_code(&self) -> Option<u16> {
+        self.close_code
+    }
+
+    /// Close reason sent by the server (`Disconnected` only)
+    #[napi(getter)]
+    pub fn close_message(&self) -> Option<String> {
+        self.close_message.clone()
+    }
+
+    /// Error message (`Disconnected` and `GaveUp` only)
+    #[napi(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    #[napi]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl From<longport::ConnectionEvent> for ConnectionEvent {
+    fn from(event: longport::ConnectionEvent) -> Self {
+        let mut res = ConnectionEvent {
+            kind: ConnectionEventKind::Connecting,
+            session_reused: false,
+            close_code: None,
+            close_message: None,
+            error: None,
+        };
+        match event {
+            longport::ConnectionEvent::Connecting => {}
+            longport::ConnectionEvent::Connected => res.kind = ConnectionEventKind::Connected,
+            longport::ConnectionEvent::Authenticated => {
+                res.kind = ConnectionEventKind::Authenticated
+            }
+            longport::ConnectionEvent::Reconnected { session_reused } => {
+                res.kind = ConnectionEventKind::Reconnected;
+                res.session_reused = session_reused;
+            }
+            longport::ConnectionEvent::Resubscribed => res.kind = ConnectionEventKind::Resubscribed,
+            longport::ConnectionEvent::Disconnected { reason, error } => {
+                res.kind = ConnectionEventKind::Disconnected;
+                res.close_code = reason.as_ref().map(|reason| reason.code.into());
+                res.close_message = reason.map(|reason| reason.message);
+                res.error = Some(error);
+            }
+            longport::ConnectionEvent::GaveUp { error, .. } => {
+                res.kind = ConnectionEventKind::GaveUp;
+                res.error = Some(error);
+            }
+        }
+        res
+    }
+}
@@ -1,6 +1,9 @@
 from datetime import datetime, date, time
 from decimal import Decimal
-from typing import Any, Callable, List, Optional, Type
+from typing import Any, Callable, Generic, List, Optional, Tuple, Type, TypeVar
+
+
+T = TypeVar("T")
 
 
 class OpenApiException(Exception):
@@ -89,6 +92,89 @@ class PushCandlestickMode:
         """
 
 
+class ConnectionEventKind:
+    """
+    Connection event kind
+    """
+
+    class Connecting(ConnectionEventKind):
+        """
+        Connecting to the server
+        """
+
+    class Connected(ConnectionEventKind):
+        """
+        The websocket connection has been established
+        """
+
+    class Authenticated(ConnectionEventKind):
+        """
+        The first session has been created
+        """
+
+    class Reconnected(ConnectionEventKind):
+        """
+        The session has been restored after a reconnection
+        """
+
+    class Resubscribed(ConnectionEventKind):
+        """
+        The subscriptions have been restored after a reconnection
+        """
+
+    class Disconnected(ConnectionEventKind):
+        """
+        The connection was lost or a reconnection attempt failed
+        """
+
+    class GaveUp(ConnectionEventKind):
+        """
+        The reconnection retries ran out, the context is closed
+        """
+
+
+class ConnectionEvent:
+    """
+    Connection state change event
+    """
+
+    kind: Type[ConnectionEventKind]
+    """
+    Event kind
+    """
+
+    session_reused: bool
+    """
+    Whether the previous session was reused (`Reconnected` only)
+    """
+
+    close_code: Optional[int]
+    """
+    Close code sent by the server (`Disconnected` only)
+    """
+
+    close_message: Optional[str]
+    """
+    Close reason sent by the server (`Disconnected` only)
+    """
+
+    error: Optional[str]
+    """
+    Error message (`Disconnected` and `GaveUp` only)
+    """
+
+
+class PushStream(Generic[T]):
+    """
+    Asynchronous iterator of the push events, it ends when the context is closed
+    """
+
+    def __aiter__(self) -> PushStream[T]: ...
+
+    async def __anext__(self) -> T: ...
+
+
+
 class Config:
     """
     Configuration options for LongPort sdk
@@ -105,6 +191,11 @@ class Config:
         push_candlestick_mode: Push candlestick mode
         enable_print_quote_packages: Enable printing the opened quote packages when connected to the server
         log_path: Set the path of the log files
+        paper_trading: Simulate the orders of the `TradeContext` locally against the live quotes instead of sending them to the exchange
+        tls_root_certificates: PEM encoded root certificates to trust in addition to the built-in ones
+        tls_pins: Hex encoded SHA-256 fingerprints of the pinned server (or intermediate) certificates
+        tls_client_cert: PEM encoded client certificate chain
+        tls_client_key: PEM encoded private key of the client certificate
     """
 
     def __init__(
@@ -120,6 +211,11 @@ class Config:
         push_candlestick_mode: Type[PushCandlestickMode] = PushCandlestickMode.Realtime,
         enable_print_quote_packages: bool = True,
         log_path: Optional[str] = None,
+        paper_trading: bool = False,
+        tls_root_certificates: Optional[str] = None,
+        tls_pins: List[str] = [],
+        tls_client_cert: Optional[str] = None,
+        tls_client_key: Optional[str] = None,
     ) -> None: ...
 
     @classmethod
@@ -142,6 +238,12 @@ class Config:
         - `LONGPORT_PUSH_CANDLESTICK_MODE` - `realtime` or `confirmed` (Default: `realtime`)
         - `LONGPORT_PRINT_QUOTE_PACKAGES` - Print quote packages when connected, `true` or `false` (Default: `true`)
         - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
+        - `LONGPORT_PAPER_TRADING` - Simulate the orders of the `TradeContext` locally, `true` or `false` (Default: `false`)
+        - `LONGPORT_PAPER_INITIAL_CASH` - Initial cash of the paper trading account, e.g. `HKD:1000000,USD:100000`
+        - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root certificates
+        - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the pinned certificates
+        - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client certificate chain
+        - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client private key
         """
 
     def refresh_access_token(self, expired_at: Optional[datetime] = None) -> str:
@@ -1893,6 +1995,21 @@ class WarrantInfo:
     """
 
 
+class WarrantList:
+    """
+    A page of the warrant screener
+    """
+
+    warrants: List[WarrantInfo]
+    """
+    Warrants
+    """
+
+    total_count: int
+    """
+    Total number of the warrants matching the filter
+    """
+
 class TradingSessionInfo:
     """
     The information of trading session
@@ -2696,6 +2813,11 @@ class QuoteContext:
         Returns the quote package details
         """
 
+    def set_on_connection_event(self, callback: Callable[[ConnectionEvent], None]) -> None:
+        """
+        Set connection event callback, it will be called when the state of the connection changes.
+        """
+
     def set_on_quote(self, callback: Callable[[str, PushQuote], None]) -> None:
         """
         Set quote callback, after receiving the quote data push, it will call back to this function.
@@ -3163,6 +3285,42 @@ class QuoteContext:
                 print(resp)
         """
 
+    def warrant_screener(self, symbol: str, sort_by: Type[WarrantSortBy], sort_order: Type[SortOrderType], warrant_type: Optional[List[Type[WarrantType]]] = None, issuer: Optional[List[int]] = None, expiry_date: Optional[List[Type[FilterWarrantExpiryDate]]] = None, price_type: Optional[List[Type[FilterWarrantInOutBoundsType]]] = None, status: Optional[List[Type[WarrantStatus]]] = None, offset: int = 0, count: int = 0) -> WarrantList:
+        """
+        Get a page of the warrants matching the filter
+
+        Args:
+            symbol: Security code
+            sort_by: Sort by field
+            sort_order: Sort order
+            warrant_type: Filter by warrant type
+            issuer: Filter by issuer
+            expiry_date: Filter by expiry date
+            price_type: Filter by price type
+            status: Filter by status
+            offset: Offset of the first warrant
+            count: Number of warrants, `0` means the default page size of the server
+
+        Returns:
+            A page of the warrants and the total number of the matching warrants
+
+        Examples:
+            ::
+
+                from longport.openapi import QuoteContext, Config, WarrantSortBy, SortOrderType
+
+                config = Config.from_env()
+                ctx = QuoteContext(config)
+
+                offset = 0
+                while True:
+                    resp = ctx.warrant_screener("700.HK", WarrantSortBy.Volume, SortOrderType.Descending, offset=offset, count=100)
+                    print(resp.warrants)
+                    offset += len(resp.warrants)
+                    if not resp.warrants or offset >= resp.total_count:
+                        break
+        """
+
     def trading_session(self) -> List[MarketTradingSession]:
         """
         Get trading session of the day
@@ -3568,6 +3726,277 @@ class QuoteContext:
         """
 
 
+class AsyncQuoteContext:
+    """
+    Quote context for asyncio
+
+    The methods return awaitables and the pushes are received from the
+    asynchronous iterators.
+
+    Examples:
+        ::
+
+            import asyncio
+            from longport.openapi import AsyncQuoteContext, Config, SubType
+
+            async def main():
+                config = Config.from_env()
+                ctx = await AsyncQuoteContext.create(config)
+                stream = ctx.quote_stream(["700.HK"])
+                await ctx.subscribe(["700.HK"], [SubType.Quote])
+                async for symbol, quote in stream:
+                    print(symbol, quote)
+
+            asyncio.run(main())
+    """
+
+    @staticmethod
+    async def create(config: Config) -> AsyncQuoteContext:
+        """
+        Create a quote context
+        """
+
+    def member_id(self) -> int:
+        """
+        Returns the member ID
+        """
+
+    def quote_level(self) -> str:
+        """
+        Returns the quote level
+        """
+
+    def quote_package_details(self) -> List[QuotePackageDetail]:
+        """
+        Returns the quote package details
+        """
+
+    def connection_events(self) -> PushStream[ConnectionEvent]:
+        """
+        Returns an asynchronous iterator of the connection events
+        """
+
+    def quote_stream(self, symbols: List[str]) -> PushStream[Tuple[str, PushQuote]]:
+        """
+        Returns an asynchronous iterator of the quote pushes of `symbols`
+        """
+
+    def depth_stream(self, symbols: List[str]) -> PushStream[Tuple[str, PushDepth]]:
+        """
+        Returns an asynchronous iterator of the depth pushes of `symbols`
+        """
+
+    def brokers_stream(self, symbols: List[str]) -> PushStream[Tuple[str, PushBrokers]]:
+        """
+        Returns an asynchronous iterator of the brokers pushes of `symbols`
+        """
+
+    def trades_stream(self, symbols: List[str]) -> PushStream[Tuple[str, PushTrades]]:
+        """
+        Returns an asynchronous iterator of the trades pushes of `symbols`
+        """
+
+    def candlestick_stream(self, symbol: str, period: Type[Period]) -> PushStream[Tuple[str, PushCandlestick]]:
+        """
+        Returns an asynchronous iterator of the candlestick pushes of `symbol` in `period`, the candlesticks must be subscribed by `subscribe_candlesticks`
+        """
+
+    async def subscribe(self, symbols: List[str], sub_types: List[Type[SubType]], is_first_push: bool = False) -> None:
+        """
+        Subscribe
+        """
+
+    async def unsubscribe(self, symbols: List[str], sub_types: List[Type[SubType]]) -> None:
+        """
+        Unsubscribe
+        """
+
+    async def subscribe_candlesticks(self, symbol: str, period: Type[Period], trade_sessions: Type[TradeSessions] = TradeSessions.Intraday) -> List[Candlestick]:
+        """
+        Subscribe security candlesticks
+        """
+
+    async def unsubscribe_candlesticks(self, symbol: str, period: Type[Period]) -> None:
+        """
+        Unsubscribe security candlesticks
+        """
+
+    async def subscriptions(self) -> List[Subscription]:
+        """
+        Get subscription information
+        """
+
+    async def static_info(self, symbols: List[str]) -> List[SecurityStaticInfo]:
+        """
+        Get basic information of securities
+        """
+
+    async def quote(self, symbols: List[str]) -> List[SecurityQuote]:
+        """
+        Get quote of securities
+        """
+
+    async def option_quote(self, symbols: List[str]) -> List[OptionQuote]:
+        """
+        Get quote of option securities
+        """
+
+    async def warrant_quote(self, symbols: List[str]) -> List[WarrantQuote]:
+        """
+        Get quote of warrant securities
+        """
+
+    async def depth(self, symbol: str) -> SecurityDepth:
+        """
+        Get security depth
+        """
+
+    async def brokers(self, symbol: str) -> SecurityBrokers:
+        """
+        Get security brokers
+        """
+
+    async def participants(self) -> List[ParticipantInfo]:
+        """
+        Get participants
+        """
+
+    async def trades(self, symbol: str, count: int) -> List[Trade]:
+        """
+        Get security trades
+        """
+
+    async def intraday(self, symbol: str, trade_sessions: Type[TradeSessions] = TradeSessions.Intraday) -> List[IntradayLine]:
+        """
+        Get security intraday lines
+        """
+
+    async def candlesticks(self, symbol: str, period: Type[Period], count: int, adjust_type: Type[AdjustType], trade_sessions: Type[TradeSessions] = TradeSessions.Intraday) -> List[Candlestick]:
+        """
+        Get security candlesticks
+        """
+
+    async def history_candlesticks_by_offset(self, symbol: str, period: Type[Period], adjust_type: Type[AdjustType], forward: bool, count: int, time: Optional[datetime] = None, trade_sessions: Type[TradeSessions] = TradeSessions.Intraday) -> List[Candlestick]:
+        """
+        Get security history candlesticks by offset
+        """
+
+    async def history_candlesticks_by_date(self, symbol: str, period: Type[Period], adjust_type: Type[AdjustType], start: Optional[date], end: Optional[date], trade_sessions: Type[TradeSessions] = TradeSessions.Intraday) -> List[Candlestick]:
+        """
+        Get security history candlesticks by date
+        """
+
+    async def option_chain_expiry_date_list(self, symbol: str) -> List[date]:
+        """
+        Get option chain expiry date list
+        """
+
+    async def option_chain_info_by_date(self, symbol: str, expiry_date: date) -> List[StrikePriceInfo]:
+        """
+        Get option chain info by date
+        """
+
+    async def warrant_issuers(self) -> List[IssuerInfo]:
+        """
+        Get warrant issuers
+        """
+
+    async def warrant_list(self, symbol: str, sort_by: Type[WarrantSortBy], sort_order: Type[SortOrderType], warrant_type: Optional[List[Type[WarrantType]]] = None, issuer: Optional[List[int]] = None, expiry_date: Optional[List[Type[FilterWarrantExpiryDate]]] = None, price_type: Optional[List[Type[FilterWarrantInOutBoundsType]]] = None, status: Optional[List[Type[WarrantStatus]]] = None) -> List[WarrantInfo]:
+        """
+        Get warrant list
+        """
+
+    async def warrant_screener(self, symbol: str, sort_by: Type[WarrantSortBy], sort_order: Type[SortOrderType], warrant_type: Optional[List[Type[WarrantType]]] = None, issuer: Optional[List[int]] = None, expiry_date: Optional[List[Type[FilterWarrantExpiryDate]]] = None, price_type: Optional[List[Type[FilterWarrantInOutBoundsType]]] = None, status: Optional[List[Type[WarrantStatus]]] = None, offset: int = 0, count: int = 0) -> WarrantList:
+        """
+        Get a page of the warrants matching the filter
+        """
+
+    async def trading_session(self) -> List[MarketTradingSession]:
+        """
+        Get trading session of the day
+        """
+
+    async def trading_days(self, market: Type[Market], begin: date, end: date) -> MarketTradingDays:
+        """
+        Get trading session of the day
+        """
+
+    async def capital_flow(self, symbol: str) -> List[CapitalFlowLine]:
+        """
+        Get capital flow intraday
+        """
+
+    async def capital_distribution(self, symbol: str) -> CapitalDistributionResponse:
+        """
+        Get capital distribution
+        """
+
+    async def calc_indexes(self, symbols: List[str], indexes: List[Type[CalcIndex]]) -> List[SecurityCalcIndex]:
+        """
+        Get calc indexes
+        """
+
+    async def watchlist(self) -> List[WatchlistGroup]:
+        """
+        Get watch list
+        """
+
+    async def create_watchlist_group(self, name: str, securities: Optional[List[str]] = None) -> int:
+        """
+        Create watchlist group
+        """
+
+    async def delete_watchlist_group(self, id: int, purge: bool = False) -> None:
+        """
+        Delete watchlist group
+        """
+
+    async def update_watchlist_group(self, id: int, name: Optional[str] = None, securities: Optional[List[str]] = None, mode: Optional[Type[SecuritiesUpdateMode]] = None) -> None:
+        """
+        Update watchlist group
+        """
+
+    async def security_list(self, market: Type[Market], category: Optional[Type[SecurityListCategory]] = None) -> List[Security]:
+        """
+        Get security list
+        """
+
+    async def market_temperature(self, market: Type[Market]) -> MarketTemperature:
+        """
+        Get current market temperature
+        """
+
+    async def history_market_temperature(self, market: Type[Market], start: date, end: date) -> HistoryMarketTemperatureResponse:
+        """
+        Get historical market temperature
+        """
+
+    async def realtime_quote(self, symbols: List[str]) -> List[RealtimeQuote]:
+        """
+        Get real-time quote
+        """
+
+    async def realtime_depth(self, symbol: str) -> SecurityDepth:
+        """
+        Get real-time depth
+        """
+
+    async def realtime_brokers(self, symbol: str) -> SecurityBrokers:
+        """
+        Get real-time brokers
+        """
+
+    async def realtime_trades(self, symbol: str, count: int) -> List[Trade]:
+        """
+        Get real-time trades
+        """
+
+    async def realtime_candlesticks(self, symbol: str, period: Type[Period], count: int) -> List[Candlestick]:
+        """
+        Get real-time candlesticks
+        """
+
+
 class OrderSide:
     """
     Order side
@@ -4965,6 +5394,11 @@ class TradeContext:
 
     def __init__(self, config: Config) -> None: ...
 
+    def set_on_connection_event(self, callback: Callable[[ConnectionEvent], None]) -> None:
+        """
+        Set connection event callback, it will be called when the state of the connection changes.
+        """
+
     def set_on_order_changed(self, callback: Callable[[PushOrderChanged], None]) -> None:
         """
         Set order changed callback, after receiving the order changed event, it will call back to this function.
@@ -5395,3 +5829,124 @@ class TradeContext:
                 )
                 print(resp)
         """
+
+
+class AsyncTradeContext:
+    """
+    Trade context for asyncio
+
+    The methods return awaitables and the pushes are received from the
+    asynchronous iterators.
+
+    Examples:
+        ::
+
+            import asyncio
+            from longport.openapi import AsyncTradeContext, Config, TopicType
+
+            async def main():
+                config = Config.from_env()
+                ctx = await AsyncTradeContext.create(config)
+                stream = ctx.order_changed_stream()
+                await ctx.subscribe([TopicType.Private])
+                async for event in stream:
+                    print(event)
+
+            asyncio.run(main())
+    """
+
+    @staticmethod
+    async def create(config: Config) -> AsyncTradeContext:
+        """
+        Create a trade context
+        """
+
+    def connection_events(self) -> PushStream[ConnectionEvent]:
+        """
+        Returns an asynchronous iterator of the connection events
+        """
+
+    def order_changed_stream(self) -> PushStream[PushOrderChanged]:
+        """
+        Returns an asynchronous iterator of the order changed events, the iterators returned by the multiple calls share the same events
+        """
+
+    async def subscribe(self, topics: List[Type[TopicType]]) -> None:
+        """
+        Subscribe
+        """
+
+    async def unsubscribe(self, topics: List[Type[TopicType]]) -> None:
+        """
+        Unsubscribe
+        """
+
+    async def history_executions(self, symbol: Optional[str] = None, start_at: Optional[datetime] = None, end_at: Optional[datetime] = None) -> List[Execution]:
+        """
+        Get history executions
+        """
+
+    async def today_executions(self, symbol: Optional[str] = None, order_id: Optional[str] = None) -> List[Execution]:
+        """
+        Get today executions
+        """
+
+    async def history_orders(self, symbol: Optional[str] = None, status: Optional[List[Type[OrderStatus]]] = None, side: Optional[Type[OrderSide]] = None, market: Optional[Type[Market]] = None, start_at: Optional[datetime] = None, end_at: Optional[datetime] = None) -> List[Order]:
+        """
+        Get history orders
+        """
+
+    async def today_orders(self, symbol: Optional[str] = None, status: Optional[List[Type[OrderStatus]]] = None, side: Optional[Type[OrderSide]] = None, market: Optional[Type[Market]] = None, order_id: Optional[str] = None) -> List[Order]:
+        """
+        Get today orders
+        """
+
+    async def replace_order(self, order_id: str, quantity: Decimal, price: Optional[Decimal] = None, trigger_price: Optional[Decimal] = None, limit_offset: Optional[Decimal] = None, trailing_amount: Optional[Decimal] = None, trailing_percent: Optional[Decimal] = None, remark: Optional[str] = None) -> None:
+        """
+        Replace order
+        """
+
+    async def submit_order(self, symbol: str, order_type: Type[OrderType], side: Type[OrderSide], submitted_quantity: Decimal, time_in_force: Type[TimeInForceType], submitted_price: Optional[Decimal] = None,  trigger_price: Optional[Decimal] = None, limit_offset: Optional[Decimal] = None, trailing_amount: Optional[Decimal] = None, trailing_percent: Optional[Decimal] = None, expire_date: Optional[date] = None,  outside_rth: Optional[Type[OutsideRTH]] = None,  remark: Optional[str] = None) -> SubmitOrderResponse:
+        """
+        Submit order
+        """
+
+    async def cancel_order(self, order_id: str) -> None:
+        """
+        Cancel order
+        """
+
+    async def account_balance(self, currency: Optional[str] = None) -> List[AccountBalance]:
+        """
+        Get account balance
+        """
+
+    async def cash_flow(self, start_at: datetime, end_at: datetime, business_type: Optional[Type[BalanceType]] = None, symbol: Optional[str] = None, page: Optional[int] = None, size: Optional[int] = None) -> List[CashFlow]:
+        """
+        Get cash flow
+        """
+
+    async def fund_positions(self, symbols: Optional[List[str]] = None) -> FundPositionsResponse:
+        """
+        Get fund positions
+        """
+
+    async def stock_positions(self, symbols: Optional[List[str]] = None) -> StockPositionsResponse:
+        """
+        Get stock positions
+        """
+
+    async def margin_ratio(self, symbol: str) -> MarginRatio:
+        """
+        Get margin ratio
+        """
+
+    async def order_detail(self, order_id: str) -> OrderDetail:
+        """
+        Get order detail
+        """
+
+    async def estimate_max_purchase_quantity(self, symbol: str, order_type: Type[OrderType], side: Type[OrderSide], price: Optional[Decimal] = None, currency: Optional[str] = None, order_id: Optional[str] = None, fractional_shares: bool = False) -> EstimateMaxPurchaseQuantityResponse:
+        """
+        Estimating the maximum purchase quantity for Hong Kong and US stocks, warrants, and options
+        """
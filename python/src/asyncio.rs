@@ -0,0 +1,97 @@
+use std::{cell::OnceCell, future::Future, pin::Pin, sync::OnceLock, time::Duration};
+
+use parking_lot::Mutex;
+use pyo3::{prelude::*, sync::GILOnceCell};
+use pyo3_async_runtimes::{
+    TaskLocals,
+    generic::{ContextExt, Runtime as GenericRuntime},
+};
+use tokio::{
+    runtime::{Handle, Runtime},
+    task::{JoinError, JoinHandle},
+};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+static HANDLE: OnceLock<Handle> = OnceLock::new();
+static ATEXIT: GILOnceCell<()> = GILOnceCell::new();
+
+tokio::task_local! {
+    static TASK_LOCALS: OnceCell<TaskLocals>;
+}
+
+/// The tokio runtime of the asyncio contexts, it is shut down by an `atexit`
+/// handler so its threads do not touch the interpreter while it is finalized
+struct AsyncioRuntime;
+
+impl GenericRuntime for AsyncioRuntime {
+    type JoinError = JoinError;
+    type JoinHandle = JoinHandle<()>;
+
+    fn spawn<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        handle().spawn(fut)
+    }
+}
+
+impl ContextExt for AsyncioRuntime {
+    fn scope<F, R>(locals: TaskLocals, fut: F) -> Pin<Box<dyn Future<Output = R> + Send>>
+    where
+        F: Future<Output = R> + Send + 'static,
+    {
+        let cell = OnceCell::new();
+        _ = cell.set(locals);
+        Box::pin(TASK_LOCALS.scope(cell, fut))
+    }
+
+    fn get_task_locals() -> Option<TaskLocals> {
+        TASK_LOCALS
+            .try_with(|cell| {
+                cell.get()
+                    .map(|locals| Python::with_gil(|py| locals.clone_ref(py)))
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn handle() -> &'static Handle {
+    HANDLE.get_or_init(|| {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the tokio runtime");
+        let handle = runtime.handle().clone();
+        *RUNTIME.lock() = Some(runtime);
+        handle
+    })
+}
+
+/// Shuts down the runtime, the tasks are dropped and the blocking tasks are
+/// waited for at most `SHUTDOWN_TIMEOUT`
+///
+/// The GIL is released so the threads can finish setting the results of the
+/// awaitables. The tasks spawned later are cancelled immediately.
+#[pyfunction]
+fn shutdown(py: Python<'_>) {
+    if let Some(runtime) = RUNTIME.lock().take() {
+        py.allow_threads(|| runtime.shutdown_timeout(SHUTDOWN_TIMEOUT));
+    }
+}
+
+/// Converts a Rust future into an awaitable running on the runtime of the
+/// asyncio contexts
+pub(crate) fn future_into_py<F, T>(py: Python<'_>, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: for<'py> IntoPyObject<'py>,
+{
+    ATEXIT.get_or_try_init(py, || {
+        py.import("atexit")?
+            .call_method1("register", (wrap_pyfunction!(shutdown, py)?,))?;
+        Ok::<_, PyErr>(())
+    })?;
+    pyo3_async_runtimes::generic::future_into_py::<AsyncioRuntime, F, T>(py, fut)
+}
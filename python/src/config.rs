@@ -1,4 +1,4 @@
-use pyo3::{prelude::*, types::PyType};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyType};
 
 use crate::{
     error::ErrorNewType,
@@ -24,6 +24,11 @@ impl Config {
         push_candlestick_mode = PushCandlestickMode::Realtime,
         enable_print_quote_packages = true,
         log_path = None,
+        paper_trading = false,
+        tls_root_certificates = None,
+        tls_pins = Vec::new(),
+        tls_client_cert = None,
+        tls_client_key = None,
     ))]
     #[allow(clippy::too_many_arguments)]
     fn py_new(
@@ -38,7 +43,12 @@ impl Config {
         push_candlestick_mode: PushCandlestickMode,
         enable_print_quote_packages: bool,
         log_path: Option<String>,
-    ) -> Self {
+        paper_trading: bool,
+        tls_root_certificates: Option<String>,
+        tls_pins: Vec<String>,
+        tls_client_cert: Option<String>,
+        tls_client_key: Option<String>,
+    ) -> PyResult<Self> {
         let mut config = longport::Config::new(app_key, app_secret, access_token);
 
         if let Some(http_url) = http_url {
@@ -65,8 +75,41 @@ impl Config {
         if let Some(log_path) = log_path {
             config = config.log_path(log_path);
         }
+        if paper_trading {
+            config = config.paper_trading();
+        }
+
+        if tls_root_certificates.is_some()
+            || !tls_pins.is_empty()
+            || tls_client_cert.is_some()
+            || tls_client_key.is_some()
+        {
+            let mut tls_config = longport::TlsConfig::new();
+            if let Some(pem) = tls_root_certificates {
+                tls_config = tls_config
+                    .add_root_certificates(pem)
+                    .map_err(ErrorNewType)?;
+            }
+            for pin in tls_pins {
+                tls_config = tls_config.pin_certificate(pin).map_err(ErrorNewType)?;
+            }
+            match (tls_client_cert, tls_client_key) {
+                (Some(cert), Some(key)) => {
+                    tls_config = tls_config
+                        .client_identity(cert, key)
+                        .map_err(ErrorNewType)?;
+                }
+                (None, None) => {}
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "tls_client_cert and tls_client_key must be set together",
+                    ));
+                }
+            }
+            config = config.tls_config(tls_config).map_err(ErrorNewType)?;
+        }
 
-        Self(config)
+        Ok(Self(config))
     }
 
     #[classmethod]
@@ -20,9 +20,12 @@ impl HttpClient {
         app_secret: String,
         access_token: String,
     ) -> PyResult<Self> {
-        Ok(Self(LbHttpClient::new(
-            HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
-        )))
+        Ok(Self(
+            LbHttpClient::new(
+                HttpClientConfig::new(app_key, app_secret, access_token).http_url(http_url),
+            )
+            .map_err(|err| ErrorNewType(longport::Error::HttpClient(err)))?,
+        ))
     }
 
     #[classmethod]
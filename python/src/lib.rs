@@ -1,8 +1,10 @@
+mod asyncio;
 mod config;
 mod decimal;
 mod error;
 mod http_client;
 mod quote;
+mod stream;
 mod time;
 mod trade;
 mod types;
@@ -17,7 +19,10 @@ fn longport(py: Python<'_>, m: Bound<PyModule>) -> PyResult<()> {
     openapi.add_class::<types::Language>()?;
     openapi.add_class::<types::Market>()?;
     openapi.add_class::<types::PushCandlestickMode>()?;
+    openapi.add_class::<types::ConnectionEventKind>()?;
+    openapi.add_class::<types::ConnectionEvent>()?;
     openapi.add_class::<http_client::HttpClient>()?;
+    openapi.add_class::<stream::PushStream>()?;
     quote::register_types(&openapi)?;
     trade::register_types(&openapi)?;
 
@@ -0,0 +1,837 @@
+use std::sync::Arc;
+
+use futures_util::stream;
+use longport::quote::{RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup};
+use pyo3::{IntoPyObjectExt, prelude::*};
+use time::PrimitiveDateTime;
+
+use crate::{
+    asyncio::future_into_py,
+    config::Config,
+    error::ErrorNewType,
+    quote::types::{
+        AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
+        FilterWarrantExpiryDate, FilterWarrantInOutBoundsType, HistoryMarketTemperatureResponse,
+        IntradayLine, IssuerInfo, MarketTemperature, MarketTradingDays, MarketTradingSession,
+        OptionQuote, ParticipantInfo, Period, PushBrokers, PushCandlestick, PushDepth, PushQuote,
+        PushTrades, QuotePackageDetail, RealtimeQuote, SecuritiesUpdateMode, Security,
+        SecurityBrokers, SecurityCalcIndex, SecurityDepth, SecurityListCategory, SecurityQuote,
+        SecurityStaticInfo, SortOrderType, StrikePriceInfo, SubType, SubTypes, Subscription, Trade,
+        TradeSessions, WarrantInfo, WarrantList, WarrantQuote, WarrantSortBy, WarrantStatus,
+        WarrantType, WatchlistGroup,
+    },
+    stream::PushStream,
+    time::{PyDateWrapper, PyOffsetDateTimeWrapper},
+    types::{ConnectionEvent, Market},
+};
+
+/// Quote context for asyncio, the methods return awaitables and the pushes
+/// are received from the asynchronous iterators
+#[pyclass]
+pub(crate) struct AsyncQuoteContext {
+    ctx: longport::quote::QuoteContext,
+}
+
+#[pymethods]
+impl AsyncQuoteContext {
+    /// Create an `AsyncQuoteContext`
+    #[staticmethod]
+    fn create<'py>(py: Python<'py>, config: &Config) -> PyResult<Bound<'py, PyAny>> {
+        let config = Arc::new(config.0.clone());
+        future_into_py(py, async move {
+            // the pushes are received from the typed streams
+            let (ctx, _) = longport::quote::QuoteContext::try_new(config)
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(AsyncQuoteContext { ctx })
+        })
+    }
+
+    /// Returns the member ID
+    fn member_id(&self) -> i64 {
+        self.ctx.member_id()
+    }
+
+    /// Returns the quote level
+    fn quote_level(&self) -> String {
+        self.ctx.quote_level().to_string()
+    }
+
+    /// Returns the quote package details
+    fn quote_package_details(&self) -> PyResult<Vec<QuotePackageDetail>> {
+        self.ctx
+            .quote_package_details()
+            .iter()
+            .cloned()
+            .map(TryInto::try_into)
+            .collect()
+    }
+
+    /// Returns an asynchronous iterator of the connection events
+    fn connection_events(&self) -> PushStream {
+        let rx = self.ctx.connection_events();
+        PushStream::new(
+            stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|event| (event, rx))
+            }),
+            |py, event| ConnectionEvent::from(event).into_py_any(py),
+        )
+    }
+
+    /// Returns an asynchronous iterator of the quote pushes of `symbols`
+    fn quote_stream(&self, symbols: Vec<String>) -> PushStream {
+        PushStream::new(self.ctx.quote_stream(symbols), |py, (symbol, quote)| {
+            (symbol, PushQuote::try_from(quote)?).into_py_any(py)
+        })
+    }
+
+    /// Returns an asynchronous iterator of the depth pushes of `symbols`
+    fn depth_stream(&self, symbols: Vec<String>) -> PushStream {
+        PushStream::new(self.ctx.depth_stream(symbols), |py, (symbol, depth)| {
+            (symbol, PushDepth::try_from(depth)?).into_py_any(py)
+        })
+    }
+
+    /// Returns an asynchronous iterator of the brokers pushes of `symbols`
+    fn brokers_stream(&self, symbols: Vec<String>) -> PushStream {
+        PushStream::new(self.ctx.brokers_stream(symbols), |py, (symbol, brokers)| {
+            (symbol, PushBrokers::try_from(brokers)?).into_py_any(py)
+        })
+    }
+
+    /// Returns an asynchronous iterator of the trades pushes of `symbols`
+    fn trades_stream(&self, symbols: Vec<String>) -> PushStream {
+        PushStream::new(self.ctx.trades_stream(symbols), |py, (symbol, trades)| {
+            (symbol, PushTrades::try_from(trades)?).into_py_any(py)
+        })
+    }
+
+    /// Returns an asynchronous iterator of the candlestick pushes of `symbol`
+    /// in `period`
+    fn candlestick_stream(&self, symbol: String, period: Period) -> PushStream {
+        PushStream::new(
+            self.ctx.candlestick_stream(&symbol, period.into()),
+            move |py, candlestick| {
+                (symbol.clone(), PushCandlestick::try_from(candlestick)?).into_py_any(py)
+            },
+        )
+    }
+
+    /// Subscribe
+    #[pyo3(signature = (symbols, sub_types, is_first_push = false))]
+    fn subscribe<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+        sub_types: Vec<SubType>,
+        is_first_push: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.subscribe(symbols, SubTypes(sub_types), is_first_push)
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Unsubscribe
+    fn unsubscribe<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+        sub_types: Vec<SubType>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.unsubscribe(symbols, SubTypes(sub_types))
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Subscribe security candlesticks
+    #[pyo3(signature = (symbol, period, trade_sessions = TradeSessions::Intraday))]
+    fn subscribe_candlesticks<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+        trade_sessions: TradeSessions,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.subscribe_candlesticks(symbol, period.into(), trade_sessions.into())
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Candlestick>>>()
+        })
+    }
+
+    /// Unsubscribe security candlesticks
+    fn unsubscribe_candlesticks<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.unsubscribe_candlesticks(symbol, longport::quote::Period::from(period))
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Get subscription information
+    fn subscriptions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.subscriptions()
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Subscription>>>()
+        })
+    }
+
+    /// Get basic information of securities
+    fn static_info<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.static_info(symbols)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<SecurityStaticInfo>>>()
+        })
+    }
+
+    /// Get quote of securities
+    fn quote<'py>(&self, py: Python<'py>, symbols: Vec<String>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.quote(symbols)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<SecurityQuote>>>()
+        })
+    }
+
+    /// Get quote of option securities
+    fn option_quote<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.option_quote(symbols)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<OptionQuote>>>()
+        })
+    }
+
+    /// Get quote of warrant securities
+    fn warrant_quote<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.warrant_quote(symbols)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<WarrantQuote>>>()
+        })
+    }
+
+    /// Get security depth
+    fn depth<'py>(&self, py: Python<'py>, symbol: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            SecurityDepth::try_from(ctx.depth(symbol).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get security brokers
+    fn brokers<'py>(&self, py: Python<'py>, symbol: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            SecurityBrokers::try_from(ctx.brokers(symbol).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get participants
+    fn participants<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.participants()
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<ParticipantInfo>>>()
+        })
+    }
+
+    /// Get security trades
+    fn trades<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        count: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.trades(symbol, count)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Trade>>>()
+        })
+    }
+
+    /// Get security intraday
+    #[pyo3(signature = (symbol, trade_sessions = TradeSessions::Intraday))]
+    fn intraday<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        trade_sessions: TradeSessions,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.intraday(symbol, trade_sessions.into())
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<IntradayLine>>>()
+        })
+    }
+
+    /// Get security candlesticks
+    #[pyo3(signature = (symbol, period, count, adjust_type, trade_sessions = TradeSessions::Intraday))]
+    fn candlesticks<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+        count: usize,
+        adjust_type: AdjustType,
+        trade_sessions: TradeSessions,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.candlesticks(
+                symbol,
+                period.into(),
+                count,
+                adjust_type.into(),
+                trade_sessions.into(),
+            )
+            .await
+            .map_err(ErrorNewType)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<PyResult<Vec<Candlestick>>>()
+        })
+    }
+
+    /// Get security history candlesticks by offset
+    #[pyo3(signature = (symbol, period, adjust_type, forward, count, time = None, trade_sessions = TradeSessions::Intraday))]
+    #[allow(clippy::too_many_arguments)]
+    fn history_candlesticks_by_offset<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+        adjust_type: AdjustType,
+        forward: bool,
+        count: usize,
+        time: Option<PyOffsetDateTimeWrapper>,
+        trade_sessions: TradeSessions,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.history_candlesticks_by_offset(
+                symbol,
+                period.into(),
+                adjust_type.into(),
+                forward,
+                time.map(|time| PrimitiveDateTime::new(time.0.date(), time.0.time())),
+                count,
+                trade_sessions.into(),
+            )
+            .await
+            .map_err(ErrorNewType)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<PyResult<Vec<Candlestick>>>()
+        })
+    }
+
+    /// Get security history candlesticks by date
+    #[pyo3(signature = (symbol, period, adjust_type, start = None, end = None, trade_sessions = TradeSessions::Intraday))]
+    #[allow(clippy::too_many_arguments)]
+    fn history_candlesticks_by_date<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+        adjust_type: AdjustType,
+        start: Option<PyDateWrapper>,
+        end: Option<PyDateWrapper>,
+        trade_sessions: TradeSessions,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.history_candlesticks_by_date(
+                symbol,
+                period.into(),
+                adjust_type.into(),
+                start.map(|d| d.0),
+                end.map(|d| d.0),
+                trade_sessions.into(),
+            )
+            .await
+            .map_err(ErrorNewType)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<PyResult<Vec<Candlestick>>>()
+        })
+    }
+
+    /// Get option chain expiry date list
+    fn option_chain_expiry_date_list<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            Ok(ctx
+                .option_chain_expiry_date_list(symbol)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(PyDateWrapper::from)
+                .collect::<Vec<_>>())
+        })
+    }
+
+    /// Get option chain info by date
+    fn option_chain_info_by_date<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        expiry_date: PyDateWrapper,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.option_chain_info_by_date(symbol, expiry_date.0)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<StrikePriceInfo>>>()
+        })
+    }
+
+    /// Get warrant issuers
+    fn warrant_issuers<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.warrant_issuers()
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<IssuerInfo>>>()
+        })
+    }
+
+    /// Query warrant list
+    #[pyo3(signature = (symbol, sort_by, sort_order, warrant_type = None, issuer = None, expiry_date = None, price_type = None, status = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn warrant_list<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrderType,
+        warrant_type: Option<Vec<WarrantType>>,
+        issuer: Option<Vec<i32>>,
+        expiry_date: Option<Vec<FilterWarrantExpiryDate>>,
+        price_type: Option<Vec<FilterWarrantInOutBoundsType>>,
+        status: Option<Vec<WarrantStatus>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let warrant_type: Option<Vec<longport::quote::WarrantType>> =
+            warrant_type.map(|v| v.into_iter().map(Into::into).collect());
+        let expiry_date: Option<Vec<longport::quote::FilterWarrantExpiryDate>> =
+            expiry_date.map(|v| v.into_iter().map(Into::into).collect());
+        let price_type: Option<Vec<longport::quote::FilterWarrantInOutBoundsType>> =
+            price_type.map(|v| v.into_iter().map(Into::into).collect());
+        let status: Option<Vec<longport::quote::WarrantStatus>> =
+            status.map(|v| v.into_iter().map(Into::into).collect());
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.warrant_list(
+                symbol,
+                sort_by.into(),
+                sort_order.into(),
+                warrant_type.as_deref(),
+                issuer.as_deref(),
+                expiry_date.as_deref(),
+                price_type.as_deref(),
+                status.as_deref(),
+            )
+            .await
+            .map_err(ErrorNewType)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<PyResult<Vec<WarrantInfo>>>()
+        })
+    }
+
+    /// Query a page of the warrants matching the filter
+    #[pyo3(signature = (symbol, sort_by, sort_order, warrant_type = None, issuer = None, expiry_date = None, price_type = None, status = None, offset = 0, count = 0))]
+    #[allow(clippy::too_many_arguments)]
+    fn warrant_screener<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrderType,
+        warrant_type: Option<Vec<WarrantType>>,
+        issuer: Option<Vec<i32>>,
+        expiry_date: Option<Vec<FilterWarrantExpiryDate>>,
+        price_type: Option<Vec<FilterWarrantInOutBoundsType>>,
+        status: Option<Vec<WarrantStatus>>,
+        offset: i32,
+        count: i32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let filter = longport::quote::WarrantFilter::new(symbol, sort_by.into(), sort_order.into())
+            .warrant_type(warrant_type.unwrap_or_default().into_iter().map(Into::into))
+            .issuer(issuer.unwrap_or_default())
+            .expiry_date(expiry_date.unwrap_or_default().into_iter().map(Into::into))
+            .price_type(price_type.unwrap_or_default().into_iter().map(Into::into))
+            .status(status.unwrap_or_default().into_iter().map(Into::into))
+            .offset(offset)
+            .count(count);
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            WarrantList::try_from(ctx.warrant_screener(filter).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get trading session of the day
+    fn trading_session<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.trading_session()
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<MarketTradingSession>>>()
+        })
+    }
+
+    /// Get trading days
+    fn trading_days<'py>(
+        &self,
+        py: Python<'py>,
+        market: Market,
+        begin: PyDateWrapper,
+        end: PyDateWrapper,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            MarketTradingDays::try_from(
+                ctx.trading_days(market.into(), begin.0, end.0)
+                    .await
+                    .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get capital flow intraday
+    fn capital_flow<'py>(&self, py: Python<'py>, symbol: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.capital_flow(symbol)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<CapitalFlowLine>>>()
+        })
+    }
+
+    /// Get capital distribution
+    fn capital_distribution<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            CapitalDistributionResponse::try_from(
+                ctx.capital_distribution(symbol)
+                    .await
+                    .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get calc indexes
+    fn calc_indexes<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+        indexes: Vec<CalcIndex>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.calc_indexes(
+                symbols,
+                indexes.into_iter().map(longport::quote::CalcIndex::from),
+            )
+            .await
+            .map_err(ErrorNewType)?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<PyResult<Vec<SecurityCalcIndex>>>()
+        })
+    }
+
+    /// Get watch list
+    fn watchlist<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.watchlist()
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<WatchlistGroup>>>()
+        })
+    }
+
+    /// Create watchlist group
+    #[pyo3(signature = (name, securities = None))]
+    fn create_watchlist_group<'py>(
+        &self,
+        py: Python<'py>,
+        name: String,
+        securities: Option<Vec<String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut req = RequestCreateWatchlistGroup::new(name);
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            Ok(ctx
+                .create_watchlist_group(req)
+                .await
+                .map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Delete watchlist group
+    #[pyo3(signature = (id, purge = false))]
+    fn delete_watchlist_group<'py>(
+        &self,
+        py: Python<'py>,
+        id: i64,
+        purge: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.delete_watchlist_group(id, purge)
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Update watchlist group
+    #[pyo3(signature = (id, name = None, securities = None, mode = None))]
+    fn update_watchlist_group<'py>(
+        &self,
+        py: Python<'py>,
+        id: i64,
+        name: Option<String>,
+        securities: Option<Vec<String>>,
+        mode: Option<SecuritiesUpdateMode>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut req = RequestUpdateWatchlistGroup::new(id);
+        if let Some(name) = name {
+            req = req.name(name);
+        }
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        if let Some(mode) = mode {
+            req = req.mode(mode.into());
+        }
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.update_watchlist_group(req)
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Get security list
+    #[pyo3(signature = (market, category = None))]
+    fn security_list<'py>(
+        &self,
+        py: Python<'py>,
+        market: Market,
+        category: Option<SecurityListCategory>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.security_list(market.into(), category.map(Into::into))
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Security>>>()
+        })
+    }
+
+    /// Get current market temperature
+    fn market_temperature<'py>(
+        &self,
+        py: Python<'py>,
+        market: Market,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            MarketTemperature::try_from(
+                ctx.market_temperature(market.into())
+                    .await
+                    .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get historical market temperature
+    fn history_market_temperature<'py>(
+        &self,
+        py: Python<'py>,
+        market: Market,
+        start_date: PyDateWrapper,
+        end: PyDateWrapper,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            HistoryMarketTemperatureResponse::try_from(
+                ctx.history_market_temperature(market.into(), start_date.0, end.0)
+                    .await
+                    .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get real-time quote
+    fn realtime_quote<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Vec<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.realtime_quote(symbols)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<RealtimeQuote>>>()
+        })
+    }
+
+    /// Get real-time depth
+    fn realtime_depth<'py>(&self, py: Python<'py>, symbol: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            SecurityDepth::try_from(ctx.realtime_depth(symbol).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get real-time brokers
+    fn realtime_brokers<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            SecurityBrokers::try_from(ctx.realtime_brokers(symbol).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get real-time trades
+    #[pyo3(signature = (symbol, count = 500))]
+    fn realtime_trades<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        count: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.realtime_trades(symbol, count)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Trade>>>()
+        })
+    }
+
+    /// Get real-time candlesticks
+    #[pyo3(signature = (symbol, period, count = 500))]
+    fn realtime_candlesticks<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        period: Period,
+        count: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.realtime_candlesticks(symbol, longport::quote::Period::from(period), count)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Candlestick>>>()
+        })
+    }
+}
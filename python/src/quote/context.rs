@@ -21,17 +21,18 @@ use crate::{
             QuotePackageDetail, RealtimeQuote, SecuritiesUpdateMode, Security, SecurityBrokers,
             SecurityCalcIndex, SecurityDepth, SecurityListCategory, SecurityQuote,
             SecurityStaticInfo, SortOrderType, StrikePriceInfo, SubType, SubTypes, Subscription,
-            Trade, TradeSessions, WarrantInfo, WarrantQuote, WarrantSortBy, WarrantStatus,
-            WarrantType, WatchlistGroup,
+            Trade, TradeSessions, WarrantInfo, WarrantList, WarrantQuote, WarrantSortBy,
+            WarrantStatus, WarrantType, WatchlistGroup,
         },
     },
     time::{PyDateWrapper, PyOffsetDateTimeWrapper},
-    types::Market,
+    types::{Market, handle_connection_event},
 };
 
 #[derive(Debug, Default)]
 pub(crate) struct Callbacks {
     pub(crate) quote: Option<PyObject>,
+    pub(crate) connection: Option<PyObject>,
     pub(crate) depth: Option<PyObject>,
     pub(crate) brokers: Option<PyObject>,
     pub(crate) trades: Option<PyObject>,
@@ -56,6 +57,11 @@ impl QuoteContext {
             }
         })
         .map_err(ErrorNewType)?;
+        ctx.on_connection_event({
+            let callbacks = callbacks.clone();
+            move |event| handle_connection_event(callbacks.lock().connection.as_ref(), event)
+        })
+        .map_err(ErrorNewType)?;
         Ok(Self { ctx, callbacks })
     }
 
@@ -79,6 +85,16 @@ impl QuoteContext {
             .collect()
     }
 
+    /// Set connection event callback, it will be called when the state of
+    /// the connection changes.
+    fn set_on_connection_event(&self, py: Python<'_>, callback: PyObject) {
+        if callback.is_none(py) {
+            self.callbacks.lock().connection = None;
+        } else {
+            self.callbacks.lock().connection = Some(callback);
+        }
+    }
+
     /// Set quote callback, after receiving the quote data push, it
     /// will call back to this function.
     fn set_on_quote(&self, py: Python<'_>, callback: PyObject) {
@@ -170,7 +186,7 @@ impl QuoteContext {
     /// Subscribe security candlesticks
     fn unsubscribe_candlesticks(&self, symbol: String, period: Period) -> PyResult<()> {
         self.ctx
-            .unsubscribe_candlesticks(symbol, period.into())
+            .unsubscribe_candlesticks(symbol, longport::quote::Period::from(period))
             .map_err(ErrorNewType)?;
         Ok(())
     }
@@ -423,6 +439,36 @@ impl QuoteContext {
             .collect()
     }
 
+    /// Query a page of the warrants matching the filter
+    #[pyo3(signature = (symbol, sort_by, sort_order, warrant_type = None, issuer = None, expiry_date = None, price_type = None, status = None, offset = 0, count = 0))]
+    #[allow(clippy::too_many_arguments)]
+    fn warrant_screener(
+        &self,
+        symbol: String,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrderType,
+        warrant_type: Option<Vec<WarrantType>>,
+        issuer: Option<Vec<i32>>,
+        expiry_date: Option<Vec<FilterWarrantExpiryDate>>,
+        price_type: Option<Vec<FilterWarrantInOutBoundsType>>,
+        status: Option<Vec<WarrantStatus>>,
+        offset: i32,
+        count: i32,
+    ) -> PyResult<WarrantList> {
+        let filter = longport::quote::WarrantFilter::new(symbol, sort_by.into(), sort_order.into())
+            .warrant_type(warrant_type.unwrap_or_default().into_iter().map(Into::into))
+            .issuer(issuer.unwrap_or_default())
+            .expiry_date(expiry_date.unwrap_or_default().into_iter().map(Into::into))
+            .price_type(price_type.unwrap_or_default().into_iter().map(Into::into))
+            .status(status.unwrap_or_default().into_iter().map(Into::into))
+            .offset(offset)
+            .count(count);
+        self.ctx
+            .warrant_screener(filter)
+            .map_err(ErrorNewType)?
+            .try_into()
+    }
+
     /// Get trading session of the day
     fn trading_session(&self) -> PyResult<Vec<MarketTradingSession>> {
         self.ctx
@@ -617,7 +663,7 @@ impl QuoteContext {
         count: usize,
     ) -> PyResult<Vec<Candlestick>> {
         self.ctx
-            .realtime_candlesticks(symbol, period.into(), count)
+            .realtime_candlesticks(symbol, longport::quote::Period::from(period), count)
             .map_err(ErrorNewType)?
             .into_iter()
             .map(TryInto::try_into)
@@ -1,3 +1,4 @@
+mod async_context;
 mod context;
 mod push;
 mod types;
@@ -42,6 +43,7 @@ pub(crate) fn register_types(parent: &Bound<PyModule>) -> PyResult<()> {
     parent.add_class::<types::WatchlistGroup>()?;
     parent.add_class::<types::SecuritiesUpdateMode>()?;
     parent.add_class::<types::WarrantInfo>()?;
+    parent.add_class::<types::WarrantList>()?;
     parent.add_class::<types::WarrantStatus>()?;
     parent.add_class::<types::WarrantType>()?;
     parent.add_class::<types::SortOrderType>()?;
@@ -60,5 +62,6 @@ pub(crate) fn register_types(parent: &Bound<PyModule>) -> PyResult<()> {
     parent.add_class::<types::SecurityBoard>()?;
 
     parent.add_class::<context::QuoteContext>()?;
+    parent.add_class::<async_context::AsyncQuoteContext>()?;
     Ok(())
 }
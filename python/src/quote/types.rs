@@ -839,6 +839,18 @@ pub(crate) struct WarrantInfo {
     status: WarrantStatus,
 }
 
+/// A page of the warrant screener
+#[pyclass]
+#[derive(Debug, Clone, PyObject)]
+#[py(remote = "longport::quote::WarrantList")]
+pub(crate) struct WarrantList {
+    /// Warrants
+    #[py(array)]
+    warrants: Vec<WarrantInfo>,
+    /// Total number of the warrants matching the filter
+    total_count: i32,
+}
+
 /// Warrant status
 #[pyclass(eq, eq_int)]
 #[derive(PyEnum, Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -0,0 +1,41 @@
+use std::{pin::Pin, sync::Arc};
+
+use futures_util::{Stream, StreamExt};
+use pyo3::{exceptions::PyStopAsyncIteration, prelude::*};
+use tokio::sync::Mutex;
+
+type BoxStream = Pin<Box<dyn Stream<Item = PyResult<PyObject>> + Send>>;
+
+/// Asynchronous iterator of the push events
+#[pyclass]
+pub(crate) struct PushStream(Arc<Mutex<BoxStream>>);
+
+impl PushStream {
+    /// Create a `PushStream`, `f` converts the items to the Python objects
+    pub(crate) fn new<S, F>(stream: S, f: F) -> Self
+    where
+        S: Stream + Send + 'static,
+        F: Fn(Python<'_>, S::Item) -> PyResult<PyObject> + Send + 'static,
+    {
+        Self(Arc::new(Mutex::new(Box::pin(
+            stream.map(move |item| Python::with_gil(|py| f(py, item))),
+        ))))
+    }
+}
+
+#[pymethods]
+impl PushStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.0.clone();
+        crate::asyncio::future_into_py(py, async move {
+            match stream.lock().await.next().await {
+                Some(item) => item,
+                None => Err(PyStopAsyncIteration::new_err(())),
+            }
+        })
+    }
+}
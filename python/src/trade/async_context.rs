@@ -0,0 +1,518 @@
+use std::sync::Arc;
+
+use futures_util::stream;
+use longport::{
+    PushReceiver,
+    trade::{
+        EstimateMaxPurchaseQuantityOptions, GetCashFlowOptions, GetFundPositionsOptions,
+        GetHistoryExecutionsOptions, GetHistoryOrdersOptions, GetStockPositionsOptions,
+        GetTodayExecutionsOptions, GetTodayOrdersOptions, PushEvent, ReplaceOrderOptions,
+        SubmitOrderOptions,
+    },
+};
+use pyo3::{IntoPyObjectExt, prelude::*};
+use tokio::sync::Mutex;
+
+use crate::{
+    asyncio::future_into_py,
+    config::Config,
+    decimal::PyDecimal,
+    error::ErrorNewType,
+    stream::PushStream,
+    time::{PyDateWrapper, PyOffsetDateTimeWrapper},
+    trade::types::{
+        AccountBalance, BalanceType, CashFlow, EstimateMaxPurchaseQuantityResponse, Execution,
+        FundPositionsResponse, MarginRatio, Order, OrderDetail, OrderSide, OrderStatus, OrderType,
+        OutsideRTH, PushOrderChanged, StockPositionsResponse, SubmitOrderResponse, TimeInForceType,
+        TopicType,
+    },
+    types::{ConnectionEvent, Market},
+};
+
+/// Trade context for asyncio, the methods return awaitables and the pushes
+/// are received from the asynchronous iterators
+#[pyclass]
+pub(crate) struct AsyncTradeContext {
+    ctx: longport::trade::TradeContext,
+    receiver: Arc<Mutex<PushReceiver<PushEvent>>>,
+}
+
+#[pymethods]
+impl AsyncTradeContext {
+    /// Create an `AsyncTradeContext`
+    #[staticmethod]
+    fn create<'py>(py: Python<'py>, config: &Config) -> PyResult<Bound<'py, PyAny>> {
+        let config = Arc::new(config.0.clone());
+        future_into_py(py, async move {
+            let (ctx, receiver) = longport::trade::TradeContext::try_new(config)
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(AsyncTradeContext {
+                ctx,
+                receiver: Arc::new(Mutex::new(receiver)),
+            })
+        })
+    }
+
+    /// Returns an asynchronous iterator of the connection events
+    fn connection_events(&self) -> PushStream {
+        let rx = self.ctx.connection_events();
+        PushStream::new(
+            stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|event| (event, rx))
+            }),
+            |py, event| ConnectionEvent::from(event).into_py_any(py),
+        )
+    }
+
+    /// Returns an asynchronous iterator of the order changed events
+    ///
+    /// The iterators returned by the multiple calls share the same events.
+    fn order_changed_stream(&self) -> PushStream {
+        PushStream::new(
+            stream::unfold(self.receiver.clone(), |receiver| async move {
+                let event = receiver.lock().await.recv().await;
+                event.map(|event| (event, receiver))
+            }),
+            |py, event| match event {
+                PushEvent::OrderChanged(order_changed) => {
+                    PushOrderChanged::try_from(order_changed)?.into_py_any(py)
+                }
+            },
+        )
+    }
+
+    /// Subscribe
+    fn subscribe<'py>(
+        &self,
+        py: Python<'py>,
+        topics: Vec<TopicType>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.subscribe(topics.into_iter().map(Into::into))
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Unsubscribe
+    fn unsubscribe<'py>(
+        &self,
+        py: Python<'py>,
+        topics: Vec<TopicType>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.unsubscribe(topics.into_iter().map(Into::into))
+                .await
+                .map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Get history executions
+    #[pyo3(signature = (symbol = None, start_at = None, end_at = None))]
+    fn history_executions<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: Option<String>,
+        start_at: Option<PyOffsetDateTimeWrapper>,
+        end_at: Option<PyOffsetDateTimeWrapper>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = GetHistoryExecutionsOptions::new();
+
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(start_at) = start_at {
+            opts = opts.start_at(start_at.0);
+        }
+        if let Some(end_at) = end_at {
+            opts = opts.end_at(end_at.0);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.history_executions(Some(opts))
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Execution>>>()
+        })
+    }
+
+    /// Get today executions
+    #[pyo3(signature = (symbol = None, order_id = None))]
+    fn today_executions<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: Option<String>,
+        order_id: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = GetTodayExecutionsOptions::new();
+
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.today_executions(Some(opts))
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Execution>>>()
+        })
+    }
+
+    /// Get history orders
+    #[pyo3(signature = (symbol = None, status = None, side = None, market = None, start_at = None, end_at = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn history_orders<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: Option<String>,
+        status: Option<Vec<OrderStatus>>,
+        side: Option<OrderSide>,
+        market: Option<Market>,
+        start_at: Option<PyOffsetDateTimeWrapper>,
+        end_at: Option<PyOffsetDateTimeWrapper>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = GetHistoryOrdersOptions::new();
+
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        opts = opts.status(status.unwrap_or_default().into_iter().map(Into::into));
+        if let Some(side) = side {
+            opts = opts.side(side.into());
+        }
+        if let Some(market) = market {
+            opts = opts.market(market.into());
+        }
+        if let Some(start_at) = start_at {
+            opts = opts.start_at(start_at.0);
+        }
+        if let Some(end_at) = end_at {
+            opts = opts.end_at(end_at.0);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.history_orders(Some(opts))
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Order>>>()
+        })
+    }
+
+    /// Get today orders
+    #[pyo3(signature = (symbol = None, status = None, side = None, market = None, order_id = None))]
+    fn today_orders<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: Option<String>,
+        status: Option<Vec<OrderStatus>>,
+        side: Option<OrderSide>,
+        market: Option<Market>,
+        order_id: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = GetTodayOrdersOptions::new();
+
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        opts = opts.status(status.unwrap_or_default().into_iter().map(Into::into));
+        if let Some(side) = side {
+            opts = opts.side(side.into());
+        }
+        if let Some(market) = market {
+            opts = opts.market(market.into());
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.today_orders(Some(opts))
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<Order>>>()
+        })
+    }
+
+    /// Replace order
+    #[pyo3(signature = (order_id, quantity, price = None, trigger_price = None, limit_offset = None, trailing_amount = None, trailing_percent = None, remark = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn replace_order<'py>(
+        &self,
+        py: Python<'py>,
+        order_id: String,
+        quantity: PyDecimal,
+        price: Option<PyDecimal>,
+        trigger_price: Option<PyDecimal>,
+        limit_offset: Option<PyDecimal>,
+        trailing_amount: Option<PyDecimal>,
+        trailing_percent: Option<PyDecimal>,
+        remark: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = ReplaceOrderOptions::new(order_id, quantity.into());
+
+        if let Some(price) = price {
+            opts = opts.price(price.into());
+        }
+        if let Some(trigger_price) = trigger_price {
+            opts = opts.trigger_price(trigger_price.into());
+        }
+        if let Some(limit_offset) = limit_offset {
+            opts = opts.limit_offset(limit_offset.into());
+        }
+        if let Some(trailing_amount) = trailing_amount {
+            opts = opts.trailing_amount(trailing_amount.into());
+        }
+        if let Some(trailing_percent) = trailing_percent {
+            opts = opts.trailing_percent(trailing_percent.into());
+        }
+        if let Some(remark) = remark {
+            opts = opts.remark(remark);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.replace_order(opts).await.map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Submit order
+    #[pyo3(signature = (symbol, order_type, side, submitted_quantity, time_in_force, submitted_price = None, trigger_price = None, limit_offset = None, trailing_amount = None, trailing_percent = None, expire_date = None, outside_rth = None, remark = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn submit_order<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        order_type: OrderType,
+        side: OrderSide,
+        submitted_quantity: PyDecimal,
+        time_in_force: TimeInForceType,
+        submitted_price: Option<PyDecimal>,
+        trigger_price: Option<PyDecimal>,
+        limit_offset: Option<PyDecimal>,
+        trailing_amount: Option<PyDecimal>,
+        trailing_percent: Option<PyDecimal>,
+        expire_date: Option<PyDateWrapper>,
+        outside_rth: Option<OutsideRTH>,
+        remark: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = SubmitOrderOptions::new(
+            symbol,
+            order_type.into(),
+            side.into(),
+            submitted_quantity.into(),
+            time_in_force.into(),
+        );
+
+        if let Some(submitted_price) = submitted_price {
+            opts = opts.submitted_price(submitted_price.into());
+        }
+        if let Some(trigger_price) = trigger_price {
+            opts = opts.trigger_price(trigger_price.into());
+        }
+        if let Some(limit_offset) = limit_offset {
+            opts = opts.limit_offset(limit_offset.into());
+        }
+        if let Some(trailing_amount) = trailing_amount {
+            opts = opts.trailing_amount(trailing_amount.into());
+        }
+        if let Some(trailing_percent) = trailing_percent {
+            opts = opts.trailing_percent(trailing_percent.into());
+        }
+        if let Some(expire_date) = expire_date {
+            opts = opts.expire_date(expire_date.0);
+        }
+        if let Some(outside_rth) = outside_rth {
+            opts = opts.outside_rth(outside_rth.into());
+        }
+        if let Some(remark) = remark {
+            opts = opts.remark(remark);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            SubmitOrderResponse::try_from(ctx.submit_order(opts).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Cancel order
+    fn cancel_order<'py>(&self, py: Python<'py>, order_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.cancel_order(order_id).await.map_err(ErrorNewType)?;
+            Ok(())
+        })
+    }
+
+    /// Get account balance
+    #[pyo3(signature = (currency = None))]
+    fn account_balance<'py>(
+        &self,
+        py: Python<'py>,
+        currency: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.account_balance(currency.as_deref())
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<AccountBalance>>>()
+        })
+    }
+
+    /// Get cash flow
+    #[pyo3(signature = (start_at, end_at, business_type = None, symbol = None, page = None, size = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn cash_flow<'py>(
+        &self,
+        py: Python<'py>,
+        start_at: PyOffsetDateTimeWrapper,
+        end_at: PyOffsetDateTimeWrapper,
+        business_type: Option<BalanceType>,
+        symbol: Option<String>,
+        page: Option<usize>,
+        size: Option<usize>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts = GetCashFlowOptions::new(start_at.0, end_at.0);
+
+        if let Some(business_type) = business_type {
+            opts = opts.business_type(business_type.into());
+        }
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(page) = page {
+            opts = opts.page(page);
+        }
+        if let Some(size) = size {
+            opts = opts.size(size);
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            ctx.cash_flow(opts)
+                .await
+                .map_err(ErrorNewType)?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<PyResult<Vec<CashFlow>>>()
+        })
+    }
+
+    /// Get fund positions
+    #[pyo3(signature = (symbols = None))]
+    fn fund_positions<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Option<Vec<String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            FundPositionsResponse::try_from(
+                ctx.fund_positions(
+                    GetFundPositionsOptions::new().symbols(symbols.unwrap_or_default()),
+                )
+                .await
+                .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get stock positions
+    #[pyo3(signature = (symbols = None))]
+    fn stock_positions<'py>(
+        &self,
+        py: Python<'py>,
+        symbols: Option<Vec<String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            StockPositionsResponse::try_from(
+                ctx.stock_positions(
+                    GetStockPositionsOptions::new().symbols(symbols.unwrap_or_default()),
+                )
+                .await
+                .map_err(ErrorNewType)?,
+            )
+        })
+    }
+
+    /// Get margin ratio
+    fn margin_ratio<'py>(&self, py: Python<'py>, symbol: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            MarginRatio::try_from(ctx.margin_ratio(symbol).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Get order detail
+    fn order_detail<'py>(&self, py: Python<'py>, order_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            OrderDetail::try_from(ctx.order_detail(order_id).await.map_err(ErrorNewType)?)
+        })
+    }
+
+    /// Estimating the maximum purchase quantity for Hong Kong and US stocks,
+    /// warrants, and options
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (symbol, order_type, side, price, currency = None, order_id = None, fractional_shares = false))]
+    fn estimate_max_purchase_quantity<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        order_type: OrderType,
+        side: OrderSide,
+        price: Option<PyDecimal>,
+        currency: Option<String>,
+        order_id: Option<String>,
+        fractional_shares: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut opts =
+            EstimateMaxPurchaseQuantityOptions::new(symbol, order_type.into(), side.into());
+
+        if let Some(price) = price {
+            opts = opts.price(price.into());
+        }
+        if let Some(currency) = currency {
+            opts = opts.currency(currency);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+        if fractional_shares {
+            opts = opts.fractional_shares();
+        }
+
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            EstimateMaxPurchaseQuantityResponse::try_from(
+                ctx.estimate_max_purchase_quantity(opts)
+                    .await
+                    .map_err(ErrorNewType)?,
+            )
+        })
+    }
+}
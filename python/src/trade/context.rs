@@ -25,12 +25,13 @@ use crate::{
             TopicType,
         },
     },
-    types::Market,
+    types::{Market, handle_connection_event},
 };
 
 #[derive(Debug, Default)]
 pub(crate) struct Callbacks {
     pub(crate) order_changed: Option<PyObject>,
+    pub(crate) connection: Option<PyObject>,
 }
 
 #[pyclass]
@@ -51,9 +52,24 @@ impl TradeContext {
             }
         })
         .map_err(ErrorNewType)?;
+        ctx.on_connection_event({
+            let callbacks = callbacks.clone();
+            move |event| handle_connection_event(callbacks.lock().connection.as_ref(), event)
+        })
+        .map_err(ErrorNewType)?;
         Ok(Self { ctx, callbacks })
     }
 
+    /// Set connection event callback, it will be called when the state of
+    /// the connection changes.
+    fn set_on_connection_event(&self, py: Python<'_>, callback: PyObject) {
+        if callback.is_none(py) {
+            self.callbacks.lock().connection = None;
+        } else {
+            self.callbacks.lock().connection = Some(callback);
+        }
+    }
+
     /// Set order changed callback, after receiving the order changed event, it
     /// will call back to this function.
     fn set_on_order_changed(&self, py: Python<'_>, callback: PyObject) {
@@ -1,3 +1,4 @@
+mod async_context;
 mod context;
 mod push;
 mod types;
@@ -30,5 +31,6 @@ pub(crate) fn register_types(parent: &Bound<PyModule>) -> PyResult<()> {
     parent.add_class::<types::FrozenTransactionFee>()?;
 
     parent.add_class::<context::TradeContext>()?;
+    parent.add_class::<async_context::AsyncTradeContext>()?;
     Ok(())
 }
@@ -42,3 +42,90 @@ pub(crate) enum PushCandlestickMode {
     /// Confirmed mode
     Confirmed,
 }
+
+/// Connection event kind
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub(crate) enum ConnectionEventKind {
+    /// Connecting to the server
+    Connecting,
+    /// The websocket connection has been established
+    Connected,
+    /// The first session has been created
+    Authenticated,
+    /// The session has been restored after a reconnection
+    Reconnected,
+    /// The subscriptions have been restored after a reconnection
+    Resubscribed,
+    /// The connection was lost or a reconnection attempt failed
+    Disconnected,
+    /// The reconnection retries ran out, the context is closed
+    GaveUp,
+}
+
+/// Connection state change event
+#[pyclass(get_all)]
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionEvent {
+    /// Event kind
+    kind: ConnectionEventKind,
+    /// Whether the previous session was reused (`Reconnected` only)
+    session_reused: bool,
+    /// Close code sent by the server (`Disconnected` only)
+    close_code: Option<u16>,
+    /// Close reason sent by the server (`Disconnected` only)
+    close_message: Option<String>,
+    /// Error message (`Disconnected` and `GaveUp` only)
+    error: Option<String>,
+}
+
+#[pymethods]
+impl ConnectionEvent {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl From<longport::ConnectionEvent> for ConnectionEvent {
+    fn from(event: longport::ConnectionEvent) -> Self {
+        let mut res = ConnectionEvent {
+            kind: ConnectionEventKind::Connecting,
+            session_reused: false,
+            close_code: None,
+            close_message: None,
+            error: None,
+        };
+        match event {
+            longport::ConnectionEvent::Connecting => {}
+            longport::ConnectionEvent::Connected => res.kind = ConnectionEventKind::Connected,
+            longport::ConnectionEvent::Authenticated => {
+                res.kind = ConnectionEventKind::Authenticated
+            }
+            longport::ConnectionEvent::Reconnected { session_reused } => {
+                res.kind = ConnectionEventKind::Reconnected;
+                res.session_reused = session_reused;
+            }
+            longport::ConnectionEvent::Resubscribed => res.kind = ConnectionEventKind::Resubscribed,
+            longport::ConnectionEvent::Disconnected { reason, error } => {
+                res.kind = ConnectionEventKind::Disconnected;
+                res.close_code = reason.as_ref().map(|reason| reason.code.into());
+                res.close_message = reason.map(|reason| reason.message);
+                res.error = Some(error);
+            }
+            longport::ConnectionEvent::GaveUp { error, .. } => {
+                res.kind = ConnectionEventKind::GaveUp;
+                res.error = Some(error);
+            }
+        }
+        res
+    }
+}
+
+pub(crate) fn handle_connection_event(
+    callback: Option<&PyObject>,
+    event: longport::ConnectionEvent,
+) {
+    if let Some(callback) = callback {
+        let _ = Python::with_gil(|py| callback.call1(py, (ConnectionEvent::from(event),)));
+    }
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashSet, ops::Add};
+use std::{
+    collections::HashSet,
+    ops::{Add, Sub},
+};
 
 use num_traits::Zero;
 use time::{Date, Duration, OffsetDateTime, Time, Weekday, macros::time};
@@ -133,39 +136,64 @@ impl Market {
             return None;
         }
 
-        let ts = ts.kind();
-
         let t = t.to_timezone(self.timezone);
+        let (time, trade_session, _) = self.find_trade_session(ts, half_days, t)?;
+
+        Some(match period {
+            Min_1 => t.replace_time(Time::from_hms(time.hour(), time.minute(), 0).ok()?),
+            Minutes(0) => return None,
+            Min_2 | Min_3 | Min_5 | Min_10 | Min_15 | Min_20 | Min_30 | Min_45 | Min_60
+            | Min_120 | Min_180 | Min_240 | Minutes(_) => {
+                let minutes = period.minutes() as i64;
+                let TradeSession { start, .. } = trade_session;
+                let start_minutes = start.hour() as i64 * 60 + start.minute() as i64;
+                let current_minutes = time.hour() as i64 * 60 + time.minute() as i64;
+                let offset_minutes = ((current_minutes - start_minutes) / minutes) * minutes;
+                t.replace_time(*start + Duration::minutes(offset_minutes))
+            }
+            Day | Week | Month | Quarter | Year | Days(_) => {
+                self.day_candlestick_time(period, t)?
+            }
+        })
+    }
+
+    /// Returns the time `t` (in the timezone of the market) clamped into its
+    /// trade session, the trade session and its index, or `None` if it is not
+    /// in any trade session
+    fn find_trade_session<H, TS>(
+        &self,
+        ts: TS,
+        half_days: H,
+        t: OffsetDateTime,
+    ) -> Option<(Time, &'static TradeSession, usize)>
+    where
+        H: Days,
+        TS: TradeSessionType,
+    {
+        let ts = ts.kind();
         let time = t.time();
         let trade_sessions = if !half_days.contains(t.date()) {
             self.trade_sessions.get(ts.0)?
         } else {
             self.half_trade_sessions.get(ts.0)?
         };
-        let res = trade_sessions.find_session(time);
-        let (time, n) = match res {
-            FindSessionResult::BeforeFirst => return None,
-            FindSessionResult::Between(n) => Some((time, n)),
+        match trade_sessions.find_session(time) {
+            FindSessionResult::BeforeFirst => None,
+            FindSessionResult::Between(n) => Some((time, &trade_sessions[n], n)),
             FindSessionResult::After(n) => {
                 if time >= trade_sessions[n].end + trade_sessions[n].timeout {
-                    return None;
+                    None
                 } else {
-                    Some((trade_sessions[n].end, n))
+                    Some((trade_sessions[n].end, &trade_sessions[n], n))
                 }
             }
-        }?;
+        }
+    }
+
+    fn day_candlestick_time(&self, period: Period, t: OffsetDateTime) -> Option<OffsetDateTime> {
+        use Period::*;
 
         Some(match period {
-            Min_1 => t.replace_time(Time::from_hms(time.hour(), time.minute(), 0).ok()?),
-            Min_2 | Min_3 | Min_5 | Min_10 | Min_15 | Min_20 | Min_30 | Min_45 | Min_60
-            | Min_120 | Min_180 | Min_240 => {
-                let minutes = period.minutes() as i64;
-                let TradeSession { start, .. } = &trade_sessions[n];
-                let start_minutes = start.hour() as i64 * 60 + start.minute() as i64;
-                let current_minutes = time.hour() as i64 * 60 + time.minute() as i64;
-                let offset_minutes = ((current_minutes - start_minutes) / minutes) * minutes;
-                t.replace_time(*start + Duration::minutes(offset_minutes))
-            }
             Day => t.replace_time(time!(00:00:00)),
             Week => {
                 let week = t.iso_week();
@@ -192,6 +220,7 @@ impl Market {
                 .replace_day(1)
                 .ok()?
                 .replace_time(time!(00:00:00)),
+            _ => return None,
         })
     }
 
@@ -309,6 +338,230 @@ impl Market {
         }
     }
 
+    /// Merges a confirmed candlestick of a lower period into the last
+    /// candlestick of `period`
+    ///
+    /// The candlesticks of the day periods are only built from the intraday
+    /// candlesticks, and the minute periods restart at the start of each trade
+    /// session, so a candlestick never spans a lunch break.
+    #[must_use]
+    pub fn merge_candlestick<H, TS, C, P, V, R>(
+        &self,
+        half_days: H,
+        period: Period,
+        input: Option<C>,
+        candlestick: &C,
+    ) -> UpdateAction<C>
+    where
+        H: Days,
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+        P: PartialOrd,
+        V: Add<Output = V>,
+        R: Add<Output = R>,
+    {
+        let trade_session = candlestick.trade_session();
+        let time = if period.is_minute() {
+            self.candlestick_time(trade_session, half_days, period, candlestick.time())
+        } else if trade_session.is_intraday() {
+            // the day candlesticks are not in any trade session
+            self.day_candlestick_time(period, candlestick.time().to_timezone(self.timezone))
+        } else {
+            None
+        };
+        let Some(time) = time else {
+            return UpdateAction::None;
+        };
+
+        match input {
+            Some(mut prev) if time == prev.time() => {
+                merge_into(&mut prev, candlestick);
+                UpdateAction::UpdateLast(prev)
+            }
+            None => UpdateAction::AppendNew {
+                confirmed: None,
+                new: new_from(time, candlestick),
+            },
+            Some(prev) if time > prev.time() => UpdateAction::AppendNew {
+                confirmed: Some(prev),
+                new: new_from(time, candlestick),
+            },
+            _ => UpdateAction::None,
+        }
+    }
+
+    /// Builds the candlesticks of `period` from the confirmed candlesticks of
+    /// a lower period in ascending order
+    ///
+    /// The candlesticks of [`Period::Days`] are built from the intraday
+    /// candlesticks, each of them covers the given number of trading days
+    /// counted from the first candlestick.
+    pub fn resample<H, TS, C, P, V, R>(
+        &self,
+        half_days: H,
+        period: Period,
+        candlesticks: impl IntoIterator<Item = C>,
+    ) -> Vec<C>
+    where
+        H: Days,
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>
+            + Clone,
+        P: PartialOrd,
+        V: Add<Output = V>,
+        R: Add<Output = R>,
+    {
+        if let Period::Days(days) = period {
+            return self.resample_days(days, candlesticks);
+        }
+
+        let mut output: Vec<C> = Vec::new();
+        for candlestick in candlesticks {
+            match self.merge_candlestick(half_days, period, output.last().cloned(), &candlestick) {
+                UpdateAction::UpdateLast(candlestick) => {
+                    if let Some(last) = output.last_mut() {
+                        *last = candlestick;
+                    }
+                }
+                UpdateAction::AppendNew { new, .. } => output.push(new),
+                UpdateAction::None => {}
+            }
+        }
+        output
+    }
+
+    fn resample_days<TS, C, P, V, R>(
+        &self,
+        days: u16,
+        candlesticks: impl IntoIterator<Item = C>,
+    ) -> Vec<C>
+    where
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+        P: PartialOrd,
+        V: Add<Output = V>,
+        R: Add<Output = R>,
+    {
+        let mut output: Vec<C> = Vec::new();
+        let mut last_day = None;
+        // the number of the trading days in the last candlestick
+        let mut count = 0;
+
+        for candlestick in candlesticks {
+            if days == 0 || !candlestick.trade_session().is_intraday() {
+                continue;
+            }
+            let Some(day) = self
+                .day_candlestick_time(Period::Day, candlestick.time().to_timezone(self.timezone))
+            else {
+                continue;
+            };
+            let new_day = last_day != Some(day);
+            last_day = Some(day);
+
+            match output.last_mut() {
+                Some(prev) if !new_day => merge_into(prev, &candlestick),
+                Some(prev) if count < days => {
+                    count += 1;
+                    merge_into(prev, &candlestick);
+                }
+                _ => {
+                    count = 1;
+                    output.push(new_from(day, &candlestick));
+                }
+            }
+        }
+        output
+    }
+
+    /// Builds the range bars from the confirmed candlesticks of a lower
+    /// minute period in ascending order
+    ///
+    /// A bar is confirmed once the difference between its high and low
+    /// reaches `range`. The bars restart at the start of each trade session,
+    /// so a bar never spans a lunch break, and the time of a bar is the time
+    /// of its first candlestick.
+    pub fn resample_range<H, TS, C, P, V, R>(
+        &self,
+        half_days: H,
+        range: P,
+        candlesticks: impl IntoIterator<Item = C>,
+    ) -> Vec<C>
+    where
+        H: Days,
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+        P: PartialOrd + Sub<Output = P> + Copy,
+        V: Add<Output = V>,
+        R: Add<Output = R>,
+    {
+        self.resample_session_bars(half_days, candlesticks, |bar| {
+            bar.high() - bar.low() >= range
+        })
+    }
+
+    /// Builds the volume bars from the confirmed candlesticks of a lower
+    /// minute period in ascending order
+    ///
+    /// A bar is confirmed once its volume reaches `volume`. The bars restart
+    /// at the start of each trade session like
+    /// [`resample_range`](Market::resample_range).
+    pub fn resample_volume<H, TS, C, P, V, R>(
+        &self,
+        half_days: H,
+        volume: V,
+        candlesticks: impl IntoIterator<Item = C>,
+    ) -> Vec<C>
+    where
+        H: Days,
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+        P: PartialOrd,
+        V: Add<Output = V> + PartialOrd + Copy,
+        R: Add<Output = R>,
+    {
+        self.resample_session_bars(half_days, candlesticks, |bar| bar.volume() >= volume)
+    }
+
+    /// Merges the candlesticks into the last bar until `confirmed` returns
+    /// `true` for it or the trade session changes, the candlesticks out of
+    /// the trade sessions are dropped
+    fn resample_session_bars<H, TS, C, P, V, R>(
+        &self,
+        half_days: H,
+        candlesticks: impl IntoIterator<Item = C>,
+        confirmed: impl Fn(&C) -> bool,
+    ) -> Vec<C>
+    where
+        H: Days,
+        TS: TradeSessionType,
+        C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+        P: PartialOrd,
+        V: Add<Output = V>,
+        R: Add<Output = R>,
+    {
+        let mut output: Vec<C> = Vec::new();
+        let mut last_session = None;
+
+        for candlestick in candlesticks {
+            let trade_session = candlestick.trade_session();
+            let t = candlestick.time().to_timezone(self.timezone);
+            let Some((_, _, n)) = self.find_trade_session(trade_session, half_days, t) else {
+                continue;
+            };
+            let session = Some((t.date(), trade_session.kind(), n));
+
+            match output.last_mut() {
+                Some(prev) if last_session == session && !confirmed(prev) => {
+                    merge_into(prev, &candlestick)
+                }
+                _ => output.push(new_from(candlestick.time(), &candlestick)),
+            }
+            last_session = session;
+        }
+        output
+    }
+
     #[must_use]
     pub fn merge_quote_day<TS, C, Q, P, V, R>(&self, input: Option<C>, quote: &Q) -> UpdateAction<C>
     where
@@ -396,3 +649,40 @@ impl Market {
         None
     }
 }
+
+/// Merges `candlestick` into `prev`
+fn merge_into<TS, C, P, V, R>(prev: &mut C, candlestick: &C)
+where
+    C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+    P: PartialOrd,
+    V: Add<Output = V>,
+    R: Add<Output = R>,
+{
+    if candlestick.high() > prev.high() {
+        prev.set_high(candlestick.high());
+    }
+    if candlestick.low() < prev.low() {
+        prev.set_low(candlestick.low());
+    }
+    prev.set_close(candlestick.close());
+    prev.set_volume(prev.volume() + candlestick.volume());
+    prev.set_turnover(prev.turnover() + candlestick.turnover());
+}
+
+/// Creates a candlestick at `time` from `candlestick`
+fn new_from<TS, C, P, V, R>(time: OffsetDateTime, candlestick: &C) -> C
+where
+    TS: TradeSessionType,
+    C: CandlestickType<PriceType = P, VolumeType = V, TurnoverType = R, TradeSessionType = TS>,
+{
+    C::new(CandlestickComponents {
+        time: time.to_timezone(time_tz::timezones::db::UTC),
+        open: candlestick.open(),
+        high: candlestick.high(),
+        low: candlestick.low(),
+        close: candlestick.close(),
+        volume: candlestick.volume(),
+        turnover: candlestick.turnover(),
+        trade_session: candlestick.trade_session(),
+    })
+}
@@ -2,7 +2,7 @@ use time::OffsetDateTime;
 
 use crate::TradeSessionType;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[allow(non_camel_case_types)]
 #[repr(u8)]
 pub enum Period {
@@ -19,16 +19,21 @@ pub enum Period {
     Min_120 = 10,
     Min_180 = 11,
     Min_240 = 12,
+    /// Any number of minutes, aligned to the start of each trade session
+    Minutes(u16) = 13,
     Day = 100,
     Week = 101,
     Month = 102,
     Quarter = 103,
     Year = 104,
+    /// Any number of trading days, counted from the first candlestick, only
+    /// supported by [`Market::resample`](crate::Market::resample)
+    Days(u16) = 105,
 }
 
 impl Period {
     #[inline]
-    pub(crate) fn minutes(&self) -> u8 {
+    pub(crate) fn minutes(&self) -> u16 {
         match self {
             Period::Min_1 => 1,
             Period::Min_2 => 2,
@@ -43,13 +48,22 @@ impl Period {
             Period::Min_120 => 120,
             Period::Min_180 => 180,
             Period::Min_240 => 240,
+            Period::Minutes(minutes) => *minutes,
             _ => unreachable!(),
         }
     }
 
     #[inline]
     pub(crate) fn is_minute(&self) -> bool {
-        (*self as u8) < 100
+        !matches!(
+            self,
+            Period::Day
+                | Period::Week
+                | Period::Month
+                | Period::Quarter
+                | Period::Year
+                | Period::Days(_)
+        )
     }
 }
 
@@ -170,6 +170,31 @@ fn sh_min240() {
     t.check_time(TRADE_SESSION_INTRADAY, time!(15:10:00), None);
 }
 
+#[test]
+fn sh_custom_minutes() {
+    let t = TestCandlestickTime::new(&CN, Period::Minutes(90));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:29:59), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:30:00), time!(09:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(10:59:59), time!(09:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:00:00), time!(11:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:29:59), time!(11:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:30:00), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(13:00:00), time!(13:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(14:29:59), time!(13:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(14:30:00), time!(14:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:00:00), time!(14:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:10:00), None);
+
+    let t = TestCandlestickTime::new(&CN, Period::Minutes(4));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:33:59), time!(09:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:34:00), time!(09:34:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:29:59), time!(11:26:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(13:03:59), time!(13:00:00));
+
+    let t = TestCandlestickTime::new(&CN, Period::Minutes(0));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:30:00), None);
+}
+
 #[test]
 fn sh_day() {
     let t = TestCandlestickTime::new(&CN, Period::Day);
@@ -0,0 +1,309 @@
+use longport_candlesticks::{
+    CandlestickComponents, CandlestickType, Market, Period, TRADE_SESSION_INTRADAY,
+    TradeSessionKind, UpdateAction,
+    markets::{CN, HK},
+};
+use time::{OffsetDateTime, macros::datetime};
+use time_tz::{OffsetDateTimeExt, PrimitiveDateTimeExt};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Candlestick {
+    time: OffsetDateTime,
+    open: i64,
+    high: i64,
+    low: i64,
+    close: i64,
+    volume: i64,
+}
+
+impl CandlestickType for Candlestick {
+    type PriceType = i64;
+    type VolumeType = i64;
+    type TurnoverType = i64;
+    type TradeSessionType = TradeSessionKind;
+
+    fn new(components: CandlestickComponents<i64, i64, i64, TradeSessionKind>) -> Self {
+        Self {
+            time: components.time,
+            open: components.open,
+            high: components.high,
+            low: components.low,
+            close: components.close,
+            volume: components.volume,
+        }
+    }
+
+    fn time(&self) -> OffsetDateTime {
+        self.time
+    }
+
+    fn set_time(&mut self, time: OffsetDateTime) {
+        self.time = time;
+    }
+
+    fn open(&self) -> i64 {
+        self.open
+    }
+
+    fn set_open(&mut self, open: i64) {
+        self.open = open;
+    }
+
+    fn high(&self) -> i64 {
+        self.high
+    }
+
+    fn set_high(&mut self, high: i64) {
+        self.high = high;
+    }
+
+    fn low(&self) -> i64 {
+        self.low
+    }
+
+    fn set_low(&mut self, low: i64) {
+        self.low = low;
+    }
+
+    fn close(&self) -> i64 {
+        self.close
+    }
+
+    fn set_close(&mut self, close: i64) {
+        self.close = close;
+    }
+
+    fn volume(&self) -> i64 {
+        self.volume
+    }
+
+    fn set_volume(&mut self, volume: i64) {
+        self.volume = volume;
+    }
+
+    fn turnover(&self) -> i64 {
+        self.volume * self.close
+    }
+
+    fn set_turnover(&mut self, _turnover: i64) {}
+
+    fn trade_session(&self) -> TradeSessionKind {
+        TRADE_SESSION_INTRADAY
+    }
+}
+
+fn candlestick(market: &Market, time: OffsetDateTime, close: i64) -> Candlestick {
+    let time = time.date().with_time(time.time());
+    Candlestick {
+        time: time.assume_timezone(market.timezone).unwrap_first(),
+        open: close,
+        high: close,
+        low: close,
+        close,
+        volume: 1,
+    }
+}
+
+fn times(market: &Market, candlesticks: &[Candlestick]) -> Vec<OffsetDateTime> {
+    candlesticks
+        .iter()
+        .map(|candlestick| candlestick.time.to_timezone(market.timezone))
+        .collect()
+}
+
+#[test]
+fn resample_minutes() {
+    let input = (0..120)
+        .map(|minute| {
+            candlestick(
+                &CN,
+                datetime!(2024-1-2 9:30 UTC) + time::Duration::minutes(minute),
+                minute,
+            )
+        })
+        .chain((0..120).map(|minute| {
+            candlestick(
+                &CN,
+                datetime!(2024-1-2 13:00 UTC) + time::Duration::minutes(minute),
+                120 + minute,
+            )
+        }));
+    let output = CN.resample(false, Period::Minutes(90), input);
+
+    assert_eq!(
+        times(&CN, &output),
+        vec![
+            datetime!(2024-1-2 9:30 +8),
+            datetime!(2024-1-2 11:00 +8),
+            datetime!(2024-1-2 13:00 +8),
+            datetime!(2024-1-2 14:30 +8),
+        ]
+    );
+    assert_eq!(
+        output[1],
+        Candlestick {
+            time: datetime!(2024-1-2 3:00 UTC),
+            open: 90,
+            high: 119,
+            low: 90,
+            close: 119,
+            volume: 30,
+        }
+    );
+    assert_eq!((output[2].volume, output[3].volume), (90, 30));
+}
+
+#[test]
+fn resample_half_day() {
+    // the half day ends at 12:00, the closing candlestick at 12:00 is merged
+    // into the last one
+    let input = [
+        candlestick(&HK, datetime!(2024-12-24 11:59 UTC), 1),
+        candlestick(&HK, datetime!(2024-12-24 12:00 UTC), 2),
+    ];
+    let output = HK.resample(true, Period::Min_60, input);
+    assert_eq!(times(&HK, &output), vec![datetime!(2024-12-24 11:30 +8)]);
+    assert_eq!(output[0].close, 2);
+
+    let output = HK.resample(false, Period::Min_60, input);
+    assert_eq!(times(&HK, &output), vec![datetime!(2024-12-24 11:30 +8)]);
+    assert_eq!(output[0].close, 1);
+}
+
+#[test]
+fn resample_days() {
+    let input = [
+        candlestick(&HK, datetime!(2024-1-2 0:00 UTC), 1),
+        candlestick(&HK, datetime!(2024-1-3 0:00 UTC), 3),
+        candlestick(&HK, datetime!(2024-1-5 0:00 UTC), 2),
+        candlestick(&HK, datetime!(2024-1-8 0:00 UTC), 4),
+    ];
+    let output = HK.resample(false, Period::Week, input);
+
+    assert_eq!(
+        times(&HK, &output),
+        vec![datetime!(2024-1-1 0:00 +8), datetime!(2024-1-8 0:00 +8)]
+    );
+    assert_eq!((output[0].open, output[0].high), (1, 3));
+    assert_eq!((output[0].low, output[0].close), (1, 2));
+    assert_eq!(output[0].volume, 3);
+}
+
+#[test]
+fn merge_candlestick() {
+    let first = candlestick(&CN, datetime!(2024-1-2 9:30 UTC), 1);
+    let second = candlestick(&CN, datetime!(2024-1-2 9:35 UTC), 2);
+
+    let UpdateAction::AppendNew {
+        confirmed: None,
+        new,
+    } = CN.merge_candlestick(false, Period::Min_5, None, &first)
+    else {
+        panic!("expect a new candlestick");
+    };
+    assert_eq!(
+        CN.merge_candlestick(false, Period::Min_5, Some(new), &second),
+        UpdateAction::AppendNew {
+            confirmed: Some(new),
+            new: second,
+        }
+    );
+
+    // the candlesticks during the lunch break are dropped
+    let lunch = candlestick(&CN, datetime!(2024-1-2 12:00 UTC), 3);
+    assert_eq!(
+        CN.merge_candlestick(false, Period::Min_5, Some(second), &lunch),
+        UpdateAction::None
+    );
+}
+
+#[test]
+fn resample_trading_days() {
+    // 2024-1-6 and 2024-1-7 are the weekend
+    let input = [
+        candlestick(&HK, datetime!(2024-1-2 0:00 UTC), 1),
+        candlestick(&HK, datetime!(2024-1-3 0:00 UTC), 2),
+        candlestick(&HK, datetime!(2024-1-4 0:00 UTC), 3),
+        candlestick(&HK, datetime!(2024-1-5 0:00 UTC), 4),
+        candlestick(&HK, datetime!(2024-1-8 0:00 UTC), 5),
+    ];
+    let output = HK.resample(false, Period::Days(2), input);
+
+    assert_eq!(
+        times(&HK, &output),
+        vec![
+            datetime!(2024-1-2 0:00 +8),
+            datetime!(2024-1-4 0:00 +8),
+            datetime!(2024-1-8 0:00 +8),
+        ]
+    );
+    assert_eq!((output[0].open, output[0].close), (1, 2));
+    assert_eq!((output[1].high, output[1].volume), (4, 2));
+    assert_eq!(output[2].volume, 1);
+
+    assert!(HK.resample(false, Period::Days(0), input).is_empty());
+}
+
+#[test]
+fn resample_range() {
+    let input = [
+        candlestick(&CN, datetime!(2024-1-2 9:30 UTC), 0),
+        candlestick(&CN, datetime!(2024-1-2 9:31 UTC), 1),
+        candlestick(&CN, datetime!(2024-1-2 9:32 UTC), 3),
+        candlestick(&CN, datetime!(2024-1-2 9:33 UTC), 4),
+        candlestick(&CN, datetime!(2024-1-2 9:34 UTC), 4),
+        // the lunch break
+        candlestick(&CN, datetime!(2024-1-2 12:00 UTC), 10),
+        candlestick(&CN, datetime!(2024-1-2 13:00 UTC), 5),
+    ];
+    let output = CN.resample_range(false, 3, input);
+
+    assert_eq!(
+        times(&CN, &output),
+        vec![
+            datetime!(2024-1-2 9:30 +8),
+            datetime!(2024-1-2 9:33 +8),
+            datetime!(2024-1-2 13:00 +8),
+        ]
+    );
+    assert_eq!(
+        output[0],
+        Candlestick {
+            time: datetime!(2024-1-2 9:30 +8),
+            open: 0,
+            high: 3,
+            low: 0,
+            close: 3,
+            volume: 3,
+        }
+    );
+    assert_eq!((output[1].high, output[1].low, output[1].volume), (4, 4, 2));
+}
+
+#[test]
+fn resample_volume() {
+    let input = [
+        candlestick(&CN, datetime!(2024-1-2 9:30 UTC), 1),
+        candlestick(&CN, datetime!(2024-1-2 9:31 UTC), 2),
+        candlestick(&CN, datetime!(2024-1-2 9:32 UTC), 3),
+        candlestick(&CN, datetime!(2024-1-2 13:00 UTC), 4),
+        candlestick(&CN, datetime!(2024-1-2 13:01 UTC), 5),
+    ];
+    let output = CN.resample_volume(false, 2, input);
+
+    assert_eq!(
+        times(&CN, &output),
+        vec![
+            datetime!(2024-1-2 9:30 +8),
+            datetime!(2024-1-2 9:32 +8),
+            datetime!(2024-1-2 13:00 +8),
+        ]
+    );
+    assert_eq!(
+        output
+            .iter()
+            .map(|candlestick| (candlestick.close, candlestick.volume))
+            .collect::<Vec<_>>(),
+        vec![(2, 2), (3, 1), (5, 2)]
+    );
+}
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
 use reqwest::{
-    Client, Method,
+    Client, Method, NoProxy, Proxy,
     header::{HeaderMap, HeaderName, HeaderValue},
 };
 use serde::Deserialize;
 
-use crate::{HttpClientConfig, HttpClientError, HttpClientResult, Json, RequestBuilder};
+use crate::{HttpClientConfig, HttpClientError, HttpClientResult, Json, RequestBuilder, geo};
 
 /// LongPort HTTP client
 pub struct HttpClient {
@@ -17,12 +17,16 @@ pub struct HttpClient {
 
 impl HttpClient {
     /// Create a new `HttpClient`
-    pub fn new(config: HttpClientConfig) -> Self {
-        Self {
-            http_cli: Client::new(),
+    ///
+    /// Returns [`HttpClientError::InvalidProxy`] if the proxy of the config is
+    /// invalid, or [`HttpClientError::ClientBuild`] if the client can not be
+    /// built with the TLS configuration.
+    pub fn new(config: HttpClientConfig) -> HttpClientResult<Self> {
+        Ok(Self {
+            http_cli: create_client(config.proxy.as_deref(), config.tls_config.as_deref())?,
             config: Arc::new(config),
             default_headers: HeaderMap::new(),
-        }
+        })
     }
 
     /// Create a new `HttpClient` from the given environment variables
@@ -33,8 +37,9 @@ impl HttpClient {
     /// - LONGPORT_APP_SECRET
     /// - LONGPORT_ACCESS_TOKEN
     /// - LONGPORT_HTTP_URL
+    /// - LONGPORT_PROXY
     pub fn from_env() -> Result<Self, HttpClientError> {
-        Ok(Self::new(HttpClientConfig::from_env()?))
+        Self::new(HttpClientConfig::from_env()?)
     }
 
     /// Set the default header
@@ -61,6 +66,12 @@ impl HttpClient {
         RequestBuilder::new(self, method, path)
     }
 
+    /// Do the best to guess whether the access point is in China Mainland or
+    /// not, through the proxy of the client
+    pub async fn is_cn(&self) -> bool {
+        geo::is_cn_with(&self.http_cli).await
+    }
+
     /// Get the socket OTP(One Time Password)
     ///
     /// Reference: <https://open.longportapp.com/en/docs/socket-token-api>
@@ -90,3 +101,25 @@ impl HttpClient {
         Ok(resp.otp)
     }
 }
+
+/// Creates the reqwest client, with the proxy `proxy` or the proxy of the
+/// environment variables, and the TLS configuration `tls_config` or the
+/// built-in root certificates
+pub(crate) fn create_client(
+    proxy: Option<&str>,
+    tls_config: Option<&rustls::ClientConfig>,
+) -> HttpClientResult<Client> {
+    let mut builder = Client::builder();
+    if let Some(tls_config) = tls_config {
+        builder = builder.use_preconfigured_tls(tls_config.clone());
+    }
+    if let Some(proxy) = proxy {
+        let proxy = Proxy::all(proxy)
+            .map_err(|err| HttpClientError::InvalidProxy(err.to_string()))?
+            .no_proxy(NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|err| HttpClientError::ClientBuild(err.to_string()))
+}
@@ -1,4 +1,8 @@
-use crate::HttpClientError;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::{HttpClientError, RetryPolicy};
 
 /// Configuration options for Http client
 #[derive(Debug, Clone)]
@@ -9,8 +13,14 @@ pub struct HttpClientConfig {
     pub(crate) app_key: String,
     /// App secret
     pub(crate) app_secret: String,
-    /// Access token
-    pub(crate) access_token: String,
+    /// Access token, shared by all the clones of this config
+    pub(crate) access_token: Arc<RwLock<String>>,
+    /// Retry policy of the rate limited requests
+    pub(crate) retry_policy: RetryPolicy,
+    /// Proxy url
+    pub(crate) proxy: Option<String>,
+    /// TLS configuration
+    pub(crate) tls_config: Option<Arc<rustls::ClientConfig>>,
 }
 
 impl HttpClientConfig {
@@ -24,7 +34,10 @@ impl HttpClientConfig {
             http_url: None,
             app_key: app_key.into(),
             app_secret: app_secret.into(),
-            access_token: access_token.into(),
+            access_token: Arc::new(RwLock::new(access_token.into())),
+            retry_policy: RetryPolicy::default(),
+            proxy: None,
+            tls_config: None,
         }
     }
 
@@ -36,6 +49,7 @@ impl HttpClientConfig {
     /// - LONGPORT_APP_SECRET
     /// - LONGPORT_ACCESS_TOKEN
     /// - LONGPORT_HTTP_URL
+    /// - LONGPORT_PROXY
     pub fn from_env() -> Result<Self, HttpClientError> {
         let _ = dotenv::dotenv();
 
@@ -54,6 +68,7 @@ impl HttpClientConfig {
 
         let mut config = Self::new(app_key, app_secret, access_token);
         config.http_url = std::env::var("LONGPORT_HTTP_URL").ok();
+        config.proxy = std::env::var("LONGPORT_PROXY").ok();
         Ok(config)
     }
 
@@ -68,4 +83,56 @@ impl HttpClientConfig {
             ..self
         }
     }
+
+    /// Specifies the retry policy of the requests rejected with `429 Too Many
+    /// Requests`
+    ///
+    /// Default: `RetryPolicy::default()`
+    #[must_use]
+    pub fn retry_policy(self, policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy: policy,
+            ..self
+        }
+    }
+
+    /// Specifies the proxy of the requests, `http://`, `https://`,
+    /// `socks5://` or `socks5h://` url with an optional `user:password@`
+    ///
+    /// The hosts listed in the `NO_PROXY` environment variable are requested
+    /// directly.
+    ///
+    /// Default: the proxy of the `HTTPS_PROXY`, `HTTP_PROXY` or `ALL_PROXY`
+    /// environment variables
+    #[must_use]
+    pub fn proxy(self, url: impl Into<String>) -> Self {
+        Self {
+            proxy: Some(url.into()),
+            ..self
+        }
+    }
+
+    /// Specifies the TLS configuration of the requests
+    ///
+    /// Default: trusts the built-in root certificates
+    #[must_use]
+    pub fn tls_config(self, config: Arc<rustls::ClientConfig>) -> Self {
+        Self {
+            tls_config: Some(config),
+            ..self
+        }
+    }
+
+    /// Returns the current access token
+    pub fn access_token(&self) -> String {
+        self.access_token.read().clone()
+    }
+
+    /// Replaces the access token
+    ///
+    /// The new token is used by the following requests of all the clients
+    /// created from this config or its clones.
+    pub fn set_access_token(&self, access_token: impl Into<String>) {
+        *self.access_token.write() = access_token.into();
+    }
 }
@@ -19,6 +19,15 @@ pub enum HttpClientError {
     #[error("invalid access token")]
     InvalidAccessToken,
 
+    /// Invalid proxy
+    #[error("invalid proxy: {0}")]
+    InvalidProxy(String),
+
+    /// Failed to build the HTTP client, e.g. the TLS configuration is not
+    /// supported
+    #[error("failed to build http client: {0}")]
+    ClientBuild(String),
+
     /// Missing environment variable
     #[error("missing environment variable: {name}")]
     MissingEnvVar {
@@ -1,11 +1,15 @@
 use std::{cell::RefCell, time::Duration};
 
+use reqwest::Client;
+
+use crate::client::create_client;
+
 // because we may call `is_cn` multi times in a short time, we cache the result
 thread_local! {
     static REGION: RefCell<Option<String>> = const { RefCell::new(None) };
 }
 
-async fn region() -> Option<String> {
+async fn region(client: &Client) -> Option<String> {
     // check user defined REGION
     if let Ok(region) = std::env::var("LONGPORT_REGION") {
         return Some(region);
@@ -13,15 +17,15 @@ async fn region() -> Option<String> {
 
     // check network connectivity
     // make sure block_on doesn't block the outer tokio runtime
-    ping().await
+    ping(client).await
 }
 
-async fn ping() -> Option<String> {
+async fn ping(client: &Client) -> Option<String> {
     if let Some(region) = REGION.with_borrow(Clone::clone) {
         return Some(region.clone());
     }
 
-    let Ok(resp) = reqwest::Client::new()
+    let Ok(resp) = client
         .get("https://api.lbkrs.com/_ping")
         .timeout(Duration::from_secs(1))
         .send()
@@ -38,8 +42,18 @@ async fn ping() -> Option<String> {
 }
 
 /// do the best to guess whether the access point is in China Mainland or not
+///
+/// The ping request goes through the proxy of the `LONGPORT_PROXY`,
+/// `HTTPS_PROXY`, `HTTP_PROXY` or `ALL_PROXY` environment variables.
 pub async fn is_cn() -> bool {
-    region()
+    match create_client(std::env::var("LONGPORT_PROXY").ok().as_deref(), None) {
+        Ok(client) => is_cn_with(&client).await,
+        Err(_) => false,
+    }
+}
+
+pub(crate) async fn is_cn_with(client: &Client) -> bool {
+    region(client)
         .await
         .is_some_and(|region| region.eq_ignore_ascii_case("CN"))
 }
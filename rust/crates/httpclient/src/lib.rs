@@ -11,6 +11,7 @@ mod error;
 mod geo;
 mod qs;
 mod request;
+mod retry;
 mod signature;
 mod timestamp;
 
@@ -21,3 +22,4 @@ pub use geo::is_cn;
 pub use qs::QsError;
 pub use request::{FromPayload, Json, RequestBuilder, ToPayload};
 pub use reqwest::Method;
+pub use retry::RetryPolicy;
@@ -13,7 +13,7 @@ use reqwest::{
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
-    HttpClient, HttpClientError, HttpClientResult, is_cn,
+    HttpClient, HttpClientError, HttpClientResult,
     signature::{SignatureParams, signature},
     timestamp::Timestamp,
 };
@@ -23,9 +23,6 @@ const HTTP_URL_CN: &str = "https://openapi.longportapp.cn";
 
 const USER_AGENT: &str = "openapi-sdk";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
-const RETRY_COUNT: usize = 5;
-const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
-const RETRY_FACTOR: f32 = 2.0;
 
 /// A JSON payload
 #[derive(Debug)]
@@ -220,7 +217,11 @@ where
             return url;
         }
 
-        if is_cn().await { HTTP_URL_CN } else { HTTP_URL }
+        if self.client.is_cn().await {
+            HTTP_URL_CN
+        } else {
+            HTTP_URL
+        }
     }
 
     async fn do_send(&self) -> HttpClientResult<R> {
@@ -237,7 +238,8 @@ where
             .unwrap_or_else(Timestamp::now);
         let app_key_value =
             HeaderValue::from_str(&config.app_key).map_err(|_| HttpClientError::InvalidApiKey)?;
-        let access_token_value = HeaderValue::from_str(&config.access_token)
+        let access_token = config.access_token();
+        let access_token_value = HeaderValue::from_str(&access_token)
             .map_err(|_| HttpClientError::InvalidAccessToken)?;
 
         let url = self.http_url().await;
@@ -271,7 +273,7 @@ where
         let sign = signature(SignatureParams {
             request: &request,
             app_key: &config.app_key,
-            access_token: Some(&config.access_token),
+            access_token: Some(&access_token),
             app_secret: &config.app_secret,
             timestamp,
         });
@@ -330,29 +332,30 @@ where
     }
 
     /// Send request and get the response
+    ///
+    /// The requests rejected with `429 Too Many Requests` are retried
+    /// according to the [`RetryPolicy`](crate::RetryPolicy) of the client.
     pub async fn send(self) -> HttpClientResult<R> {
-        match self.do_send().await {
-            Ok(resp) => Ok(resp),
-            Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                let mut retry_delay = RETRY_INITIAL_DELAY;
-
-                for _ in 0..RETRY_COUNT {
-                    tokio::time::sleep(retry_delay).await;
-
-                    match self.do_send().await {
-                        Ok(resp) => return Ok(resp),
-                        Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                            retry_delay =
-                                Duration::from_secs_f32(retry_delay.as_secs_f32() * RETRY_FACTOR);
-                            continue;
+        let retry_policy = &self.client.config.retry_policy;
+        let mut attempt = 0;
+
+        loop {
+            match self.do_send().await {
+                Ok(resp) => return Ok(resp),
+                Err(err @ HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
+                    match retry_policy.delay(attempt) {
+                        Some(delay) => {
+                            tokio::time::sleep(delay).await;
+                            attempt += 1;
+                        }
+                        None => {
+                            retry_policy.give_up(attempt, &err);
+                            return Err(err);
                         }
-                        Err(err) => return Err(err),
                     }
                 }
-
-                Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS))
+                Err(err) => return Err(err),
             }
-            Err(err) => Err(err),
         }
     }
 }
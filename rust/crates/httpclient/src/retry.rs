@@ -0,0 +1,189 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug},
+    sync::Arc,
+    time::Duration,
+};
+
+use rand::Rng;
+
+type GiveUpCallback = Arc<dyn Fn(u32, &dyn Error) + Send + Sync>;
+
+/// Retry policy with exponential backoff
+///
+/// The delay before the `n`th retry (starting from `0`) is
+/// `initial_delay * factor^n`, capped at `max_delay`, then randomized by
+/// `±jitter`.
+///
+/// Default: retries `5` times starting from `100ms`, the factor is `2.0`, the
+/// max delay is `30s` and no jitter.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    initial_delay: Duration,
+    max_delay: Duration,
+    factor: f64,
+    jitter: f64,
+    max_attempts: Option<u32>,
+    on_give_up: Option<GiveUpCallback>,
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("factor", &self.factor)
+            .field("jitter", &self.jitter)
+            .field("max_attempts", &self.max_attempts)
+            .field("on_give_up", &self.on_give_up.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(30),
+            factor: 2.0,
+            jitter: 0.0,
+            max_attempts: Some(5),
+            on_give_up: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy` with the default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the delay before the first retry
+    #[must_use]
+    pub fn initial_delay(self, delay: Duration) -> Self {
+        Self {
+            initial_delay: delay,
+            ..self
+        }
+    }
+
+    /// Specifies the upper bound of the delays
+    #[must_use]
+    pub fn max_delay(self, delay: Duration) -> Self {
+        Self {
+            max_delay: delay,
+            ..self
+        }
+    }
+
+    /// Specifies the multiplier applied to the delay after each retry
+    #[must_use]
+    pub fn factor(self, factor: f64) -> Self {
+        Self {
+            factor: factor.max(1.0),
+            ..self
+        }
+    }
+
+    /// Specifies the jitter ratio in `[0, 1]`, e.g. `0.2` randomizes each
+    /// delay by `±20%`
+    #[must_use]
+    pub fn jitter(self, jitter: f64) -> Self {
+        Self {
+            jitter: jitter.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Specifies the maximum number of retries, `None` means retry forever
+    #[must_use]
+    pub fn max_attempts(self, max_attempts: Option<u32>) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    /// Sets the callback to be called with the number of retries and the last
+    /// error when the retries run out
+    #[must_use]
+    pub fn on_give_up<F>(self, f: F) -> Self
+    where
+        F: Fn(u32, &dyn Error) + Send + Sync + 'static,
+    {
+        Self {
+            on_give_up: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Returns the delay before the `attempt`th retry (starting from `0`), or
+    /// `None` if the retries run out
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if self
+            .max_attempts
+            .is_some_and(|max_attempts| attempt >= max_attempts)
+        {
+            return None;
+        }
+
+        let delay = self.initial_delay.as_secs_f64() * self.factor.powf(attempt as f64);
+        let delay = delay.min(self.max_delay.as_secs_f64());
+        let delay = if self.jitter > 0.0 {
+            delay * (1.0 + self.jitter * rand::rng().random_range(-1.0..=1.0))
+        } else {
+            delay
+        };
+        Some(Duration::from_secs_f64(delay))
+    }
+
+    /// Calls the give-up callback
+    pub fn give_up(&self, attempts: u32, err: &dyn Error) {
+        tracing::error!(attempts = attempts, error = %err, "retries exhausted");
+        if let Some(on_give_up) = &self.on_give_up {
+            on_give_up(attempts, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(5))
+            .max_attempts(Some(4));
+        let delays = (0..5).map(|n| policy.delay(n)).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4)),
+                Some(Duration::from_secs(5)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn jitter() {
+        let policy = RetryPolicy::new()
+            .initial_delay(Duration::from_secs(10))
+            .jitter(0.5);
+        for _ in 0..100 {
+            let delay = policy.delay(0).unwrap();
+            assert!(delay >= Duration::from_secs(5) && delay <= Duration::from_secs(15));
+        }
+    }
+
+    #[test]
+    fn retry_forever() {
+        let policy = RetryPolicy::new().max_attempts(None);
+        assert_eq!(policy.delay(1000), Some(Duration::from_secs(30)));
+    }
+}
@@ -0,0 +1,78 @@
+//! Command codes understood by the mock server
+//!
+//! Reference: <https://open.longportapp.com/en/docs/socket/protocol/overview>
+
+/// Authentication
+pub const AUTH: u8 = 2;
+
+/// Reconnect with a session id
+pub const RECONNECT: u8 = 3;
+
+/// Query User Quote Profile
+pub const QUERY_USER_QUOTE_PROFILE: u8 = 4;
+
+/// Subscribe Quote
+pub const SUBSCRIBE: u8 = 6;
+
+/// Unsubscribe Quote
+pub const UNSUBSCRIBE: u8 = 7;
+
+/// Get Trading Session Of The Day
+pub const GET_TRADING_SESSION: u8 = 8;
+
+/// Get Market Trading Days
+pub const GET_TRADING_DAYS: u8 = 9;
+
+/// Get Basic Information Of Securities
+pub const GET_BASIC_INFO: u8 = 10;
+
+/// Get Real-time Quotes Of Securities
+pub const GET_REALTIME_QUOTE: u8 = 11;
+
+/// Get Security Depth
+pub const GET_SECURITY_DEPTH: u8 = 14;
+
+/// Get Security Brokers
+pub const GET_SECURITY_BROKERS: u8 = 15;
+
+/// Get Broker IDs
+pub const GET_BROKER_IDS: u8 = 16;
+
+/// Get Security Trades
+pub const GET_SECURITY_TRADES: u8 = 17;
+
+/// Get Security Intraday
+pub const GET_SECURITY_INTRADAY: u8 = 18;
+
+/// Get Security Candlesticks
+pub const GET_SECURITY_CANDLESTICKS: u8 = 19;
+
+/// Get Option Chain Info By Date
+pub const GET_OPTION_CHAIN_INFO_BY_DATE: u8 = 21;
+
+/// Get Filtered Warrant
+pub const GET_FILTERED_WARRANT: u8 = 23;
+
+/// Get History candlesticks
+pub const GET_SECURITY_HISTORY_CANDLESTICKS: u8 = 27;
+
+/// Push Real-time Quote
+pub const PUSH_REALTIME_QUOTE: u8 = 101;
+
+/// Push Real-time Depth
+pub const PUSH_REALTIME_DEPTH: u8 = 102;
+
+/// Push Real-time Brokers
+pub const PUSH_REALTIME_BROKERS: u8 = 103;
+
+/// Push Real-time Trades
+pub const PUSH_REALTIME_TRADES: u8 = 104;
+
+/// Subscribe Trade Topics
+pub const TRADE_SUBSCRIBE: u8 = 16;
+
+/// Unsubscribe Trade Topics
+pub const TRADE_UNSUBSCRIBE: u8 = 17;
+
+/// Trade Push Notification
+pub const TRADE_PUSH_NOTIFICATION: u8 = 18;
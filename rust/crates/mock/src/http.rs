@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use poem::{Body, Request, handler, http::Method, web::Data, web::Json};
+use rust_decimal::Decimal;
+use serde_json::{Map, Value, json};
+
+use crate::{
+    MockOrder,
+    server::HttpError,
+    state::{State, now_timestamp},
+};
+
+#[handler]
+pub(crate) async fn handle(
+    req: &Request,
+    body: Body,
+    Data(state): Data<&Arc<State>>,
+) -> Json<Value> {
+    let mut args = Map::new();
+    for (key, value) in req.params::<Vec<(String, String)>>().unwrap_or_default() {
+        match args.get_mut(&key) {
+            Some(Value::Array(values)) => values.push(Value::String(value)),
+            Some(prev) => *prev = Value::Array(vec![prev.take(), Value::String(value)]),
+            None => {
+                args.insert(key, Value::String(value));
+            }
+        }
+    }
+    if let Ok(Value::Object(body)) = body.into_json::<Value>().await {
+        args.extend(body);
+    }
+    let args = Value::Object(args);
+
+    if let Some(access_token) = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+    {
+        *state.last_access_token.lock() = Some(access_token.to_string());
+    }
+
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    tracing::debug!(method = %method, path = %path, args = %args, "mock http request");
+
+    let handler = state
+        .http_handlers
+        .read()
+        .get(&(method.clone(), path.clone()))
+        .cloned();
+    let res = match handler {
+        Some(handler) => handler(&args),
+        None => handle_builtin(state, &method, &path, &args),
+    };
+
+    Json(match res {
+        Ok(data) => json!({ "code": 0, "message": "", "data": data }),
+        Err(err) => json!({ "code": err.code, "message": err.message }),
+    })
+}
+
+fn handle_builtin(
+    state: &State,
+    method: &Method,
+    path: &str,
+    args: &Value,
+) -> Result<Value, HttpError> {
+    match (method.as_str(), path) {
+        ("GET", "/v1/socket/token") => Ok(json!({
+            "otp": state.issue_otp(),
+            "limit": 10,
+            "online": 0,
+        })),
+        ("GET", "/v1/token/refresh") => Ok(json!({
+            "token": format!("mock-token-{}", state.next_id()),
+            "expired_at": get_str(args, "expired_at"),
+        })),
+        ("POST", "/v1/trade/order") => submit_order(state, args),
+        ("PUT", "/v1/trade/order") => replace_order(state, args),
+        ("DELETE", "/v1/trade/order") => cancel_order(state, args),
+        ("GET", "/v1/trade/order/today") => {
+            let orders = state
+                .orders
+                .lock()
+                .iter()
+                .filter(|order| matches_filter(order, args))
+                .map(MockOrder::to_order_json)
+                .collect::<Vec<_>>();
+            Ok(json!({ "orders": orders, "has_more": false }))
+        }
+        ("GET", "/v1/trade/execution/today") => {
+            let symbol = get_str(args, "symbol");
+            let order_id = get_str(args, "order_id");
+            let trades = state
+                .executions
+                .lock()
+                .iter()
+                .filter(|execution| symbol.is_none_or(|symbol| execution.symbol == symbol))
+                .filter(|execution| order_id.is_none_or(|order_id| execution.order_id == order_id))
+                .map(|execution| {
+                    json!({
+                        "order_id": execution.order_id,
+                        "trade_id": execution.trade_id,
+                        "symbol": execution.symbol,
+                        "trade_done_at": execution.trade_done_at.to_string(),
+                        "quantity": execution.quantity.to_string(),
+                        "price": execution.price.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            Ok(json!({ "trades": trades, "has_more": false }))
+        }
+        ("GET", "/v1/asset/account") => Ok(json!({ "list": *state.account_balance.lock() })),
+        ("GET", "/v1/asset/stock") => Ok(json!({ "list": *state.stock_positions.lock() })),
+        _ => Err(HttpError::new(
+            404,
+            format!("unhandled route: {method} {path}"),
+        )),
+    }
+}
+
+fn submit_order(state: &State, args: &Value) -> Result<Value, HttpError> {
+    let required = |key| {
+        get_str(args, key)
+            .map(ToString::to_string)
+            .ok_or_else(|| HttpError::new(400, format!("missing field `{key}`")))
+    };
+    let now = now_timestamp();
+    let order = MockOrder {
+        order_id: state.next_id().to_string(),
+        symbol: required("symbol")?,
+        side: required("side")?,
+        order_type: required("order_type")?,
+        status: "NewStatus".to_string(),
+        time_in_force: required("time_in_force")?,
+        quantity: get_decimal(args, "submitted_quantity")
+            .ok_or_else(|| HttpError::new(400, "missing field `submitted_quantity`"))?,
+        executed_quantity: Decimal::ZERO,
+        price: get_decimal(args, "submitted_price"),
+        executed_price: None,
+        trigger_price: get_decimal(args, "trigger_price"),
+        last_done: None,
+        last_share: None,
+        currency: currency_of(&required("symbol")?).to_string(),
+        msg: String::new(),
+        remark: get_str(args, "remark").unwrap_or_default().to_string(),
+        submitted_at: now,
+        updated_at: now,
+    };
+    let order_id = order.order_id.clone();
+    state.push_order_changed(&order);
+    state.orders.lock().push(order);
+    Ok(json!({ "order_id": order_id }))
+}
+
+fn replace_order(state: &State, args: &Value) -> Result<Value, HttpError> {
+    let order = update_order(state, args, |order| {
+        if let Some(quantity) = get_decimal(args, "quantity") {
+            order.quantity = quantity;
+        }
+        if let Some(price) = get_decimal(args, "price") {
+            order.price = Some(price);
+        }
+        if let Some(trigger_price) = get_decimal(args, "trigger_price") {
+            order.trigger_price = Some(trigger_price);
+        }
+        order.status = "ReplacedStatus".to_string();
+    })?;
+    state.push_order_changed(&order);
+    Ok(json!({}))
+}
+
+fn cancel_order(state: &State, args: &Value) -> Result<Value, HttpError> {
+    let order = update_order(state, args, |order| {
+        order.status = "CanceledStatus".to_string();
+    })?;
+    state.push_order_changed(&order);
+    Ok(json!({}))
+}
+
+fn update_order(
+    state: &State,
+    args: &Value,
+    f: impl FnOnce(&mut MockOrder),
+) -> Result<MockOrder, HttpError> {
+    let order_id =
+        get_str(args, "order_id").ok_or_else(|| HttpError::new(400, "missing field `order_id`"))?;
+    let mut orders = state.orders.lock();
+    let order = orders
+        .iter_mut()
+        .find(|order| order.order_id == order_id)
+        .ok_or_else(|| HttpError::new(404, format!("order not found: {order_id}")))?;
+    if order.is_finished() {
+        return Err(HttpError::new(
+            400,
+            format!("order is already finished: {order_id}"),
+        ));
+    }
+    f(order);
+    order.updated_at = now_timestamp();
+    Ok(order.clone())
+}
+
+fn matches_filter(order: &MockOrder, args: &Value) -> bool {
+    let matches = |key: &str, value: &str| match args.get(key) {
+        Some(Value::String(expected)) => expected == value,
+        Some(Value::Array(expected)) => expected.iter().any(|item| item == value),
+        _ => true,
+    };
+    matches("symbol", &order.symbol)
+        && matches("status", &order.status)
+        && matches("side", &order.side)
+        && matches("order_id", &order.order_id)
+}
+
+fn currency_of(symbol: &str) -> &'static str {
+    match symbol.rsplit_once('.').map(|(_, market)| market) {
+        Some("HK") => "HKD",
+        Some("SG") => "SGD",
+        Some("SH" | "SZ") => "CNY",
+        _ => "USD",
+    }
+}
+
+fn get_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(Value::as_str)
+}
+
+fn get_decimal(args: &Value, key: &str) -> Option<Decimal> {
+    match args.get(key)? {
+        Value::String(value) => value.parse().ok(),
+        Value::Number(value) => value.to_string().parse().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,39 @@
+//! LongPort OpenAPI mock server
+//!
+//! A local server that speaks the LongPort websocket binary protocol and the
+//! HTTP API, so `QuoteContext` and `TradeContext` can be exercised end-to-end
+//! without network access or a real account.
+//!
+//! ```no_run
+//! # async fn example() -> std::io::Result<()> {
+//! use longport_mock::MockServer;
+//! use longport_proto::quote::PushQuote;
+//!
+//! let server = MockServer::start().await?;
+//! // config.http_url(server.http_url())
+//! //     .quote_ws_url(server.quote_ws_url())
+//! //     .trade_ws_url(server.trade_ws_url())
+//! server.push_quote(PushQuote {
+//!     symbol: "700.HK".to_string(),
+//!     last_done: "380.0".to_string(),
+//!     ..Default::default()
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![deny(unreachable_pub)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![warn(missing_docs)]
+
+pub mod cmd_code;
+mod http;
+mod order;
+mod server;
+mod state;
+mod ws;
+
+pub use order::MockOrder;
+pub use poem::http::Method;
+pub use server::{Channel, HttpError, MockServer, ResponseError};
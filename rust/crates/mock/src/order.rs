@@ -0,0 +1,127 @@
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
+
+/// An order held by the mock server
+///
+/// Enum-like fields use the same strings as the OpenAPI JSON payloads, e.g.
+/// `side` is `Buy` or `Sell` and `status` is `NewStatus`, `FilledStatus`,
+/// etc.
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    /// Order ID
+    pub order_id: String,
+    /// Security code
+    pub symbol: String,
+    /// Order side
+    pub side: String,
+    /// Order type
+    pub order_type: String,
+    /// Order status
+    pub status: String,
+    /// Time in force type
+    pub time_in_force: String,
+    /// Submitted quantity
+    pub quantity: Decimal,
+    /// Executed quantity
+    pub executed_quantity: Decimal,
+    /// Submitted price
+    pub price: Option<Decimal>,
+    /// Average executed price
+    pub executed_price: Option<Decimal>,
+    /// Trigger price
+    pub trigger_price: Option<Decimal>,
+    /// Price of the last fill
+    pub last_done: Option<Decimal>,
+    /// Quantity of the last fill
+    pub last_share: Option<Decimal>,
+    /// Currency
+    pub currency: String,
+    /// Rejected message
+    pub msg: String,
+    /// Remark
+    pub remark: String,
+    /// Submitted time (unix timestamp)
+    pub submitted_at: i64,
+    /// Last updated time (unix timestamp)
+    pub updated_at: i64,
+}
+
+impl MockOrder {
+    /// Returns `true` if the order can no longer be changed
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "FilledStatus" | "RejectedStatus" | "CanceledStatus" | "ExpiredStatus"
+        )
+    }
+
+    /// `Order` object returned by the order query endpoints
+    pub(crate) fn to_order_json(&self) -> Value {
+        json!({
+            "order_id": self.order_id,
+            "status": self.status,
+            "stock_name": self.symbol,
+            "quantity": self.quantity.to_string(),
+            "executed_quantity": self.executed_quantity.to_string(),
+            "price": decimal_or_empty(self.price),
+            "executed_price": self.executed_price.unwrap_or_default().to_string(),
+            "submitted_at": self.submitted_at.to_string(),
+            "side": self.side,
+            "symbol": self.symbol,
+            "order_type": self.order_type,
+            "last_done": decimal_or_empty(self.last_done),
+            "trigger_price": decimal_or_empty(self.trigger_price),
+            "msg": self.msg,
+            "tag": "Normal",
+            "time_in_force": self.time_in_force,
+            "expire_date": "",
+            "updated_at": self.updated_at.to_string(),
+            "trigger_at": "0",
+            "trailing_amount": "",
+            "trailing_percent": "",
+            "limit_offset": "",
+            "trigger_status": "NOT_USED",
+            "currency": self.currency,
+            "outside_rth": "UnknownOutsideRth",
+            "remark": self.remark,
+        })
+    }
+
+    /// Payload of the `order_changed_lb` push event
+    pub(crate) fn to_push_json(&self) -> Value {
+        json!({
+            "event": "order_changed_lb",
+            "data": {
+                "side": self.side,
+                "stock_name": self.symbol,
+                "submitted_quantity": self.quantity.to_string(),
+                "symbol": self.symbol,
+                "order_type": self.order_type,
+                "submitted_price": self.price.unwrap_or_default().to_string(),
+                "executed_quantity": self.executed_quantity.to_string(),
+                "executed_price": self.executed_price.unwrap_or_default().to_string(),
+                "order_id": self.order_id,
+                "currency": self.currency,
+                "status": self.status,
+                "submitted_at": self.submitted_at.to_string(),
+                "updated_at": self.updated_at.to_string(),
+                "trigger_price": decimal_or_empty(self.trigger_price),
+                "msg": self.msg,
+                "tag": "Normal",
+                "trigger_status": "NOT_USED",
+                "trigger_at": "0",
+                "trailing_amount": "",
+                "trailing_percent": "",
+                "limit_offset": "",
+                "account_no": "MOCK",
+                "last_share": decimal_or_empty(self.last_share),
+                "last_price": decimal_or_empty(self.last_done),
+                "remark": self.remark,
+            }
+        })
+    }
+}
+
+fn decimal_or_empty(value: Option<Decimal>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
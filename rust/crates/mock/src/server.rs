@@ -0,0 +1,457 @@
+use std::{collections::HashMap, io, net::SocketAddr, sync::Arc};
+
+use longport_proto::quote::{PushBrokers, PushDepth, PushQuote, PushTrade, SubType};
+use longport_wscli::codec::Packet;
+use poem::{
+    EndpointExt, IntoResponse, Route, Server, get, handler,
+    http::Method,
+    listener::TcpAcceptor,
+    web::{Data, websocket::WebSocket},
+};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::{
+    MockOrder, cmd_code, http,
+    state::{Execution, Outgoing, State, now_timestamp},
+    ws,
+};
+
+/// Websocket channel of the mock server
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Channel {
+    /// Quote websocket (`/quote`)
+    Quote,
+    /// Trade websocket (`/trade`)
+    Trade,
+}
+
+/// Error returned by a websocket request handler
+#[derive(Debug, Clone)]
+pub struct ResponseError {
+    /// Response status, must not be `0`
+    pub status: u8,
+    /// Error code
+    pub code: u64,
+    /// Error message
+    pub message: String,
+}
+
+impl ResponseError {
+    /// Bad request status
+    pub const STATUS_BAD_REQUEST: u8 = 3;
+
+    /// Unauthenticated status
+    pub const STATUS_UNAUTHENTICATED: u8 = 5;
+
+    /// Server internal error status
+    pub const STATUS_SERVER_ERROR: u8 = 7;
+
+    /// Create a new `ResponseError`
+    pub fn new(status: u8, code: u64, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Error returned by a HTTP request handler
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    /// OpenAPI error code, must not be `0`
+    pub code: i32,
+    /// Error message
+    pub message: String,
+}
+
+impl HttpError {
+    /// Create a new `HttpError`
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Mock LongPort OpenAPI server
+///
+/// Serves HTTP requests on `/v1/...`, the quote websocket on `/quote` and the
+/// trade websocket on `/trade` of a single local port. The server stops when
+/// this object is dropped.
+///
+/// Built-in behaviour:
+///
+/// - Socket OTP issuing, websocket auth and session reconnect
+/// - User quote profile, trading days (every weekday), quote subscriptions
+/// - Trade topic subscriptions and `order_changed_lb` notifications
+/// - Submitting, replacing, cancelling and querying today's orders and
+///   executions, account balance and stock positions
+///
+/// Any other request must be scripted with [`MockServer::on_quote`],
+/// [`MockServer::on_quote_raw`] or [`MockServer::on_http`], which also
+/// override the built-in handlers.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        for conn in self.state.connections.lock().values() {
+            let _ = conn.tx.send(Outgoing::Close("server shutdown".to_string()));
+        }
+    }
+}
+
+#[handler]
+fn quote_ws(ws: WebSocket, Data(state): Data<&Arc<State>>) -> impl IntoResponse {
+    let state = state.clone();
+    ws.on_upgrade(move |socket| ws::serve(state, Channel::Quote, socket))
+}
+
+#[handler]
+fn trade_ws(ws: WebSocket, Data(state): Data<&Arc<State>>) -> impl IntoResponse {
+    let state = state.clone();
+    ws.on_upgrade(move |socket| ws::serve(state, Channel::Trade, socket))
+}
+
+impl MockServer {
+    /// Start a mock server on a random local port
+    pub async fn start() -> io::Result<Self> {
+        Self::bind("127.0.0.1:0").await
+    }
+
+    /// Start a mock server on the specified address
+    pub async fn bind(addr: &str) -> io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(State::default());
+        *state.quote_profile.lock() = (1, "LV1".to_string());
+
+        let app = Route::new()
+            .at("/quote", get(quote_ws))
+            .at("/trade", get(trade_ws))
+            .at("/v1/*path", http::handle)
+            .data(state.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::new_with_acceptor(TcpAcceptor::from_tokio(listener)?);
+        tokio::spawn(async move {
+            let shutdown = async move {
+                let _ = shutdown_rx.await;
+            };
+            if let Err(err) = server.run_with_graceful_shutdown(app, shutdown, None).await {
+                tracing::error!(error = %err, "mock server error");
+            }
+        });
+
+        tracing::info!(addr = %addr, "mock server started");
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// Returns the local address of the server
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the value for `Config::http_url`
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the value for `Config::quote_ws_url`
+    pub fn quote_ws_url(&self) -> String {
+        format!("ws://{}/quote", self.addr)
+    }
+
+    /// Returns the value for `Config::trade_ws_url`
+    pub fn trade_ws_url(&self) -> String {
+        format!("ws://{}/trade", self.addr)
+    }
+
+    /// Set the member id and quote level returned by the user quote profile
+    pub fn set_quote_profile(&self, member_id: i64, quote_level: impl Into<String>) {
+        *self.state.quote_profile.lock() = (member_id, quote_level.into());
+    }
+
+    /// Handle the quote websocket command `command_code` with raw protobuf
+    /// bodies
+    pub fn on_quote_raw<F>(&self, command_code: u8, f: F)
+    where
+        F: Fn(&[u8]) -> Result<Vec<u8>, ResponseError> + Send + Sync + 'static,
+    {
+        self.state
+            .quote_handlers
+            .write()
+            .insert(command_code, Arc::new(f));
+    }
+
+    /// Handle the quote websocket command `command_code` with typed protobuf
+    /// messages
+    pub fn on_quote<Req, Resp, F>(&self, command_code: u8, f: F)
+    where
+        Req: prost::Message + Default,
+        Resp: prost::Message,
+        F: Fn(Req) -> Result<Resp, ResponseError> + Send + Sync + 'static,
+    {
+        self.on_quote_raw(command_code, move |body| {
+            let req = Req::decode(body).map_err(|err| {
+                ResponseError::new(ResponseError::STATUS_BAD_REQUEST, 400, err.to_string())
+            })?;
+            f(req).map(|resp| resp.encode_to_vec())
+        });
+    }
+
+    /// Handle the HTTP request `method path`
+    ///
+    /// The handler receives the query string and the JSON body merged into a
+    /// single object and returns the `data` field of the response.
+    pub fn on_http<F>(&self, method: Method, path: impl Into<String>, f: F)
+    where
+        F: Fn(&Value) -> Result<Value, HttpError> + Send + Sync + 'static,
+    {
+        self.state
+            .http_handlers
+            .write()
+            .insert((method, path.into()), Arc::new(f));
+    }
+
+    /// Returns the quote subscriptions of all connections, as a map of symbol
+    /// to subscribed types
+    pub fn subscriptions(&self) -> HashMap<String, Vec<SubType>> {
+        let mut res: HashMap<String, Vec<SubType>> = HashMap::new();
+        for conn in self.state.connections.lock().values() {
+            if conn.channel != Channel::Quote {
+                continue;
+            }
+            for (symbol, sub_types) in &conn.subscriptions {
+                let entry = res.entry(symbol.clone()).or_default();
+                for sub_type in sub_types {
+                    if let Ok(sub_type) = SubType::try_from(*sub_type)
+                        && !entry.contains(&sub_type)
+                    {
+                        entry.push(sub_type);
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Push a raw packet to all connections of `channel`, returns the number
+    /// of receivers
+    pub fn push_raw(&self, channel: Channel, command_code: u8, body: Vec<u8>) -> usize {
+        self.state
+            .broadcast(channel, encode_push(command_code, body), |_| true)
+    }
+
+    fn push_quote_message(
+        &self,
+        command_code: u8,
+        sub_type: SubType,
+        symbol: &str,
+        body: Vec<u8>,
+    ) -> usize {
+        self.state
+            .broadcast(Channel::Quote, encode_push(command_code, body), |conn| {
+                conn.subscriptions
+                    .get(symbol)
+                    .is_some_and(|sub_types| sub_types.contains(&sub_type.into()))
+            })
+    }
+
+    /// Push a quote to the connections subscribed to `SubType::Quote` of the
+    /// symbol, returns the number of receivers
+    pub fn push_quote(&self, push: PushQuote) -> usize {
+        let symbol = push.symbol.clone();
+        self.push_quote_message(
+            cmd_code::PUSH_REALTIME_QUOTE,
+            SubType::Quote,
+            &symbol,
+            prost::Message::encode_to_vec(&push),
+        )
+    }
+
+    /// Push a depth to the connections subscribed to `SubType::Depth` of the
+    /// symbol, returns the number of receivers
+    pub fn push_depth(&self, push: PushDepth) -> usize {
+        let symbol = push.symbol.clone();
+        self.push_quote_message(
+            cmd_code::PUSH_REALTIME_DEPTH,
+            SubType::Depth,
+            &symbol,
+            prost::Message::encode_to_vec(&push),
+        )
+    }
+
+    /// Push brokers to the connections subscribed to `SubType::Brokers` of
+    /// the symbol, returns the number of receivers
+    pub fn push_brokers(&self, push: PushBrokers) -> usize {
+        let symbol = push.symbol.clone();
+        self.push_quote_message(
+            cmd_code::PUSH_REALTIME_BROKERS,
+            SubType::Brokers,
+            &symbol,
+            prost::Message::encode_to_vec(&push),
+        )
+    }
+
+    /// Push trades to the connections subscribed to `SubType::Trade` of the
+    /// symbol, returns the number of receivers
+    pub fn push_trades(&self, push: PushTrade) -> usize {
+        let symbol = push.symbol.clone();
+        self.push_quote_message(
+            cmd_code::PUSH_REALTIME_TRADES,
+            SubType::Trade,
+            &symbol,
+            prost::Message::encode_to_vec(&push),
+        )
+    }
+
+    /// Close all connections of `channel`, returns the number of closed
+    /// connections
+    ///
+    /// The sessions remain valid, so clients are able to reconnect with them.
+    pub fn disconnect(&self, channel: Channel) -> usize {
+        let mut connections = self.state.connections.lock();
+        let ids = connections
+            .iter()
+            .filter(|(_, conn)| conn.channel == channel)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        ids.into_iter()
+            .filter_map(|id| connections.remove(&id))
+            .filter(|conn| {
+                conn.tx
+                    .send(Outgoing::Close("disconnected by mock server".to_string()))
+                    .is_ok()
+            })
+            .count()
+    }
+
+    /// Invalidate all sessions, clients must authenticate again with a new OTP
+    /// after reconnecting
+    pub fn expire_sessions(&self) {
+        self.state.sessions.lock().clear();
+    }
+
+    /// Returns the access token of the last HTTP request
+    pub fn last_access_token(&self) -> Option<String> {
+        self.state.last_access_token.lock().clone()
+    }
+
+    /// Returns all orders submitted today
+    pub fn orders(&self) -> Vec<MockOrder> {
+        self.state.orders.lock().clone()
+    }
+
+    /// Returns the order with the specified id
+    pub fn order(&self, order_id: &str) -> Option<MockOrder> {
+        self.state
+            .orders
+            .lock()
+            .iter()
+            .find(|order| order.order_id == order_id)
+            .cloned()
+    }
+
+    /// Fill `quantity` of an order at `price` and push the change
+    ///
+    /// Returns `false` if the order does not exist or is already finished.
+    pub fn fill_order(&self, order_id: &str, quantity: Decimal, price: Decimal) -> bool {
+        let order = {
+            let mut orders = self.state.orders.lock();
+            let Some(order) = orders
+                .iter_mut()
+                .find(|order| order.order_id == order_id && !order.is_finished())
+            else {
+                return false;
+            };
+
+            let quantity = quantity.min(order.quantity - order.executed_quantity);
+            let executed_amount = order.executed_price.unwrap_or_default()
+                * order.executed_quantity
+                + price * quantity;
+            order.executed_quantity += quantity;
+            if !order.executed_quantity.is_zero() {
+                order.executed_price = Some(executed_amount / order.executed_quantity);
+            }
+            order.last_done = Some(price);
+            order.last_share = Some(quantity);
+            order.status = if order.executed_quantity >= order.quantity {
+                "FilledStatus"
+            } else {
+                "PartialFilledStatus"
+            }
+            .to_string();
+            order.updated_at = now_timestamp();
+
+            self.state.executions.lock().push(Execution {
+                order_id: order.order_id.clone(),
+                trade_id: self.state.next_id().to_string(),
+                symbol: order.symbol.clone(),
+                quantity,
+                price,
+                trade_done_at: order.updated_at,
+            });
+            order.clone()
+        };
+        self.state.push_order_changed(&order);
+        true
+    }
+
+    /// Reject an order with the message `msg` and push the change
+    ///
+    /// Returns `false` if the order does not exist or is already finished.
+    pub fn reject_order(&self, order_id: &str, msg: impl Into<String>) -> bool {
+        let order = {
+            let mut orders = self.state.orders.lock();
+            let Some(order) = orders
+                .iter_mut()
+                .find(|order| order.order_id == order_id && !order.is_finished())
+            else {
+                return false;
+            };
+            order.status = "RejectedStatus".to_string();
+            order.msg = msg.into();
+            order.updated_at = now_timestamp();
+            order.clone()
+        };
+        self.state.push_order_changed(&order);
+        true
+    }
+
+    /// Set the items returned by `GET /v1/asset/account`, in the OpenAPI JSON
+    /// format
+    pub fn set_account_balance(&self, list: Vec<Value>) {
+        *self.state.account_balance.lock() = list;
+    }
+
+    /// Set the channels returned by `GET /v1/asset/stock`, in the OpenAPI
+    /// JSON format
+    pub fn set_stock_positions(&self, list: Vec<Value>) {
+        *self.state.stock_positions.lock() = list;
+    }
+}
+
+fn encode_push(command_code: u8, body: Vec<u8>) -> Vec<u8> {
+    Packet::Push {
+        command_code,
+        body,
+        signature: None,
+    }
+    .encode()
+}
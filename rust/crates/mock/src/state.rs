@@ -0,0 +1,153 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::{Mutex, RwLock};
+use poem::http::Method;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::{
+    MockOrder,
+    server::{Channel, HttpError, ResponseError},
+};
+
+pub(crate) type WsHandler = Arc<dyn Fn(&[u8]) -> Result<Vec<u8>, ResponseError> + Send + Sync>;
+pub(crate) type HttpHandler = Arc<dyn Fn(&Value) -> Result<Value, HttpError> + Send + Sync>;
+
+pub(crate) const SESSION_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub(crate) enum Outgoing {
+    Packet(Vec<u8>),
+    Close(String),
+}
+
+pub(crate) struct Connection {
+    pub(crate) channel: Channel,
+    pub(crate) tx: mpsc::UnboundedSender<Outgoing>,
+    /// symbol -> sub types (quote channel) or topic -> [] (trade channel)
+    pub(crate) subscriptions: HashMap<String, HashSet<i32>>,
+}
+
+pub(crate) struct Execution {
+    pub(crate) order_id: String,
+    pub(crate) trade_id: String,
+    pub(crate) symbol: String,
+    pub(crate) quantity: rust_decimal::Decimal,
+    pub(crate) price: rust_decimal::Decimal,
+    pub(crate) trade_done_at: i64,
+}
+
+#[derive(Default)]
+pub(crate) struct State {
+    next_id: AtomicU64,
+    pub(crate) otps: Mutex<HashSet<String>>,
+    pub(crate) sessions: Mutex<HashMap<String, SystemTime>>,
+    pub(crate) connections: Mutex<HashMap<u64, Connection>>,
+    pub(crate) quote_handlers: RwLock<HashMap<u8, WsHandler>>,
+    pub(crate) trade_handlers: RwLock<HashMap<u8, WsHandler>>,
+    pub(crate) http_handlers: RwLock<HashMap<(Method, String), HttpHandler>>,
+    pub(crate) orders: Mutex<Vec<MockOrder>>,
+    pub(crate) executions: Mutex<Vec<Execution>>,
+    pub(crate) account_balance: Mutex<Vec<Value>>,
+    pub(crate) stock_positions: Mutex<Vec<Value>>,
+    pub(crate) quote_profile: Mutex<(i64, String)>,
+    pub(crate) last_access_token: Mutex<Option<String>>,
+}
+
+impl State {
+    pub(crate) fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn issue_otp(&self) -> String {
+        let otp = format!("mock-otp-{}", self.next_id());
+        self.otps.lock().insert(otp.clone());
+        otp
+    }
+
+    pub(crate) fn issue_session(&self) -> (String, SystemTime) {
+        let session_id = format!("mock-session-{}", self.next_id());
+        let deadline = SystemTime::now() + SESSION_TTL;
+        self.sessions.lock().insert(session_id.clone(), deadline);
+        (session_id, deadline)
+    }
+
+    pub(crate) fn handlers(&self, channel: Channel) -> &RwLock<HashMap<u8, WsHandler>> {
+        match channel {
+            Channel::Quote => &self.quote_handlers,
+            Channel::Trade => &self.trade_handlers,
+        }
+    }
+
+    pub(crate) fn add_connection(
+        &self,
+        channel: Channel,
+        tx: mpsc::UnboundedSender<Outgoing>,
+    ) -> u64 {
+        let id = self.next_id();
+        self.connections.lock().insert(
+            id,
+            Connection {
+                channel,
+                tx,
+                subscriptions: HashMap::new(),
+            },
+        );
+        id
+    }
+
+    pub(crate) fn remove_connection(&self, id: u64) {
+        self.connections.lock().remove(&id);
+    }
+
+    /// Sends a push packet to every connection of `channel` accepted by
+    /// `filter`, returns the number of receivers
+    pub(crate) fn broadcast(
+        &self,
+        channel: Channel,
+        data: Vec<u8>,
+        filter: impl Fn(&Connection) -> bool,
+    ) -> usize {
+        self.connections
+            .lock()
+            .values()
+            .filter(|conn| conn.channel == channel && filter(conn))
+            .filter(|conn| conn.tx.send(Outgoing::Packet(data.clone())).is_ok())
+            .count()
+    }
+
+    pub(crate) fn push_order_changed(&self, order: &MockOrder) -> usize {
+        use longport_proto::trade::{ContentType, DispatchType, Notification};
+        use prost::Message;
+
+        let body = Notification {
+            topic: "private".to_string(),
+            content_type: ContentType::ContentJson.into(),
+            dispatch_type: DispatchType::DispatchDirect.into(),
+            data: order.to_push_json().to_string().into_bytes(),
+        }
+        .encode_to_vec();
+        let packet = longport_wscli::codec::Packet::Push {
+            command_code: crate::cmd_code::TRADE_PUSH_NOTIFICATION,
+            body,
+            signature: None,
+        }
+        .encode();
+        self.broadcast(Channel::Trade, packet, |conn| {
+            conn.subscriptions.contains_key("private")
+        })
+    }
+}
+
+pub(crate) fn now_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use longport_proto::{
+    control::{AuthRequest, AuthResponse, ReconnectRequest, ReconnectResponse},
+    quote::{
+        MarketTradeDayRequest, MarketTradeDayResponse, SubscribeRequest, UnsubscribeRequest,
+        UserQuoteProfileResponse,
+    },
+    trade::{Sub, SubResponse, Unsub, UnsubResponse},
+};
+use longport_wscli::codec::Packet;
+use poem::web::websocket::{CloseCode, Message, WebSocketStream};
+use prost::Message as _;
+use time::{Date, Weekday, macros::format_description};
+use tokio::sync::mpsc;
+
+use crate::{
+    cmd_code,
+    server::{Channel, ResponseError},
+    state::{Outgoing, SESSION_TTL, State},
+};
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+pub(crate) async fn serve(state: Arc<State>, channel: Channel, socket: WebSocketStream) {
+    let (mut sink, mut stream) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let conn_id = state.add_connection(channel, tx);
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+
+    tracing::debug!(conn_id = conn_id, channel = ?channel, "mock connection opened");
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                match item {
+                    Some(Ok(Message::Binary(data))) => {
+                        let Some(resp) = handle_packet(&state, conn_id, channel, &data) else {
+                            continue;
+                        };
+                        if sink.send(Message::Binary(resp)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+            item = rx.recv() => {
+                match item {
+                    Some(Outgoing::Packet(data)) => {
+                        if sink.send(Message::Binary(data)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Outgoing::Close(reason)) => {
+                        let _ = sink.send(Message::Close(Some((CloseCode::Away, reason)))).await;
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            _ = ping.tick() => {
+                if sink.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    state.remove_connection(conn_id);
+    tracing::debug!(conn_id = conn_id, channel = ?channel, "mock connection closed");
+}
+
+fn handle_packet(state: &State, conn_id: u64, channel: Channel, data: &[u8]) -> Option<Vec<u8>> {
+    let (command_code, request_id, body) = match Packet::decode(data) {
+        Ok(Packet::Request {
+            command_code,
+            request_id,
+            body,
+            ..
+        }) => (command_code, request_id, body),
+        Ok(_) => return None,
+        Err(err) => {
+            tracing::error!(error = %err, "failed to decode packet");
+            return None;
+        }
+    };
+
+    let handler = state.handlers(channel).read().get(&command_code).cloned();
+    let res = match handler {
+        Some(handler) => handler(&body),
+        None => handle_builtin(state, conn_id, channel, command_code, &body),
+    };
+
+    let (status, body) = match res {
+        Ok(body) => (0, body),
+        Err(err) => (
+            err.status,
+            longport_proto::Error {
+                code: err.code,
+                msg: err.message,
+            }
+            .encode_to_vec(),
+        ),
+    };
+
+    Some(
+        Packet::Response {
+            command_code,
+            request_id,
+            status,
+            body,
+            signature: None,
+        }
+        .encode(),
+    )
+}
+
+fn handle_builtin(
+    state: &State,
+    conn_id: u64,
+    channel: Channel,
+    command_code: u8,
+    body: &[u8],
+) -> Result<Vec<u8>, ResponseError> {
+    match (channel, command_code) {
+        (_, cmd_code::AUTH) => handle_auth(state, decode(body)?),
+        (_, cmd_code::RECONNECT) => handle_reconnect(state, decode(body)?),
+        (Channel::Quote, cmd_code::QUERY_USER_QUOTE_PROFILE) => {
+            let (member_id, quote_level) = state.quote_profile.lock().clone();
+            Ok(UserQuoteProfileResponse {
+                member_id,
+                quote_level,
+                ..Default::default()
+            }
+            .encode_to_vec())
+        }
+        (Channel::Quote, cmd_code::GET_TRADING_DAYS) => handle_trading_days(decode(body)?),
+        (Channel::Quote, cmd_code::SUBSCRIBE) => {
+            let req: SubscribeRequest = decode(body)?;
+            if let Some(conn) = state.connections.lock().get_mut(&conn_id) {
+                for symbol in req.symbol {
+                    conn.subscriptions
+                        .entry(symbol)
+                        .or_default()
+                        .extend(req.sub_type.iter().copied());
+                }
+            }
+            Ok(Vec::new())
+        }
+        (Channel::Quote, cmd_code::UNSUBSCRIBE) => {
+            let req: UnsubscribeRequest = decode(body)?;
+            if let Some(conn) = state.connections.lock().get_mut(&conn_id) {
+                let symbols = if req.unsub_all {
+                    conn.subscriptions.keys().cloned().collect()
+                } else {
+                    req.symbol
+                };
+                for symbol in symbols {
+                    if let Some(sub_types) = conn.subscriptions.get_mut(&symbol) {
+                        if req.unsub_all {
+                            sub_types.clear();
+                        } else {
+                            for sub_type in &req.sub_type {
+                                sub_types.remove(sub_type);
+                            }
+                        }
+                        if sub_types.is_empty() {
+                            conn.subscriptions.remove(&symbol);
+                        }
+                    }
+                }
+            }
+            Ok(Vec::new())
+        }
+        (Channel::Trade, cmd_code::TRADE_SUBSCRIBE) => {
+            let req: Sub = decode(body)?;
+            let mut connections = state.connections.lock();
+            let Some(conn) = connections.get_mut(&conn_id) else {
+                return Ok(SubResponse::default().encode_to_vec());
+            };
+            for topic in &req.topics {
+                conn.subscriptions.insert(topic.clone(), HashSet::new());
+            }
+            Ok(SubResponse {
+                success: req.topics,
+                fail: vec![],
+                current: conn.subscriptions.keys().cloned().collect(),
+            }
+            .encode_to_vec())
+        }
+        (Channel::Trade, cmd_code::TRADE_UNSUBSCRIBE) => {
+            let req: Unsub = decode(body)?;
+            let mut connections = state.connections.lock();
+            let Some(conn) = connections.get_mut(&conn_id) else {
+                return Ok(UnsubResponse::default().encode_to_vec());
+            };
+            for topic in &req.topics {
+                conn.subscriptions.remove(topic);
+            }
+            Ok(UnsubResponse {
+                current: conn.subscriptions.keys().cloned().collect(),
+            }
+            .encode_to_vec())
+        }
+        _ => Err(ResponseError::new(
+            ResponseError::STATUS_BAD_REQUEST,
+            404,
+            format!("unhandled command: {command_code}"),
+        )),
+    }
+}
+
+fn handle_auth(state: &State, req: AuthRequest) -> Result<Vec<u8>, ResponseError> {
+    if !state.otps.lock().remove(&req.token) {
+        return Err(ResponseError::new(
+            ResponseError::STATUS_UNAUTHENTICATED,
+            401,
+            "invalid otp",
+        ));
+    }
+
+    let (session_id, deadline) = state.issue_session();
+    Ok(AuthResponse {
+        session_id,
+        expires: deadline
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64,
+        limit: 10,
+        online: 1,
+    }
+    .encode_to_vec())
+}
+
+fn handle_reconnect(state: &State, req: ReconnectRequest) -> Result<Vec<u8>, ResponseError> {
+    let valid = state
+        .sessions
+        .lock()
+        .get(&req.session_id)
+        .is_some_and(|deadline| *deadline > SystemTime::now());
+    if !valid {
+        return Err(ResponseError::new(
+            ResponseError::STATUS_UNAUTHENTICATED,
+            401,
+            "session expired",
+        ));
+    }
+
+    state
+        .sessions
+        .lock()
+        .insert(req.session_id.clone(), SystemTime::now() + SESSION_TTL);
+    Ok(ReconnectResponse {
+        session_id: req.session_id,
+        expires: SESSION_TTL.as_millis() as i64,
+        limit: 10,
+        online: 1,
+    }
+    .encode_to_vec())
+}
+
+/// Every weekday in the requested range is a normal trading day
+fn handle_trading_days(req: MarketTradeDayRequest) -> Result<Vec<u8>, ResponseError> {
+    let format = format_description!("[year][month][day]");
+    let parse = |value: &str| {
+        Date::parse(value, format).map_err(|err| {
+            ResponseError::new(ResponseError::STATUS_BAD_REQUEST, 400, err.to_string())
+        })
+    };
+    let (mut day, end_day) = (parse(&req.beg_day)?, parse(&req.end_day)?);
+    let mut trade_day = Vec::new();
+
+    while day <= end_day {
+        if !matches!(day.weekday(), Weekday::Saturday | Weekday::Sunday) {
+            trade_day.push(day.format(format).unwrap());
+        }
+        day = day.next_day().unwrap();
+    }
+
+    Ok(MarketTradeDayResponse {
+        trade_day,
+        half_trade_day: vec![],
+    }
+    .encode_to_vec())
+}
+
+fn decode<T: prost::Message + Default>(body: &[u8]) -> Result<T, ResponseError> {
+    T::decode(body)
+        .map_err(|err| ResponseError::new(ResponseError::STATUS_BAD_REQUEST, 400, err.to_string()))
+}
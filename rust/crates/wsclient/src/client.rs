@@ -19,13 +19,14 @@ use tokio::{
     sync::{mpsc, oneshot},
 };
 use tokio_tungstenite::{
-    MaybeTlsStream, WebSocketStream,
+    Connector, MaybeTlsStream, WebSocketStream,
     tungstenite::{Message, client::IntoClientRequest, http::Uri},
 };
 use url::Url;
 
 use crate::{
-    WsClientError, WsClientResult, WsCloseReason, WsEvent, WsResponseErrorDetail, codec::Packet,
+    Proxy, WsClientError, WsClientResult, WsCloseReason, WsEvent, WsResponseErrorDetail,
+    codec::Packet, proxy::ProxyStream,
 };
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -97,15 +98,15 @@ impl From<RateLimit> for RateLimiter {
 struct Context<'a> {
     request_id: u32,
     inflight_requests: HashMap<u32, oneshot::Sender<WsClientResult<Vec<u8>>>>,
-    sink: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-    stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    sink: SplitSink<WebSocketStream<MaybeTlsStream<ProxyStream>>, Message>,
+    stream: SplitStream<WebSocketStream<MaybeTlsStream<ProxyStream>>>,
     command_rx: &'a mut mpsc::UnboundedReceiver<Command>,
     event_sender: &'a mut mpsc::UnboundedSender<WsEvent>,
 }
 
 impl<'a> Context<'a> {
     fn new(
-        conn: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        conn: WebSocketStream<MaybeTlsStream<ProxyStream>>,
         command_rx: &'a mut mpsc::UnboundedReceiver<Command>,
         event_sender: &'a mut mpsc::UnboundedSender<WsEvent>,
     ) -> Self {
@@ -265,6 +266,10 @@ pub struct WsClient {
 
 impl WsClient {
     /// Connect to `url` and returns a `WsClient` object
+    ///
+    /// Connects through `proxy` if it is not `None`, and uses `tls_config`
+    /// for the `wss://` connections if it is not `None`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn open(
         request: impl IntoClientRequest,
         version: ProtocolVersion,
@@ -272,9 +277,11 @@ impl WsClient {
         platform: Platform,
         event_sender: mpsc::UnboundedSender<WsEvent>,
         rate_limit: Vec<(u8, RateLimit)>,
+        proxy: Option<&Proxy>,
+        tls_config: Option<Arc<rustls::ClientConfig>>,
     ) -> WsClientResult<Self> {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let conn = do_connect(request, version, codec, platform).await?;
+        let conn = do_connect(request, version, codec, platform, proxy, tls_config).await?;
         tokio::spawn(client_loop(conn, command_rx, event_sender));
         Ok(Self {
             command_tx,
@@ -408,7 +415,9 @@ async fn do_connect(
     version: ProtocolVersion,
     codec: CodecType,
     platform: Platform,
-) -> WsClientResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    proxy: Option<&Proxy>,
+    tls_config: Option<Arc<rustls::ClientConfig>>,
+) -> WsClientResult<WebSocketStream<MaybeTlsStream<ProxyStream>>> {
     let mut request = request.into_client_request()?;
     let mut url_obj = Url::parse(&request.uri().to_string())?;
     url_obj.query_pairs_mut().extend_pairs(&[
@@ -418,13 +427,33 @@ async fn do_connect(
     ]);
     *request.uri_mut() = Uri::from_str(url_obj.as_ref()).expect("valid url");
 
-    let conn = match tokio::time::timeout(
-        CONNECT_TIMEOUT,
-        tokio_tungstenite::connect_async(request).map_err(WsClientError::from),
-    )
-    .map_err(|_| WsClientError::ConnectTimeout)
-    .await
-    .and_then(std::convert::identity)
+    let connector = tls_config.map(Connector::Rustls);
+    let connect = async move {
+        let host = url_obj
+            .host_str()
+            .ok_or(url::ParseError::EmptyHost)?
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        let port = url_obj
+            .port_or_known_default()
+            .ok_or(url::ParseError::InvalidPort)?;
+        let stream = match proxy {
+            Some(proxy) => proxy.connect(host, port).await?,
+            None => ProxyStream::Tcp(
+                TcpStream::connect((host, port))
+                    .await
+                    .map_err(tokio_tungstenite::tungstenite::Error::Io)?,
+            ),
+        };
+        Ok(
+            tokio_tungstenite::client_async_tls_with_config(request, stream, None, connector)
+                .await?,
+        )
+    };
+    let conn = match tokio::time::timeout(CONNECT_TIMEOUT, connect)
+        .map_err(|_| WsClientError::ConnectTimeout)
+        .await
+        .and_then(std::convert::identity)
     {
         Ok((conn, _)) => conn,
         Err(err) => return Err(err),
@@ -434,7 +463,7 @@ async fn do_connect(
 }
 
 async fn client_loop(
-    conn: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    conn: WebSocketStream<MaybeTlsStream<ProxyStream>>,
     mut command_tx: mpsc::UnboundedReceiver<Command>,
     mut event_sender: mpsc::UnboundedSender<WsEvent>,
 ) {
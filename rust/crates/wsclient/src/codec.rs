@@ -1,3 +1,7 @@
+//! LongPort websocket binary packet codec
+//!
+//! Reference: <https://open.longportapp.com/en/docs/socket/protocol/overview>
+
 use std::io::{Cursor, Read};
 
 use byteorder::{BE, ReadBytesExt, WriteBytesExt};
@@ -9,10 +13,13 @@ const PACKAGE_TYPE_REQUEST: u8 = 1;
 const PACKAGE_TYPE_RESPONSE: u8 = 2;
 const PACKAGE_TYPE_PUSH: u8 = 3;
 
-#[derive(Debug)]
-pub(crate) struct Signature {
-    pub(crate) nonce: [u8; 8],
-    pub(crate) signature: [u8; 16],
+/// Packet signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// Nonce
+    pub nonce: [u8; 8],
+    /// Signature
+    pub signature: [u8; 16],
 }
 
 #[derive(Debug)]
@@ -44,32 +51,49 @@ impl PacketHeader {
     }
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-pub(crate) enum Packet {
+/// Websocket packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    /// Request packet
     Request {
+        /// Command code
         command_code: u8,
+        /// Request id
         request_id: u32,
+        /// Timeout in milliseconds
         timeout_millis: u16,
+        /// Message body
         body: Vec<u8>,
+        /// Signature
         signature: Option<Signature>,
     },
+    /// Response packet
     Response {
+        /// Command code
         command_code: u8,
+        /// Request id
         request_id: u32,
+        /// Status code, `0` means success
         status: u8,
+        /// Message body
         body: Vec<u8>,
+        /// Signature
         signature: Option<Signature>,
     },
+    /// Push packet
     Push {
+        /// Command code
         command_code: u8,
+        /// Message body
         body: Vec<u8>,
+        /// Signature
         signature: Option<Signature>,
     },
 }
 
 impl Packet {
-    pub(crate) fn encode(&self) -> Vec<u8> {
+    /// Encode this packet to bytes
+    pub fn encode(&self) -> Vec<u8> {
         match self {
             Packet::Request {
                 command_code,
@@ -99,12 +123,63 @@ impl Packet {
 
                 data
             }
-            Packet::Response { .. } => unimplemented!(),
-            Packet::Push { .. } => unimplemented!(),
+            Packet::Response {
+                command_code,
+                request_id,
+                status,
+                body,
+                signature,
+            } => {
+                let header = PacketHeader {
+                    ty: PACKAGE_TYPE_RESPONSE,
+                    verify: signature.is_some(),
+                    gzip: false,
+                }
+                .encode();
+                let mut data = vec![header, *command_code];
+
+                let _ = data.write_u32::<BE>(*request_id);
+                let _ = data.write_u8(*status);
+                let _ = data.write_u24::<BE>(body.len() as u32);
+
+                data.extend(body);
+
+                if let Some(signature) = signature {
+                    data.extend_from_slice(&signature.nonce);
+                    data.extend_from_slice(&signature.signature);
+                }
+
+                data
+            }
+            Packet::Push {
+                command_code,
+                body,
+                signature,
+            } => {
+                let header = PacketHeader {
+                    ty: PACKAGE_TYPE_PUSH,
+                    verify: signature.is_some(),
+                    gzip: false,
+                }
+                .encode();
+                let mut data = vec![header, *command_code];
+
+                let _ = data.write_u24::<BE>(body.len() as u32);
+
+                data.extend(body);
+
+                if let Some(signature) = signature {
+                    data.extend_from_slice(&signature.nonce);
+                    data.extend_from_slice(&signature.signature);
+                }
+
+                data
+            }
         }
     }
 
-    pub(crate) fn decode(data: &[u8]) -> WsClientResult<Packet> {
+    /// Decode a packet from bytes
+    pub fn decode(data: &[u8]) -> WsClientResult<Packet> {
         if data.is_empty() {
             return Err(WsClientError::UnexpectedResponse);
         }
@@ -254,3 +329,40 @@ fn parse_push(header: &PacketHeader, data: &[u8]) -> std::io::Result<Packet> {
         signature,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let packets = [
+            Packet::Request {
+                command_code: 2,
+                request_id: 10,
+                timeout_millis: 5000,
+                body: vec![1, 2, 3],
+                signature: None,
+            },
+            Packet::Response {
+                command_code: 2,
+                request_id: 10,
+                status: 0,
+                body: vec![4, 5, 6],
+                signature: Some(Signature {
+                    nonce: [1; 8],
+                    signature: [2; 16],
+                }),
+            },
+            Packet::Push {
+                command_code: 101,
+                body: vec![7, 8, 9],
+                signature: None,
+            },
+        ];
+
+        for packet in packets {
+            assert_eq!(Packet::decode(&packet.encode()).unwrap(), packet);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use prost::DecodeError;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 
 /// Connection close reason
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WsCloseReason {
     /// Close code
     pub code: CloseCode,
@@ -67,6 +67,14 @@ pub enum WsClientError {
     #[error(transparent)]
     InvalidUrl(#[from] url::ParseError),
 
+    /// Invalid proxy url
+    #[error("invalid proxy: {0}")]
+    InvalidProxy(String),
+
+    /// Failed to connect through the proxy
+    #[error("proxy error: {0}")]
+    Proxy(std::io::Error),
+
     /// Websocket error
     #[error(transparent)]
     Websocket(#[from] tokio_tungstenite::tungstenite::Error),
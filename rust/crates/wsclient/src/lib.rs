@@ -7,10 +7,12 @@
 #![allow(clippy::result_large_err)]
 
 mod client;
-mod codec;
+pub mod codec;
 mod error;
 mod event;
+mod proxy;
 
 pub use client::{CodecType, Platform, ProtocolVersion, RateLimit, WsClient, WsSession};
 pub use error::{WsClientError, WsClientResult, WsCloseReason, WsResponseErrorDetail};
 pub use event::WsEvent;
+pub use proxy::Proxy;
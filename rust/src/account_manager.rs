@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    Config, Error, PushReceiver, QuoteContext, Result, TradeContext, quote,
+    trade::{self, TopicType},
+};
+
+/// A trade push event of an account of the [`AccountManager`]
+#[derive(Debug)]
+pub struct AccountPushEvent {
+    /// Account id
+    pub account_id: String,
+    /// Push event
+    pub event: trade::PushEvent,
+}
+
+struct InnerAccountManager {
+    quote_ctx: QuoteContext,
+    accounts: BTreeMap<String, TradeContext>,
+    dropped_push_events: Arc<AtomicU64>,
+}
+
+/// Manages the trade contexts of several accounts in one process
+///
+/// All the accounts share one [`QuoteContext`] for the market data, and the
+/// push events of their trade contexts are merged into one receiver, each
+/// event tagged by the id of its account.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{AccountManager, Config, trade::TopicType};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (manager, _quote_receiver, mut trade_receiver) = AccountManager::try_new(
+///     Arc::new(Config::from_env()?),
+///     [
+///         ("sub1", Arc::new(Config::new("app_key1", "app_secret1", "access_token1"))),
+///         ("sub2", Arc::new(Config::new("app_key2", "app_secret2", "access_token2"))),
+///     ],
+/// )
+/// .await?;
+/// manager.subscribe([TopicType::Private]).await?;
+///
+/// let orders = manager.trade("sub1")?.today_orders(None).await?;
+/// println!("{:?}", orders);
+///
+/// while let Some(event) = trade_receiver.recv().await {
+///     println!("{}: {:?}", event.account_id, event.event);
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct AccountManager(Arc<InnerAccountManager>);
+
+impl AccountManager {
+    /// Create an `AccountManager`
+    ///
+    /// `quote_config` is used to create the shared [`QuoteContext`], and the
+    /// [`TradeContext`] of each account is created with its own config, one
+    /// after another. The push channel options of `quote_config` also apply
+    /// to the merged trade push events.
+    ///
+    /// Returns the manager, the push events of the quote context and the
+    /// merged push events of the trade contexts.
+    pub async fn try_new<I, K>(
+        quote_config: Arc<Config>,
+        accounts: I,
+    ) -> Result<(
+        Self,
+        PushReceiver<quote::PushEvent>,
+        PushReceiver<AccountPushEvent>,
+    )>
+    where
+        I: IntoIterator<Item = (K, Arc<Config>)>,
+        K: Into<String>,
+    {
+        let mut configs = BTreeMap::new();
+        for (account_id, config) in accounts {
+            let account_id = account_id.into();
+            if configs.contains_key(&account_id) {
+                return Err(Error::DuplicateAccount { account_id });
+            }
+            configs.insert(account_id, config);
+        }
+
+        let (quote_ctx, quote_rx) = QuoteContext::try_new(quote_config.clone()).await?;
+        let dropped_push_events = Arc::new(AtomicU64::new(0));
+        let (push_tx, push_rx) = quote_config.create_push_channel(dropped_push_events.clone());
+        let push_tx = Arc::new(push_tx);
+        let mut accounts = BTreeMap::new();
+
+        for (account_id, config) in configs {
+            let (ctx, mut rx) = TradeContext::try_new(config).await?;
+            let push_tx = push_tx.clone();
+            let id = account_id.clone();
+            tokio::spawn(async move {
+                loop {
+                    push_tx.ready().await;
+                    if push_tx.is_closed() {
+                        break;
+                    }
+                    let Some(event) = rx.recv().await else {
+                        break;
+                    };
+                    let event = AccountPushEvent {
+                        account_id: id.clone(),
+                        event,
+                    };
+                    if !push_tx.send(event) {
+                        break;
+                    }
+                }
+            });
+            accounts.insert(account_id, ctx);
+        }
+
+        Ok((
+            Self(Arc::new(InnerAccountManager {
+                quote_ctx,
+                accounts,
+                dropped_push_events,
+            })),
+            quote_rx,
+            push_rx,
+        ))
+    }
+
+    /// Returns the shared quote context
+    #[inline]
+    pub fn quote(&self) -> &QuoteContext {
+        &self.0.quote_ctx
+    }
+
+    /// Returns the trade context of the account
+    pub fn trade(&self, account_id: &str) -> Result<&TradeContext> {
+        self.0
+            .accounts
+            .get(account_id)
+            .ok_or_else(|| Error::UnknownAccount {
+                account_id: account_id.to_string(),
+            })
+    }
+
+    /// Returns the ids of the accounts, in ascending order
+    pub fn account_ids(&self) -> impl Iterator<Item = &str> {
+        self.0.accounts.keys().map(String::as_str)
+    }
+
+    /// Returns the accounts and their trade contexts, in ascending order of
+    /// the account ids
+    pub fn accounts(&self) -> impl Iterator<Item = (&str, &TradeContext)> {
+        self.0
+            .accounts
+            .iter()
+            .map(|(account_id, ctx)| (account_id.as_str(), ctx))
+    }
+
+    /// Subscribe the topics of all the accounts
+    pub async fn subscribe<I>(&self, topics: I) -> Result<()>
+    where
+        I: IntoIterator<Item = TopicType>,
+    {
+        let topics = topics.into_iter().collect::<Vec<_>>();
+        for ctx in self.0.accounts.values() {
+            ctx.subscribe(topics.iter().copied()).await?;
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe the topics of all the accounts
+    pub async fn unsubscribe<I>(&self, topics: I) -> Result<()>
+    where
+        I: IntoIterator<Item = TopicType>,
+    {
+        let topics = topics.into_iter().collect::<Vec<_>>();
+        for ctx in self.0.accounts.values() {
+            ctx.unsubscribe(topics.iter().copied()).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of the merged trade push events dropped because
+    /// the receiver was full, see [`Config::push_channel`]
+    #[inline]
+    pub fn dropped_push_events(&self) -> u64 {
+        self.0.dropped_push_events.load(Ordering::Relaxed)
+    }
+}
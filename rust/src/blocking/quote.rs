@@ -1,19 +1,21 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use time::{Date, PrimitiveDateTime};
 
 use crate::{
-    Config, Market, QuoteContext, Result,
-    blocking::runtime::BlockingRuntime,
+    Config, ConnectionEvent, Market, QuoteContext, Result,
+    blocking::runtime::{BlockingRuntime, spawn_callback_thread},
     quote::{
-        AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
-        FilterWarrantExpiryDate, FilterWarrantInOutBoundsType, HistoryMarketTemperatureResponse,
-        IntradayLine, IssuerInfo, MarketTemperature, MarketTradingDays, MarketTradingSession,
-        OptionQuote, ParticipantInfo, Period, PushEvent, QuotePackageDetail, RealtimeQuote,
-        RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup, Security, SecurityBrokers,
-        SecurityCalcIndex, SecurityDepth, SecurityListCategory, SecurityQuote, SecurityStaticInfo,
-        SortOrderType, StrikePriceInfo, SubFlags, Subscription, Trade, TradeSessions, WarrantInfo,
-        WarrantQuote, WarrantSortBy, WarrantStatus, WarrantType, WatchlistGroup,
+        AdjustType, AggregatePeriod, BrokerQueueView, CalcIndex, Candlestick,
+        CapitalDistributionResponse, CapitalFlowLine, FilterWarrantExpiryDate,
+        FilterWarrantInOutBoundsType, HistoryMarketTemperatureResponse, IntradayLine, IssuerInfo,
+        MarketTemperature, MarketTradingDays, MarketTradingSession, OptionChainSnapshot,
+        OptionQuote, OrderBook, ParticipantInfo, Period, PushEvent, QuotePackageDetail,
+        RealtimeQuote, RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup, Security,
+        SecurityBrokers, SecurityCalcIndex, SecurityDepth, SecurityListCategory, SecurityQuote,
+        SecurityStaticInfo, SortOrderType, StrikePriceInfo, SubFlags, Subscription, Trade,
+        TradeSessions, WarrantFilter, WarrantInfo, WarrantList, WarrantQuote, WarrantSortBy,
+        WarrantStatus, WarrantType, WatchlistGroup,
     },
 };
 
@@ -32,6 +34,41 @@ impl QuoteContextSync {
         Ok(Self { rt })
     }
 
+    /// Create a `QuoteContextSync` object that replays a recording
+    ///
+    /// See [`QuoteContext::replay`].
+    pub fn replay<F>(
+        config: Arc<Config>,
+        path: impl Into<PathBuf>,
+        speed: f64,
+        push_callback: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(PushEvent) + Send + 'static,
+    {
+        let path = path.into();
+        let rt = BlockingRuntime::try_new(
+            move || QuoteContext::replay(config, path, speed),
+            push_callback,
+        )?;
+        Ok(Self { rt })
+    }
+
+    /// Calls `callback` in a new thread on the connection state change
+    /// events
+    ///
+    /// See [`QuoteContext::connection_events`].
+    pub fn on_connection_event<F>(&self, callback: F) -> Result<()>
+    where
+        F: FnMut(ConnectionEvent) + Send + 'static,
+    {
+        let rx = self
+            .rt
+            .call(|ctx| async move { Ok(ctx.connection_events()) })?;
+        spawn_callback_thread(rx, callback);
+        Ok(())
+    }
+
     /// Returns the member ID
     pub fn member_id(&self) -> Result<i64> {
         self.rt.call(|ctx| async move { Ok(ctx.member_id()) })
@@ -159,14 +196,40 @@ impl QuoteContextSync {
     }
 
     /// Unsubscribe security candlesticks
-    pub fn unsubscribe_candlesticks<T>(&self, symbol: T, period: Period) -> Result<()>
+    pub fn unsubscribe_candlesticks<T>(
+        &self,
+        symbol: T,
+        period: impl Into<AggregatePeriod>,
+    ) -> Result<()>
     where
         T: AsRef<str> + Send + 'static,
     {
+        let period = period.into();
         self.rt
             .call(move |ctx| async move { ctx.unsubscribe_candlesticks(symbol, period).await })
     }
 
+    /// Aggregates the candlesticks of `periods` locally from the trades of
+    /// `symbol`, see [`QuoteContext::aggregate_candlesticks`](crate::quote::QuoteContext::aggregate_candlesticks)
+    pub fn aggregate_candlesticks<T, I, P>(
+        &self,
+        symbol: T,
+        periods: I,
+        trade_sessions: TradeSessions,
+        warm_up: usize,
+    ) -> Result<()>
+    where
+        T: AsRef<str> + Send + 'static,
+        I: IntoIterator<Item = P>,
+        P: Into<AggregatePeriod>,
+    {
+        let periods: Vec<AggregatePeriod> = periods.into_iter().map(Into::into).collect();
+        self.rt.call(move |ctx| async move {
+            ctx.aggregate_candlesticks(symbol, periods, trade_sessions, warm_up)
+                .await
+        })
+    }
+
     /// Get subscription information
     ///
     /// # Examples
@@ -572,6 +635,18 @@ impl QuoteContextSync {
         )
     }
 
+    /// Get the option chain of an expiry date with the quotes, the implied
+    /// volatility and the Greeks of the options
+    pub fn option_chain_snapshot(
+        &self,
+        underlying: impl Into<String> + Send + 'static,
+        expiry_date: Date,
+    ) -> Result<OptionChainSnapshot> {
+        self.rt.call(
+            move |ctx| async move { ctx.option_chain_snapshot(underlying, expiry_date).await },
+        )
+    }
+
     /// Get warrant issuers
     ///
     /// # Examples
@@ -628,6 +703,12 @@ impl QuoteContextSync {
         })
     }
 
+    /// Query a page of the warrants matching the filter
+    pub fn warrant_screener(&self, filter: WarrantFilter) -> Result<WarrantList> {
+        self.rt
+            .call(move |ctx| async move { ctx.warrant_screener(filter).await })
+    }
+
     /// Get trading session of the day
     ///
     /// # Examples
@@ -976,6 +1057,15 @@ impl QuoteContextSync {
             .call(move |ctx| async move { ctx.realtime_depth(symbol).await })
     }
 
+    /// Get the order book of the subscribed symbol from the local storage
+    pub fn realtime_order_book(
+        &self,
+        symbol: impl Into<String> + Send + 'static,
+    ) -> Result<OrderBook> {
+        self.rt
+            .call(move |ctx| async move { ctx.realtime_order_book(symbol).await })
+    }
+
     /// Get real-time trades
     ///
     /// Get real-time trades of the subscribed symbols, it always returns the
@@ -1041,6 +1131,16 @@ impl QuoteContextSync {
             .call(move |ctx| async move { ctx.realtime_brokers(symbol).await })
     }
 
+    /// Get the broker queue of the subscribed symbol from the local storage,
+    /// joined with the participant names
+    pub fn broker_queue_view(
+        &self,
+        symbol: impl Into<String> + Send + 'static,
+    ) -> Result<BrokerQueueView> {
+        self.rt
+            .call(move |ctx| async move { ctx.broker_queue_view(symbol).await })
+    }
+
     /// Get real-time candlesticks
     ///
     /// Get real-time candlesticks of the subscribed symbols, it always returns
@@ -1072,10 +1172,43 @@ impl QuoteContextSync {
     pub fn realtime_candlesticks(
         &self,
         symbol: impl Into<String> + Send + 'static,
-        period: Period,
+        period: impl Into<AggregatePeriod>,
         count: usize,
     ) -> Result<Vec<Candlestick>> {
+        let period = period.into();
         self.rt
             .call(move |ctx| async move { ctx.realtime_candlesticks(symbol, period, count).await })
     }
+
+    /// Calls `f` with the trades of the symbol in memory, without copying
+    /// them, see [`QuoteContext::with_realtime_trades`](crate::quote::QuoteContext::with_realtime_trades)
+    pub fn with_realtime_trades<F, R>(
+        &self,
+        symbol: impl Into<String> + Send + 'static,
+        f: F,
+    ) -> Result<R>
+    where
+        F: FnOnce(&[Trade]) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.rt
+            .call(move |ctx| async move { ctx.with_realtime_trades(symbol, f).await })
+    }
+
+    /// Calls `f` with the candlesticks of the subscribed period in memory,
+    /// without copying them, see [`QuoteContext::with_realtime_candlesticks`](crate::quote::QuoteContext::with_realtime_candlesticks)
+    pub fn with_realtime_candlesticks<F, R>(
+        &self,
+        symbol: impl Into<String> + Send + 'static,
+        period: impl Into<AggregatePeriod>,
+        f: F,
+    ) -> Result<R>
+    where
+        F: FnOnce(&[Candlestick]) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let period = period.into();
+        self.rt
+            .call(move |ctx| async move { ctx.with_realtime_candlesticks(symbol, period, f).await })
+    }
 }
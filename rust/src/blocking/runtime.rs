@@ -1,10 +1,9 @@
 use std::{sync::Arc, thread};
 
+use crate::{PushReceiver, Result, blocking::BlockingError};
 use futures_util::{Future, future::BoxFuture};
 use tokio::sync::mpsc;
 
-use crate::{Result, blocking::BlockingError};
-
 const THREAD_NAME: &str = "longport-sync-runtime";
 
 type ExecFn<Ctx> = Box<dyn FnOnce(Arc<Ctx>) -> BoxFuture<'static, ()> + Send + 'static>;
@@ -23,7 +22,7 @@ where
     ) -> Result<Self>
     where
         CreateCtx: FnOnce() -> CreateCtxFut + Send + 'static,
-        CreateCtxFut: Future<Output = Result<(Ctx, mpsc::UnboundedReceiver<PushType>)>>,
+        CreateCtxFut: Future<Output = Result<(Ctx, PushReceiver<PushType>)>>,
         PushCallback: FnMut(PushType) + Send + 'static,
         PushType: Send + 'static,
     {
@@ -110,3 +109,18 @@ where
         reply_rx.recv().map_err(|_| BlockingError::Closed)?
     }
 }
+
+/// Calls `callback` with the items received from `rx` in a new thread
+pub(crate) fn spawn_callback_thread<T, F>(mut rx: mpsc::UnboundedReceiver<T>, mut callback: F)
+where
+    T: Send + 'static,
+    F: FnMut(T) + Send + 'static,
+{
+    thread::Builder::new()
+        .spawn(move || {
+            while let Some(item) = rx.blocking_recv() {
+                callback(item);
+            }
+        })
+        .expect("spawn thread");
+}
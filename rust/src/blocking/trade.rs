@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use crate::{
-    Config, Result,
-    blocking::runtime::BlockingRuntime,
+    Config, ConnectionEvent, Result,
+    blocking::runtime::{BlockingRuntime, spawn_callback_thread},
     trade::{
         AccountBalance, CashFlow, EstimateMaxPurchaseQuantityOptions,
         EstimateMaxPurchaseQuantityResponse, Execution, FundPositionsResponse, GetCashFlowOptions,
@@ -28,6 +28,21 @@ impl TradeContextSync {
         Ok(Self { rt })
     }
 
+    /// Calls `callback` in a new thread on the connection state change
+    /// events
+    ///
+    /// See [`TradeContext::connection_events`].
+    pub fn on_connection_event<F>(&self, callback: F) -> Result<()>
+    where
+        F: FnMut(ConnectionEvent) + Send + 'static,
+    {
+        let rx = self
+            .rt
+            .call(|ctx| async move { Ok(ctx.connection_events()) })?;
+        spawn_callback_thread(rx, callback);
+        Ok(())
+    }
+
     /// Subscribe topics
     pub fn subscribe<I>(&self, topics: I) -> Result<()>
     where
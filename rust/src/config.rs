@@ -3,13 +3,16 @@ use std::{
     fmt::{self, Display},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, atomic::AtomicU64},
+    time::Duration,
 };
 
 use http::Method;
 pub(crate) use http::{HeaderValue, Request, header};
-use longport_httpcli::{HttpClient, HttpClientConfig, Json, is_cn};
+use longport_httpcli::{HttpClient, HttpClientConfig, Json, RetryPolicy};
+use longport_wscli::Proxy;
 use num_enum::IntoPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
@@ -17,7 +20,11 @@ use tracing::{Level, Subscriber, subscriber::NoSubscriber};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{filter::Targets, layer::SubscriberExt};
 
-use crate::error::Result;
+use crate::{
+    TlsConfig,
+    error::{Error, Result},
+    push_channel::{self, PushOverflowPolicy, PushReceiver, PushSender},
+};
 
 const DEFAULT_QUOTE_WS_URL: &str = "wss://openapi-quote.longportapp.com/v2";
 const DEFAULT_TRADE_WS_URL: &str = "wss://openapi-trade.longportapp.com/v2";
@@ -88,6 +95,18 @@ pub struct Config {
     pub(crate) enable_print_quote_packages: bool,
     pub(crate) language: Language,
     pub(crate) log_path: Option<PathBuf>,
+    pub(crate) quote_record_path: Option<PathBuf>,
+    pub(crate) paper_trading: bool,
+    pub(crate) paper_initial_cash: Vec<(String, Decimal)>,
+    pub(crate) push_channel_capacity: Option<usize>,
+    pub(crate) push_overflow_policy: PushOverflowPolicy,
+    pub(crate) reconnect_policy: Option<RetryPolicy>,
+    pub(crate) access_token_expired_at: Option<OffsetDateTime>,
+    pub(crate) candlestick_cache_dir: Option<PathBuf>,
+    pub(crate) realtime_trades_capacity: Option<usize>,
+    pub(crate) realtime_candlesticks_capacity: Option<usize>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) tls_config: Option<Arc<rustls::ClientConfig>>,
 }
 
 impl Config {
@@ -106,6 +125,18 @@ impl Config {
             push_candlestick_mode: None,
             enable_print_quote_packages: true,
             log_path: None,
+            quote_record_path: None,
+            paper_trading: false,
+            paper_initial_cash: Vec::new(),
+            push_channel_capacity: None,
+            push_overflow_policy: PushOverflowPolicy::DropOldest,
+            reconnect_policy: None,
+            access_token_expired_at: None,
+            candlestick_cache_dir: None,
+            realtime_trades_capacity: None,
+            realtime_candlesticks_capacity: None,
+            proxy: None,
+            tls_config: None,
         }
     }
 
@@ -134,10 +165,35 @@ impl Config {
     ///   `true` or `false` (Default: `true`)
     /// - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no
     ///   logs`)
+    /// - `LONGPORT_QUOTE_RECORD_PATH` - Record the quote pushes to the file
+    ///   (Default: `no recording`)
+    /// - `LONGPORT_PAPER_TRADING` - Simulate the orders of the `TradeContext`
+    ///   locally, `true` or `false` (Default: `false`)
+    /// - `LONGPORT_PAPER_INITIAL_CASH` - Initial cash of the paper trading
+    ///   account, e.g. `HKD:1000000,USD:100000`
+    /// - `LONGPORT_PUSH_CHANNEL_CAPACITY` - Capacity of the push channels
+    ///   (Default: `unbounded`)
+    /// - `LONGPORT_PUSH_OVERFLOW_POLICY` - `drop_oldest`, `drop_newest`,
+    ///   `conflate` or `block` (Default: `drop_oldest`)
+    /// - `LONGPORT_ACCESS_TOKEN_EXPIRED_AT` - The expiration time of the access
+    ///   token in RFC 3339 format, used by the
+    ///   [`TokenRefresher`](crate::TokenRefresher)
+    /// - `LONGPORT_CANDLESTICK_CACHE_DIR` - Cache the history candlesticks in
+    ///   the directory (Default: `no cache`)
+    /// - `LONGPORT_REALTIME_TRADES_CAPACITY` - Number of the trades kept for
+    ///   each symbol (Default: `500`)
+    /// - `LONGPORT_REALTIME_CANDLESTICKS_CAPACITY` - Number of the candlesticks
+    ///   kept for each subscribed period (Default: `500`)
+    /// - `LONGPORT_PROXY` - Proxy of the HTTP requests and the websocket
+    ///   connections, see [`Config::proxy`] (Default: the proxy of the
+    ///   `HTTPS_PROXY` or `ALL_PROXY` environment variables)
+    /// - `LONGPORT_TLS_ROOT_CA`, `LONGPORT_TLS_PINS`, `LONGPORT_TLS_CLIENT_CERT`
+    ///   and `LONGPORT_TLS_CLIENT_KEY` - TLS configuration, see
+    ///   [`TlsConfig::from_env`]
     pub fn from_env() -> Result<Self> {
         let _ = dotenv::dotenv();
 
-        let http_cli_config = HttpClientConfig::from_env()?;
+        let mut http_cli_config = HttpClientConfig::from_env()?;
         let language = std::env::var("LONGPORT_LANGUAGE")
             .ok()
             .and_then(|value| value.parse::<Language>().ok())
@@ -158,6 +214,78 @@ impl Config {
             .unwrap_or("true")
             == "true";
         let log_path = std::env::var("LONGPORT_LOG_PATH").ok().map(PathBuf::from);
+        let quote_record_path = std::env::var("LONGPORT_QUOTE_RECORD_PATH")
+            .ok()
+            .map(PathBuf::from);
+        let paper_trading = std::env::var("LONGPORT_PAPER_TRADING").as_deref() == Ok("true");
+        let paper_initial_cash = match std::env::var("LONGPORT_PAPER_INITIAL_CASH") {
+            Ok(value) => value
+                .split(',')
+                .filter(|item| !item.trim().is_empty())
+                .map(|item| {
+                    let (currency, amount) = item.split_once(':').ok_or_else(|| {
+                        Error::parse_field_error("LONGPORT_PAPER_INITIAL_CASH", item)
+                    })?;
+                    let amount = amount.trim().parse::<Decimal>().map_err(|err| {
+                        Error::parse_field_error("LONGPORT_PAPER_INITIAL_CASH", err)
+                    })?;
+                    Ok((currency.trim().to_string(), amount))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Err(_) => Vec::new(),
+        };
+        let push_channel_capacity =
+            match std::env::var("LONGPORT_PUSH_CHANNEL_CAPACITY") {
+                Ok(value) => Some(value.parse::<usize>().map_err(|err| {
+                    Error::parse_field_error("LONGPORT_PUSH_CHANNEL_CAPACITY", err)
+                })?),
+                Err(_) => None,
+            };
+        let push_overflow_policy = match std::env::var("LONGPORT_PUSH_OVERFLOW_POLICY") {
+            Ok(value) => value
+                .parse::<PushOverflowPolicy>()
+                .map_err(|_| Error::parse_field_error("LONGPORT_PUSH_OVERFLOW_POLICY", value))?,
+            Err(_) => PushOverflowPolicy::DropOldest,
+        };
+
+        let candlestick_cache_dir = std::env::var("LONGPORT_CANDLESTICK_CACHE_DIR")
+            .ok()
+            .map(PathBuf::from);
+        let realtime_trades_capacity = match std::env::var("LONGPORT_REALTIME_TRADES_CAPACITY") {
+            Ok(value) => Some(value.parse::<usize>().map_err(|err| {
+                Error::parse_field_error("LONGPORT_REALTIME_TRADES_CAPACITY", err)
+            })?),
+            Err(_) => None,
+        };
+        let realtime_candlesticks_capacity =
+            match std::env::var("LONGPORT_REALTIME_CANDLESTICKS_CAPACITY") {
+                Ok(value) => Some(value.parse::<usize>().map_err(|err| {
+                    Error::parse_field_error("LONGPORT_REALTIME_CANDLESTICKS_CAPACITY", err)
+                })?),
+                Err(_) => None,
+            };
+        let proxy = std::env::var("LONGPORT_PROXY").ok();
+        if let Some(proxy) = &proxy {
+            proxy
+                .parse::<Proxy>()
+                .map_err(|err| Error::parse_field_error("LONGPORT_PROXY", err))?;
+        }
+        let access_token_expired_at = match std::env::var("LONGPORT_ACCESS_TOKEN_EXPIRED_AT") {
+            Ok(value) => Some(
+                OffsetDateTime::parse(&value, &time::format_description::well_known::Rfc3339)
+                    .map_err(|err| {
+                        Error::parse_field_error("LONGPORT_ACCESS_TOKEN_EXPIRED_AT", err)
+                    })?,
+            ),
+            Err(_) => None,
+        };
+
+        let tls_config = TlsConfig::from_env()?
+            .map(|tls_config| tls_config.build())
+            .transpose()?;
+        if let Some(tls_config) = &tls_config {
+            http_cli_config = http_cli_config.tls_config(tls_config.clone());
+        }
 
         Ok(Config {
             http_cli_config,
@@ -168,6 +296,18 @@ impl Config {
             push_candlestick_mode,
             enable_print_quote_packages,
             log_path,
+            quote_record_path,
+            paper_trading,
+            paper_initial_cash,
+            push_channel_capacity,
+            push_overflow_policy,
+            reconnect_policy: None,
+            access_token_expired_at,
+            candlestick_cache_dir,
+            realtime_trades_capacity,
+            realtime_candlesticks_capacity,
+            proxy,
+            tls_config,
         })
     }
 
@@ -254,9 +394,16 @@ impl Config {
     }
 
     #[inline]
-    pub(crate) fn create_http_client(&self) -> HttpClient {
-        HttpClient::new(self.http_cli_config.clone())
-            .header(header::ACCEPT_LANGUAGE, self.language.as_str())
+    pub(crate) fn create_http_client(&self) -> Result<HttpClient> {
+        Ok(HttpClient::new(self.http_cli_config.clone())?
+            .header(header::ACCEPT_LANGUAGE, self.language.as_str()))
+    }
+
+    async fn is_cn(&self) -> bool {
+        match self.create_http_client() {
+            Ok(http_cli) => http_cli.is_cn().await,
+            Err(_) => false,
+        }
     }
 
     fn create_ws_request(&self, url: &str) -> tokio_tungstenite::tungstenite::Result<Request<()>> {
@@ -274,7 +421,7 @@ impl Config {
         match self.quote_ws_url.as_deref() {
             Some(url) => (url, self.create_ws_request(url)),
             None => {
-                let url = if is_cn().await {
+                let url = if self.is_cn().await {
                     DEFAULT_QUOTE_WS_URL_CN
                 } else {
                     DEFAULT_QUOTE_WS_URL
@@ -290,7 +437,7 @@ impl Config {
         match self.trade_ws_url.as_deref() {
             Some(url) => (url, self.create_ws_request(url)),
             None => {
-                let url = if is_cn().await {
+                let url = if self.is_cn().await {
                     DEFAULT_TRADE_WS_URL_CN
                 } else {
                     DEFAULT_TRADE_WS_URL
@@ -300,6 +447,32 @@ impl Config {
         }
     }
 
+    /// Specifies the expiration time of the access token, used by the
+    /// [`TokenRefresher`](crate::TokenRefresher) to decide when to refresh it
+    ///
+    /// Default: `unknown`, the token is refreshed as soon as the refresher
+    /// starts
+    pub fn access_token_expired_at(self, expired_at: OffsetDateTime) -> Self {
+        Self {
+            access_token_expired_at: Some(expired_at),
+            ..self
+        }
+    }
+
+    /// Returns the current `access_token`
+    pub fn access_token(&self) -> String {
+        self.http_cli_config.access_token()
+    }
+
+    /// Replaces the `access_token`
+    ///
+    /// The new token is used by the following HTTP requests and websocket
+    /// reconnections of all the contexts created from this `Config` or its
+    /// clones.
+    pub fn set_access_token(&self, access_token: impl Into<String>) {
+        self.http_cli_config.set_access_token(access_token);
+    }
+
     /// Gets a new `access_token`
     ///
     /// `expired_at` - The expiration time of the access token, defaults to `90`
@@ -325,7 +498,7 @@ impl Config {
         };
 
         let new_token = self
-            .create_http_client()
+            .create_http_client()?
             .request(Method::GET, "/v1/token/refresh")
             .query_params(request)
             .response::<Json<Response>>()
@@ -364,6 +537,211 @@ impl Config {
         self
     }
 
+    /// Record the quote pushes received by the `QuoteContext` to the file at
+    /// `path`, the file can be replayed later with
+    /// [`QuoteContext::replay`](crate::quote::QuoteContext::replay)
+    ///
+    /// The file is truncated when the `QuoteContext` is created, the records
+    /// are written by a background task and flushed every second and when the
+    /// `QuoteContext` is closed.
+    ///
+    /// Default: `None`
+    pub fn record_quote_pushes(mut self, path: impl Into<PathBuf>) -> Self {
+        self.quote_record_path = Some(path.into());
+        self
+    }
+
+    /// Simulate the orders of the `TradeContext` locally instead of sending
+    /// them to the exchange
+    ///
+    /// Orders are matched against the live quotes, and the account balance
+    /// and stock positions are kept in memory. See
+    /// [`TradeContext`](crate::trade::TradeContext) for the details.
+    ///
+    /// The quotes are received by a dedicated `QuoteContext` created by
+    /// `TradeContext::try_new`, which opens another quote connection, use
+    /// [`TradeContext::try_new_paper`](crate::trade::TradeContext::try_new_paper)
+    /// to match the orders against an existing `QuoteContext` instead.
+    ///
+    /// Default: `false`
+    pub fn paper_trading(self) -> Self {
+        Self {
+            paper_trading: true,
+            ..self
+        }
+    }
+
+    /// Sets the initial cash of the paper trading account in `currency`
+    ///
+    /// Default: `HKD 1,000,000` and `USD 1,000,000` if no initial cash is
+    /// specified
+    pub fn paper_initial_cash(mut self, currency: impl Into<String>, amount: Decimal) -> Self {
+        self.paper_initial_cash.push((currency.into(), amount));
+        self
+    }
+
+    /// Bounds the push channels of the `QuoteContext` and `TradeContext` to
+    /// `capacity` events, `policy` decides what to do when a channel is full
+    ///
+    /// The number of the dropped events can be read with
+    /// [`QuoteContext::dropped_push_events`](crate::quote::QuoteContext::dropped_push_events)
+    /// and
+    /// [`TradeContext::dropped_push_events`](crate::trade::TradeContext::dropped_push_events).
+    ///
+    /// With [`PushOverflowPolicy::Block`], a consumer that stops receiving
+    /// stalls the whole context, including its requests and its push streams.
+    ///
+    /// Default: `unbounded`
+    pub fn push_channel(self, capacity: usize, policy: PushOverflowPolicy) -> Self {
+        Self {
+            push_channel_capacity: Some(capacity),
+            push_overflow_policy: policy,
+            ..self
+        }
+    }
+
+    /// Specifies the retry policy of the HTTP requests rejected with `429 Too
+    /// Many Requests`
+    ///
+    /// The websocket reconnections are retried with
+    /// [`Config::reconnect_policy`].
+    ///
+    /// Default: `RetryPolicy::default()`
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.http_cli_config = self.http_cli_config.retry_policy(policy);
+        self
+    }
+
+    /// Specifies the retry policy of the websocket reconnections
+    ///
+    /// When the reconnection retries run out, the context sends
+    /// [`ConnectionEvent::GaveUp`](crate::ConnectionEvent::GaveUp), closes the
+    /// push channel and fails all the following requests.
+    ///
+    /// Default: retried forever every `2s`
+    pub fn reconnect_policy(self, policy: RetryPolicy) -> Self {
+        Self {
+            reconnect_policy: Some(policy),
+            ..self
+        }
+    }
+
+    pub(crate) fn create_reconnect_policy(&self) -> RetryPolicy {
+        self.reconnect_policy.clone().unwrap_or_else(|| {
+            RetryPolicy::new()
+                .initial_delay(Duration::from_secs(2))
+                .factor(1.0)
+                .max_attempts(None)
+        })
+    }
+
+    /// Caches the history candlesticks returned by
+    /// [`QuoteContext::history_candlesticks_range`](crate::quote::QuoteContext::history_candlesticks_range)
+    /// in the directory
+    ///
+    /// The candlesticks are appended to a file per symbol, period, adjust type
+    /// and trade sessions, and only the trading days missing from the file are
+    /// requested. The forward-adjusted candlesticks are requested again when
+    /// a corporate action changes the adjustment.
+    ///
+    /// Only `history_candlesticks_range` uses the cache,
+    /// `history_candlesticks_by_offset` and `history_candlesticks_by_date`
+    /// always request the server.
+    ///
+    /// Default: `no cache`
+    pub fn candlestick_cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.candlestick_cache_dir = Some(path.into());
+        self
+    }
+
+    /// Specifies the number of the trades kept in memory for each symbol,
+    /// which is the most
+    /// [`QuoteContext::realtime_trades`](crate::quote::QuoteContext::realtime_trades)
+    /// can return
+    ///
+    /// The older trades are dropped as the new ones are pushed. The capacity
+    /// applies to all the symbols subscribed with the contexts created from
+    /// this `Config`.
+    ///
+    /// Default: `500`
+    pub fn realtime_trades_capacity(self, capacity: usize) -> Self {
+        Self {
+            realtime_trades_capacity: Some(capacity),
+            ..self
+        }
+    }
+
+    /// Specifies the number of the candlesticks kept in memory for each
+    /// period subscribed with
+    /// [`QuoteContext::subscribe_candlesticks`](crate::quote::QuoteContext::subscribe_candlesticks),
+    /// which is the most
+    /// [`QuoteContext::realtime_candlesticks`](crate::quote::QuoteContext::realtime_candlesticks)
+    /// can return
+    ///
+    /// The older candlesticks are dropped as the new ones are appended. The
+    /// capacity applies to all the periods subscribed with the contexts
+    /// created from this `Config`, it can not be set per subscription.
+    ///
+    /// Default: `500`
+    pub fn realtime_candlesticks_capacity(self, capacity: usize) -> Self {
+        Self {
+            realtime_candlesticks_capacity: Some(capacity),
+            ..self
+        }
+    }
+
+    /// Specifies the proxy of the HTTP requests and the websocket connections,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` url with an optional
+    /// `user:password@`
+    ///
+    /// The websocket connections go through HTTP CONNECT (over TLS for
+    /// `https://`) or SOCKS5, and the hosts listed in the `NO_PROXY`
+    /// environment variable are connected directly.
+    ///
+    /// Default: the proxy of the `HTTPS_PROXY` or `ALL_PROXY` environment
+    /// variables, creating a context fails if it is invalid
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        let url = url.into();
+        self.http_cli_config = self.http_cli_config.proxy(url.clone());
+        Self {
+            proxy: Some(url),
+            ..self
+        }
+    }
+
+    /// Specifies the TLS configuration of the HTTP requests and the websocket
+    /// connections
+    ///
+    /// Returns an error if the client certificate can not be used.
+    ///
+    /// Default: trusts the built-in root certificates
+    pub fn tls_config(mut self, tls_config: TlsConfig) -> Result<Self> {
+        let tls_config = tls_config.build()?;
+        self.http_cli_config = self.http_cli_config.tls_config(tls_config.clone());
+        Ok(Self {
+            tls_config: Some(tls_config),
+            ..self
+        })
+    }
+
+    pub(crate) fn create_ws_proxy(&self) -> Result<Option<Proxy>> {
+        match &self.proxy {
+            Some(url) => Ok(Some(url.parse().map_err(Error::from)?)),
+            None => Ok(Proxy::from_env()?),
+        }
+    }
+
+    pub(crate) fn create_push_channel<T>(
+        &self,
+        dropped: Arc<AtomicU64>,
+    ) -> (PushSender<T>, PushReceiver<T>) {
+        push_channel::channel(
+            self.push_channel_capacity,
+            self.push_overflow_policy,
+            dropped,
+        )
+    }
+
     pub(crate) fn create_log_subscriber(
         &self,
         path: impl AsRef<Path>,
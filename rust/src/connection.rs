@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use longport_wscli::{WsClientError, WsCloseReason};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+
+use crate::Error;
+
+/// Connection state change event of a `QuoteContext` or `TradeContext`
+///
+/// The context sends `Connecting` → `Connected` → `Authenticated` while it
+/// is created, and the reconnections are described by `Disconnected` →
+/// `Connecting` → `Connected` → `Reconnected` → `Resubscribed`. A failed
+/// reconnection attempt emits another `Disconnected` before it is retried,
+/// and `GaveUp` is the last event once the retries of the
+/// [`RetryPolicy`](crate::RetryPolicy) run out.
+///
+/// Since the context can only be subscribed after it is created, each new
+/// receiver first gets the latest event, the current state of the
+/// connection (`Authenticated` if nothing happened since the context was
+/// created), followed by the events occurred after it is created.
+#[derive(Debug, Clone)]
+pub enum ConnectionEvent {
+    /// Connecting to the server
+    Connecting,
+    /// The websocket connection has been established
+    Connected,
+    /// The first session has been created with a new OTP
+    Authenticated,
+    /// The session has been restored after a reconnection
+    Reconnected {
+        /// `true` if the previous session was reused, `false` if a new
+        /// session was created with a fresh OTP
+        session_reused: bool,
+    },
+    /// The subscriptions have been restored after a reconnection
+    Resubscribed,
+    /// The connection was lost or a reconnection attempt failed
+    Disconnected {
+        /// The close frame sent by the server, if any
+        reason: Option<WsCloseReason>,
+        /// The error that caused the disconnection
+        error: String,
+    },
+    /// The reconnection retries ran out, the context is closed
+    GaveUp {
+        /// The number of the reconnection attempts
+        attempts: u32,
+        /// The last error
+        error: String,
+    },
+}
+
+impl ConnectionEvent {
+    pub(crate) fn disconnected(err: &Error) -> Self {
+        let reason = match err {
+            Error::WsClient(WsClientError::ConnectionClosed { reason }) => reason.clone(),
+            _ => None,
+        };
+        ConnectionEvent::Disconnected {
+            reason,
+            error: err.to_string(),
+        }
+    }
+}
+
+/// Sends the connection events to all the receivers returned by
+/// `connection_events`, replaying the latest event to the new receivers
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionEvents(Arc<Mutex<Inner>>);
+
+#[derive(Default)]
+struct Inner {
+    senders: Vec<mpsc::UnboundedSender<ConnectionEvent>>,
+    latest: Option<ConnectionEvent>,
+}
+
+impl ConnectionEvents {
+    pub(crate) fn subscribe(&self) -> mpsc::UnboundedReceiver<ConnectionEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut inner = self.0.lock();
+        if let Some(event) = &inner.latest {
+            _ = tx.send(event.clone());
+        }
+        inner.senders.push(tx);
+        rx
+    }
+
+    pub(crate) fn send(&self, event: ConnectionEvent) {
+        tracing::info!(event = ?event, "connection event");
+        let mut inner = self.0.lock();
+        inner.senders.retain(|tx| tx.send(event.clone()).is_ok());
+        inner.latest = Some(event);
+    }
+}
@@ -55,6 +55,37 @@ pub enum Error {
         time: OffsetDateTime,
     },
 
+    /// The operation requires a server connection, which is not available
+    /// when replaying a recording
+    #[error("not supported when replaying a recording")]
+    ReplayNotSupported,
+
+    /// Paper trading error
+    #[error("paper trading: {0}")]
+    PaperTrading(String),
+
+    /// Unknown account of the `AccountManager`
+    #[error("unknown account: {account_id}")]
+    UnknownAccount {
+        /// Account id
+        account_id: String,
+    },
+
+    /// Duplicate account of the `AccountManager`
+    #[error("duplicate account: {account_id}")]
+    DuplicateAccount {
+        /// Account id
+        account_id: String,
+    },
+
+    /// Invalid TLS configuration
+    #[error("tls: {0}")]
+    Tls(String),
+
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     /// HTTP client error
     #[error(transparent)]
     HttpClient(#[from] HttpClientError),
@@ -116,6 +147,12 @@ impl Error {
             | Error::UnknownTradeSession { .. }
             | Error::ParseField { .. }
             | Error::UnknownCommand(_)
+            | Error::ReplayNotSupported
+            | Error::PaperTrading(_)
+            | Error::UnknownAccount { .. }
+            | Error::DuplicateAccount { .. }
+            | Error::Tls(_)
+            | Error::Io(_)
             | Error::HttpClient(_)
             | Error::WsClient(_) => SimpleError::Other(self.to_string()),
             #[cfg(feature = "blocking")]
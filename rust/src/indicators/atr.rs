@@ -0,0 +1,59 @@
+use rust_decimal::Decimal;
+
+use crate::{indicators::Indicator, quote::Candlestick};
+
+/// Average true range with Wilder's smoothing
+///
+/// The true range of the first candlestick is `high - low`, the first value is
+/// the simple average of the first `period` true ranges.
+///
+/// Default: `period = 14`
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<Decimal>,
+    count: usize,
+    value: Decimal,
+}
+
+impl Atr {
+    /// Create a new `Atr`
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_close: None,
+            count: 0,
+            value: Decimal::ZERO,
+        }
+    }
+}
+
+impl Default for Atr {
+    fn default() -> Self {
+        Self::new(14)
+    }
+}
+
+impl Indicator for Atr {
+    type Output = Decimal;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        let range = candlestick.high - candlestick.low;
+        let true_range = match self.prev_close.replace(candlestick.close) {
+            Some(prev_close) => range
+                .max((candlestick.high - prev_close).abs())
+                .max((candlestick.low - prev_close).abs()),
+            None => range,
+        };
+        let period = Decimal::from(self.period);
+
+        self.count += 1;
+        if self.count <= self.period {
+            self.value += true_range / period;
+            (self.count == self.period).then_some(self.value)
+        } else {
+            self.value = (self.value * (period - Decimal::ONE) + true_range) / period;
+            Some(self.value)
+        }
+    }
+}
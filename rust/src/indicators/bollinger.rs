@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::{indicators::Indicator, quote::Candlestick};
+
+/// Value of [`BollingerBands`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BollingerValue {
+    /// `middle + multiplier * standard deviation`
+    pub upper: Decimal,
+    /// Simple moving average of the close prices
+    pub middle: Decimal,
+    /// `middle - multiplier * standard deviation`
+    pub lower: Decimal,
+}
+
+/// Bollinger bands, the standard deviation is the population standard
+/// deviation of the close prices in the window
+///
+/// Default: `period = 20`, `multiplier = 2`
+#[derive(Debug, Clone)]
+pub struct BollingerBands {
+    period: usize,
+    multiplier: Decimal,
+    window: VecDeque<Decimal>,
+}
+
+impl BollingerBands {
+    /// Create a new `BollingerBands`
+    pub fn new(period: usize, multiplier: Decimal) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            multiplier,
+            window: VecDeque::with_capacity(period + 1),
+        }
+    }
+}
+
+impl Default for BollingerBands {
+    fn default() -> Self {
+        Self::new(20, Decimal::TWO)
+    }
+}
+
+impl Indicator for BollingerBands {
+    type Output = BollingerValue;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        self.window.push_back(candlestick.close);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = Decimal::from(self.period);
+        let middle = self.window.iter().sum::<Decimal>() / n;
+        let variance = self
+            .window
+            .iter()
+            .map(|close| (close - middle) * (close - middle))
+            .sum::<Decimal>()
+            / n;
+        let width = self.multiplier * variance.sqrt().unwrap_or_default();
+        Some(BollingerValue {
+            upper: middle + width,
+            middle,
+            lower: middle - width,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    indicators::{Indicator, ma::Smoothing},
+    quote::Candlestick,
+};
+
+/// Value of [`Kdj`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KdjValue {
+    /// K
+    pub k: Decimal,
+    /// D
+    pub d: Decimal,
+    /// `3 * k - 2 * d`
+    pub j: Decimal,
+}
+
+/// Stochastic oscillator (KDJ)
+///
+/// `RSV` is the position of the close price between the lowest low and the
+/// highest high of the last `n` candlesticks, `K` is the smoothed `RSV` with
+/// the factor `1 / m1` and `D` is the smoothed `K` with the factor `1 / m2`,
+/// both start from `50`. `RSV` is `50` when the highest high equals the lowest
+/// low.
+///
+/// Default: `n = 9`, `m1 = 3`, `m2 = 3`
+#[derive(Debug, Clone)]
+pub struct Kdj {
+    n: usize,
+    window: VecDeque<(Decimal, Decimal)>,
+    k: Smoothing,
+    d: Smoothing,
+}
+
+impl Kdj {
+    /// Create a new `Kdj`
+    pub fn new(n: usize, m1: usize, m2: usize) -> Self {
+        let n = n.max(1);
+        let initial = Decimal::ONE_HUNDRED / Decimal::TWO;
+        Self {
+            n,
+            window: VecDeque::with_capacity(n + 1),
+            k: Smoothing::sma(m1).initial(initial),
+            d: Smoothing::sma(m2).initial(initial),
+        }
+    }
+}
+
+impl Default for Kdj {
+    fn default() -> Self {
+        Self::new(9, 3, 3)
+    }
+}
+
+impl Indicator for Kdj {
+    type Output = KdjValue;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        self.window.push_back((candlestick.high, candlestick.low));
+        if self.window.len() > self.n {
+            self.window.pop_front();
+        }
+
+        let (high, low) = self.window.iter().fold(
+            (Decimal::MIN, Decimal::MAX),
+            |(max_high, min_low), (high, low)| (max_high.max(*high), min_low.min(*low)),
+        );
+        let rsv = if high > low {
+            (candlestick.close - low) / (high - low) * Decimal::ONE_HUNDRED
+        } else {
+            Decimal::ONE_HUNDRED / Decimal::TWO
+        };
+        let k = self.k.next(rsv);
+        let d = self.d.next(k);
+        Some(KdjValue {
+            k,
+            d,
+            j: Decimal::from(3) * k - Decimal::TWO * d,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use rust_decimal::Decimal;
+
+use crate::{indicators::Indicator, quote::Candlestick};
+
+/// Simple moving average of the close prices
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<Decimal>,
+    sum: Decimal,
+}
+
+impl Sma {
+    /// Create a new `Sma`
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for Sma {
+    type Output = Decimal;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        self.window.push_back(candlestick.close);
+        self.sum += candlestick.close;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        (self.window.len() == self.period).then(|| self.sum / Decimal::from(self.period))
+    }
+}
+
+/// Exponential moving average of the close prices
+///
+/// The smoothing factor is `2 / (period + 1)`, the first value is the first
+/// close price.
+#[derive(Debug, Clone)]
+pub struct Ema(Smoothing);
+
+impl Ema {
+    /// Create a new `Ema`
+    pub fn new(period: usize) -> Self {
+        Self(Smoothing::ema(period))
+    }
+}
+
+impl Indicator for Ema {
+    type Output = Decimal;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        Some(self.0.next(candlestick.close))
+    }
+}
+
+/// Exponential smoothing of a series of values
+#[derive(Debug, Clone)]
+pub(crate) struct Smoothing {
+    alpha: Decimal,
+    value: Option<Decimal>,
+}
+
+impl Smoothing {
+    /// The smoothing factor is `2 / (period + 1)`
+    pub(crate) fn ema(period: usize) -> Self {
+        Self::new(Decimal::TWO / Decimal::from(period.max(1) + 1))
+    }
+
+    /// The smoothing factor is `1 / period`
+    pub(crate) fn sma(period: usize) -> Self {
+        Self::new(Decimal::ONE / Decimal::from(period.max(1)))
+    }
+
+    fn new(alpha: Decimal) -> Self {
+        Self { alpha, value: None }
+    }
+
+    /// Specifies the value before the first one
+    pub(crate) fn initial(self, value: Decimal) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    pub(crate) fn next(&mut self, value: Decimal) -> Decimal {
+        let value = match self.value {
+            Some(prev) => prev + self.alpha * (value - prev),
+            None => value,
+        };
+        self.value = Some(value);
+        value
+    }
+}
@@ -0,0 +1,58 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    indicators::{Indicator, ma::Smoothing},
+    quote::Candlestick,
+};
+
+/// Value of [`Macd`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MacdValue {
+    /// Fast EMA - slow EMA
+    pub dif: Decimal,
+    /// EMA of `dif`
+    pub dea: Decimal,
+    /// `(dif - dea) * 2`
+    pub macd: Decimal,
+}
+
+/// Moving average convergence divergence
+///
+/// Default: `fast = 12`, `slow = 26`, `signal = 9`
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Smoothing,
+    slow: Smoothing,
+    signal: Smoothing,
+}
+
+impl Macd {
+    /// Create a new `Macd`
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: Smoothing::ema(fast),
+            slow: Smoothing::ema(slow),
+            signal: Smoothing::ema(signal),
+        }
+    }
+}
+
+impl Default for Macd {
+    fn default() -> Self {
+        Self::new(12, 26, 9)
+    }
+}
+
+impl Indicator for Macd {
+    type Output = MacdValue;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        let dif = self.fast.next(candlestick.close) - self.slow.next(candlestick.close);
+        let dea = self.signal.next(dif);
+        Some(MacdValue {
+            dif,
+            dea,
+            macd: (dif - dea) * Decimal::TWO,
+        })
+    }
+}
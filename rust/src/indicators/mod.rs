@@ -0,0 +1,349 @@
+//! Technical indicators
+//!
+//! The indicators are calculated incrementally with [`Decimal`](crate::Decimal)
+//! prices. An [`IndicatorSeries`] is seeded with the candlesticks returned by
+//! [`QuoteContext::candlesticks`](crate::QuoteContext::candlesticks) and then
+//! kept up to date with the [`PushCandlestick`] events.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use futures_util::StreamExt;
+//! use longport::{
+//!     Config,
+//!     indicators::{IndicatorSeries, Macd},
+//!     quote::{AdjustType, Period, QuoteContext, TradeSessions},
+//! };
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let config = Arc::new(Config::from_env()?);
+//! let (ctx, _) = QuoteContext::try_new(config).await?;
+//!
+//! let mut macd = IndicatorSeries::new(Macd::default());
+//! let mut stream = ctx.candlestick_stream("700.HK", Period::OneMinute);
+//! let history = ctx
+//!     .subscribe_candlesticks("700.HK", Period::OneMinute, TradeSessions::Intraday)
+//!     .await?;
+//! macd.extend(&history);
+//!
+//! while let Some(push) = stream.next().await {
+//!     println!("{:?}", macd.update_push(&push));
+//! }
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! # });
+//! ```
+
+mod atr;
+mod bollinger;
+mod kdj;
+mod ma;
+mod macd;
+mod rsi;
+
+pub use atr::Atr;
+pub use bollinger::{BollingerBands, BollingerValue};
+pub use kdj::{Kdj, KdjValue};
+pub use ma::{Ema, Sma};
+pub use macd::{Macd, MacdValue};
+pub use rsi::Rsi;
+
+use time::OffsetDateTime;
+
+use crate::quote::{Candlestick, PushCandlestick};
+
+/// A technical indicator calculated candlestick by candlestick
+pub trait Indicator: Clone {
+    /// The value of the indicator
+    type Output: Clone;
+
+    /// Feeds the next confirmed candlestick, returns the new value or `None`
+    /// if there are not enough candlesticks yet
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output>;
+}
+
+/// Keeps an [`Indicator`] up to date with the confirmed and the unconfirmed
+/// candlesticks
+///
+/// Only the confirmed candlesticks are fed to the indicator. The value of an
+/// unconfirmed candlestick is calculated on a copy of the indicator, so the
+/// last candlestick can be updated any number of times before it is confirmed.
+/// An unconfirmed candlestick is treated as confirmed when a candlestick with
+/// a later timestamp arrives, and the candlesticks not later than the last
+/// confirmed one are ignored.
+#[derive(Debug, Clone)]
+pub struct IndicatorSeries<I: Indicator> {
+    indicator: I,
+    last_timestamp: Option<OffsetDateTime>,
+    pending: Option<Candlestick>,
+    value: Option<I::Output>,
+}
+
+impl<I: Indicator> IndicatorSeries<I> {
+    /// Create a new `IndicatorSeries`
+    pub fn new(indicator: I) -> Self {
+        Self {
+            indicator,
+            last_timestamp: None,
+            pending: None,
+            value: None,
+        }
+    }
+
+    /// Feeds the history candlesticks, they are treated as confirmed
+    ///
+    /// The last candlestick returned by
+    /// [`QuoteContext::candlesticks`](crate::QuoteContext::candlesticks)
+    /// during the trading hours is not finished yet, feed it with
+    /// [`IndicatorSeries::update`] instead if that matters.
+    pub fn extend<'a>(&mut self, candlesticks: impl IntoIterator<Item = &'a Candlestick>) {
+        for candlestick in candlesticks {
+            self.update(candlestick, true);
+        }
+    }
+
+    /// Feeds a candlestick, returns the value of the indicator including it
+    pub fn update(&mut self, candlestick: &Candlestick, is_confirmed: bool) -> Option<I::Output> {
+        if self
+            .last_timestamp
+            .is_some_and(|last_timestamp| candlestick.timestamp <= last_timestamp)
+        {
+            return self.value.clone();
+        }
+
+        if let Some(pending) = self
+            .pending
+            .take_if(|pending| pending.timestamp < candlestick.timestamp)
+        {
+            self.confirm(&pending);
+        }
+
+        if is_confirmed {
+            self.pending = None;
+            self.confirm(candlestick);
+        } else {
+            self.value = self.indicator.clone().next(candlestick);
+            self.pending = Some(*candlestick);
+        }
+        self.value.clone()
+    }
+
+    /// Feeds a candlestick push event
+    #[inline]
+    pub fn update_push(&mut self, push: &PushCandlestick) -> Option<I::Output> {
+        self.update(&push.candlestick, push.is_confirmed)
+    }
+
+    /// Returns the value of the indicator including the unconfirmed
+    /// candlestick
+    #[inline]
+    pub fn value(&self) -> Option<&I::Output> {
+        self.value.as_ref()
+    }
+
+    fn confirm(&mut self, candlestick: &Candlestick) {
+        self.value = self.indicator.next(candlestick);
+        self.last_timestamp = Some(candlestick.timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::quote::TradeSession;
+
+    fn candlestick(minute: i64, close: Decimal) -> Candlestick {
+        bar(minute, close, close, close)
+    }
+
+    fn bar(minute: i64, high: Decimal, low: Decimal, close: Decimal) -> Candlestick {
+        Candlestick {
+            close,
+            open: close,
+            low,
+            high,
+            volume: 0,
+            turnover: Decimal::ZERO,
+            timestamp: datetime!(2025-01-02 09:30 UTC) + time::Duration::minutes(minute),
+            trade_session: TradeSession::Intraday,
+        }
+    }
+
+    fn run<I: Indicator>(indicator: I, closes: &[Decimal]) -> Vec<Option<I::Output>> {
+        let mut series = IndicatorSeries::new(indicator);
+        closes
+            .iter()
+            .enumerate()
+            .map(|(idx, close)| series.update(&candlestick(idx as i64, *close), true))
+            .collect()
+    }
+
+    fn run_bars<I: Indicator>(
+        indicator: I,
+        bars: &[(Decimal, Decimal, Decimal)],
+    ) -> Vec<Option<I::Output>> {
+        let mut series = IndicatorSeries::new(indicator);
+        bars.iter()
+            .enumerate()
+            .map(|(idx, (high, low, close))| {
+                series.update(&bar(idx as i64, *high, *low, *close), true)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unconfirmed_candlestick() {
+        let mut series = IndicatorSeries::new(Sma::new(3));
+        series.extend(&[candlestick(0, decimal!(1)), candlestick(1, decimal!(2))]);
+        assert_eq!(series.value(), None);
+
+        assert_eq!(
+            series.update(&candlestick(2, decimal!(3)), false),
+            Some(decimal!(2))
+        );
+        assert_eq!(
+            series.update(&candlestick(2, decimal!(6)), false),
+            Some(decimal!(3))
+        );
+        assert_eq!(
+            series.update(&candlestick(2, decimal!(6)), true),
+            Some(decimal!(3))
+        );
+
+        // the confirmed candlestick is not fed again
+        assert_eq!(
+            series.update(&candlestick(2, decimal!(100)), true),
+            Some(decimal!(3))
+        );
+
+        assert_eq!(
+            series.update(&candlestick(3, decimal!(9)), false),
+            Some(decimal!(17) / decimal!(3))
+        );
+        // the unconfirmed candlestick is confirmed by a later one
+        assert_eq!(
+            series.update(&candlestick(4, decimal!(3)), false),
+            Some(decimal!(6))
+        );
+        assert_eq!(series.value(), Some(&decimal!(6)));
+    }
+
+    #[test]
+    fn sma_and_ema() {
+        let closes = [decimal!(1), decimal!(2), decimal!(3), decimal!(4)];
+        assert_eq!(
+            run(Sma::new(3), &closes),
+            vec![None, None, Some(decimal!(2)), Some(decimal!(3))]
+        );
+        assert_eq!(
+            run(Ema::new(3), &closes),
+            vec![
+                Some(decimal!(1)),
+                Some(decimal!(1.5)),
+                Some(decimal!(2.25)),
+                Some(decimal!(3.125))
+            ]
+        );
+    }
+
+    #[test]
+    fn rsi() {
+        let values = run(
+            Rsi::new(2),
+            &[decimal!(10), decimal!(11), decimal!(10), decimal!(12)],
+        );
+        assert_eq!(values[..2], [None, None]);
+        assert_eq!(values[2], Some(decimal!(50)));
+        assert_eq!(
+            values[3].map(|value| value.round_dp(4)),
+            Some(decimal!(83.3333))
+        );
+    }
+
+    #[test]
+    fn macd() {
+        let values = run(Macd::new(2, 4, 3), &[decimal!(10), decimal!(12)]);
+        // fast: 10, 11.3333; slow: 10, 10.8; dea alpha: 0.5
+        let value = values[1].unwrap();
+        assert_eq!(value.dif.round_dp(4), decimal!(0.5333));
+        assert_eq!(value.dea.round_dp(4), decimal!(0.2667));
+        assert_eq!(value.macd.round_dp(4), decimal!(0.5333));
+    }
+
+    #[test]
+    fn bollinger_bands() {
+        let values = run(
+            BollingerBands::new(4, decimal!(2)),
+            &[decimal!(2), decimal!(4), decimal!(4), decimal!(6)],
+        );
+        // mean: 4, standard deviation: sqrt(8 / 4)
+        let value = values[3].unwrap();
+        assert_eq!(value.middle, decimal!(4));
+        assert_eq!(value.upper.round_dp(4), decimal!(6.8284));
+        assert_eq!(value.lower.round_dp(4), decimal!(1.1716));
+    }
+
+    #[test]
+    fn kdj() {
+        let values = run_bars(
+            Kdj::new(3, 3, 3),
+            &[
+                (decimal!(10), decimal!(8), decimal!(9)),
+                (decimal!(12), decimal!(9), decimal!(12)),
+                (decimal!(11), decimal!(7), decimal!(8)),
+                (decimal!(10), decimal!(9), decimal!(10)),
+            ],
+        );
+        let values = values
+            .into_iter()
+            .map(|value| {
+                let value = value.unwrap();
+                (
+                    value.k.round_dp(4),
+                    value.d.round_dp(4),
+                    value.j.round_dp(4),
+                )
+            })
+            .collect::<Vec<_>>();
+        // rsv: 50, 100, 20, 60 (the first candlestick leaves the window)
+        assert_eq!(
+            values,
+            vec![
+                (decimal!(50), decimal!(50), decimal!(50)),
+                (decimal!(66.6667), decimal!(55.5556), decimal!(88.8889)),
+                (decimal!(51.1111), decimal!(54.0741), decimal!(45.1852)),
+                (decimal!(54.0741), decimal!(54.0741), decimal!(54.0741)),
+            ]
+        );
+    }
+
+    #[test]
+    fn atr() {
+        let values = run_bars(
+            Atr::new(3),
+            &[
+                (decimal!(11), decimal!(9), decimal!(10)),
+                (decimal!(12), decimal!(10), decimal!(11)),
+                (decimal!(13), decimal!(10), decimal!(12)),
+                (decimal!(12), decimal!(8), decimal!(9)),
+                (decimal!(15), decimal!(14), decimal!(14.5)),
+            ],
+        );
+        // true range: 2, 2, 3, 4, 6 (the gap from the previous close)
+        assert_eq!(
+            values
+                .into_iter()
+                .map(|value| value.map(|value| value.round_dp(4)))
+                .collect::<Vec<_>>(),
+            vec![
+                None,
+                None,
+                Some(decimal!(2.3333)),
+                Some(decimal!(2.8889)),
+                Some(decimal!(3.9259))
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use rust_decimal::Decimal;
+
+use crate::{indicators::Indicator, quote::Candlestick};
+
+/// Relative strength index with Wilder's smoothing
+///
+/// The first value is available after `period` price changes.
+///
+/// Default: `period = 14`
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<Decimal>,
+    count: usize,
+    avg_gain: Decimal,
+    avg_loss: Decimal,
+}
+
+impl Rsi {
+    /// Create a new `Rsi`
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_close: None,
+            count: 0,
+            avg_gain: Decimal::ZERO,
+            avg_loss: Decimal::ZERO,
+        }
+    }
+}
+
+impl Default for Rsi {
+    fn default() -> Self {
+        Self::new(14)
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = Decimal;
+
+    fn next(&mut self, candlestick: &Candlestick) -> Option<Self::Output> {
+        let prev_close = self.prev_close.replace(candlestick.close)?;
+        let change = candlestick.close - prev_close;
+        let gain = change.max(Decimal::ZERO);
+        let loss = (-change).max(Decimal::ZERO);
+        let period = Decimal::from(self.period);
+
+        self.count += 1;
+        if self.count <= self.period {
+            // the first averages are the simple averages
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.count < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - Decimal::ONE) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - Decimal::ONE) + loss) / period;
+        }
+
+        let total = self.avg_gain + self.avg_loss;
+        Some(if total.is_zero() {
+            Decimal::ONE_HUNDRED / Decimal::TWO
+        } else {
+            Decimal::ONE_HUNDRED * self.avg_gain / total
+        })
+    }
+}
@@ -8,22 +8,36 @@
 #[macro_use]
 mod macros;
 
+mod account_manager;
 mod config;
+mod connection;
 mod error;
+mod push_channel;
 mod serde_utils;
+mod tls;
+mod token_refresher;
 mod types;
 
 #[cfg(feature = "blocking")]
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 pub mod blocking;
+pub mod indicators;
+pub mod portfolio;
 pub mod quote;
 pub mod trade;
 
+pub use account_manager::{AccountManager, AccountPushEvent};
 pub use config::{Config, Language, PushCandlestickMode};
+pub use connection::ConnectionEvent;
 pub use error::{Error, Result, SimpleError};
-pub use longport_httpcli as httpclient;
+pub use longport_httpcli::{self as httpclient, RetryPolicy};
 pub use longport_wscli as wsclient;
+pub use push_channel::{PushOverflowPolicy, PushReceiver};
 pub use quote::QuoteContext;
 pub use rust_decimal::Decimal;
+pub use tls::TlsConfig;
+pub use token_refresher::{
+    DotEnvTokenStore, FileTokenStore, TokenRefresher, TokenRefresherHandle, TokenStore,
+};
 pub use trade::TradeContext;
 pub use types::Market;
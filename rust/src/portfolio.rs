@@ -0,0 +1,573 @@
+//! Portfolio valuation
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    Market, QuoteContext, Result, TradeContext,
+    quote::{PushQuote, SecurityQuote, SubFlags, parse_market_from_symbol},
+    trade::{AccountBalance, FundPosition, Order, OrderSide, PushOrderChanged, StockPosition},
+};
+
+/// A stock position valued with the latest price
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    /// Security code
+    pub symbol: String,
+    /// Security name
+    pub symbol_name: String,
+    /// Market
+    pub market: Market,
+    /// Currency
+    pub currency: String,
+    /// The number of holdings, negative for a short position
+    pub quantity: Decimal,
+    /// Cost price
+    pub cost_price: Decimal,
+    /// The number of holdings before market opening
+    pub init_quantity: Decimal,
+    /// Latest price
+    pub last_done: Option<Decimal>,
+    /// Yesterday's close
+    pub prev_close: Option<Decimal>,
+    /// Net amount of the trades since market opening, positive for buying
+    day_trade_amount: Decimal,
+}
+
+impl Holding {
+    fn new(position: &StockPosition) -> Self {
+        let init_quantity = position.init_quantity.unwrap_or(position.quantity);
+        Self {
+            symbol: position.symbol.clone(),
+            symbol_name: position.symbol_name.clone(),
+            market: position.market,
+            currency: position.currency.clone(),
+            quantity: position.quantity,
+            cost_price: position.cost_price,
+            init_quantity,
+            last_done: None,
+            prev_close: None,
+            // the prices of the trades before the snapshot are unknown, count
+            // them at the cost price
+            day_trade_amount: (position.quantity - init_quantity) * position.cost_price,
+        }
+    }
+
+    /// Returns the latest price, or the cost price if no quote has been
+    /// received
+    #[inline]
+    pub fn price(&self) -> Decimal {
+        self.last_done.unwrap_or(self.cost_price)
+    }
+
+    /// Returns the market value
+    #[inline]
+    pub fn market_value(&self) -> Decimal {
+        self.quantity * self.price()
+    }
+
+    /// Returns the cost value
+    #[inline]
+    pub fn cost_value(&self) -> Decimal {
+        self.quantity * self.cost_price
+    }
+
+    /// Returns the unrealized profit and loss
+    #[inline]
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.market_value() - self.cost_value()
+    }
+
+    /// Returns the profit and loss since yesterday's close, including the
+    /// trades of today
+    pub fn day_pnl(&self) -> Decimal {
+        let prev_value = self.init_quantity * self.prev_close.unwrap_or_else(|| self.price());
+        self.market_value() - prev_value - self.day_trade_amount
+    }
+
+    /// Applies a trade of `quantity` (negative for selling) with the amount
+    /// `amount` (negative for selling)
+    fn apply_trade(&mut self, quantity: Decimal, amount: Decimal) {
+        let new_quantity = self.quantity + quantity;
+        if self.quantity.is_zero()
+            || self.quantity.is_sign_positive() == quantity.is_sign_positive()
+        {
+            // open or increase the position
+            self.cost_price = (self.cost_value() + amount) / new_quantity;
+        } else if !new_quantity.is_zero()
+            && new_quantity.is_sign_positive() != self.quantity.is_sign_positive()
+        {
+            // the position is reversed
+            self.cost_price = amount / quantity;
+        }
+        self.quantity = new_quantity;
+        self.day_trade_amount += amount;
+    }
+}
+
+/// The exposure of a group of holdings
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Exposure {
+    /// Net market value
+    pub market_value: Decimal,
+    /// Market value of the long positions
+    pub long_value: Decimal,
+    /// Market value of the short positions, as a positive number
+    pub short_value: Decimal,
+    /// Cost value
+    pub cost_value: Decimal,
+    /// Unrealized profit and loss
+    pub unrealized_pnl: Decimal,
+    /// Profit and loss since yesterday's close
+    pub day_pnl: Decimal,
+}
+
+impl Exposure {
+    fn add(&mut self, market_value: Decimal, cost_value: Decimal, day_pnl: Decimal) {
+        self.market_value += market_value;
+        if market_value.is_sign_negative() {
+            self.short_value -= market_value;
+        } else {
+            self.long_value += market_value;
+        }
+        self.cost_value += cost_value;
+        self.unrealized_pnl += market_value - cost_value;
+        self.day_pnl += day_pnl;
+    }
+}
+
+/// Portfolio event
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortfolioEvent {
+    /// The latest price of a holding changed
+    PriceChanged {
+        /// Security code
+        symbol: String,
+        /// Latest price
+        last_done: Decimal,
+    },
+    /// A fill changed the quantity of a holding
+    ///
+    /// The quote of a newly opened position is not subscribed until
+    /// [`Portfolio::refresh`].
+    PositionChanged {
+        /// Security code
+        symbol: String,
+        /// The number of holdings after the fill
+        quantity: Decimal,
+        /// Executed quantity, negative for selling
+        executed_quantity: Decimal,
+        /// Average price of the fill
+        executed_price: Decimal,
+    },
+}
+
+/// Portfolio valuation and real-time profit and loss
+///
+/// Loaded from the positions and balances of [`TradeContext`] and the quotes
+/// of [`QuoteContext`], it is kept up to date with the quote pushes
+/// ([`Portfolio::apply_quote`]) and the order pushes
+/// ([`Portfolio::apply_order_changed`]) until the next
+/// [`Portfolio::refresh`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{
+///     Config, QuoteContext, TradeContext,
+///     portfolio::Portfolio,
+///     quote::PushEventDetail,
+///     trade::{PushEvent, TopicType},
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let (quote_ctx, mut quote_receiver) = QuoteContext::try_new(config.clone()).await?;
+/// let (trade_ctx, mut trade_receiver) = TradeContext::try_new(config).await?;
+/// trade_ctx.subscribe([TopicType::Private]).await?;
+///
+/// let mut portfolio = Portfolio::load(&trade_ctx, &quote_ctx).await?;
+/// loop {
+///     let event = tokio::select! {
+///         Some(event) = quote_receiver.recv() => match event.detail {
+///             PushEventDetail::Quote(quote) => portfolio.apply_quote(&event.symbol, &quote),
+///             _ => None,
+///         },
+///         Some(PushEvent::OrderChanged(order)) = trade_receiver.recv() => {
+///             portfolio.apply_order_changed(&order)
+///         }
+///         else => break,
+///     };
+///     if event.is_some() {
+///         println!("{:?}", portfolio.exposure_by_currency());
+///     }
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    holdings: HashMap<String, Holding>,
+    fund_positions: Vec<FundPosition>,
+    balances: Vec<AccountBalance>,
+    /// Executed quantity and amount of each order
+    executions: HashMap<String, (Decimal, Decimal)>,
+}
+
+impl Portfolio {
+    /// Create a new `Portfolio` from the positions, the balances and the
+    /// orders of today
+    pub fn new(
+        stock_positions: Vec<StockPosition>,
+        fund_positions: Vec<FundPosition>,
+        balances: Vec<AccountBalance>,
+        today_orders: Vec<Order>,
+    ) -> Self {
+        let mut holdings: HashMap<String, Holding> = HashMap::new();
+        for position in &stock_positions {
+            match holdings.get_mut(&position.symbol) {
+                // the same security in several account channels
+                Some(holding) => {
+                    let other = Holding::new(position);
+                    let quantity = holding.quantity + other.quantity;
+                    if !quantity.is_zero() {
+                        holding.cost_price = (holding.cost_value() + other.cost_value()) / quantity;
+                    }
+                    holding.quantity = quantity;
+                    holding.init_quantity += other.init_quantity;
+                    holding.day_trade_amount += other.day_trade_amount;
+                }
+                None => {
+                    holdings.insert(position.symbol.clone(), Holding::new(position));
+                }
+            }
+        }
+
+        Self {
+            holdings,
+            fund_positions,
+            balances,
+            executions: today_orders
+                .into_iter()
+                .map(|order| {
+                    let amount = order.executed_quantity * order.executed_price.unwrap_or_default();
+                    (order.order_id, (order.executed_quantity, amount))
+                })
+                .collect(),
+        }
+    }
+
+    /// Load the portfolio and subscribe the quotes of the stock positions
+    pub async fn load(trade_ctx: &TradeContext, quote_ctx: &QuoteContext) -> Result<Self> {
+        let mut portfolio = Self::default();
+        portfolio.refresh(trade_ctx, quote_ctx).await?;
+        Ok(portfolio)
+    }
+
+    /// Reload the positions, the balances and the quotes from the server, and
+    /// subscribe the quotes of the stock positions
+    ///
+    /// The fills applied since the last refresh are replaced by the positions
+    /// returned by the server.
+    pub async fn refresh(
+        &mut self,
+        trade_ctx: &TradeContext,
+        quote_ctx: &QuoteContext,
+    ) -> Result<()> {
+        let today_orders = trade_ctx.today_orders(None).await?;
+        let stock_positions = trade_ctx
+            .stock_positions(None)
+            .await?
+            .channels
+            .into_iter()
+            .flat_map(|channel| channel.positions)
+            .collect();
+        let fund_positions = trade_ctx
+            .fund_positions(None)
+            .await?
+            .channels
+            .into_iter()
+            .flat_map(|channel| channel.positions)
+            .collect();
+        let balances = trade_ctx.account_balance(None).await?;
+
+        let mut portfolio = Self::new(stock_positions, fund_positions, balances, today_orders);
+        let symbols = portfolio.holdings.keys().cloned().collect::<Vec<_>>();
+        if !symbols.is_empty() {
+            for quote in quote_ctx.quote(&symbols).await? {
+                portfolio.set_quote(&quote);
+            }
+            quote_ctx
+                .subscribe(&symbols, SubFlags::QUOTE, false)
+                .await?;
+        }
+
+        *self = portfolio;
+        Ok(())
+    }
+
+    /// Returns the holding of the security
+    #[inline]
+    pub fn holding(&self, symbol: &str) -> Option<&Holding> {
+        self.holdings.get(symbol)
+    }
+
+    /// Returns the holdings of all the stock positions
+    pub fn holdings(&self) -> impl Iterator<Item = &Holding> {
+        self.holdings.values()
+    }
+
+    /// Returns the fund positions
+    #[inline]
+    pub fn fund_positions(&self) -> &[FundPosition] {
+        &self.fund_positions
+    }
+
+    /// Returns the account balances of the last refresh
+    #[inline]
+    pub fn balances(&self) -> &[AccountBalance] {
+        &self.balances
+    }
+
+    /// Returns the exposure of the stock and fund positions per currency
+    pub fn exposure_by_currency(&self) -> HashMap<String, Exposure> {
+        let mut exposures: HashMap<String, Exposure> = HashMap::new();
+        for holding in self.holdings.values() {
+            exposures.entry(holding.currency.clone()).or_default().add(
+                holding.market_value(),
+                holding.cost_value(),
+                holding.day_pnl(),
+            );
+        }
+        for position in &self.fund_positions {
+            exposures.entry(position.currency.clone()).or_default().add(
+                position.holding_units * position.current_net_asset_value,
+                position.holding_units * position.cost_net_asset_value,
+                Decimal::ZERO,
+            );
+        }
+        exposures
+    }
+
+    /// Returns the exposure of the stock positions per market
+    pub fn exposure_by_market(&self) -> HashMap<Market, Exposure> {
+        let mut exposures: HashMap<Market, Exposure> = HashMap::new();
+        for holding in self.holdings.values() {
+            exposures.entry(holding.market).or_default().add(
+                holding.market_value(),
+                holding.cost_value(),
+                holding.day_pnl(),
+            );
+        }
+        exposures
+    }
+
+    /// Applies a quote push, returns `None` if the security is not held or the
+    /// price is not changed
+    pub fn apply_quote(&mut self, symbol: &str, quote: &PushQuote) -> Option<PortfolioEvent> {
+        let holding = self.holdings.get_mut(symbol)?;
+        if holding.last_done == Some(quote.last_done) {
+            return None;
+        }
+        holding.last_done = Some(quote.last_done);
+        Some(PortfolioEvent::PriceChanged {
+            symbol: symbol.to_string(),
+            last_done: quote.last_done,
+        })
+    }
+
+    /// Applies an order push, returns `None` if the push does not contain a
+    /// new fill
+    ///
+    /// A push without the average executed price is skipped, its fills are
+    /// applied with the next push of the order or the next refresh.
+    pub fn apply_order_changed(&mut self, order: &PushOrderChanged) -> Option<PortfolioEvent> {
+        let executed_price = order.executed_price?;
+        let amount = order.executed_quantity * executed_price;
+        let (prev_quantity, prev_amount) = self
+            .executions
+            .insert(order.order_id.clone(), (order.executed_quantity, amount))
+            .unwrap_or_default();
+        let mut quantity = order.executed_quantity - prev_quantity;
+        if quantity <= Decimal::ZERO {
+            return None;
+        }
+        let mut amount = amount - prev_amount;
+        if order.side == OrderSide::Sell {
+            quantity = -quantity;
+            amount = -amount;
+        }
+
+        let holding = self
+            .holdings
+            .entry(order.symbol.clone())
+            .or_insert_with(|| Holding {
+                symbol: order.symbol.clone(),
+                symbol_name: order.stock_name.clone(),
+                market: parse_market_from_symbol(&order.symbol).unwrap_or(Market::Unknown),
+                currency: order.currency.clone(),
+                quantity: Decimal::ZERO,
+                cost_price: Decimal::ZERO,
+                init_quantity: Decimal::ZERO,
+                last_done: None,
+                prev_close: None,
+                day_trade_amount: Decimal::ZERO,
+            });
+        holding.apply_trade(quantity, amount);
+        let quantity_after = holding.quantity;
+        if quantity_after.is_zero() && holding.init_quantity.is_zero() {
+            self.holdings.remove(&order.symbol);
+        }
+
+        Some(PortfolioEvent::PositionChanged {
+            symbol: order.symbol.clone(),
+            quantity: quantity_after,
+            executed_quantity: quantity,
+            executed_price: amount / quantity,
+        })
+    }
+
+    fn set_quote(&mut self, quote: &SecurityQuote) {
+        if let Some(holding) = self.holdings.get_mut(&quote.symbol) {
+            holding.last_done = Some(quote.last_done);
+            holding.prev_close = Some(quote.prev_close);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::trade::{OrderStatus, OrderTag, OrderType};
+
+    fn position(
+        symbol: &str,
+        quantity: i64,
+        cost_price: &str,
+        init_quantity: i64,
+    ) -> StockPosition {
+        StockPosition {
+            symbol: symbol.to_string(),
+            symbol_name: symbol.to_string(),
+            quantity: quantity.into(),
+            available_quantity: quantity.into(),
+            currency: "HKD".to_string(),
+            cost_price: cost_price.parse().unwrap(),
+            market: Market::HK,
+            init_quantity: Some(init_quantity.into()),
+        }
+    }
+
+    fn order_changed(
+        order_id: &str,
+        side: OrderSide,
+        executed_quantity: i64,
+        executed_price: &str,
+    ) -> PushOrderChanged {
+        PushOrderChanged {
+            side,
+            stock_name: "Tencent".to_string(),
+            submitted_quantity: 1000.into(),
+            symbol: "700.HK".to_string(),
+            order_type: OrderType::LO,
+            submitted_price: executed_price.parse().unwrap(),
+            executed_quantity: executed_quantity.into(),
+            executed_price: Some(executed_price.parse().unwrap()),
+            order_id: order_id.to_string(),
+            currency: "HKD".to_string(),
+            status: OrderStatus::PartialFilled,
+            submitted_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            trigger_price: None,
+            msg: String::new(),
+            tag: OrderTag::Normal,
+            trigger_status: None,
+            trigger_at: None,
+            trailing_amount: None,
+            trailing_percent: None,
+            limit_offset: None,
+            account_no: String::new(),
+            last_share: None,
+            last_price: None,
+            remark: String::new(),
+        }
+    }
+
+    fn quote(last_done: &str) -> PushQuote {
+        PushQuote {
+            last_done: last_done.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn portfolio() {
+        let mut portfolio = Portfolio::new(
+            vec![
+                position("700.HK", 100, "300", 100),
+                position("5.HK", 400, "60", 400),
+            ],
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+        portfolio.holdings.get_mut("700.HK").unwrap().prev_close = Some(Decimal::from(310));
+        assert_eq!(portfolio.apply_quote("700.HK", &quote("320")), {
+            Some(PortfolioEvent::PriceChanged {
+                symbol: "700.HK".to_string(),
+                last_done: Decimal::from(320),
+            })
+        });
+        assert_eq!(portfolio.apply_quote("700.HK", &quote("320")), None);
+        assert_eq!(portfolio.apply_quote("9988.HK", &quote("80")), None);
+
+        let holding = portfolio.holding("700.HK").unwrap();
+        assert_eq!(holding.market_value(), Decimal::from(32000));
+        assert_eq!(holding.unrealized_pnl(), Decimal::from(2000));
+        assert_eq!(holding.day_pnl(), Decimal::from(1000));
+
+        // buy 100 at 330, fill 50 twice, the first push without the average
+        // executed price is skipped
+        let mut order = order_changed("1", OrderSide::Buy, 25, "330");
+        order.executed_price = None;
+        order.last_price = Some(Decimal::from(330));
+        assert_eq!(portfolio.apply_order_changed(&order), None);
+        portfolio.apply_order_changed(&order_changed("1", OrderSide::Buy, 50, "330"));
+        assert_eq!(
+            portfolio.apply_order_changed(&order_changed("1", OrderSide::Buy, 100, "330")),
+            Some(PortfolioEvent::PositionChanged {
+                symbol: "700.HK".to_string(),
+                quantity: Decimal::from(200),
+                executed_quantity: Decimal::from(50),
+                executed_price: Decimal::from(330),
+            })
+        );
+        assert_eq!(
+            portfolio.apply_order_changed(&order_changed("1", OrderSide::Buy, 100, "330")),
+            None
+        );
+        let holding = portfolio.holding("700.HK").unwrap();
+        assert_eq!(holding.cost_price, Decimal::from(315));
+        // (320 - 310) * 100 + (320 - 330) * 100
+        assert_eq!(holding.day_pnl(), Decimal::ZERO);
+
+        // sell all
+        portfolio.apply_order_changed(&order_changed("2", OrderSide::Sell, 200, "325"));
+        let holding = portfolio.holding("700.HK").unwrap();
+        assert_eq!(holding.quantity, Decimal::ZERO);
+        assert_eq!(holding.cost_price, Decimal::from(315));
+        // (325 - 310) * 100 + (325 - 330) * 100
+        assert_eq!(holding.day_pnl(), Decimal::from(1000));
+
+        let exposures = portfolio.exposure_by_market();
+        let exposure = exposures[&Market::HK];
+        assert_eq!(exposure.market_value, Decimal::from(24000));
+        assert_eq!(exposure.cost_value, Decimal::from(24000));
+        assert_eq!(exposure.day_pnl, Decimal::from(1000));
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    pin::Pin,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use futures_util::{Stream, task::AtomicWaker};
+use parking_lot::Mutex;
+use tokio::sync::{Notify, mpsc::error::TryRecvError};
+
+use crate::{AccountPushEvent, quote, trade};
+
+/// What to do when a bounded push channel is full
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PushOverflowPolicy {
+    /// Drop the oldest queued event to make room for the new one
+    #[default]
+    DropOldest,
+    /// Drop the new event
+    DropNewest,
+    /// Replace the queued `PushQuote`/`PushDepth` of the same symbol with the
+    /// new one, other events fall back to
+    /// [`DropOldest`](PushOverflowPolicy::DropOldest)
+    ConflatePerSymbol,
+    /// Stop processing the incoming messages until the consumer catches up
+    ///
+    /// NOTE: A stalled consumer blocks the whole context: it does not handle
+    /// any requests (e.g. `QuoteContext::quote`) and the push streams (e.g.
+    /// `QuoteContext::quote_stream`) do not receive any events until the
+    /// receiver has room again. Do not wait for a request of the context in
+    /// the task that consumes the pushes.
+    Block,
+}
+
+impl FromStr for PushOverflowPolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        match s {
+            "drop_oldest" => Ok(PushOverflowPolicy::DropOldest),
+            "drop_newest" => Ok(PushOverflowPolicy::DropNewest),
+            "conflate" => Ok(PushOverflowPolicy::ConflatePerSymbol),
+            "block" => Ok(PushOverflowPolicy::Block),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The events that can be conflated by
+/// [`PushOverflowPolicy::ConflatePerSymbol`]
+pub(crate) trait Conflate {
+    /// Events with the same key replace each other, `None` means the event
+    /// is never conflated
+    fn conflate_key(&self) -> Option<(&str, u8)>;
+}
+
+impl Conflate for quote::PushEvent {
+    fn conflate_key(&self) -> Option<(&str, u8)> {
+        match &self.detail {
+            quote::PushEventDetail::Quote(_) => Some((&self.symbol, 0)),
+            quote::PushEventDetail::Depth(_) => Some((&self.symbol, 1)),
+            _ => None,
+        }
+    }
+}
+
+impl Conflate for trade::PushEvent {
+    fn conflate_key(&self) -> Option<(&str, u8)> {
+        None
+    }
+}
+
+impl Conflate for AccountPushEvent {
+    fn conflate_key(&self) -> Option<(&str, u8)> {
+        None
+    }
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    capacity: Option<usize>,
+    policy: PushOverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    rx_waker: AtomicWaker,
+    space_notify: Notify,
+}
+
+/// Creates a push channel, `capacity` of `None` means unbounded
+///
+/// All channels created with the same `dropped` counter add to it.
+pub(crate) fn channel<T>(
+    capacity: Option<usize>,
+    policy: PushOverflowPolicy,
+    dropped: Arc<AtomicU64>,
+) -> (PushSender<T>, PushReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            sender_closed: false,
+            receiver_closed: false,
+        }),
+        capacity: capacity.map(|capacity| capacity.max(1)),
+        policy,
+        dropped,
+        rx_waker: AtomicWaker::new(),
+        space_notify: Notify::new(),
+    });
+    (
+        PushSender {
+            shared: shared.clone(),
+        },
+        PushReceiver { shared },
+    )
+}
+
+pub(crate) struct PushSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Conflate> PushSender<T> {
+    /// Queues the event according to the overflow policy, returns `false` if
+    /// the receiver has been dropped
+    ///
+    /// With [`PushOverflowPolicy::Block`] the queue may exceed the capacity,
+    /// the caller is expected to wait for [`PushSender::ready`] before
+    /// producing more events.
+    pub(crate) fn send(&self, event: T) -> bool {
+        let mut state = self.shared.state.lock();
+        if state.receiver_closed {
+            return false;
+        }
+
+        match self.shared.capacity {
+            Some(capacity) if state.queue.len() >= capacity => match self.shared.policy {
+                PushOverflowPolicy::DropOldest => {
+                    state.queue.pop_front();
+                    state.queue.push_back(event);
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                PushOverflowPolicy::DropNewest => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return true;
+                }
+                PushOverflowPolicy::ConflatePerSymbol => {
+                    let key = event.conflate_key();
+                    let pos = key.and_then(|key| {
+                        state
+                            .queue
+                            .iter()
+                            .position(|queued| queued.conflate_key() == Some(key))
+                    });
+                    match pos {
+                        Some(pos) => state.queue[pos] = event,
+                        None => {
+                            state.queue.pop_front();
+                            state.queue.push_back(event);
+                        }
+                    }
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                PushOverflowPolicy::Block => state.queue.push_back(event),
+            },
+            _ => state.queue.push_back(event),
+        }
+
+        drop(state);
+        self.shared.rx_waker.wake();
+        true
+    }
+}
+
+impl<T> PushSender<T> {
+    /// Waits until the queue has room for new events, returns immediately
+    /// unless the policy is [`PushOverflowPolicy::Block`]
+    pub(crate) async fn ready(&self) {
+        let Some(capacity) = self.shared.capacity else {
+            return;
+        };
+        if self.shared.policy != PushOverflowPolicy::Block {
+            return;
+        }
+
+        loop {
+            let notified = self.shared.space_notify.notified();
+            {
+                let state = self.shared.state.lock();
+                if state.receiver_closed || state.queue.len() < capacity {
+                    return;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.state.lock().receiver_closed
+    }
+}
+
+impl<T> Drop for PushSender<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().sender_closed = true;
+        self.shared.rx_waker.wake();
+    }
+}
+
+/// Receives the push events of a context
+///
+/// The channel is unbounded by default, use
+/// [`Config::push_channel`](crate::Config::push_channel) to bound it.
+pub struct PushReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> PushReceiver<T> {
+    /// Receives the next event, returns `None` if the context has been
+    /// closed and all the queued events have been received
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Receives up to `limit` events into `buffer`, waits until at least one
+    /// event is available
+    ///
+    /// Returns the number of the received events, `0` means the context has
+    /// been closed and all the queued events have been received (or `limit`
+    /// is `0`).
+    pub async fn recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        let Some(event) = self.recv().await else {
+            return 0;
+        };
+        buffer.push(event);
+
+        let mut count = 1;
+        while count < limit {
+            match self.try_recv() {
+                Ok(event) => {
+                    buffer.push(event);
+                    count += 1;
+                }
+                Err(_) => break,
+            }
+        }
+        count
+    }
+
+    /// Blocking receives the next event, for use outside of an asynchronous
+    /// runtime
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        struct ThreadWaker(Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match self.poll_recv(&mut cx) {
+                Poll::Ready(event) => return event,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    /// Closes the receiving half without dropping it
+    ///
+    /// The context stops queueing new events, the queued events can still be
+    /// received.
+    pub fn close(&mut self) {
+        self.shared.state.lock().receiver_closed = true;
+        self.shared.space_notify.notify_one();
+    }
+
+    /// Returns `true` if the context has been closed or [`PushReceiver::close`]
+    /// has been called
+    pub fn is_closed(&self) -> bool {
+        let state = self.shared.state.lock();
+        state.sender_closed || state.receiver_closed
+    }
+
+    /// Tries to receive the next event without waiting
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.state.lock();
+        match state.queue.pop_front() {
+            Some(event) => {
+                drop(state);
+                self.shared.space_notify.notify_one();
+                Ok(event)
+            }
+            None if state.sender_closed || state.receiver_closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Polls to receive the next event
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(event) => return Poll::Ready(Some(event)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(TryRecvError::Empty) => {}
+        }
+
+        self.shared.rx_waker.register(cx.waker());
+        match self.try_recv() {
+            Ok(event) => Poll::Ready(Some(event)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    /// Returns the number of the queued events
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().queue.len()
+    }
+
+    /// Returns `true` if there are no queued events
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of the events dropped by the overflow policy
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Stream for PushReceiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for PushReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+        state.receiver_closed = true;
+        state.queue.clear();
+        drop(state);
+        self.shared.space_notify.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Event(&'static str, u32);
+
+    impl Conflate for Event {
+        fn conflate_key(&self) -> Option<(&str, u8)> {
+            (!self.0.is_empty()).then_some((self.0, 0))
+        }
+    }
+
+    fn drain(rx: &mut PushReceiver<Event>) -> Vec<u32> {
+        std::iter::from_fn(|| rx.try_recv().ok().map(|event| event.1)).collect()
+    }
+
+    fn send_all(tx: &PushSender<Event>, events: impl IntoIterator<Item = Event>) {
+        for event in events {
+            tx.send(event);
+        }
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let (tx, mut rx) = channel(Some(2), PushOverflowPolicy::DropOldest, Default::default());
+        send_all(&tx, [Event("", 1), Event("", 2), Event("", 3)]);
+        assert_eq!(drain(&mut rx), vec![2, 3]);
+        assert_eq!(rx.dropped(), 1);
+    }
+
+    #[test]
+    fn drop_newest() {
+        let (tx, mut rx) = channel(Some(2), PushOverflowPolicy::DropNewest, Default::default());
+        send_all(&tx, [Event("", 1), Event("", 2), Event("", 3)]);
+        assert_eq!(drain(&mut rx), vec![1, 2]);
+        assert_eq!(rx.dropped(), 1);
+    }
+
+    #[test]
+    fn conflate_per_symbol() {
+        let (tx, mut rx) = channel(
+            Some(2),
+            PushOverflowPolicy::ConflatePerSymbol,
+            Default::default(),
+        );
+        send_all(
+            &tx,
+            [Event("A", 1), Event("B", 2), Event("A", 3), Event("", 4)],
+        );
+        assert_eq!(drain(&mut rx), vec![2, 4]);
+        assert_eq!(rx.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn recv_many_and_close() {
+        let (tx, mut rx) = channel(None, PushOverflowPolicy::DropOldest, Default::default());
+        send_all(&tx, [Event("", 1), Event("", 2), Event("", 3)]);
+
+        let mut buffer = Vec::new();
+        assert_eq!(rx.recv_many(&mut buffer, 2).await, 2);
+        assert_eq!(
+            buffer.iter().map(|event| event.1).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        rx.close();
+        assert!(rx.is_closed());
+        assert!(!tx.send(Event("", 4)));
+        assert_eq!(rx.recv().await.map(|event| event.1), Some(3));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn blocking_recv() {
+        let (tx, mut rx) = channel(None, PushOverflowPolicy::DropOldest, Default::default());
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            tx.send(Event("", 1));
+        });
+        assert_eq!(rx.blocking_recv().map(|event| event.1), Some(1));
+        handle.join().unwrap();
+        assert!(rx.blocking_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn block() {
+        let (tx, mut rx) = channel(Some(1), PushOverflowPolicy::Block, Default::default());
+        send_all(&tx, [Event("", 1), Event("", 2)]);
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(50), tx.ready())
+                .await
+                .is_err()
+        );
+        assert_eq!(rx.recv().await.map(|event| event.1), Some(1));
+        assert_eq!(rx.recv().await.map(|event| event.1), Some(2));
+        tx.ready().await;
+        drop(tx);
+        assert!(rx.recv().await.is_none());
+        assert_eq!(rx.dropped(), 0);
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use time::OffsetDateTime;
+
+use crate::quote::{Brokers, ParticipantInfo, PushBrokers, SecurityBrokers};
+
+/// Side of the broker queue
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BrokerSide {
+    /// Ask
+    Ask,
+    /// Bid
+    Bid,
+}
+
+/// A broker in the queue
+#[derive(Debug, Copy, Clone)]
+pub struct QueuedBroker<'a> {
+    /// Position
+    pub position: i32,
+    /// Broker ID
+    pub broker_id: i32,
+    /// The participant of the broker
+    pub participant: Option<&'a ParticipantInfo>,
+}
+
+/// The best position of a broker on one side changed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BrokerMoved {
+    /// Broker ID
+    pub broker_id: i32,
+    /// Side
+    pub side: BrokerSide,
+    /// The best position before, `None` means the broker was not in the queue
+    pub from: Option<i32>,
+    /// The best position after, `None` means the broker left the queue
+    pub to: Option<i32>,
+}
+
+/// Activity of a broker on one side
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SideActivity {
+    /// Number of times the broker entered the queue
+    pub entries: u64,
+    /// Number of times the broker left the queue
+    pub exits: u64,
+    /// Number of times the best position changed while in the queue
+    pub moves: u64,
+    /// The best position ever seen
+    pub best_position: Option<i32>,
+}
+
+/// Activity summary of a broker
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BrokerActivity {
+    /// Broker ID
+    pub broker_id: i32,
+    /// Activity on the ask side
+    pub ask: SideActivity,
+    /// Activity on the bid side
+    pub bid: SideActivity,
+    /// The time the broker was first seen in the queue
+    pub first_seen: OffsetDateTime,
+    /// The time the broker was last seen in the queue
+    pub last_seen: OffsetDateTime,
+}
+
+/// HK broker queue of a security with the participant names
+///
+/// Created by
+/// [`QuoteContext::broker_queue_view`](crate::quote::QuoteContext::broker_queue_view)
+/// and kept up to date with the [`PushBrokers`] events, it follows the
+/// position of each broker and summarizes the activities.
+#[derive(Debug, Clone)]
+pub struct BrokerQueueView {
+    participants: HashMap<i32, ParticipantInfo>,
+    ask_brokers: Vec<Brokers>,
+    bid_brokers: Vec<Brokers>,
+    activities: HashMap<i32, BrokerActivity>,
+}
+
+impl BrokerQueueView {
+    /// Create a new `BrokerQueueView` from the participants and the current
+    /// broker queue
+    pub fn new(participants: Vec<ParticipantInfo>, brokers: SecurityBrokers) -> Self {
+        let participants = participants
+            .into_iter()
+            .flat_map(|info| {
+                info.broker_ids
+                    .clone()
+                    .into_iter()
+                    .map(move |broker_id| (broker_id, info.clone()))
+            })
+            .collect();
+        let mut view = Self {
+            participants,
+            ask_brokers: Vec::new(),
+            bid_brokers: Vec::new(),
+            activities: HashMap::new(),
+        };
+        view.apply_at(
+            &PushBrokers {
+                ask_brokers: brokers.ask_brokers,
+                bid_brokers: brokers.bid_brokers,
+            },
+            OffsetDateTime::now_utc(),
+        );
+        view
+    }
+
+    /// Returns the participant of the broker
+    #[inline]
+    pub fn participant(&self, broker_id: i32) -> Option<&ParticipantInfo> {
+        self.participants.get(&broker_id)
+    }
+
+    /// Returns the brokers of one side in ascending order of position
+    pub fn queue(&self, side: BrokerSide) -> Vec<QueuedBroker<'_>> {
+        self.brokers(side)
+            .iter()
+            .flat_map(|brokers| {
+                brokers.broker_ids.iter().map(|broker_id| QueuedBroker {
+                    position: brokers.position,
+                    broker_id: *broker_id,
+                    participant: self.participant(*broker_id),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the positions of the broker on one side
+    pub fn positions(&self, broker_id: i32, side: BrokerSide) -> Vec<i32> {
+        positions(self.brokers(side), broker_id)
+    }
+
+    /// Returns the activity summary of the broker
+    #[inline]
+    pub fn activity(&self, broker_id: i32) -> Option<&BrokerActivity> {
+        self.activities.get(&broker_id)
+    }
+
+    /// Returns the activity summaries of all the brokers seen in the queue
+    pub fn activities(&self) -> impl Iterator<Item = &BrokerActivity> {
+        self.activities.values()
+    }
+
+    /// Applies a brokers push, returns the brokers whose best position is
+    /// changed
+    pub fn apply(&mut self, push: &PushBrokers) -> Vec<BrokerMoved> {
+        self.apply_at(push, OffsetDateTime::now_utc())
+    }
+
+    fn brokers(&self, side: BrokerSide) -> &[Brokers] {
+        match side {
+            BrokerSide::Ask => &self.ask_brokers,
+            BrokerSide::Bid => &self.bid_brokers,
+        }
+    }
+
+    fn apply_at(&mut self, push: &PushBrokers, now: OffsetDateTime) -> Vec<BrokerMoved> {
+        let mut moved = Vec::new();
+        for (side, brokers, changes) in [
+            (BrokerSide::Ask, &mut self.ask_brokers, &push.ask_brokers),
+            (BrokerSide::Bid, &mut self.bid_brokers, &push.bid_brokers),
+        ] {
+            let prev = best_positions(brokers);
+            replace(brokers, changes);
+            let current = best_positions(brokers);
+
+            for (broker_id, from, to) in prev
+                .iter()
+                .map(|(broker_id, from)| (*broker_id, Some(*from), current.get(broker_id).copied()))
+                .chain(
+                    current
+                        .iter()
+                        .filter(|(broker_id, _)| !prev.contains_key(broker_id))
+                        .map(|(broker_id, to)| (*broker_id, None, Some(*to))),
+                )
+            {
+                if to.is_some() {
+                    let activity =
+                        self.activities
+                            .entry(broker_id)
+                            .or_insert_with(|| BrokerActivity {
+                                broker_id,
+                                ask: SideActivity::default(),
+                                bid: SideActivity::default(),
+                                first_seen: now,
+                                last_seen: now,
+                            });
+                    activity.last_seen = now;
+                }
+                if from == to {
+                    continue;
+                }
+
+                if let Some(activity) = self.activities.get_mut(&broker_id) {
+                    let activity = match side {
+                        BrokerSide::Ask => &mut activity.ask,
+                        BrokerSide::Bid => &mut activity.bid,
+                    };
+                    match (from, to) {
+                        (None, Some(_)) => activity.entries += 1,
+                        (Some(_), None) => activity.exits += 1,
+                        _ => activity.moves += 1,
+                    }
+                    if let Some(to) = to {
+                        activity.best_position =
+                            Some(activity.best_position.map_or(to, |best| best.min(to)));
+                    }
+                }
+                moved.push(BrokerMoved {
+                    broker_id,
+                    side,
+                    from,
+                    to,
+                });
+            }
+        }
+        moved.sort_by_key(|moved| (moved.side == BrokerSide::Bid, moved.broker_id));
+        moved
+    }
+}
+
+fn positions(brokers: &[Brokers], broker_id: i32) -> Vec<i32> {
+    brokers
+        .iter()
+        .filter(|brokers| brokers.broker_ids.contains(&broker_id))
+        .map(|brokers| brokers.position)
+        .collect()
+}
+
+fn best_positions(brokers: &[Brokers]) -> HashMap<i32, i32> {
+    let mut positions = HashMap::new();
+    // the brokers are in ascending order of position
+    for brokers in brokers.iter().rev() {
+        for broker_id in &brokers.broker_ids {
+            positions.insert(*broker_id, brokers.position);
+        }
+    }
+    positions
+}
+
+fn replace(brokers: &mut Vec<Brokers>, others: &[Brokers]) {
+    for other in others {
+        match brokers.binary_search_by_key(&other.position, |brokers| brokers.position) {
+            Ok(index) => brokers[index] = other.clone(),
+            Err(index) => brokers.insert(index, other.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brokers(position: i32, broker_ids: &[i32]) -> Brokers {
+        Brokers {
+            position,
+            broker_ids: broker_ids.to_vec(),
+        }
+    }
+
+    fn participant(broker_ids: &[i32], name: &str) -> ParticipantInfo {
+        ParticipantInfo {
+            broker_ids: broker_ids.to_vec(),
+            name_cn: name.to_string(),
+            name_en: name.to_string(),
+            name_hk: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn broker_queue() {
+        let mut view = BrokerQueueView::new(
+            vec![participant(&[1, 2], "A"), participant(&[3], "B")],
+            SecurityBrokers {
+                ask_brokers: vec![brokers(1, &[1, 3]), brokers(2, &[1])],
+                bid_brokers: vec![brokers(1, &[2])],
+            },
+        );
+        assert_eq!(view.participant(2).unwrap().name_en, "A");
+        assert!(view.participant(4).is_none());
+        let queue = view.queue(BrokerSide::Ask);
+        assert_eq!(
+            queue
+                .iter()
+                .map(|broker| (
+                    broker.position,
+                    broker.broker_id,
+                    broker.participant.map(|info| info.name_en.as_str())
+                ))
+                .collect::<Vec<_>>(),
+            vec![(1, 1, Some("A")), (1, 3, Some("B")), (2, 1, Some("A"))]
+        );
+        assert_eq!(view.positions(1, BrokerSide::Ask), vec![1, 2]);
+
+        let moved = view.apply(&PushBrokers {
+            ask_brokers: vec![brokers(1, &[3]), brokers(2, &[1, 4])],
+            bid_brokers: vec![brokers(1, &[])],
+        });
+        assert_eq!(
+            moved,
+            vec![
+                BrokerMoved {
+                    broker_id: 1,
+                    side: BrokerSide::Ask,
+                    from: Some(1),
+                    to: Some(2),
+                },
+                BrokerMoved {
+                    broker_id: 4,
+                    side: BrokerSide::Ask,
+                    from: None,
+                    to: Some(2),
+                },
+                BrokerMoved {
+                    broker_id: 2,
+                    side: BrokerSide::Bid,
+                    from: Some(1),
+                    to: None,
+                },
+            ]
+        );
+
+        let activity = view.activity(1).unwrap();
+        assert_eq!(
+            activity.ask,
+            SideActivity {
+                entries: 1,
+                exits: 0,
+                moves: 1,
+                best_position: Some(1),
+            }
+        );
+        let activity = view.activity(2).unwrap();
+        assert_eq!((activity.bid.entries, activity.bid.exits), (1, 1));
+        assert_eq!(view.activities().count(), 4);
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+use time_tz::{OffsetDateTimeExt, Tz};
+
+use crate::{
+    Error, Market, Result,
+    quote::{AdjustType, Candlestick, Period, QuoteContext, TradeSessions, history},
+};
+
+/// `QuoteContext::trading_days` only supports the most recent year
+const TRADING_DAYS_HISTORY: Duration = Duration::days(365);
+/// `QuoteContext::trading_days` only supports intervals less than one month
+const TRADING_DAYS_INTERVAL: Duration = Duration::days(27);
+
+/// Returns `true` if the candlesticks of `period` can be cached
+///
+/// Each cached candlestick belongs to a single trading day, so the weekly and
+/// longer candlesticks are never cached.
+pub(crate) fn is_cacheable(period: Period) -> bool {
+    !matches!(
+        period,
+        Period::UnknownPeriod | Period::Week | Period::Month | Period::Quarter | Period::Year
+    )
+}
+
+/// A line of the cache file, the candlesticks of the days requested at once
+///
+/// The entries are appended to the file, the later ones replace the
+/// candlesticks with the same timestamp of the earlier ones.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    /// The days whose candlesticks are all in the entry
+    days: Vec<Date>,
+    candlesticks: Vec<Candlestick>,
+}
+
+/// Cached candlesticks of a symbol, period, adjust type and trade sessions
+#[derive(Debug, Default)]
+struct CacheFile {
+    /// The days whose candlesticks are all in the cache
+    days: BTreeSet<Date>,
+    /// Candlesticks sorted by the timestamp
+    candlesticks: Vec<Candlestick>,
+}
+
+impl CacheFile {
+    fn merge(&mut self, entry: CacheEntry) {
+        self.days.extend(entry.days);
+        merge(&mut self.candlesticks, entry.candlesticks);
+    }
+}
+
+pub(crate) struct CandlestickCache<'a> {
+    pub(crate) ctx: &'a QuoteContext,
+    pub(crate) dir: &'a Path,
+    pub(crate) symbol: &'a str,
+    pub(crate) market: Market,
+    pub(crate) timezone: &'static Tz,
+    pub(crate) period: Period,
+    pub(crate) adjust_type: AdjustType,
+    pub(crate) trade_sessions: TradeSessions,
+}
+
+impl CandlestickCache<'_> {
+    fn path(&self) -> PathBuf {
+        let trade_sessions = match self.trade_sessions {
+            TradeSessions::Intraday => "intraday",
+            TradeSessions::All => "all",
+        };
+        self.dir.join(encode_symbol(self.symbol)).join(format!(
+            "{}_{}_{}.jsonl",
+            self.period.as_str_name().to_lowercase(),
+            self.adjust_type.as_str_name().to_lowercase(),
+            trade_sessions
+        ))
+    }
+
+    fn local_date(&self, t: OffsetDateTime) -> Date {
+        t.to_timezone(self.timezone).date()
+    }
+
+    /// Returns the candlesticks between `start` and `end`, only the days that
+    /// are not in the cache are requested from the server
+    pub(crate) async fn range(
+        &self,
+        start: PrimitiveDateTime,
+        end: PrimitiveDateTime,
+    ) -> Result<Vec<Candlestick>> {
+        let path = self.path();
+        let mut file = tokio::task::spawn_blocking({
+            let path = path.clone();
+            move || load(&path)
+        })
+        .await
+        .expect("BUG: load candlestick cache")?;
+
+        let today = self.local_date(OffsetDateTime::now_utc());
+        let last_day = end.date().min(today);
+        let trading_days = self.trading_days(start.date(), last_day, today).await?;
+        let mut gaps = missing_days(&file.days, &trading_days);
+        let mut fetched = Vec::new();
+        let mut truncate = false;
+
+        // the forward-adjusted prices change after a corporate action, the last
+        // cached day is requested again with the missing days following it, or
+        // alone if there are none
+        if self.adjust_type == AdjustType::ForwardAdjust
+            && let Some(cached_day) = file.days.last().copied()
+        {
+            let pos = gaps
+                .iter()
+                .position(|&(first, _)| first > 0 && trading_days[first - 1] == cached_day);
+            let last = match pos {
+                Some(pos) => trading_days[gaps.remove(pos).1],
+                None => cached_day,
+            };
+            let candlesticks = self.fetch(cached_day, last).await?;
+
+            if self.is_adjustment_valid(&file, cached_day, &candlesticks) {
+                if pos.is_some() {
+                    fetched.push((cached_day, last, candlesticks));
+                }
+            } else {
+                tracing::info!(
+                    symbol = self.symbol,
+                    "forward adjustment changed, invalidate the candlestick cache"
+                );
+                file = CacheFile::default();
+                truncate = true;
+                gaps = missing_days(&file.days, &trading_days);
+            }
+        }
+
+        for (first, last) in gaps {
+            let (first, last) = (trading_days[first], trading_days[last]);
+            fetched.push((first, last, self.fetch(first, last).await?));
+        }
+
+        // only the completed days are saved, the candlesticks of today are
+        // requested again next time
+        let mut entries = Vec::new();
+        for (first, last, candlesticks) in fetched {
+            let mut entry = CacheEntry::default();
+            let mut day = first;
+            while day <= last && day < today {
+                entry.days.push(day);
+                day = day.next_day().expect("BUG: date overflow");
+            }
+            let (saved, unsaved) = candlesticks
+                .into_iter()
+                .partition(|candlestick| self.local_date(candlestick.timestamp) < today);
+            entry.candlesticks = saved;
+            merge(&mut file.candlesticks, unsaved);
+            entries.push(entry);
+        }
+
+        if truncate || entries.iter().any(|entry| !entry.days.is_empty()) {
+            entries = tokio::task::spawn_blocking(move || {
+                save(&path, &entries, truncate).map(|_| entries)
+            })
+            .await
+            .expect("BUG: save candlestick cache")?;
+        }
+        for entry in entries {
+            file.merge(entry);
+        }
+
+        Ok(file
+            .candlesticks
+            .into_iter()
+            .filter(|candlestick| {
+                let t = candlestick.timestamp.to_timezone(self.timezone);
+                let t = PrimitiveDateTime::new(t.date(), t.time());
+                t >= start && t <= end
+            })
+            .collect())
+    }
+
+    /// Returns `true` if the cached candlesticks of `day` are the same as the
+    /// ones just requested
+    fn is_adjustment_valid(&self, file: &CacheFile, day: Date, fetched: &[Candlestick]) -> bool {
+        let candlesticks = |candlesticks: &[Candlestick]| {
+            candlesticks
+                .iter()
+                .filter(|candlestick| self.local_date(candlestick.timestamp) == day)
+                .map(|candlestick| (candlestick.timestamp, candlestick.close))
+                .collect::<Vec<_>>()
+        };
+        candlesticks(&file.candlesticks) == candlesticks(fetched)
+    }
+
+    async fn fetch(&self, first: Date, last: Date) -> Result<Vec<Candlestick>> {
+        history::fetch_range(
+            self.ctx.clone(),
+            self.symbol.to_string(),
+            self.period,
+            self.adjust_type,
+            self.trade_sessions,
+            PrimitiveDateTime::new(first, Time::MIDNIGHT),
+            PrimitiveDateTime::new(last, Time::MAX),
+        )
+        .try_collect()
+        .await
+    }
+
+    /// Returns the trading days between `begin` and `end`, the weekdays are
+    /// used for the days before the most recent year
+    async fn trading_days(&self, begin: Date, end: Date, today: Date) -> Result<Vec<Date>> {
+        let mut days = Vec::new();
+        if begin > end {
+            return Ok(days);
+        }
+
+        let history_begin = today - TRADING_DAYS_HISTORY;
+        let mut day = begin;
+        while day <= end && day < history_begin {
+            if !matches!(day.weekday(), Weekday::Saturday | Weekday::Sunday) {
+                days.push(day);
+            }
+            day = day.next_day().expect("BUG: date overflow");
+        }
+
+        while day <= end {
+            let interval_end = (day + TRADING_DAYS_INTERVAL).min(end);
+            let resp = self
+                .ctx
+                .trading_days(self.market, day, interval_end)
+                .await?;
+            let mut interval_days = resp.trading_days;
+            interval_days.extend(resp.half_trading_days);
+            interval_days.sort();
+            days.extend(interval_days);
+            day = interval_end.next_day().expect("BUG: date overflow");
+        }
+
+        Ok(days)
+    }
+}
+
+/// Returns the index ranges (inclusive) of the consecutive trading days
+/// missing from `days`
+fn missing_days(days: &BTreeSet<Date>, trading_days: &[Date]) -> Vec<(usize, usize)> {
+    let mut gaps = Vec::new();
+    let mut idx = 0;
+
+    while idx < trading_days.len() {
+        if days.contains(&trading_days[idx]) {
+            idx += 1;
+            continue;
+        }
+        let first = idx;
+        while idx < trading_days.len() && !days.contains(&trading_days[idx]) {
+            idx += 1;
+        }
+        gaps.push((first, idx - 1));
+    }
+
+    gaps
+}
+
+/// Encodes the symbol as a directory name, the characters other than the
+/// ASCII letters, digits, `-`, `_` and a `.` that is not the first character
+/// are percent-encoded
+fn encode_symbol(symbol: &str) -> String {
+    let mut res = String::with_capacity(symbol.len());
+    for (idx, b) in symbol.bytes().enumerate() {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || (b == b'.' && idx > 0) {
+            res.push(b as char);
+        } else {
+            _ = write!(res, "%{b:02X}");
+        }
+    }
+    res
+}
+
+/// Merges `candlesticks` into `cached`, the new candlesticks replace the
+/// cached ones with the same timestamp
+fn merge(cached: &mut Vec<Candlestick>, candlesticks: Vec<Candlestick>) {
+    if candlesticks.is_empty() {
+        return;
+    }
+    let mut map = std::mem::take(cached)
+        .into_iter()
+        .map(|candlestick| (candlestick.timestamp, candlestick))
+        .collect::<BTreeMap<_, _>>();
+    map.extend(
+        candlesticks
+            .into_iter()
+            .map(|candlestick| (candlestick.timestamp, candlestick)),
+    );
+    *cached = map.into_values().collect();
+}
+
+/// Reads the entries of the cache file, an invalid line (e.g. written
+/// partially) and the following ones are ignored
+fn load(path: &Path) -> Result<CacheFile> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(CacheFile::default()),
+        Err(err) => return Err(Error::Io(err)),
+    };
+
+    let mut file = CacheFile::default();
+    for line in data.lines() {
+        match serde_json::from_str(line) {
+            Ok(entry) => file.merge(entry),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), error = %err, "invalid candlestick cache");
+                break;
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Appends the entries to the cache file, or replaces its content if
+/// `truncate` is `true`
+fn save(path: &Path, entries: &[CacheEntry], truncate: bool) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut data = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut data, entry)?;
+        data.push(b'\n');
+    }
+
+    if truncate {
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, path)?;
+    } else {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&data)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_symbols() {
+        assert_eq!(encode_symbol("700.HK"), "700.HK");
+        assert_eq!(encode_symbol("BRK-B.US"), "BRK-B.US");
+        assert_eq!(encode_symbol("../a/b.US"), "%2E.%2Fa%2Fb.US");
+        assert_eq!(encode_symbol(".HSI.HK"), "%2EHSI.HK");
+        assert_eq!(encode_symbol("a\\b"), "a%5Cb");
+    }
+}
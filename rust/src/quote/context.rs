@@ -1,26 +1,44 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
+use futures_util::{Stream, TryStreamExt, stream};
 use longport_httpcli::{HttpClient, Json, Method};
 use longport_proto::quote;
 use longport_wscli::WsClientError;
 use serde::{Deserialize, Serialize};
-use time::{Date, PrimitiveDateTime};
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+use time_tz::OffsetDateTimeExt;
 use tokio::sync::{mpsc, oneshot};
 use tracing::{Subscriber, dispatcher, instrument::WithSubscriber};
 
 use crate::{
-    Config, Error, Language, Market, Result,
+    Config, ConnectionEvent, Error, Language, Market, PushOverflowPolicy, PushReceiver, Result,
+    connection::ConnectionEvents,
+    push_channel,
     quote::{
-        AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
-        HistoryMarketTemperatureResponse, IntradayLine, IssuerInfo, MarketTemperature,
-        MarketTradingDays, MarketTradingSession, OptionQuote, ParticipantInfo, Period, PushEvent,
+        AdjustType, AggregatePeriod, BookChanged, BrokerQueueView, CalcIndex, Candlestick,
+        CapitalDistributionResponse, CapitalFlowLine, HistoryMarketTemperatureResponse,
+        IntradayLine, IssuerInfo, MarketTemperature, MarketTradingDays, MarketTradingSession,
+        OptionChainSnapshot, OptionQuote, OrderBook, ParticipantInfo, Period, PushBrokers,
+        PushCandlestick, PushDepth, PushEvent, PushEventDetail, PushQuote, PushStream, PushTrades,
         QuotePackageDetail, RealtimeQuote, RequestCreateWatchlistGroup,
-        RequestUpdateWatchlistGroup, Security, SecurityBrokers, SecurityCalcIndex, SecurityDepth,
-        SecurityListCategory, SecurityQuote, SecurityStaticInfo, StrikePriceInfo, Subscription,
-        Trade, TradeSessions, WarrantInfo, WarrantQuote, WarrantType, WatchlistGroup,
+        RequestUpdateWatchlistGroup, Security, SecurityBoard, SecurityBrokers, SecurityCalcIndex,
+        SecurityDepth, SecurityListCategory, SecurityQuote, SecurityStaticInfo, StrikePriceInfo,
+        Subscription, Trade, TradeSessions, WarrantFilter, WarrantInfo, WarrantList, WarrantQuote,
+        WarrantType, WatchlistGroup,
         cache::{Cache, CacheWithKey},
         cmd_code,
-        core::{Command, Core},
+        core::{Command, Core, parse_market_from_symbol},
+        history,
+        push_stream::PushStreamKind,
+        store::get_market,
         sub_flags::SubFlags,
         types::{
             FilterWarrantExpiryDate, FilterWarrantInOutBoundsType, SecuritiesUpdateMode,
@@ -37,6 +55,7 @@ const ISSUER_INFO_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const OPTION_CHAIN_STRIKE_INFO_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const TRADING_SESSION_CACHE_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 2);
+const MAX_BATCH_SYMBOLS: usize = 500;
 
 struct InnerQuoteContext {
     language: Language,
@@ -51,6 +70,11 @@ struct InnerQuoteContext {
     quote_level: String,
     quote_package_details: Vec<QuotePackageDetail>,
     log_subscriber: Arc<dyn Subscriber + Send + Sync>,
+    push_channel_capacity: Option<usize>,
+    push_overflow_policy: PushOverflowPolicy,
+    dropped_push_events: Arc<AtomicU64>,
+    connection_events: ConnectionEvents,
+    candlestick_cache_dir: Option<PathBuf>,
 }
 
 impl Drop for InnerQuoteContext {
@@ -67,9 +91,7 @@ pub struct QuoteContext(Arc<InnerQuoteContext>);
 
 impl QuoteContext {
     /// Create a `QuoteContext`
-    pub async fn try_new(
-        config: Arc<Config>,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
+    pub async fn try_new(config: Arc<Config>) -> Result<(Self, PushReceiver<PushEvent>)> {
         let log_subscriber = config.create_log_subscriber("quote");
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
@@ -82,51 +104,174 @@ impl QuoteContext {
             );
         });
 
-        let language = config.language;
-        let http_cli = config.create_http_client();
         let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let (push_tx, push_rx) = mpsc::unbounded_channel();
-        let core = Core::try_new(config, command_rx, push_tx)
-            .with_subscriber(log_subscriber.clone())
-            .await?;
-        let member_id = core.member_id();
-        let quote_level = core.quote_level().to_string();
-        let quote_package_details = core.quote_package_details().to_vec();
-        tokio::spawn(core.run().with_subscriber(log_subscriber.clone()));
+        let dropped_push_events = Arc::new(AtomicU64::new(0));
+        let (push_tx, push_rx) = config.create_push_channel(dropped_push_events.clone());
+        let connection_events = ConnectionEvents::default();
+        let core = Core::try_new(
+            config.clone(),
+            command_rx,
+            push_tx,
+            connection_events.clone(),
+        )
+        .with_subscriber(log_subscriber.clone())
+        .await?;
+
+        Ok((
+            Self::from_core(
+                &config,
+                core,
+                command_tx,
+                dropped_push_events,
+                connection_events,
+                log_subscriber,
+            )?,
+            push_rx,
+        ))
+    }
+
+    /// Create a `QuoteContext` that replays a recording made with
+    /// [`Config::record_quote_pushes`] instead of connecting to the server
+    ///
+    /// The recorded pushes go through the same decoding, subscription filter
+    /// and candlestick merging as the live session. `speed` is the playback
+    /// rate, `1.0` keeps the original intervals, and a value that is not
+    /// positive replays as fast as possible. The receiver is closed when the
+    /// replay is finished.
+    ///
+    /// The recording is read by a blocking task of the Tokio runtime, so the
+    /// context must be created within the runtime like
+    /// [`QuoteContext::try_new`].
+    ///
+    /// The realtime methods such as [`QuoteContext::realtime_quote`] return
+    /// the replayed data, methods that require the quote server return
+    /// [`Error::ReplayNotSupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{Config, quote::QuoteContext};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, mut receiver) = QuoteContext::replay(config, "quote.rec", 10.0).await?;
+    ///
+    /// while let Some(event) = receiver.recv().await {
+    ///     println!("{:?}", event);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn replay(
+        config: Arc<Config>,
+        path: impl AsRef<Path>,
+        speed: f64,
+    ) -> Result<(Self, PushReceiver<PushEvent>)> {
+        let log_subscriber = config.create_log_subscriber("quote");
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
-            tracing::info!("quote context created");
+            tracing::info!(path = ?path.as_ref(), speed = speed, "creating quote context for replay");
         });
 
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let dropped_push_events = Arc::new(AtomicU64::new(0));
+        let (push_tx, push_rx) = config.create_push_channel(dropped_push_events.clone());
+        let connection_events = ConnectionEvents::default();
+        let core = Core::try_new_replay(
+            config.clone(),
+            command_rx,
+            push_tx,
+            connection_events.clone(),
+            path.as_ref(),
+            speed,
+        )?;
+
         Ok((
-            QuoteContext(Arc::new(InnerQuoteContext {
-                language,
-                http_cli,
+            Self::from_core(
+                &config,
+                core,
                 command_tx,
-                cache_participants: Cache::new(PARTICIPANT_INFO_CACHE_TIMEOUT),
-                cache_issuers: Cache::new(ISSUER_INFO_CACHE_TIMEOUT),
-                cache_option_chain_expiry_date_list: CacheWithKey::new(
-                    OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT,
-                ),
-                cache_option_chain_strike_info: CacheWithKey::new(
-                    OPTION_CHAIN_STRIKE_INFO_CACHE_TIMEOUT,
-                ),
-                cache_trading_session: Cache::new(TRADING_SESSION_CACHE_TIMEOUT),
-                member_id,
-                quote_level,
-                quote_package_details,
+                dropped_push_events,
+                connection_events,
                 log_subscriber,
-            })),
+            )?,
             push_rx,
         ))
     }
 
+    fn from_core(
+        config: &Config,
+        core: Core,
+        command_tx: mpsc::UnboundedSender<Command>,
+        dropped_push_events: Arc<AtomicU64>,
+        connection_events: ConnectionEvents,
+        log_subscriber: Arc<dyn Subscriber + Send + Sync>,
+    ) -> Result<Self> {
+        let http_cli = config.create_http_client()?;
+        let member_id = core.member_id();
+        let quote_level = core.quote_level().to_string();
+        let quote_package_details = core.quote_package_details().to_vec();
+        tokio::spawn(core.run().with_subscriber(log_subscriber.clone()));
+
+        dispatcher::with_default(&log_subscriber.clone().into(), || {
+            tracing::info!("quote context created");
+        });
+
+        Ok(QuoteContext(Arc::new(InnerQuoteContext {
+            language: config.language,
+            http_cli,
+            command_tx,
+            cache_participants: Cache::new(PARTICIPANT_INFO_CACHE_TIMEOUT),
+            cache_issuers: Cache::new(ISSUER_INFO_CACHE_TIMEOUT),
+            cache_option_chain_expiry_date_list: CacheWithKey::new(
+                OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT,
+            ),
+            cache_option_chain_strike_info: CacheWithKey::new(
+                OPTION_CHAIN_STRIKE_INFO_CACHE_TIMEOUT,
+            ),
+            cache_trading_session: Cache::new(TRADING_SESSION_CACHE_TIMEOUT),
+            member_id,
+            quote_level,
+            quote_package_details,
+            log_subscriber,
+            push_channel_capacity: config.push_channel_capacity,
+            push_overflow_policy: config.push_overflow_policy,
+            dropped_push_events,
+            connection_events,
+            candlestick_cache_dir: config.candlestick_cache_dir.clone(),
+        })))
+    }
+
+    #[inline]
+    pub(crate) fn candlestick_cache_dir(&self) -> Option<&Path> {
+        self.0.candlestick_cache_dir.as_deref()
+    }
+
     /// Returns the log subscriber
     #[inline]
     pub fn log_subscriber(&self) -> Arc<dyn Subscriber + Send + Sync> {
         self.0.log_subscriber.clone()
     }
 
+    /// Returns the number of the push events dropped because the receiver or
+    /// a push stream was full, see [`Config::push_channel`]
+    #[inline]
+    pub fn dropped_push_events(&self) -> u64 {
+        self.0.dropped_push_events.load(Ordering::Relaxed)
+    }
+
+    /// Returns a receiver of the connection state change events
+    ///
+    /// Each call returns a new receiver that first gets the latest event, the
+    /// current state of the connection, then the events occurred after it is
+    /// created, see [`ConnectionEvent`] for the sequence of the events.
+    /// No events are sent by a context created with [`QuoteContext::replay`].
+    pub fn connection_events(&self) -> mpsc::UnboundedReceiver<ConnectionEvent> {
+        self.0.connection_events.subscribe()
+    }
+
     /// Returns the member ID
     #[inline]
     pub fn member_id(&self) -> i64 {
@@ -286,6 +431,9 @@ impl QuoteContext {
 
     /// Subscribe security candlesticks
     ///
+    /// Subscribing a period again with other `trade_sessions` rebuilds its
+    /// candlesticks with the new `trade_sessions`.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -331,7 +479,14 @@ impl QuoteContext {
     }
 
     /// Unsubscribe security candlesticks
-    pub async fn unsubscribe_candlesticks<T>(&self, symbol: T, period: Period) -> Result<()>
+    ///
+    /// `period` is a [`Period`] or an [`AggregatePeriod`] passed to
+    /// [`QuoteContext::aggregate_candlesticks`].
+    pub async fn unsubscribe_candlesticks<T>(
+        &self,
+        symbol: T,
+        period: impl Into<AggregatePeriod>,
+    ) -> Result<()>
     where
         T: AsRef<str>,
     {
@@ -340,13 +495,295 @@ impl QuoteContext {
             .command_tx
             .send(Command::UnsubscribeCandlesticks {
                 symbol: normalize_symbol(symbol.as_ref()).into(),
-                period,
+                period: period.into().into(),
                 reply_tx,
             })
             .map_err(|_| WsClientError::ClientClosed)?;
         reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
     }
 
+    /// Aggregates the candlesticks of `periods` locally from the trades of
+    /// `symbol`
+    ///
+    /// The symbol is subscribed with the quotes and trades once, and the
+    /// candlesticks of every period are built by the SDK with the same rules
+    /// as [`QuoteContext::subscribe_candlesticks`]. They are pushed as
+    /// [`PushCandlestick`](crate::quote::PushCandlestick) events and can be
+    /// read with [`QuoteContext::realtime_candlesticks`].
+    ///
+    /// Each period is seeded with the latest `warm_up` (at most `1000`)
+    /// candlesticks returned by
+    /// [`QuoteContext::history_candlesticks_by_offset`], `0` starts from an
+    /// empty series. The periods already subscribed with the same
+    /// `trade_sessions` are kept and the ones subscribed with other
+    /// `trade_sessions` are rebuilt, use
+    /// [`QuoteContext::unsubscribe_candlesticks`] to remove a period.
+    ///
+    /// The custom minute periods of [`AggregatePeriod::Minutes`] are
+    /// aggregated too, they are seeded with the one-minute candlesticks
+    /// resampled to the period and are not pushed, read them with
+    /// [`QuoteContext::realtime_candlesticks`] or
+    /// [`QuoteContext::with_realtime_candlesticks`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::{sync::Arc, time::Duration};
+    ///
+    /// use longport::{
+    ///     Config,
+    ///     quote::{AggregatePeriod, Period, QuoteContext, TradeSessions},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// ctx.aggregate_candlesticks(
+    ///     "700.HK",
+    ///     [Period::OneMinute, Period::FiveMinute, Period::SixtyMinute],
+    ///     TradeSessions::Intraday,
+    ///     100,
+    /// )
+    /// .await?;
+    /// tokio::time::sleep(Duration::from_secs(5)).await;
+    ///
+    /// let resp = ctx
+    ///     .realtime_candlesticks("700.HK", Period::FiveMinute, 10)
+    ///     .await?;
+    /// println!("{:?}", resp);
+    ///
+    /// ctx.aggregate_candlesticks(
+    ///     "700.HK",
+    ///     [AggregatePeriod::Minutes(7)],
+    ///     TradeSessions::Intraday,
+    ///     100,
+    /// )
+    /// .await?;
+    /// let resp = ctx
+    ///     .realtime_candlesticks("700.HK", AggregatePeriod::Minutes(7), 10)
+    ///     .await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn aggregate_candlesticks<T, I, P>(
+        &self,
+        symbol: T,
+        periods: I,
+        trade_sessions: TradeSessions,
+        warm_up: usize,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = P>,
+        P: Into<AggregatePeriod>,
+    {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.0
+            .command_tx
+            .send(Command::AggregateCandlesticks {
+                symbol: normalize_symbol(symbol.as_ref()).into(),
+                periods: periods
+                    .into_iter()
+                    .map(|period| period.into().into())
+                    .collect(),
+                trade_sessions,
+                warm_up,
+                reply_tx,
+            })
+            .map_err(|_| WsClientError::ClientClosed)?;
+        reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
+    }
+
+    /// Returns a stream of the quote pushes of `symbols`, each item is a
+    /// `(symbol, quote)` pair
+    ///
+    /// The stream only filters the pushes, the symbols must be subscribed
+    /// with [`SubFlags::QUOTE`] by [`QuoteContext::subscribe`]. An empty
+    /// `symbols` receives the pushes of all symbols.
+    ///
+    /// The push events are still sent to the receiver returned by
+    /// [`QuoteContext::try_new`], drop it if only the streams are used.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use futures_util::StreamExt;
+    /// use longport::{
+    ///     Config,
+    ///     quote::{QuoteContext, SubFlags},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let mut stream = ctx.quote_stream(["700.HK", "AAPL.US"]);
+    /// ctx.subscribe(["700.HK", "AAPL.US"], SubFlags::QUOTE, true)
+    ///     .await?;
+    /// while let Some((symbol, quote)) = stream.next().await {
+    ///     println!("{} {}", symbol, quote.last_done);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn quote_stream<I, T>(&self, symbols: I) -> PushStream<(String, PushQuote)>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.push_stream(PushStreamKind::Quote, symbols, |event| match event.detail {
+            PushEventDetail::Quote(quote) => Some((event.symbol, quote)),
+            _ => None,
+        })
+    }
+
+    /// Returns a stream of the depth pushes of `symbols`, each item is a
+    /// `(symbol, depth)` pair
+    ///
+    /// See [`QuoteContext::quote_stream`] for the details.
+    pub fn depth_stream<I, T>(&self, symbols: I) -> PushStream<(String, PushDepth)>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.push_stream(PushStreamKind::Depth, symbols, |event| match event.detail {
+            PushEventDetail::Depth(depth) => Some((event.symbol, depth)),
+            _ => None,
+        })
+    }
+
+    /// Returns a stream of the order book changes of `symbols`, each item is a
+    /// `(symbol, changed)` pair
+    ///
+    /// The stream keeps an [`OrderBook`] per symbol built from the depth
+    /// pushes, and only yields the price levels changed by each push. The
+    /// first item of a symbol contains all the levels of the book. The symbols
+    /// must be subscribed with [`SubFlags::DEPTH`] by
+    /// [`QuoteContext::subscribe`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use futures_util::StreamExt;
+    /// use longport::{
+    ///     Config,
+    ///     quote::{QuoteContext, SubFlags},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let mut stream = ctx.book_changed_stream(["700.HK"]);
+    /// ctx.subscribe(["700.HK"], SubFlags::DEPTH, true).await?;
+    /// while let Some((symbol, changed)) = stream.next().await {
+    ///     println!(
+    ///         "{} {:?} {:?} imbalance: {:?}",
+    ///         symbol,
+    ///         changed.asks,
+    ///         changed.bids,
+    ///         changed.book.imbalance(5)
+    ///     );
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn book_changed_stream<I, T>(&self, symbols: I) -> PushStream<(String, BookChanged)>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut books: HashMap<String, OrderBook> = HashMap::new();
+        self.push_stream(PushStreamKind::Depth, symbols, move |event| {
+            let PushEventDetail::Depth(depth) = event.detail else {
+                return None;
+            };
+            let changed = books.entry(event.symbol.clone()).or_default().apply(&depth);
+            (!changed.is_empty()).then_some((event.symbol, changed))
+        })
+    }
+
+    /// Returns a stream of the brokers pushes of `symbols`, each item is a
+    /// `(symbol, brokers)` pair
+    ///
+    /// See [`QuoteContext::quote_stream`] for the details.
+    pub fn brokers_stream<I, T>(&self, symbols: I) -> PushStream<(String, PushBrokers)>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.push_stream(PushStreamKind::Brokers, symbols, |event| {
+            match event.detail {
+                PushEventDetail::Brokers(brokers) => Some((event.symbol, brokers)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns a stream of the trades pushes of `symbols`, each item is a
+    /// `(symbol, trades)` pair
+    ///
+    /// See [`QuoteContext::quote_stream`] for the details.
+    pub fn trades_stream<I, T>(&self, symbols: I) -> PushStream<(String, PushTrades)>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.push_stream(PushStreamKind::Trades, symbols, |event| {
+            match event.detail {
+                PushEventDetail::Trade(trades) => Some((event.symbol, trades)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns a stream of the candlestick pushes of `symbol` in `period`
+    ///
+    /// The candlesticks must be subscribed by
+    /// [`QuoteContext::subscribe_candlesticks`].
+    pub fn candlestick_stream(
+        &self,
+        symbol: impl AsRef<str>,
+        period: Period,
+    ) -> PushStream<PushCandlestick> {
+        self.push_stream(
+            PushStreamKind::Candlestick(period),
+            [symbol],
+            |event| match event.detail {
+                PushEventDetail::Candlestick(candlestick) => Some(candlestick),
+                _ => None,
+            },
+        )
+    }
+
+    fn push_stream<I, T, F, R>(&self, kind: PushStreamKind, symbols: I, map: F) -> PushStream<R>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+        F: FnMut(PushEvent) -> Option<R> + Send + Sync + 'static,
+    {
+        let (tx, rx) = push_channel::channel(
+            self.0.push_channel_capacity,
+            self.0.push_overflow_policy,
+            self.0.dropped_push_events.clone(),
+        );
+        let _ = self.0.command_tx.send(Command::AddPushStream {
+            kind,
+            symbols: symbols
+                .into_iter()
+                .map(|symbol| normalize_symbol(symbol.as_ref()).to_string())
+                .collect(),
+            tx,
+        });
+        PushStream::new(rx, map)
+    }
+
     /// Get subscription information
     ///
     /// # Examples
@@ -899,6 +1336,70 @@ impl QuoteContext {
         Ok(candlesticks)
     }
 
+    /// Get security history candlesticks between `start` and `end`
+    /// (inclusive, in the local time of the market), paging through the range
+    /// automatically
+    ///
+    /// The pages are requested one by one as the stream is polled, so the
+    /// requests are throttled by the rate limits of the quote server, and the
+    /// duplicate candlesticks at the page boundaries are removed.
+    ///
+    /// If [`Config::candlestick_cache_dir`] is set, the candlesticks are read
+    /// from the local cache and only the missing trading days are requested.
+    /// Weekly and longer periods are not cached. This is the only history
+    /// method that uses the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use futures_util::TryStreamExt;
+    /// use longport::{
+    ///     Config,
+    ///     quote::{AdjustType, Period, QuoteContext, TradeSessions},
+    /// };
+    /// use time::macros::datetime;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let candlesticks: Vec<_> = ctx
+    ///     .history_candlesticks_range(
+    ///         "700.HK",
+    ///         Period::OneMinute,
+    ///         AdjustType::NoAdjust,
+    ///         TradeSessions::Intraday,
+    ///         datetime!(2024-01-01 00:00),
+    ///         datetime!(2024-12-31 23:59),
+    ///     )
+    ///     .try_collect()
+    ///     .await?;
+    /// println!("{}", candlesticks.len());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn history_candlesticks_range(
+        &self,
+        symbol: impl Into<String>,
+        period: Period,
+        adjust_type: AdjustType,
+        trade_sessions: TradeSessions,
+        start: PrimitiveDateTime,
+        end: PrimitiveDateTime,
+    ) -> impl Stream<Item = Result<Candlestick>> + Send + 'static {
+        history::candlesticks_range(
+            self.clone(),
+            symbol.into(),
+            period,
+            adjust_type,
+            trade_sessions,
+            start,
+            end,
+        )
+    }
+
     /// Get option chain expiry date list
     ///
     /// Reference: <https://open.longportapp.com/en/docs/quote/pull/optionchain-date>
@@ -993,6 +1494,100 @@ impl QuoteContext {
             .await
     }
 
+    /// Get the option chain of an expiry date with the quotes, the implied
+    /// volatility and the Greeks of the options
+    ///
+    /// The quotes and the calculated indexes are requested in batches of
+    /// 500 symbols, see [`OptionChainSnapshot`] for the details of the local
+    /// calculation. The time to expiry is counted from today in the timezone
+    /// of the market of `underlying`.
+    ///
+    /// Returns [`Error::InvalidSecuritySymbol`] if there is no quote of
+    /// `underlying`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{Config, quote::QuoteContext};
+    /// use time::macros::date;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let chain = ctx
+    ///     .option_chain_snapshot("AAPL.US", date!(2023 - 01 - 20))
+    ///     .await?;
+    /// for point in chain.smile() {
+    ///     println!("{:?}", point);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn option_chain_snapshot(
+        &self,
+        underlying: impl Into<String>,
+        expiry_date: Date,
+    ) -> Result<OptionChainSnapshot> {
+        let underlying = underlying.into();
+        let timezone = parse_market_from_symbol(&underlying)
+            .and_then(|market| get_market(market, SecurityBoard::Unknown))
+            .ok_or_else(|| Error::UnknownMarket {
+                symbol: underlying.clone(),
+            })?
+            .timezone;
+        let strikes = self
+            .option_chain_info_by_date(underlying.clone(), expiry_date)
+            .await?;
+        let underlying_price = self
+            .quote([underlying.clone()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::InvalidSecuritySymbol {
+                symbol: underlying.clone(),
+            })?
+            .last_done;
+
+        let symbols = strikes
+            .iter()
+            .flat_map(|info| [info.call_symbol.clone(), info.put_symbol.clone()])
+            .filter(|symbol| !symbol.is_empty())
+            .collect::<Vec<_>>();
+        let mut quotes = Vec::with_capacity(symbols.len());
+        let mut calc_indexes = Vec::with_capacity(symbols.len());
+        for symbols in symbols.chunks(MAX_BATCH_SYMBOLS) {
+            quotes.extend(self.option_quote(symbols.iter().cloned()).await?);
+            calc_indexes.extend(
+                self.calc_indexes(
+                    symbols.iter().cloned(),
+                    [
+                        CalcIndex::ImpliedVolatility,
+                        CalcIndex::OpenInterest,
+                        CalcIndex::Delta,
+                        CalcIndex::Gamma,
+                        CalcIndex::Theta,
+                        CalcIndex::Vega,
+                        CalcIndex::Rho,
+                    ],
+                )
+                .await?,
+            );
+        }
+
+        Ok(OptionChainSnapshot::new(
+            underlying,
+            underlying_price,
+            expiry_date,
+            OffsetDateTime::now_utc().to_timezone(timezone).date(),
+            strikes,
+            quotes,
+            calc_indexes,
+        ))
+    }
+
     /// Get warrant issuers
     ///
     /// Reference: <https://open.longportapp.com/en/docs/quote/pull/issuer>
@@ -1040,38 +1635,107 @@ impl QuoteContext {
         price_type: Option<&[FilterWarrantInOutBoundsType]>,
         status: Option<&[WarrantStatus]>,
     ) -> Result<Vec<WarrantInfo>> {
+        let filter = WarrantFilter::new(symbol, sort_by, sort_order)
+            .warrant_type(warrant_type.unwrap_or_default().iter().copied())
+            .issuer(issuer.unwrap_or_default().iter().copied())
+            .expiry_date(expiry_date.unwrap_or_default().iter().copied())
+            .price_type(price_type.unwrap_or_default().iter().copied())
+            .status(status.unwrap_or_default().iter().copied());
+        Ok(self.warrant_screener(filter).await?.warrants)
+    }
+
+    /// Query a page of the warrants matching the filter
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{
+    ///     Config,
+    ///     quote::{QuoteContext, SortOrderType, WarrantFilter, WarrantSortBy, WarrantType},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let filter = WarrantFilter::new("700.HK", WarrantSortBy::Volume, SortOrderType::Descending)
+    ///     .warrant_type([WarrantType::Call])
+    ///     .offset(20)
+    ///     .count(20);
+    /// let resp = ctx.warrant_screener(filter).await?;
+    /// println!("{} of {}", resp.warrants.len(), resp.total_count);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn warrant_screener(&self, filter: WarrantFilter) -> Result<WarrantList> {
         let resp = self
             .request::<_, quote::WarrantFilterListResponse>(
                 cmd_code::GET_FILTERED_WARRANT,
                 quote::WarrantFilterListRequest {
-                    symbol: symbol.into(),
-                    filter_config: Some(quote::FilterConfig {
-                        sort_by: sort_by.into(),
-                        sort_order: sort_order.into(),
-                        sort_offset: 0,
-                        sort_count: 0,
-                        r#type: warrant_type
-                            .map(|types| types.iter().map(|ty| (*ty).into()).collect())
-                            .unwrap_or_default(),
-                        issuer: issuer.map(|types| types.to_vec()).unwrap_or_default(),
-                        expiry_date: expiry_date
-                            .map(|e| e.iter().map(|e| (*e).into()).collect())
-                            .unwrap_or_default(),
-                        price_type: price_type
-                            .map(|types| types.iter().map(|ty| (*ty).into()).collect())
-                            .unwrap_or_default(),
-                        status: status
-                            .map(|status| status.iter().map(|status| (*status).into()).collect())
-                            .unwrap_or_default(),
-                    }),
+                    symbol: filter.symbol.clone(),
+                    filter_config: Some(filter.to_config()),
                     language: self.0.language.into(),
                 },
             )
             .await?;
-        resp.warrant_list
-            .into_iter()
-            .map(TryInto::try_into)
-            .collect::<Result<Vec<_>>>()
+        resp.try_into()
+    }
+
+    /// Returns a stream of all the warrants matching the filter, starting
+    /// from the offset of the filter
+    ///
+    /// The pages are requested one by one when the stream is polled, with the
+    /// count of the filter as the page size, or `100` if it is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use futures_util::TryStreamExt;
+    /// use longport::{
+    ///     Config,
+    ///     quote::{QuoteContext, SortOrderType, WarrantFilter, WarrantSortBy},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// let filter = WarrantFilter::new("700.HK", WarrantSortBy::Volume, SortOrderType::Descending);
+    /// let warrants: Vec<_> = ctx.warrant_screener_stream(filter).try_collect().await?;
+    /// println!("{}", warrants.len());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn warrant_screener_stream(
+        &self,
+        filter: WarrantFilter,
+    ) -> impl Stream<Item = Result<WarrantInfo>> + Send + 'static {
+        let ctx = self.clone();
+        let count = if filter.count > 0 { filter.count } else { 100 };
+        let filter = filter.count(count);
+
+        stream::try_unfold(Some(filter), move |filter| {
+            let ctx = ctx.clone();
+            async move {
+                let Some(filter) = filter else {
+                    return Ok::<_, Error>(None);
+                };
+                let offset = filter.offset;
+                let resp = ctx.warrant_screener(filter.clone()).await?;
+                let next_offset = offset + resp.warrants.len() as i32;
+                let next = (resp.warrants.len() as i32 == count && next_offset < resp.total_count)
+                    .then(|| filter.offset(next_offset));
+                Ok(Some((
+                    stream::iter(resp.warrants.into_iter().map(Ok::<_, Error>)),
+                    next,
+                )))
+            }
+        })
+        .try_flatten()
     }
 
     /// Get trading session of the day
@@ -1659,6 +2323,14 @@ impl QuoteContext {
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
 
+    /// Get the order book of the subscribed symbol from the local storage
+    ///
+    /// See [`QuoteContext::realtime_depth`] for the details.
+    pub async fn realtime_order_book(&self, symbol: impl Into<String>) -> Result<OrderBook> {
+        let depth = self.realtime_depth(symbol).await?;
+        Ok(OrderBook::new(depth.asks, depth.bids))
+    }
+
     /// Get real-time trades
     ///
     /// Get real-time trades of the subscribed symbols, it always returns the
@@ -1745,6 +2417,18 @@ impl QuoteContext {
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
 
+    /// Get the broker queue of the subscribed symbol from the local storage,
+    /// joined with the participant names
+    ///
+    /// Keep the returned view up to date with
+    /// [`BrokerQueueView::apply`] on the [`PushBrokers`] events to follow the
+    /// brokers over time.
+    pub async fn broker_queue_view(&self, symbol: impl Into<String>) -> Result<BrokerQueueView> {
+        let participants = self.participants().await?;
+        let brokers = self.realtime_brokers(symbol).await?;
+        Ok(BrokerQueueView::new(participants, brokers))
+    }
+
     /// Get real-time candlesticks
     ///
     /// Get real-time candlesticks of the subscribed symbols, it always returns
@@ -1778,7 +2462,7 @@ impl QuoteContext {
     pub async fn realtime_candlesticks(
         &self,
         symbol: impl Into<String>,
-        period: Period,
+        period: impl Into<AggregatePeriod>,
         count: usize,
     ) -> Result<Vec<Candlestick>> {
         let (reply_tx, reply_rx) = oneshot::channel();
@@ -1786,13 +2470,92 @@ impl QuoteContext {
             .command_tx
             .send(Command::GetRealtimeCandlesticks {
                 symbol: symbol.into(),
-                period,
+                period: period.into().into(),
                 count,
                 reply_tx,
             })
             .map_err(|_| WsClientError::ClientClosed)?;
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
+
+    /// Calls `f` with the trades of the symbol in memory, without copying
+    /// them
+    ///
+    /// `f` runs on the task that handles the pushes, so it should return
+    /// quickly. The number of the trades kept in memory is specified by
+    /// [`Config::realtime_trades_capacity`](crate::Config::realtime_trades_capacity).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{
+    ///     Config,
+    ///     quote::{QuoteContext, SubFlags},
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?.realtime_trades_capacity(50000));
+    /// let (ctx, _) = QuoteContext::try_new(config).await?;
+    ///
+    /// ctx.subscribe(["700.HK"], SubFlags::TRADE, false).await?;
+    /// let volume = ctx
+    ///     .with_realtime_trades("700.HK", |trades| {
+    ///         trades.iter().map(|trade| trade.volume).sum::<i64>()
+    ///     })
+    ///     .await?;
+    /// println!("{}", volume);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn with_realtime_trades<F, R>(&self, symbol: impl Into<String>, f: F) -> Result<R>
+    where
+        F: FnOnce(&[Trade]) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.0
+            .command_tx
+            .send(Command::VisitRealtimeTrades {
+                symbol: symbol.into(),
+                f: Box::new(move |trades| {
+                    let _ = reply_tx.send(f(trades));
+                }),
+            })
+            .map_err(|_| WsClientError::ClientClosed)?;
+        Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
+    }
+
+    /// Calls `f` with the candlesticks of the subscribed period in memory,
+    /// without copying them
+    ///
+    /// `f` runs on the task that handles the pushes, so it should return
+    /// quickly. The number of the candlesticks kept in memory is specified by
+    /// [`Config::realtime_candlesticks_capacity`](crate::Config::realtime_candlesticks_capacity).
+    pub async fn with_realtime_candlesticks<F, R>(
+        &self,
+        symbol: impl Into<String>,
+        period: impl Into<AggregatePeriod>,
+        f: F,
+    ) -> Result<R>
+    where
+        F: FnOnce(&[Candlestick]) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.0
+            .command_tx
+            .send(Command::VisitRealtimeCandlesticks {
+                symbol: symbol.into(),
+                period: period.into().into(),
+                f: Box::new(move |candlesticks| {
+                    let _ = reply_tx.send(f(candlesticks));
+                }),
+            })
+            .map_err(|_| WsClientError::ClientClosed)?;
+        Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
+    }
 }
 
 fn normalize_symbol(symbol: &str) -> &str {
@@ -1,16 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
+    path::Path,
     sync::Arc,
 };
 
 use comfy_table::Table;
 use itertools::Itertools;
-use longport_candlesticks::{TradeSessionType, UpdateAction};
+use longport_candlesticks::{Period as CandlestickPeriod, TradeSessionType, UpdateAction};
 use longport_httpcli::HttpClient;
 use longport_proto::quote::{
-    self, AdjustType, MarketTradeDayRequest, MarketTradeDayResponse, MultiSecurityRequest, Period,
-    PushQuoteTag, SecurityCandlestickRequest, SecurityCandlestickResponse,
-    SecurityStaticInfoResponse, SubscribeRequest, UnsubscribeRequest,
+    self, AdjustType, Direction, HistoryCandlestickQueryType, MarketTradeDayRequest,
+    MarketTradeDayResponse, MultiSecurityRequest, Period, PushQuoteTag, SecurityCandlestickRequest,
+    SecurityCandlestickResponse, SecurityHistoryCandlestickRequest, SecurityStaticInfoResponse,
+    SubscribeRequest, UnsubscribeRequest, security_history_candlestick_request::OffsetQuery,
 };
 use longport_wscli::{
     CodecType, Platform, ProtocolVersion, RateLimit, WsClient, WsClientError, WsEvent, WsSession,
@@ -24,18 +26,22 @@ use tokio::{
 use crate::{
     Config, Error, Market, Result,
     config::PushCandlestickMode,
+    connection::{ConnectionEvent, ConnectionEvents},
+    push_channel::PushSender,
     quote::{
         Candlestick, PushCandlestick, PushEvent, PushEventDetail, PushQuote, PushTrades,
         RealtimeQuote, SecurityBoard, SecurityBrokers, SecurityDepth, Subscription, Trade,
         TradeSession, TradeSessions, cmd_code,
-        store::{Candlesticks, Store, TailCandlestick},
+        push_stream::{PushDispatcher, PushStreamKind},
+        recorder::{self, ControlRecord, Record, RecordReader, Recorder},
+        store::{Candlesticks, Store, TailCandlestick, convert_period, get_market, quote_period},
         sub_flags::SubFlags,
         types::QuotePackageDetail,
         utils::{format_date, parse_date},
     },
 };
 
-const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+type Visitor<T> = Box<dyn FnOnce(&[T]) + Send>;
 
 pub(crate) enum Command {
     Request {
@@ -62,7 +68,14 @@ pub(crate) enum Command {
     },
     UnsubscribeCandlesticks {
         symbol: String,
-        period: Period,
+        period: CandlestickPeriod,
+        reply_tx: oneshot::Sender<Result<()>>,
+    },
+    AggregateCandlesticks {
+        symbol: String,
+        periods: Vec<CandlestickPeriod>,
+        trade_sessions: TradeSessions,
+        warm_up: usize,
         reply_tx: oneshot::Sender<Result<()>>,
     },
     Subscriptions {
@@ -87,10 +100,24 @@ pub(crate) enum Command {
     },
     GetRealtimeCandlesticks {
         symbol: String,
-        period: Period,
+        period: CandlestickPeriod,
         count: usize,
         reply_tx: oneshot::Sender<Vec<Candlestick>>,
     },
+    VisitRealtimeTrades {
+        symbol: String,
+        f: Visitor<Trade>,
+    },
+    VisitRealtimeCandlesticks {
+        symbol: String,
+        period: CandlestickPeriod,
+        f: Visitor<Candlestick>,
+    },
+    AddPushStream {
+        kind: PushStreamKind,
+        symbols: HashSet<String>,
+        tx: PushSender<PushEvent>,
+    },
 }
 
 #[derive(Debug, Default)]
@@ -130,11 +157,12 @@ pub(crate) struct Core {
     config: Arc<Config>,
     rate_limit: Vec<(u8, RateLimit)>,
     command_rx: mpsc::UnboundedReceiver<Command>,
-    push_tx: mpsc::UnboundedSender<PushEvent>,
+    dispatcher: PushDispatcher,
     event_tx: mpsc::UnboundedSender<WsEvent>,
     event_rx: mpsc::UnboundedReceiver<WsEvent>,
     http_cli: HttpClient,
-    ws_cli: WsClient,
+    /// `None` when replaying a recording
+    ws_cli: Option<WsClient>,
     session: Option<WsSession>,
     close: bool,
     subscriptions: HashMap<String, SubFlags>,
@@ -144,22 +172,28 @@ pub(crate) struct Core {
     quote_level: String,
     quote_package_details: Vec<QuotePackageDetail>,
     push_candlestick_mode: PushCandlestickMode,
+    recorder: Option<Recorder>,
+    replay_rx: Option<mpsc::UnboundedReceiver<Record>>,
+    connection_events: ConnectionEvents,
 }
 
 impl Core {
     pub(crate) async fn try_new(
         config: Arc<Config>,
         command_rx: mpsc::UnboundedReceiver<Command>,
-        push_tx: mpsc::UnboundedSender<PushEvent>,
+        push_tx: PushSender<PushEvent>,
+        connection_events: ConnectionEvents,
     ) -> Result<Self> {
-        let http_cli = config.create_http_client();
+        let http_cli = config.create_http_client()?;
         let otp = http_cli.get_otp().await?;
 
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
         tracing::info!("connecting to quote server");
+        connection_events.send(ConnectionEvent::Connecting);
         let (url, res) = config.create_quote_ws_request().await;
         let request = res.map_err(WsClientError::from)?;
+        let proxy = config.create_ws_proxy()?;
 
         let mut ws_cli = WsClient::open(
             request,
@@ -168,12 +202,16 @@ impl Core {
             Platform::OpenAPI,
             event_tx.clone(),
             vec![],
+            proxy.as_ref(),
+            config.tls_config.clone(),
         )
         .await?;
 
         tracing::info!(url = url, "quote server connected");
+        connection_events.send(ConnectionEvent::Connected);
 
         let session = ws_cli.request_auth(otp, config.create_metadata()).await?;
+        connection_events.send(ConnectionEvent::Authenticated);
 
         // fetch user profile
         let resp = ws_cli
@@ -255,6 +293,12 @@ impl Core {
             println!("{table}");
         }
 
+        let recorder = config
+            .quote_record_path
+            .as_ref()
+            .map(Recorder::create)
+            .transpose()?;
+
         tracing::info!(
             member_id = member_id,
             quote_level = quote_level,
@@ -262,24 +306,73 @@ impl Core {
             "quote context initialized",
         );
 
-        Ok(Self {
+        let store = Store::new(&config);
+        let mut core = Self {
             config,
             rate_limit,
             command_rx,
-            push_tx,
+            dispatcher: PushDispatcher::new(push_tx),
             event_tx,
             event_rx,
             http_cli,
-            ws_cli,
+            ws_cli: Some(ws_cli),
             session: Some(session),
             close: false,
             subscriptions: HashMap::new(),
             trading_days: current_trade_days,
-            store: Store::default(),
+            store,
             member_id,
             quote_level,
             quote_package_details,
             push_candlestick_mode,
+            recorder,
+            replay_rx: None,
+            connection_events,
+        };
+        core.record_trading_days();
+        Ok(core)
+    }
+
+    /// Create a core that replays the recording at `path` instead of
+    /// connecting to the server
+    pub(crate) fn try_new_replay(
+        config: Arc<Config>,
+        command_rx: mpsc::UnboundedReceiver<Command>,
+        push_tx: PushSender<PushEvent>,
+        connection_events: ConnectionEvents,
+        path: &Path,
+        speed: f64,
+    ) -> Result<Self> {
+        let reader = RecordReader::open(path)?;
+        let (replay_tx, replay_rx) = mpsc::unbounded_channel();
+        recorder::replay(reader, speed, replay_tx);
+
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let http_cli = config.create_http_client()?;
+        let push_candlestick_mode = config.push_candlestick_mode.unwrap_or_default();
+        let store = Store::new(&config);
+
+        Ok(Self {
+            config,
+            rate_limit: vec![],
+            command_rx,
+            dispatcher: PushDispatcher::new(push_tx),
+            event_tx,
+            event_rx,
+            http_cli,
+            ws_cli: None,
+            session: None,
+            close: false,
+            subscriptions: HashMap::new(),
+            trading_days: TradingDays::default(),
+            store,
+            member_id: 0,
+            quote_level: String::new(),
+            quote_package_details: vec![],
+            push_candlestick_mode,
+            recorder: None,
+            replay_rx: Some(replay_rx),
+            connection_events,
         })
     }
 
@@ -299,88 +392,131 @@ impl Core {
     }
 
     pub(crate) async fn run(mut self) {
+        if let Some(replay_rx) = self.replay_rx.take() {
+            self.replay_loop(replay_rx).await;
+            return;
+        }
+
+        let reconnect_policy = self.config.create_reconnect_policy();
+
         while !self.close {
-            match self.main_loop().await {
+            let mut err = match self.main_loop().await {
                 Ok(()) => return,
-                Err(err) => tracing::error!(error = %err, "quote disconnected"),
-            }
+                Err(err) => err,
+            };
+            tracing::error!(error = %err, "quote disconnected");
+            self.connection_events
+                .send(ConnectionEvent::disconnected(&err));
 
+            let mut attempt = 0;
             loop {
-                // reconnect
-                tokio::time::sleep(RECONNECT_DELAY).await;
-
-                tracing::info!("connecting to quote server");
-                let (url, res) = self.config.create_quote_ws_request().await;
-                let request = res.expect("BUG: failed to create quote ws request");
-
-                match WsClient::open(
-                    request,
-                    ProtocolVersion::Version1,
-                    CodecType::Protobuf,
-                    Platform::OpenAPI,
-                    self.event_tx.clone(),
-                    self.rate_limit.clone(),
-                )
-                .await
-                {
-                    Ok(ws_cli) => self.ws_cli = ws_cli,
-                    Err(err) => {
-                        tracing::error!(error = %err, "failed to connect quote server");
-                        continue;
+                let Some(delay) = reconnect_policy.delay(attempt) else {
+                    reconnect_policy.give_up(attempt, &err);
+                    self.connection_events.send(ConnectionEvent::GaveUp {
+                        attempts: attempt,
+                        error: err.to_string(),
+                    });
+                    return;
+                };
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+
+                match self.reconnect().await {
+                    Ok(()) => break,
+                    Err(reconnect_err) => {
+                        self.connection_events
+                            .send(ConnectionEvent::disconnected(&reconnect_err));
+                        err = reconnect_err;
                     }
                 }
+            }
+        }
+    }
 
-                tracing::info!(url = url, "quote server connected");
-
-                // request new session
-                match &self.session {
-                    Some(session) if !session.is_expired() => {
-                        match self
-                            .ws_cli
-                            .request_reconnect(&session.session_id, self.config.create_metadata())
-                            .await
-                        {
-                            Ok(new_session) => self.session = Some(new_session),
-                            Err(err) => {
-                                self.session = None; // invalid session
-                                tracing::error!(error = %err, "failed to request session id");
-                                continue;
-                            }
-                        }
+    async fn reconnect(&mut self) -> Result<()> {
+        tracing::info!("connecting to quote server");
+        self.connection_events.send(ConnectionEvent::Connecting);
+        let (url, res) = self.config.create_quote_ws_request().await;
+        let request = res.map_err(WsClientError::from)?;
+        let proxy = self.config.create_ws_proxy()?;
+
+        match WsClient::open(
+            request,
+            ProtocolVersion::Version1,
+            CodecType::Protobuf,
+            Platform::OpenAPI,
+            self.event_tx.clone(),
+            self.rate_limit.clone(),
+            proxy.as_ref(),
+            self.config.tls_config.clone(),
+        )
+        .await
+        {
+            Ok(ws_cli) => self.ws_cli = Some(ws_cli),
+            Err(err) => {
+                tracing::error!(error = %err, "failed to connect quote server");
+                return Err(err.into());
+            }
+        }
+        let ws_cli = self.ws_cli.as_ref().expect("BUG: ws client not connected");
+
+        tracing::info!(url = url, "quote server connected");
+        self.connection_events.send(ConnectionEvent::Connected);
+
+        // request new session
+        match &self.session {
+            Some(session) if !session.is_expired() => {
+                match ws_cli
+                    .request_reconnect(&session.session_id, self.config.create_metadata())
+                    .await
+                {
+                    Ok(new_session) => {
+                        self.session = Some(new_session);
+                        self.connection_events.send(ConnectionEvent::Reconnected {
+                            session_reused: true,
+                        });
                     }
-                    _ => {
-                        let otp = match self.http_cli.get_otp().await {
-                            Ok(otp) => otp,
-                            Err(err) => {
-                                tracing::error!(error = %err, "failed to request otp");
-                                continue;
-                            }
-                        };
-
-                        match self
-                            .ws_cli
-                            .request_auth(otp, self.config.create_metadata())
-                            .await
-                        {
-                            Ok(new_session) => self.session = Some(new_session),
-                            Err(err) => {
-                                tracing::error!(error = %err, "failed to request session id");
-                                continue;
-                            }
-                        }
+                    Err(err) => {
+                        self.session = None; // invalid session
+                        tracing::error!(error = %err, "failed to request session id");
+                        return Err(err.into());
                     }
                 }
+            }
+            _ => {
+                let otp = match self.http_cli.get_otp().await {
+                    Ok(otp) => otp,
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to request otp");
+                        return Err(err.into());
+                    }
+                };
 
-                // handle reconnect
-                match self.resubscribe().await {
-                    Ok(()) => break,
+                match ws_cli
+                    .request_auth(otp, self.config.create_metadata())
+                    .await
+                {
+                    Ok(new_session) => {
+                        self.session = Some(new_session);
+                        self.connection_events.send(ConnectionEvent::Reconnected {
+                            session_reused: false,
+                        });
+                    }
                     Err(err) => {
-                        tracing::error!(error = %err, "failed to subscribe topics");
-                        continue;
+                        tracing::error!(error = %err, "failed to request session id");
+                        return Err(err.into());
                     }
                 }
             }
         }
+
+        // handle reconnect
+        if let Err(err) = self.resubscribe().await {
+            tracing::error!(error = %err, "failed to subscribe topics");
+            return Err(err);
+        }
+        self.connection_events.send(ConnectionEvent::Resubscribed);
+        Ok(())
     }
 
     async fn main_loop(&mut self) -> Result<()> {
@@ -390,6 +526,8 @@ impl Core {
         );
 
         loop {
+            self.dispatcher.ready().await;
+
             tokio::select! {
                 item = self.event_rx.recv() => {
                     match item {
@@ -407,8 +545,11 @@ impl Core {
                     }
                 }
                 _ = update_trading_days_interval.tick() => {
-                    if let Ok(days) = fetch_trading_days(&self.ws_cli).await {
+                    if let Some(ws_cli) = &self.ws_cli
+                        && let Ok(days) = fetch_trading_days(ws_cli).await
+                    {
                         self.trading_days = days;
+                        self.record_trading_days();
                     }
                 }
             }
@@ -462,6 +603,19 @@ impl Core {
                 let _ = reply_tx.send(self.handle_unsubscribe_candlesticks(symbol, period).await);
                 Ok(())
             }
+            Command::AggregateCandlesticks {
+                symbol,
+                periods,
+                trade_sessions,
+                warm_up,
+                reply_tx,
+            } => {
+                let _ = reply_tx.send(
+                    self.handle_aggregate_candlesticks(symbol, periods, trade_sessions, warm_up)
+                        .await,
+                );
+                Ok(())
+            }
             Command::Subscriptions { reply_tx } => {
                 let res = self.handle_subscriptions().await;
                 let _ = reply_tx.send(res);
@@ -496,6 +650,29 @@ impl Core {
                 let _ = reply_tx.send(self.handle_get_realtime_candlesticks(symbol, period, count));
                 Ok(())
             }
+            Command::VisitRealtimeTrades { symbol, f } => {
+                f(self
+                    .store
+                    .securities
+                    .get_mut(&symbol)
+                    .map(|data| &*data.trades.make_contiguous())
+                    .unwrap_or_default());
+                Ok(())
+            }
+            Command::VisitRealtimeCandlesticks { symbol, period, f } => {
+                f(self
+                    .store
+                    .securities
+                    .get(&symbol)
+                    .and_then(|data| data.candlesticks.get(&period))
+                    .map(|candlesticks| candlesticks.candlesticks.as_slice())
+                    .unwrap_or_default());
+                Ok(())
+            }
+            Command::AddPushStream { kind, symbols, tx } => {
+                self.dispatcher.add_stream(kind, symbols, tx);
+                Ok(())
+            }
         }
     }
 
@@ -505,8 +682,14 @@ impl Core {
         body: Vec<u8>,
         reply_tx: oneshot::Sender<Result<Vec<u8>>>,
     ) -> Result<()> {
-        let res = self.ws_cli.request_raw(command_code, None, body).await;
-        let _ = reply_tx.send(res.map_err(Into::into));
+        let res = match &self.ws_cli {
+            Some(ws_cli) => ws_cli
+                .request_raw(command_code, None, body)
+                .await
+                .map_err(Into::into),
+            None => Err(Error::ReplayNotSupported),
+        };
+        let _ = reply_tx.send(res);
         Ok(())
     }
 
@@ -517,24 +700,33 @@ impl Core {
         is_first_push: bool,
     ) -> Result<()> {
         // send request
-        let req = SubscribeRequest {
-            symbol: symbols.clone(),
-            sub_type: sub_types.into(),
-            is_first_push,
-        };
-        self.ws_cli
-            .request::<_, ()>(cmd_code::SUBSCRIBE, None, req)
-            .await?;
+        // when replaying, only the local subscriptions are updated
+        if let Some(ws_cli) = &self.ws_cli {
+            let req = SubscribeRequest {
+                symbol: symbols.clone(),
+                sub_type: sub_types.into(),
+                is_first_push,
+            };
+            ws_cli
+                .request::<_, ()>(cmd_code::SUBSCRIBE, None, req)
+                .await?;
+        }
 
-        // update subscriptions
+        self.record(Record::Control(ControlRecord::Subscribe {
+            symbols: symbols.clone(),
+            sub_types: sub_types.bits(),
+        }));
+        self.add_subscriptions(symbols, sub_types);
+        Ok(())
+    }
+
+    fn add_subscriptions(&mut self, symbols: Vec<String>, sub_types: SubFlags) {
         for symbol in symbols {
             self.subscriptions
                 .entry(symbol)
                 .and_modify(|flags| *flags |= sub_types)
                 .or_insert(sub_types);
         }
-
-        Ok(())
     }
 
     async fn handle_unsubscribe(
@@ -574,15 +766,25 @@ impl Core {
             })
             .collect::<Vec<_>>();
 
-        for req in requests {
-            self.ws_cli
-                .request::<_, ()>(cmd_code::UNSUBSCRIBE, None, req)
-                .await?;
+        if let Some(ws_cli) = &self.ws_cli {
+            for req in requests {
+                ws_cli
+                    .request::<_, ()>(cmd_code::UNSUBSCRIBE, None, req)
+                    .await?;
+            }
         }
 
-        // update subscriptions
+        self.record(Record::Control(ControlRecord::Unsubscribe {
+            symbols: symbols.clone(),
+            sub_types: sub_types.bits(),
+        }));
+        self.remove_subscriptions(&symbols, sub_types);
+        Ok(())
+    }
+
+    fn remove_subscriptions(&mut self, symbols: &[String], sub_types: SubFlags) {
         let mut remove_symbols = Vec::new();
-        for symbol in &symbols {
+        for symbol in symbols {
             if let Some(cur_flags) = self.subscriptions.get_mut(symbol) {
                 *cur_flags &= !sub_types;
                 if cur_flags.is_empty() {
@@ -594,7 +796,6 @@ impl Core {
         for symbol in remove_symbols {
             self.subscriptions.remove(symbol);
         }
-        Ok(())
     }
 
     async fn handle_subscribe_candlesticks(
@@ -609,7 +810,7 @@ impl Core {
             .store
             .securities
             .get_mut(&symbol)
-            .and_then(|data| data.candlesticks.get_mut(&period))
+            .and_then(|data| data.candlesticks.get_mut(&convert_period(period)))
             .filter(|candlesticks| candlesticks.trade_sessions == trade_sessions)
         {
             candlesticks.trade_sessions = trade_sessions;
@@ -617,35 +818,11 @@ impl Core {
             return Ok(candlesticks.candlesticks.clone());
         }
 
-        tracing::info!(symbol = symbol, "fetch symbol board");
-
-        let security_data = self.store.securities.entry(symbol.clone()).or_default();
-        if security_data.board == SecurityBoard::Unknown {
-            // update board
-            let resp: SecurityStaticInfoResponse = self
-                .ws_cli
-                .request(
-                    cmd_code::GET_BASIC_INFO,
-                    None,
-                    MultiSecurityRequest {
-                        symbol: vec![symbol.clone()],
-                    },
-                )
-                .await?;
-            if resp.secu_static_info.is_empty() {
-                return Err(Error::InvalidSecuritySymbol {
-                    symbol: symbol.clone(),
-                });
-            }
-            security_data.board = resp.secu_static_info[0].board.parse().unwrap_or_default();
-        }
-
-        tracing::info!(symbol = symbol, board = ?security_data.board, "got the symbol board");
+        let board = self.fetch_board(&symbol).await?;
 
         // pull candlesticks
         tracing::info!(symbol = symbol, period = ?period, "pull history candlesticks");
-        let resp: SecurityCandlestickResponse = self
-            .ws_cli
+        let resp: SecurityCandlestickResponse = connected(&self.ws_cli)?
             .request(
                 cmd_code::GET_SECURITY_CANDLESTICKS,
                 None,
@@ -660,61 +837,213 @@ impl Core {
             .await?;
         tracing::info!(symbol = symbol, period = ?period, len = resp.candlesticks.len(), "got history candlesticks");
 
-        let mut candlesticks = vec![];
-        let mut tails = HashMap::new();
+        let candlesticks = resp
+            .candlesticks
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Candlestick>>>()?;
+        self.load_candlesticks(
+            &symbol,
+            convert_period(period),
+            trade_sessions,
+            board,
+            candlesticks.clone(),
+        );
+        self.subscribe_quote_for_candlesticks(&symbol).await?;
+        Ok(candlesticks)
+    }
 
-        for candlestick in resp.candlesticks {
-            let candlestick: Candlestick = candlestick.try_into()?;
-            let index = candlesticks.len();
-            candlesticks.push(candlestick);
-            tails.insert(
-                candlestick.trade_session,
-                TailCandlestick { index, candlestick },
-            );
+    async fn handle_aggregate_candlesticks(
+        &mut self,
+        symbol: String,
+        periods: Vec<CandlestickPeriod>,
+        trade_sessions: TradeSessions,
+        warm_up: usize,
+    ) -> Result<()> {
+        tracing::info!(symbol = symbol, periods = ?periods, warm_up = warm_up, "aggregate candlesticks");
+
+        let board = self.fetch_board(&symbol).await?;
+
+        for period in periods {
+            if self
+                .store
+                .securities
+                .get(&symbol)
+                .and_then(|data| data.candlesticks.get(&period))
+                .is_some_and(|candlesticks| candlesticks.trade_sessions == trade_sessions)
+            {
+                continue;
+            }
+
+            let candlesticks = if warm_up == 0 {
+                vec![]
+            } else if let Some(quote_period) = quote_period(period) {
+                self.fetch_warm_up_candlesticks(&symbol, quote_period, trade_sessions, warm_up)
+                    .await?
+            } else {
+                // the custom periods are resampled from the one-minute
+                // candlesticks
+                let minutes = match period {
+                    CandlestickPeriod::Minutes(minutes) => minutes as usize,
+                    _ => 1,
+                };
+                let candlesticks = self
+                    .fetch_warm_up_candlesticks(
+                        &symbol,
+                        Period::OneMinute,
+                        trade_sessions,
+                        warm_up.saturating_mul(minutes),
+                    )
+                    .await?;
+                let mut candlesticks = parse_market_from_symbol(&symbol)
+                    .and_then(|market_type| {
+                        Some(get_market(market_type, board)?.resample(
+                            self.trading_days.half_days(market_type),
+                            period,
+                            candlesticks,
+                        ))
+                    })
+                    .unwrap_or_default();
+                candlesticks.drain(..candlesticks.len().saturating_sub(warm_up));
+                candlesticks
+            };
+            self.load_candlesticks(&symbol, period, trade_sessions, board, candlesticks);
         }
 
-        tracing::info!(symbol = symbol, period = ?period, count = candlesticks.len(), tails = ?tails, "candlesticks loaded");
+        self.subscribe_quote_for_candlesticks(&symbol).await
+    }
 
-        security_data
-            .candlesticks
-            .entry(period)
-            .or_insert_with(|| Candlesticks {
-                trade_sessions,
-                candlesticks: candlesticks.clone(),
-                tails,
+    async fn fetch_warm_up_candlesticks(
+        &self,
+        symbol: &str,
+        period: Period,
+        trade_sessions: TradeSessions,
+        count: usize,
+    ) -> Result<Vec<Candlestick>> {
+        tracing::info!(symbol = symbol, period = ?period, "pull history candlesticks for warming up");
+        let resp: SecurityCandlestickResponse = connected(&self.ws_cli)?
+            .request(
+                cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
+                None,
+                SecurityHistoryCandlestickRequest {
+                    symbol: symbol.to_string(),
+                    period: period.into(),
+                    adjust_type: AdjustType::NoAdjust.into(),
+                    query_type: HistoryCandlestickQueryType::QueryByOffset.into(),
+                    offset_request: Some(OffsetQuery {
+                        direction: Direction::Backward.into(),
+                        date: String::new(),
+                        minute: String::new(),
+                        count: count.min(1000) as i32,
+                    }),
+                    date_request: None,
+                    trade_session: trade_sessions as i32,
+                },
+            )
+            .await?;
+        resp.candlesticks
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect()
+    }
+
+    async fn fetch_board(&mut self, symbol: &str) -> Result<SecurityBoard> {
+        let ws_cli = connected(&self.ws_cli)?;
+        let security_data = self.store.securities.entry(symbol.to_string()).or_default();
+        if security_data.board != SecurityBoard::Unknown {
+            return Ok(security_data.board);
+        }
+
+        tracing::info!(symbol = symbol, "fetch symbol board");
+        let resp: SecurityStaticInfoResponse = ws_cli
+            .request(
+                cmd_code::GET_BASIC_INFO,
+                None,
+                MultiSecurityRequest {
+                    symbol: vec![symbol.to_string()],
+                },
+            )
+            .await?;
+        if resp.secu_static_info.is_empty() {
+            return Err(Error::InvalidSecuritySymbol {
+                symbol: symbol.to_string(),
             });
+        }
+        security_data.board = resp.secu_static_info[0].board.parse().unwrap_or_default();
+        tracing::info!(symbol = symbol, board = ?security_data.board, "got the symbol board");
+        Ok(security_data.board)
+    }
 
-        // subscribe
+    fn load_candlesticks(
+        &mut self,
+        symbol: &str,
+        period: CandlestickPeriod,
+        trade_sessions: TradeSessions,
+        board: SecurityBoard,
+        candlesticks: Vec<Candlestick>,
+    ) {
+        tracing::info!(symbol = symbol, period = ?period, count = candlesticks.len(), "candlesticks loaded");
+
+        // the callers keep the buffer of the same trade sessions, a buffer of
+        // other trade sessions is rebuilt like the replay does
+        let capacity = self.store.candlesticks_capacity;
+        self.store
+            .securities
+            .entry(symbol.to_string())
+            .or_default()
+            .candlesticks
+            .insert(
+                period,
+                new_candlesticks(capacity, trade_sessions, candlesticks.clone()),
+            );
+
+        self.record(Record::Control(ControlRecord::SubscribeCandlesticks {
+            symbol: symbol.to_string(),
+            period: record_period(period),
+            minutes: record_minutes(period),
+            trade_sessions: trade_sessions as i32,
+            board,
+            candlesticks,
+        }));
+    }
+
+    async fn subscribe_quote_for_candlesticks(&mut self, symbol: &str) -> Result<()> {
         if self
             .subscriptions
-            .get(&symbol)
+            .get(symbol)
             .copied()
             .unwrap_or_else(SubFlags::empty)
             .contains(SubFlags::QUOTE | SubFlags::TRADE)
         {
-            return Ok(candlesticks);
+            return Ok(());
         }
 
-        tracing::info!(symbol = symbol, period = ?period, "subscribe quote for candlesticks");
+        tracing::info!(symbol = symbol, "subscribe quote for candlesticks");
 
         let req = SubscribeRequest {
-            symbol: vec![symbol.clone()],
+            symbol: vec![symbol.to_string()],
             sub_type: (SubFlags::QUOTE | SubFlags::TRADE).into(),
             is_first_push: true,
         };
-        self.ws_cli
+        connected(&self.ws_cli)?
             .request::<_, ()>(cmd_code::SUBSCRIBE, None, req)
             .await?;
 
-        tracing::info!(symbol = symbol, period = ?period, "subscribed quote for candlesticks");
-        Ok(candlesticks)
+        tracing::info!(symbol = symbol, "subscribed quote for candlesticks");
+        Ok(())
     }
 
     async fn handle_unsubscribe_candlesticks(
         &mut self,
         symbol: String,
-        period: Period,
+        period: CandlestickPeriod,
     ) -> Result<()> {
+        self.record(Record::Control(ControlRecord::UnsubscribeCandlesticks {
+            symbol: symbol.clone(),
+            period: record_period(period),
+            minutes: record_minutes(period),
+        }));
+
         if let Some(periods) = self
             .store
             .securities
@@ -729,9 +1058,12 @@ impl Core {
                 .copied()
                 .unwrap_or_else(SubFlags::empty);
 
-            if periods.is_empty() && !sub_flags.intersects(SubFlags::QUOTE | SubFlags::TRADE) {
+            if periods.is_empty()
+                && !sub_flags.intersects(SubFlags::QUOTE | SubFlags::TRADE)
+                && let Some(ws_cli) = &self.ws_cli
+            {
                 tracing::info!(symbol = symbol, "unsubscribe quote for candlesticks");
-                self.ws_cli
+                ws_cli
                     .request::<_, ()>(
                         cmd_code::UNSUBSCRIBE,
                         None,
@@ -774,7 +1106,12 @@ impl Core {
                     sub_types: SubFlags::empty(),
                     candlesticks: vec![],
                 })
-                .candlesticks = data.candlesticks.keys().copied().collect();
+                .candlesticks = data
+                .candlesticks
+                .keys()
+                .copied()
+                .filter_map(quote_period)
+                .collect();
         }
 
         subscriptions.into_values().collect()
@@ -783,7 +1120,152 @@ impl Core {
     async fn handle_ws_event(&mut self, event: WsEvent) -> Result<()> {
         match event {
             WsEvent::Error(err) => Err(err.into()),
-            WsEvent::Push { command_code, body } => self.handle_push(command_code, body),
+            WsEvent::Push { command_code, body } => {
+                if self.recorder.is_some() {
+                    self.record(Record::Push {
+                        command_code,
+                        body: body.clone(),
+                    });
+                }
+                self.handle_push(command_code, body)
+            }
+        }
+    }
+
+    fn record(&mut self, record: Record) {
+        // the writer task logs the error before it stops
+        if let Some(recorder) = &self.recorder
+            && !recorder.write(record)
+        {
+            self.recorder = None;
+        }
+    }
+
+    fn record_trading_days(&mut self) {
+        if self.recorder.is_none() {
+            return;
+        }
+
+        let records = self
+            .trading_days
+            .normal_days
+            .iter()
+            .map(|(market, normal_days)| ControlRecord::TradingDays {
+                market: market.to_string(),
+                normal_days: normal_days.iter().copied().map(format_date).collect(),
+                half_days: self
+                    .trading_days
+                    .half_days
+                    .get(market)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .map(format_date)
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        for record in records {
+            self.record(Record::Control(record));
+        }
+    }
+
+    async fn replay_loop(&mut self, mut replay_rx: mpsc::UnboundedReceiver<Record>) {
+        let mut replaying = true;
+
+        loop {
+            self.dispatcher.ready().await;
+
+            tokio::select! {
+                item = replay_rx.recv(), if replaying => {
+                    match item {
+                        Some(record) => self.handle_record(record),
+                        None => {
+                            // drop the sender to close the push channel, so the receiver knows
+                            // the replay is finished
+                            replaying = false;
+                            self.dispatcher.close();
+                        }
+                    }
+                }
+                item = self.command_rx.recv() => {
+                    match item {
+                        Some(command) => {
+                            let _ = self.handle_command(command).await;
+                        }
+                        None => return,
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_record(&mut self, record: Record) {
+        match record {
+            Record::Push { command_code, body } => {
+                let _ = self.handle_push(command_code, body);
+            }
+            Record::Control(ControlRecord::Subscribe { symbols, sub_types }) => {
+                self.add_subscriptions(symbols, SubFlags::from_bits_truncate(sub_types));
+            }
+            Record::Control(ControlRecord::Unsubscribe { symbols, sub_types }) => {
+                self.remove_subscriptions(&symbols, SubFlags::from_bits_truncate(sub_types));
+            }
+            Record::Control(ControlRecord::SubscribeCandlesticks {
+                symbol,
+                period,
+                minutes,
+                trade_sessions,
+                board,
+                candlesticks,
+            }) => {
+                let Some(period) = replay_period(period, minutes) else {
+                    return;
+                };
+                let trade_sessions = if trade_sessions == TradeSessions::All as i32 {
+                    TradeSessions::All
+                } else {
+                    TradeSessions::Intraday
+                };
+                let capacity = self.store.candlesticks_capacity;
+                let security_data = self.store.securities.entry(symbol).or_default();
+                security_data.board = board;
+                security_data.candlesticks.insert(
+                    period,
+                    new_candlesticks(capacity, trade_sessions, candlesticks),
+                );
+            }
+            Record::Control(ControlRecord::UnsubscribeCandlesticks {
+                symbol,
+                period,
+                minutes,
+            }) => {
+                if let (Some(data), Some(period)) = (
+                    self.store.securities.get_mut(&symbol),
+                    replay_period(period, minutes),
+                ) {
+                    data.candlesticks.remove(&period);
+                }
+            }
+            Record::Control(ControlRecord::TradingDays {
+                market,
+                normal_days,
+                half_days,
+            }) => {
+                let Ok(market) = market.parse::<Market>() else {
+                    return;
+                };
+                let parse_days = |days: Vec<String>| {
+                    days.iter()
+                        .filter_map(|day| parse_date(day).ok())
+                        .collect::<HashSet<_>>()
+                };
+                self.trading_days
+                    .normal_days
+                    .insert(market, parse_days(normal_days));
+                self.trading_days
+                    .half_days
+                    .insert(market, parse_days(half_days));
+            }
         }
     }
 
@@ -809,7 +1291,7 @@ impl Core {
         tracing::info!(subscriptions = ?subscriptions, "resubscribe");
 
         for (flags, symbols) in subscriptions {
-            self.ws_cli
+            connected(&self.ws_cli)?
                 .request::<_, ()>(
                     cmd_code::SUBSCRIBE,
                     None,
@@ -866,7 +1348,7 @@ impl Core {
                     *period,
                     action,
                     self.push_candlestick_mode,
-                    &mut self.push_tx,
+                    &mut self.dispatcher,
                 );
             }
         }
@@ -904,7 +1386,7 @@ impl Core {
                     *period,
                     action,
                     self.push_candlestick_mode,
-                    &mut self.push_tx,
+                    &mut self.dispatcher,
                 );
             }
         }
@@ -947,7 +1429,7 @@ impl Core {
                     return Ok(());
                 }
 
-                let _ = self.push_tx.send(event);
+                self.dispatcher.send(event);
             }
             Err(err) => {
                 tracing::error!(error = %err, "failed to parse push message");
@@ -981,24 +1463,21 @@ impl Core {
     fn handle_get_realtime_depth(&self, symbol: String) -> SecurityDepth {
         let mut result = SecurityDepth::default();
         if let Some(data) = self.store.securities.get(&symbol) {
-            result.asks.clone_from(&data.asks);
-            result.bids.clone_from(&data.bids);
+            result.asks = data.order_book.asks().to_vec();
+            result.bids = data.order_book.bids().to_vec();
         }
         result
     }
 
     fn handle_get_realtime_trades(&self, symbol: String, count: usize) -> Vec<Trade> {
-        let mut res = Vec::new();
-
-        if let Some(data) = self.store.securities.get(&symbol) {
-            let trades = if data.trades.len() >= count {
-                &data.trades[data.trades.len() - count..]
-            } else {
-                &data.trades
-            };
-            res = trades.to_vec();
-        }
-        res
+        self.store
+            .securities
+            .get(&symbol)
+            .map(|data| {
+                let skip = data.trades.len().saturating_sub(count);
+                data.trades.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
     }
 
     fn handle_get_realtime_brokers(&self, symbol: String) -> SecurityBrokers {
@@ -1013,7 +1492,7 @@ impl Core {
     fn handle_get_realtime_candlesticks(
         &self,
         symbol: String,
-        period: Period,
+        period: CandlestickPeriod,
         count: usize,
     ) -> Vec<Candlestick> {
         self.store
@@ -1043,13 +1522,14 @@ enum MergeType {
 fn merge_type(
     board: SecurityBoard,
     trade_session: TradeSession,
-    period: Period,
+    period: CandlestickPeriod,
 ) -> Option<MergeType> {
-    use Period::*;
+    use CandlestickPeriod::*;
     use SecurityBoard::*;
     use TradeSession::*;
 
-    if !trade_session.is_intraday() && period >= Day {
+    let is_minute = !matches!(period, Day | Week | Month | Quarter | Year | Days(_));
+    if !trade_session.is_intraday() && !is_minute {
         return None;
     }
 
@@ -1066,6 +1546,60 @@ fn merge_type(
     })
 }
 
+/// Returns the [`Period`] value of the period in the recording, `0` for the
+/// custom periods
+fn record_period(period: CandlestickPeriod) -> i32 {
+    quote_period(period).unwrap_or(Period::UnknownPeriod).into()
+}
+
+/// Returns the number of minutes of the custom minute period in the recording
+fn record_minutes(period: CandlestickPeriod) -> Option<u16> {
+    match period {
+        CandlestickPeriod::Minutes(minutes) => Some(minutes),
+        _ => None,
+    }
+}
+
+fn replay_period(period: i32, minutes: Option<u16>) -> Option<CandlestickPeriod> {
+    match minutes {
+        Some(minutes) => Some(CandlestickPeriod::Minutes(minutes)),
+        None => Period::try_from(period)
+            .ok()
+            .filter(|period| *period != Period::UnknownPeriod)
+            .map(convert_period),
+    }
+}
+
+#[inline]
+fn connected(ws_cli: &Option<WsClient>) -> Result<&WsClient> {
+    ws_cli.as_ref().ok_or(Error::ReplayNotSupported)
+}
+
+fn new_candlesticks(
+    capacity: usize,
+    trade_sessions: TradeSessions,
+    candlesticks: Vec<Candlestick>,
+) -> Candlesticks {
+    let mut tails = HashMap::new();
+    for (index, candlestick) in candlesticks.iter().enumerate() {
+        tails.insert(
+            candlestick.trade_session,
+            TailCandlestick {
+                index,
+                candlestick: *candlestick,
+            },
+        );
+    }
+    let mut candlesticks = Candlesticks {
+        capacity,
+        trade_sessions,
+        candlesticks,
+        tails,
+    };
+    candlesticks.check_and_remove();
+    candlesticks
+}
+
 async fn fetch_trading_days(cli: &WsClient) -> Result<TradingDays> {
     let mut days = TradingDays::default();
     let begin_day = OffsetDateTime::now_utc().date() - time::Duration::days(5);
@@ -1113,10 +1647,10 @@ fn update_and_push_candlestick(
     candlesticks: &mut Candlesticks,
     ts: TradeSession,
     symbol: &str,
-    period: Period,
+    period: CandlestickPeriod,
     action: UpdateAction<Candlestick>,
     push_candlestick_mode: PushCandlestickMode,
-    tx: &mut mpsc::UnboundedSender<PushEvent>,
+    dispatcher: &mut PushDispatcher,
 ) {
     let mut push_candlesticks = Vec::new();
 
@@ -1173,6 +1707,11 @@ fn update_and_push_candlestick(
         UpdateAction::None => {}
     };
 
+    // the custom periods can not be pushed, they are read from the store
+    let Some(period) = quote_period(period) else {
+        return;
+    };
+
     for (candlestick, is_confirmed) in push_candlesticks {
         if candlesticks.trade_sessions.contains(ts) {
             tracing::info!(
@@ -1183,7 +1722,7 @@ fn update_and_push_candlestick(
                 trade_session = ?ts,
                 "push candlestick"
             );
-            let _ = tx.send(PushEvent {
+            dispatcher.send(PushEvent {
                 sequence: 0,
                 symbol: symbol.to_string(),
                 detail: PushEventDetail::Candlestick(PushCandlestick {
@@ -1196,7 +1735,7 @@ fn update_and_push_candlestick(
     }
 }
 
-fn parse_market_from_symbol(symbol: &str) -> Option<Market> {
+pub(crate) fn parse_market_from_symbol(symbol: &str) -> Option<Market> {
     let market = symbol.rfind('.').map(|idx| &symbol[idx + 1..])?;
     Some(match market {
         "US" => Market::US,
@@ -1219,34 +1758,27 @@ mod tests {
 
     #[test]
     fn test_merge_type() {
-        use Period::*;
+        use CandlestickPeriod::*;
         use SecurityBoard::*;
         use TradeSession::*;
 
         assert_eq!(merge_type(USDJI, Intraday, Day), Some(MergeType::QuoteDay));
         assert_eq!(merge_type(USDJI, Overnight, Day), None);
-        assert_eq!(
-            merge_type(USDJI, Intraday, OneMinute),
-            Some(MergeType::Quote)
-        );
-        assert_eq!(
-            merge_type(USDJI, Overnight, OneMinute),
-            Some(MergeType::Quote)
-        );
+        assert_eq!(merge_type(USDJI, Intraday, Min_1), Some(MergeType::Quote));
+        assert_eq!(merge_type(USDJI, Overnight, Min_1), Some(MergeType::Quote));
         assert_eq!(merge_type(USDJI, Intraday, Week), Some(MergeType::Quote));
         assert_eq!(merge_type(USDJI, Overnight, Week), None);
 
         assert_eq!(merge_type(USMain, Intraday, Day), Some(MergeType::QuoteDay));
         assert_eq!(merge_type(USMain, Overnight, Day), None);
+        assert_eq!(merge_type(USMain, Intraday, Min_1), Some(MergeType::Trade));
+        assert_eq!(merge_type(USMain, Overnight, Min_1), Some(MergeType::Trade));
+        assert_eq!(merge_type(USMain, Intraday, Week), Some(MergeType::Trade));
+        assert_eq!(merge_type(USMain, Overnight, Week), None);
         assert_eq!(
-            merge_type(USMain, Intraday, OneMinute),
-            Some(MergeType::Trade)
-        );
-        assert_eq!(
-            merge_type(USMain, Overnight, OneMinute),
+            merge_type(USMain, Overnight, Minutes(7)),
             Some(MergeType::Trade)
         );
-        assert_eq!(merge_type(USMain, Intraday, Week), Some(MergeType::Trade));
-        assert_eq!(merge_type(USMain, Overnight, Week), None);
+        assert_eq!(merge_type(USMain, Overnight, Days(2)), None);
     }
 }
@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
+use time::{OffsetDateTime, PrimitiveDateTime};
+use time_tz::{OffsetDateTimeExt, Tz};
+
+use crate::{
+    Error, Result,
+    quote::{
+        AdjustType, Candlestick, Period, QuoteContext, SecurityBoard, TradeSessions,
+        candlestick_cache::{self, CandlestickCache},
+        core::parse_market_from_symbol,
+        store::get_market,
+    },
+};
+
+/// The maximum number of candlesticks of a history candlesticks request
+const PAGE_SIZE: usize = 1000;
+
+struct RangeState {
+    ctx: QuoteContext,
+    symbol: String,
+    period: Period,
+    adjust_type: AdjustType,
+    trade_sessions: TradeSessions,
+    timezone: &'static Tz,
+    cursor: PrimitiveDateTime,
+    end: PrimitiveDateTime,
+    last: Option<OffsetDateTime>,
+    done: bool,
+}
+
+impl RangeState {
+    fn local_time(&self, t: OffsetDateTime) -> PrimitiveDateTime {
+        let t = t.to_timezone(self.timezone);
+        PrimitiveDateTime::new(t.date(), t.time())
+    }
+
+    async fn next_page(&mut self) -> Result<Vec<Candlestick>> {
+        let page = self
+            .ctx
+            .history_candlesticks_by_offset(
+                self.symbol.clone(),
+                self.period,
+                self.adjust_type,
+                true,
+                Some(self.cursor),
+                PAGE_SIZE,
+                self.trade_sessions,
+            )
+            .await?;
+        if page.len() < PAGE_SIZE {
+            self.done = true;
+        }
+
+        let mut candlesticks = Vec::with_capacity(page.len());
+        for candlestick in page {
+            let time = self.local_time(candlestick.timestamp);
+            if time > self.end {
+                self.done = true;
+                break;
+            }
+            // the pages overlap at the boundaries
+            if self.last.is_some_and(|last| candlestick.timestamp <= last) {
+                continue;
+            }
+            self.last = Some(candlestick.timestamp);
+            self.cursor = time;
+            candlesticks.push(candlestick);
+        }
+
+        if candlesticks.is_empty() {
+            self.done = true;
+        }
+        Ok(candlesticks)
+    }
+}
+
+/// Requests the candlesticks between `start` and `end` page by page
+pub(crate) fn fetch_range(
+    ctx: QuoteContext,
+    symbol: String,
+    period: Period,
+    adjust_type: AdjustType,
+    trade_sessions: TradeSessions,
+    start: PrimitiveDateTime,
+    end: PrimitiveDateTime,
+) -> impl Stream<Item = Result<Candlestick>> + Send + 'static {
+    let timezone = parse_market_from_symbol(&symbol)
+        .and_then(|market| get_market(market, SecurityBoard::Unknown))
+        .map(|market| market.timezone);
+    let state = match timezone {
+        Some(timezone) => Ok(RangeState {
+            ctx,
+            symbol,
+            period,
+            adjust_type,
+            trade_sessions,
+            timezone,
+            cursor: start,
+            end,
+            last: None,
+            done: start > end,
+        }),
+        None => Err(Error::UnknownMarket { symbol }),
+    };
+
+    stream::once(async move { state })
+        .map_ok(|state| {
+            stream::try_unfold(state, |mut state| async move {
+                if state.done {
+                    return Ok(None);
+                }
+                let candlesticks = state.next_page().await?;
+                Ok::<_, Error>(Some((candlesticks, state)))
+            })
+        })
+        .try_flatten()
+        .map_ok(|candlesticks| stream::iter(candlesticks.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Returns the candlesticks between `start` and `end`, from the candlestick
+/// cache if it is enabled
+pub(crate) fn candlesticks_range(
+    ctx: QuoteContext,
+    symbol: String,
+    period: Period,
+    adjust_type: AdjustType,
+    trade_sessions: TradeSessions,
+    start: PrimitiveDateTime,
+    end: PrimitiveDateTime,
+) -> impl Stream<Item = Result<Candlestick>> + Send + 'static {
+    let cache_dir = ctx
+        .candlestick_cache_dir()
+        .filter(|_| candlestick_cache::is_cacheable(period))
+        .map(Path::to_path_buf);
+    let Some(cache_dir) = cache_dir else {
+        return fetch_range(ctx, symbol, period, adjust_type, trade_sessions, start, end)
+            .left_stream();
+    };
+
+    stream::once(async move {
+        let market = parse_market_from_symbol(&symbol).ok_or_else(|| Error::UnknownMarket {
+            symbol: symbol.clone(),
+        })?;
+        let timezone = get_market(market, SecurityBoard::Unknown)
+            .ok_or_else(|| Error::UnknownMarket {
+                symbol: symbol.clone(),
+            })?
+            .timezone;
+        CandlestickCache {
+            ctx: &ctx,
+            dir: &cache_dir,
+            symbol: &symbol,
+            market,
+            timezone,
+            period,
+            adjust_type,
+            trade_sessions,
+        }
+        .range(start, end)
+        .await
+    })
+    .map_ok(|candlesticks| stream::iter(candlesticks.into_iter().map(Ok)))
+    .try_flatten()
+    .right_stream()
+}
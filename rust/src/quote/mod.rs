@@ -1,30 +1,48 @@
 //! Quote related types
 
+mod broker_queue;
 mod cache;
+mod candlestick_cache;
 mod cmd_code;
 mod context;
 mod core;
+mod history;
+mod option_chain;
+mod order_book;
+mod push_stream;
 mod push_types;
+mod recorder;
 mod store;
 mod sub_flags;
 mod types;
 mod utils;
 
+pub use broker_queue::{
+    BrokerActivity, BrokerMoved, BrokerQueueView, BrokerSide, QueuedBroker, SideActivity,
+};
 pub use context::QuoteContext;
+pub(crate) use core::parse_market_from_symbol;
 pub use longport_proto::quote::{AdjustType, Period, TradeStatus};
+pub use option_chain::{
+    OptionChainSnapshot, OptionChainStrike, OptionContract, OptionGreeks, SmilePoint,
+    VolatilitySurface, black_scholes, implied_volatility,
+};
+pub use order_book::{BookChanged, BookLevel, OrderBook};
+pub use push_stream::PushStream;
 pub use push_types::{
     PushBrokers, PushCandlestick, PushDepth, PushEvent, PushEventDetail, PushQuote, PushTrades,
 };
 pub use sub_flags::SubFlags;
 pub use types::{
-    Brokers, CalcIndex, Candlestick, CapitalDistribution, CapitalDistributionResponse,
-    CapitalFlowLine, Depth, DerivativeType, FilterWarrantExpiryDate, FilterWarrantInOutBoundsType,
-    Granularity, HistoryMarketTemperatureResponse, IntradayLine, IssuerInfo, MarketTemperature,
-    MarketTradingDays, MarketTradingSession, OptionDirection, OptionQuote, OptionType,
-    ParticipantInfo, PrePostQuote, QuotePackageDetail, RealtimeQuote, RequestCreateWatchlistGroup,
-    RequestUpdateWatchlistGroup, SecuritiesUpdateMode, Security, SecurityBoard, SecurityBrokers,
-    SecurityCalcIndex, SecurityDepth, SecurityListCategory, SecurityQuote, SecurityStaticInfo,
-    SortOrderType, StrikePriceInfo, Subscription, Trade, TradeDirection, TradeSession,
-    TradeSessions, TradingSessionInfo, WarrantInfo, WarrantQuote, WarrantSortBy, WarrantStatus,
-    WarrantType, WatchlistGroup, WatchlistSecurity,
+    AggregatePeriod, Brokers, CalcIndex, Candlestick, CapitalDistribution,
+    CapitalDistributionResponse, CapitalFlowLine, Depth, DerivativeType, FilterWarrantExpiryDate,
+    FilterWarrantInOutBoundsType, Granularity, HistoryMarketTemperatureResponse, IntradayLine,
+    IssuerInfo, MarketTemperature, MarketTradingDays, MarketTradingSession, OptionDirection,
+    OptionQuote, OptionType, ParticipantInfo, PrePostQuote, QuotePackageDetail, RealtimeQuote,
+    RequestCreateWatchlistGroup, RequestUpdateWatchlistGroup, SecuritiesUpdateMode, Security,
+    SecurityBoard, SecurityBrokers, SecurityCalcIndex, SecurityDepth, SecurityListCategory,
+    SecurityQuote, SecurityStaticInfo, SortOrderType, StrikePriceInfo, Subscription, Trade,
+    TradeDirection, TradeSession, TradeSessions, TradingSessionInfo, WarrantFilter, WarrantInfo,
+    WarrantList, WarrantQuote, WarrantSortBy, WarrantStatus, WarrantType, WatchlistGroup,
+    WatchlistSecurity,
 };
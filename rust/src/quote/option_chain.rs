@@ -0,0 +1,605 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::{
+    Decimal,
+    prelude::{FromPrimitive, ToPrimitive},
+};
+use time::Date;
+
+use crate::quote::{OptionDirection, OptionQuote, SecurityCalcIndex, StrikePriceInfo};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Black-Scholes price and Greeks of an option
+///
+/// `theta` is the change of the price per calendar day, `vega` and `rho` are
+/// the changes of the price per 1% change of the volatility and the interest
+/// rate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OptionGreeks {
+    /// Theoretical price
+    pub price: Decimal,
+    /// Delta
+    pub delta: Decimal,
+    /// Gamma
+    pub gamma: Decimal,
+    /// Theta
+    pub theta: Decimal,
+    /// Vega
+    pub vega: Decimal,
+    /// Rho
+    pub rho: Decimal,
+}
+
+/// Calculates the Black-Scholes price and Greeks of an European option
+///
+/// `years` is the time to expiry in years, `rate` and `volatility` are
+/// annualized, e.g. `0.05` for 5%. Returns `None` if the direction is unknown
+/// or any of the inputs is not positive.
+pub fn black_scholes(
+    direction: OptionDirection,
+    spot: Decimal,
+    strike: Decimal,
+    years: Decimal,
+    rate: Decimal,
+    volatility: Decimal,
+) -> Option<OptionGreeks> {
+    let greeks = Inputs::new(direction, spot, strike, years, rate)?.greeks(volatility.to_f64()?)?;
+    Some(OptionGreeks {
+        price: Decimal::from_f64(greeks.price)?,
+        delta: Decimal::from_f64(greeks.delta)?,
+        gamma: Decimal::from_f64(greeks.gamma)?,
+        theta: Decimal::from_f64(greeks.theta)?,
+        vega: Decimal::from_f64(greeks.vega)?,
+        rho: Decimal::from_f64(greeks.rho)?,
+    })
+}
+
+/// Solves the Black-Scholes implied volatility from the price of an European
+/// option
+///
+/// Returns `None` if the price is out of the no-arbitrage bounds.
+pub fn implied_volatility(
+    direction: OptionDirection,
+    spot: Decimal,
+    strike: Decimal,
+    years: Decimal,
+    rate: Decimal,
+    price: Decimal,
+) -> Option<Decimal> {
+    let inputs = Inputs::new(direction, spot, strike, years, rate)?;
+    let price = price.to_f64()?;
+    let (mut low, mut high) = (1e-4, 5.0);
+    if price <= inputs.greeks(low)?.price || price >= inputs.greeks(high)?.price {
+        return None;
+    }
+    // the price is increasing with the volatility
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if inputs.greeks(mid)?.price < price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low < 1e-8 {
+            break;
+        }
+    }
+    Decimal::from_f64((low + high) / 2.0).map(|value| value.round_dp(6))
+}
+
+struct Greeks {
+    price: f64,
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+}
+
+struct Inputs {
+    is_call: bool,
+    spot: f64,
+    strike: f64,
+    years: f64,
+    rate: f64,
+}
+
+impl Inputs {
+    fn new(
+        direction: OptionDirection,
+        spot: Decimal,
+        strike: Decimal,
+        years: Decimal,
+        rate: Decimal,
+    ) -> Option<Self> {
+        let is_call = match direction {
+            OptionDirection::Call => true,
+            OptionDirection::Put => false,
+            OptionDirection::Unknown => return None,
+        };
+        let inputs = Self {
+            is_call,
+            spot: spot.to_f64()?,
+            strike: strike.to_f64()?,
+            years: years.to_f64()?,
+            rate: rate.to_f64()?,
+        };
+        (inputs.spot > 0.0 && inputs.strike > 0.0 && inputs.years > 0.0).then_some(inputs)
+    }
+
+    fn greeks(&self, volatility: f64) -> Option<Greeks> {
+        if volatility <= 0.0 {
+            return None;
+        }
+        let Self {
+            spot,
+            strike,
+            years,
+            rate,
+            ..
+        } = *self;
+        let sqrt_years = years.sqrt();
+        let d1 = ((spot / strike).ln() + (rate + volatility * volatility / 2.0) * years)
+            / (volatility * sqrt_years);
+        let d2 = d1 - volatility * sqrt_years;
+        let discount = (-rate * years).exp();
+        let pdf = norm_pdf(d1);
+        let gamma = pdf / (spot * volatility * sqrt_years);
+        let vega = spot * pdf * sqrt_years / 100.0;
+        let decay = -spot * pdf * volatility / (2.0 * sqrt_years);
+
+        let greeks = if self.is_call {
+            Greeks {
+                price: spot * norm_cdf(d1) - strike * discount * norm_cdf(d2),
+                delta: norm_cdf(d1),
+                gamma,
+                theta: (decay - rate * strike * discount * norm_cdf(d2)) / DAYS_PER_YEAR,
+                vega,
+                rho: strike * years * discount * norm_cdf(d2) / 100.0,
+            }
+        } else {
+            Greeks {
+                price: strike * discount * norm_cdf(-d2) - spot * norm_cdf(-d1),
+                delta: norm_cdf(d1) - 1.0,
+                gamma,
+                theta: (decay + rate * strike * discount * norm_cdf(-d2)) / DAYS_PER_YEAR,
+                vega,
+                rho: -strike * years * discount * norm_cdf(-d2) / 100.0,
+            }
+        };
+        greeks.price.is_finite().then_some(greeks)
+    }
+}
+
+fn norm_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+fn norm_cdf(x: f64) -> f64 {
+    (1.0 + erf(x / std::f64::consts::SQRT_2)) / 2.0
+}
+
+// Abramowitz and Stegun 7.1.26, the maximum error is 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let y = 1.0
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+    y.copysign(x)
+}
+
+/// An option contract in the option chain
+#[derive(Debug, Clone)]
+pub struct OptionContract {
+    /// Quote of the option
+    pub quote: OptionQuote,
+    /// Calculated indexes of the option returned by the server
+    pub calc_index: Option<SecurityCalcIndex>,
+    /// Implied volatility solved from the latest price
+    pub implied_volatility: Option<Decimal>,
+    /// Greeks calculated with the implied volatility
+    pub greeks: Option<OptionGreeks>,
+}
+
+impl OptionContract {
+    /// Returns the implied volatility solved from the latest price, or the
+    /// one in the quote if it can not be solved
+    pub fn volatility(&self) -> Option<Decimal> {
+        self.implied_volatility.or_else(|| {
+            (self.quote.implied_volatility > Decimal::ZERO).then_some(self.quote.implied_volatility)
+        })
+    }
+
+    fn calculate(&mut self, spot: Decimal, years: Decimal, rate: Decimal) {
+        let quote = &self.quote;
+        self.implied_volatility = implied_volatility(
+            quote.direction,
+            spot,
+            quote.strike_price,
+            years,
+            rate,
+            quote.last_done,
+        );
+        self.greeks = self.volatility().and_then(|volatility| {
+            black_scholes(
+                quote.direction,
+                spot,
+                quote.strike_price,
+                years,
+                rate,
+                volatility,
+            )
+        });
+    }
+}
+
+/// The call and the put options at a strike price
+#[derive(Debug, Clone)]
+pub struct OptionChainStrike {
+    /// Strike price
+    pub strike_price: Decimal,
+    /// Is standard
+    pub standard: bool,
+    /// Call option
+    pub call: Option<OptionContract>,
+    /// Put option
+    pub put: Option<OptionContract>,
+}
+
+/// A point of the implied volatility smile
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SmilePoint {
+    /// Strike price
+    pub strike_price: Decimal,
+    /// Implied volatility of the call option
+    pub call: Option<Decimal>,
+    /// Implied volatility of the put option
+    pub put: Option<Decimal>,
+}
+
+impl SmilePoint {
+    /// Returns the implied volatility of the out-of-the-money option, which
+    /// is the more liquid one, at the underlying price
+    pub fn otm(&self, underlying_price: Decimal) -> Option<Decimal> {
+        if self.strike_price >= underlying_price {
+            self.call.or(self.put)
+        } else {
+            self.put.or(self.call)
+        }
+    }
+}
+
+/// Snapshot of the option chain of an expiry date
+///
+/// Returned by
+/// [`QuoteContext::option_chain_snapshot`](crate::quote::QuoteContext::option_chain_snapshot),
+/// the implied volatility and the Greeks are calculated locally with the
+/// Black-Scholes model, an interest rate of zero and no dividends. Use
+/// [`OptionChainSnapshot::risk_free_rate`] to recalculate them with another
+/// rate.
+#[derive(Debug, Clone)]
+pub struct OptionChainSnapshot {
+    /// Underlying security symbol
+    pub underlying_symbol: String,
+    /// Latest price of the underlying security
+    pub underlying_price: Decimal,
+    /// Expiry date
+    pub expiry_date: Date,
+    /// Time to expiry in years, at least one day
+    pub years_to_expiry: Decimal,
+    /// Annualized risk-free interest rate used to calculate the Greeks
+    pub risk_free_rate: Decimal,
+    /// Strikes in ascending order of the strike price
+    pub strikes: Vec<OptionChainStrike>,
+}
+
+impl OptionChainSnapshot {
+    /// Create a new `OptionChainSnapshot` by joining the quotes and the
+    /// calculated indexes of the options by strike
+    pub fn new(
+        underlying_symbol: impl Into<String>,
+        underlying_price: Decimal,
+        expiry_date: Date,
+        today: Date,
+        strikes: Vec<StrikePriceInfo>,
+        quotes: Vec<OptionQuote>,
+        calc_indexes: Vec<SecurityCalcIndex>,
+    ) -> Self {
+        let mut quotes = quotes
+            .into_iter()
+            .map(|quote| (quote.symbol.clone(), quote))
+            .collect::<HashMap<_, _>>();
+        let mut calc_indexes = calc_indexes
+            .into_iter()
+            .map(|index| (index.symbol.clone(), index))
+            .collect::<HashMap<_, _>>();
+        let mut contract = |symbol: &str| {
+            Some(OptionContract {
+                quote: quotes.remove(symbol)?,
+                calc_index: calc_indexes.remove(symbol),
+                implied_volatility: None,
+                greeks: None,
+            })
+        };
+
+        let mut strikes = strikes
+            .into_iter()
+            .map(|info| OptionChainStrike {
+                strike_price: info.price,
+                standard: info.standard,
+                call: contract(&info.call_symbol),
+                put: contract(&info.put_symbol),
+            })
+            .collect::<Vec<_>>();
+        strikes.sort_by_key(|strike| strike.strike_price);
+
+        let days = (expiry_date - today).whole_days().max(1);
+        Self {
+            underlying_symbol: underlying_symbol.into(),
+            underlying_price,
+            expiry_date,
+            years_to_expiry: Decimal::from(days) / Decimal::from(DAYS_PER_YEAR as i64),
+            risk_free_rate: Decimal::ZERO,
+            strikes,
+        }
+        .risk_free_rate(Decimal::ZERO)
+    }
+
+    /// Recalculates the implied volatility and the Greeks with the
+    /// annualized risk-free interest rate
+    #[must_use]
+    pub fn risk_free_rate(mut self, rate: Decimal) -> Self {
+        self.risk_free_rate = rate;
+        for strike in &mut self.strikes {
+            for contract in [&mut strike.call, &mut strike.put].into_iter().flatten() {
+                contract.calculate(self.underlying_price, self.years_to_expiry, rate);
+            }
+        }
+        self
+    }
+
+    /// Returns the strike closest to the underlying price
+    pub fn at_the_money(&self) -> Option<&OptionChainStrike> {
+        self.strikes
+            .iter()
+            .min_by_key(|strike| (strike.strike_price - self.underlying_price).abs())
+    }
+
+    /// Returns the implied volatility smile
+    pub fn smile(&self) -> Vec<SmilePoint> {
+        self.strikes
+            .iter()
+            .map(|strike| SmilePoint {
+                strike_price: strike.strike_price,
+                call: strike.call.as_ref().and_then(OptionContract::volatility),
+                put: strike.put.as_ref().and_then(OptionContract::volatility),
+            })
+            .collect()
+    }
+}
+
+/// Implied volatility surface built from the option chains of several expiry
+/// dates
+///
+/// Each point is the implied volatility of the out-of-the-money option, see
+/// [`SmilePoint::otm`].
+#[derive(Debug, Clone, Default)]
+pub struct VolatilitySurface {
+    smiles: BTreeMap<Date, Vec<(Decimal, Decimal)>>,
+}
+
+impl VolatilitySurface {
+    /// Create a new `VolatilitySurface` from the option chain snapshots
+    pub fn new<'a>(snapshots: impl IntoIterator<Item = &'a OptionChainSnapshot>) -> Self {
+        let mut surface = Self::default();
+        for snapshot in snapshots {
+            surface.insert(snapshot);
+        }
+        surface
+    }
+
+    /// Adds or replaces the smile of the expiry date of the snapshot
+    pub fn insert(&mut self, snapshot: &OptionChainSnapshot) {
+        let smile = snapshot
+            .smile()
+            .into_iter()
+            .filter_map(|point| Some((point.strike_price, point.otm(snapshot.underlying_price)?)))
+            .collect();
+        self.smiles.insert(snapshot.expiry_date, smile);
+    }
+
+    /// Returns the expiry dates in ascending order
+    pub fn expiry_dates(&self) -> impl Iterator<Item = Date> + '_ {
+        self.smiles.keys().copied()
+    }
+
+    /// Returns the `(strike price, implied volatility)` pairs of the expiry
+    /// date in ascending order of the strike price
+    pub fn smile(&self, expiry_date: Date) -> &[(Decimal, Decimal)] {
+        self.smiles
+            .get(&expiry_date)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the implied volatility at the strike price of the expiry date,
+    /// linearly interpolated between the neighbouring strikes
+    pub fn implied_volatility(&self, expiry_date: Date, strike_price: Decimal) -> Option<Decimal> {
+        let smile = self.smile(expiry_date);
+        match smile.binary_search_by_key(&strike_price, |(strike, _)| *strike) {
+            Ok(index) => Some(smile[index].1),
+            Err(index) if index > 0 && index < smile.len() => {
+                let (low_strike, low) = smile[index - 1];
+                let (high_strike, high) = smile[index];
+                Some(low + (high - low) * (strike_price - low_strike) / (high_strike - low_strike))
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::quote::{OptionType, TradeStatus};
+
+    fn option_quote(
+        symbol: &str,
+        direction: OptionDirection,
+        strike: i64,
+        price: Decimal,
+    ) -> OptionQuote {
+        OptionQuote {
+            symbol: symbol.to_string(),
+            last_done: price,
+            prev_close: price,
+            open: price,
+            high: price,
+            low: price,
+            timestamp: time::OffsetDateTime::UNIX_EPOCH,
+            volume: 0,
+            turnover: Decimal::ZERO,
+            trade_status: TradeStatus::Normal,
+            implied_volatility: decimal!(0.5),
+            open_interest: 0,
+            expiry_date: date!(2025 - 03 - 21),
+            strike_price: Decimal::from(strike),
+            contract_multiplier: Decimal::from(100),
+            contract_type: OptionType::American,
+            contract_size: Decimal::from(100),
+            direction,
+            historical_volatility: Decimal::ZERO,
+            underlying_symbol: "AAPL.US".to_string(),
+        }
+    }
+
+    #[test]
+    fn greeks() {
+        // S = 100, K = 100, T = 1, r = 5%, sigma = 20%
+        let call = black_scholes(
+            OptionDirection::Call,
+            decimal!(100),
+            decimal!(100),
+            decimal!(1),
+            decimal!(0.05),
+            decimal!(0.2),
+        )
+        .unwrap();
+        assert_eq!(call.price.round_dp(2), decimal!(10.45));
+        assert_eq!(call.delta.round_dp(4), decimal!(0.6368));
+        assert_eq!(call.gamma.round_dp(4), decimal!(0.0188));
+        assert_eq!(call.vega.round_dp(4), decimal!(0.3752));
+        assert_eq!(call.theta.round_dp(4), decimal!(-0.0176));
+        assert_eq!(call.rho.round_dp(4), decimal!(0.5323));
+
+        let put = black_scholes(
+            OptionDirection::Put,
+            decimal!(100),
+            decimal!(100),
+            decimal!(1),
+            decimal!(0.05),
+            decimal!(0.2),
+        )
+        .unwrap();
+        assert_eq!(put.price.round_dp(2), decimal!(5.57));
+        assert_eq!(put.delta.round_dp(4), decimal!(-0.3632));
+
+        let volatility = implied_volatility(
+            OptionDirection::Put,
+            decimal!(100),
+            decimal!(100),
+            decimal!(1),
+            decimal!(0.05),
+            put.price,
+        )
+        .unwrap();
+        assert_eq!(volatility.round_dp(4), decimal!(0.2));
+        assert_eq!(
+            implied_volatility(
+                OptionDirection::Call,
+                decimal!(100),
+                decimal!(100),
+                decimal!(1),
+                decimal!(0),
+                decimal!(200),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn snapshot() {
+        let strike = |price: i64| StrikePriceInfo {
+            price: Decimal::from(price),
+            call_symbol: format!("C{price}"),
+            put_symbol: format!("P{price}"),
+            standard: true,
+        };
+        let snapshot = OptionChainSnapshot::new(
+            "AAPL.US",
+            decimal!(100),
+            date!(2025 - 03 - 21),
+            date!(2025 - 02 - 19),
+            vec![strike(110), strike(100), strike(90)],
+            vec![
+                option_quote("C90", OptionDirection::Call, 90, decimal!(11)),
+                option_quote("P90", OptionDirection::Put, 90, decimal!(0.8)),
+                option_quote("C100", OptionDirection::Call, 100, decimal!(4)),
+                option_quote("P100", OptionDirection::Put, 100, decimal!(4)),
+                option_quote("C110", OptionDirection::Call, 110, decimal!(1)),
+            ],
+            vec![],
+        );
+        assert_eq!(
+            snapshot
+                .strikes
+                .iter()
+                .map(|strike| strike.strike_price)
+                .collect::<Vec<_>>(),
+            vec![decimal!(90), decimal!(100), decimal!(110)]
+        );
+        assert!(snapshot.strikes[2].put.is_none());
+        assert_eq!(snapshot.at_the_money().unwrap().strike_price, decimal!(100));
+
+        let atm = snapshot.at_the_money().unwrap();
+        let call = atm.call.as_ref().unwrap();
+        assert!(call.greeks.unwrap().delta > decimal!(0.5));
+        assert!(atm.put.as_ref().unwrap().greeks.unwrap().delta < decimal!(-0.4));
+        // with r = 0 the call and the put of the same price have the same
+        // implied volatility
+        let smile = snapshot.smile();
+        assert_eq!(smile[1].call, smile[1].put);
+        // the put of 110 is missing
+        assert_eq!(smile[2].put, None);
+
+        let surface = VolatilitySurface::new([&snapshot]);
+        assert_eq!(
+            surface.expiry_dates().collect::<Vec<_>>(),
+            vec![date!(2025 - 03 - 21)]
+        );
+        let low = surface
+            .implied_volatility(date!(2025 - 03 - 21), decimal!(90))
+            .unwrap();
+        let mid = surface
+            .implied_volatility(date!(2025 - 03 - 21), decimal!(100))
+            .unwrap();
+        assert_eq!(low, smile[0].put.unwrap());
+        assert_eq!(
+            surface.implied_volatility(date!(2025 - 03 - 21), decimal!(95)),
+            Some((low + mid) / decimal!(2))
+        );
+        assert_eq!(
+            surface.implied_volatility(date!(2025 - 03 - 21), decimal!(120)),
+            None
+        );
+
+        let snapshot = snapshot.risk_free_rate(decimal!(0.05));
+        let smile = snapshot.smile();
+        assert!(smile[1].call.unwrap() < smile[1].put.unwrap());
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::quote::{Depth, PushDepth};
+
+/// A price level changed by a depth push
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BookLevel {
+    /// Price
+    pub price: Decimal,
+    /// Volume, `0` means the level is removed
+    pub volume: i64,
+    /// Number of orders
+    pub order_num: i64,
+}
+
+/// The price levels changed by a depth push
+#[derive(Debug, Clone)]
+pub struct BookChanged {
+    /// Changed ask levels, in ascending order of price
+    pub asks: Vec<BookLevel>,
+    /// Changed bid levels, in descending order of price
+    pub bids: Vec<BookLevel>,
+    /// The order book after the push
+    pub book: OrderBook,
+}
+
+impl BookChanged {
+    /// Returns `true` if no level is changed
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.asks.is_empty() && self.bids.is_empty()
+    }
+}
+
+/// Order book built from the depth pushes
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    asks: Vec<Depth>,
+    bids: Vec<Depth>,
+}
+
+impl OrderBook {
+    /// Create a new `OrderBook` from the ask and bid depth
+    pub fn new(mut asks: Vec<Depth>, mut bids: Vec<Depth>) -> Self {
+        asks.sort_by_key(|depth| depth.position);
+        bids.sort_by_key(|depth| depth.position);
+        Self { asks, bids }
+    }
+
+    /// Ask depth, in ascending order of position
+    #[inline]
+    pub fn asks(&self) -> &[Depth] {
+        &self.asks
+    }
+
+    /// Bid depth, in ascending order of position
+    #[inline]
+    pub fn bids(&self) -> &[Depth] {
+        &self.bids
+    }
+
+    /// Returns the lowest ask level
+    pub fn best_ask(&self) -> Option<&Depth> {
+        levels(&self.asks).next()
+    }
+
+    /// Returns the highest bid level
+    pub fn best_bid(&self) -> Option<&Depth> {
+        levels(&self.bids).next()
+    }
+
+    /// Returns `best ask - best bid`
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price? - self.best_bid()?.price?)
+    }
+
+    /// Returns `(best ask + best bid) / 2`
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.best_ask()?.price? + self.best_bid()?.price?) / Decimal::TWO)
+    }
+
+    /// Returns the mid price weighted by the volume on the opposite side,
+    /// `(best ask * bid volume + best bid * ask volume) / (bid volume + ask
+    /// volume)`
+    pub fn microprice(&self) -> Option<Decimal> {
+        let (ask, bid) = (self.best_ask()?, self.best_bid()?);
+        let (ask_volume, bid_volume) = (Decimal::from(ask.volume), Decimal::from(bid.volume));
+        Some((ask.price? * bid_volume + bid.price? * ask_volume) / (ask_volume + bid_volume))
+    }
+
+    /// Returns the total volume of the best `levels` ask levels
+    pub fn ask_volume(&self, levels: usize) -> i64 {
+        self::levels(&self.asks)
+            .take(levels)
+            .map(|depth| depth.volume)
+            .sum()
+    }
+
+    /// Returns the total volume of the best `levels` bid levels
+    pub fn bid_volume(&self, levels: usize) -> i64 {
+        self::levels(&self.bids)
+            .take(levels)
+            .map(|depth| depth.volume)
+            .sum()
+    }
+
+    /// Returns `(bid volume - ask volume) / (bid volume + ask volume)` of the
+    /// best `levels` levels, from `-1` to `1`
+    pub fn imbalance(&self, levels: usize) -> Option<Decimal> {
+        let (ask_volume, bid_volume) = (self.ask_volume(levels), self.bid_volume(levels));
+        let total = ask_volume + bid_volume;
+        (total > 0).then(|| Decimal::from(bid_volume - ask_volume) / Decimal::from(total))
+    }
+
+    /// Applies a depth push, the levels in the push replace the levels at the
+    /// same positions
+    pub fn apply(&mut self, depth: &PushDepth) -> BookChanged {
+        let prev_asks = price_levels(&self.asks);
+        let prev_bids = price_levels(&self.bids);
+
+        self.merge(depth);
+
+        let mut asks = diff(&prev_asks, &price_levels(&self.asks));
+        asks.sort_by_key(|level| level.price);
+        let mut bids = diff(&prev_bids, &price_levels(&self.bids));
+        bids.sort_by_key(|level| std::cmp::Reverse(level.price));
+
+        BookChanged {
+            asks,
+            bids,
+            book: self.clone(),
+        }
+    }
+
+    pub(crate) fn merge(&mut self, depth: &PushDepth) {
+        replace(&mut self.asks, &depth.asks);
+        replace(&mut self.bids, &depth.bids);
+    }
+}
+
+fn levels(depth: &[Depth]) -> impl Iterator<Item = &Depth> {
+    depth
+        .iter()
+        .filter(|depth| depth.price.is_some() && depth.volume > 0)
+}
+
+fn price_levels(depth: &[Depth]) -> HashMap<Decimal, (i64, i64)> {
+    levels(depth)
+        .filter_map(|depth| Some((depth.price?, (depth.volume, depth.order_num))))
+        .collect()
+}
+
+fn replace(levels: &mut Vec<Depth>, others: &[Depth]) {
+    for depth in others {
+        match levels.binary_search_by_key(&depth.position, |depth| depth.position) {
+            Ok(index) => levels[index] = depth.clone(),
+            Err(index) => levels.insert(index, depth.clone()),
+        }
+    }
+}
+
+fn diff(
+    prev: &HashMap<Decimal, (i64, i64)>,
+    current: &HashMap<Decimal, (i64, i64)>,
+) -> Vec<BookLevel> {
+    let changed = current
+        .iter()
+        .filter(|(price, level)| prev.get(price) != Some(level))
+        .map(|(price, (volume, order_num))| BookLevel {
+            price: *price,
+            volume: *volume,
+            order_num: *order_num,
+        });
+    let removed = prev
+        .keys()
+        .filter(|price| !current.contains_key(price))
+        .map(|price| BookLevel {
+            price: *price,
+            volume: 0,
+            order_num: 0,
+        });
+    changed.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(position: i32, price: i64, volume: i64) -> Depth {
+        Depth {
+            position,
+            price: Some(Decimal::from(price)),
+            volume,
+            order_num: 1,
+        }
+    }
+
+    fn level(price: i64, volume: i64) -> BookLevel {
+        BookLevel {
+            price: Decimal::from(price),
+            volume,
+            order_num: if volume > 0 { 1 } else { 0 },
+        }
+    }
+
+    #[test]
+    fn analytics() {
+        let book = OrderBook::new(
+            vec![depth(2, 102, 300), depth(1, 101, 100)],
+            vec![depth(1, 100, 300), depth(2, 99, 500)],
+        );
+        assert_eq!(book.best_ask().unwrap().price, Some(Decimal::from(101)));
+        assert_eq!(book.spread(), Some(Decimal::ONE));
+        assert_eq!(book.mid_price(), Some("100.5".parse().unwrap()));
+        // (101 * 300 + 100 * 100) / 400
+        assert_eq!(book.microprice(), Some("100.75".parse().unwrap()));
+        assert_eq!(book.ask_volume(1), 100);
+        assert_eq!(book.ask_volume(10), 400);
+        assert_eq!(book.bid_volume(2), 800);
+        assert_eq!(
+            book.imbalance(2),
+            Some("0.3333333333333333333333333333".parse().unwrap())
+        );
+        assert_eq!(OrderBook::default().imbalance(5), None);
+        assert_eq!(OrderBook::default().mid_price(), None);
+    }
+
+    #[test]
+    fn apply() {
+        let mut book = OrderBook::default();
+        let changed = book.apply(&PushDepth {
+            asks: vec![depth(1, 101, 100), depth(2, 102, 300)],
+            bids: vec![depth(1, 100, 300)],
+        });
+        assert_eq!(changed.asks, vec![level(101, 100), level(102, 300)]);
+        assert_eq!(changed.bids, vec![level(100, 300)]);
+
+        // the best ask is taken, the levels are shifted
+        let changed = book.apply(&PushDepth {
+            asks: vec![depth(1, 102, 300), depth(2, 103, 200)],
+            bids: vec![depth(1, 100, 300), depth(2, 99, 100)],
+        });
+        assert_eq!(changed.asks, vec![level(101, 0), level(103, 200)]);
+        assert_eq!(changed.bids, vec![level(99, 100)]);
+        assert_eq!(
+            changed.book.best_ask().unwrap().price,
+            Some(Decimal::from(102))
+        );
+
+        // partial update
+        let changed = book.apply(&PushDepth {
+            asks: vec![depth(2, 103, 250)],
+            bids: vec![],
+        });
+        assert_eq!(changed.asks, vec![level(103, 250)]);
+        assert!(changed.bids.is_empty());
+        assert_eq!(book.ask_volume(2), 550);
+
+        let changed = book.apply(&PushDepth {
+            asks: vec![depth(2, 103, 250)],
+            bids: vec![],
+        });
+        assert!(changed.is_empty());
+    }
+}
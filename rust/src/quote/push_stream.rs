@@ -0,0 +1,143 @@
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use longport_proto::quote::Period;
+
+use crate::{
+    push_channel::{PushReceiver, PushSender},
+    quote::{PushEvent, PushEventDetail},
+};
+
+/// The kind of push events delivered to a [`PushStream`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum PushStreamKind {
+    Quote,
+    Depth,
+    Brokers,
+    Trades,
+    Candlestick(Period),
+}
+
+impl PushStreamKind {
+    fn matches(&self, detail: &PushEventDetail) -> bool {
+        match (self, detail) {
+            (PushStreamKind::Quote, PushEventDetail::Quote(_))
+            | (PushStreamKind::Depth, PushEventDetail::Depth(_))
+            | (PushStreamKind::Brokers, PushEventDetail::Brokers(_))
+            | (PushStreamKind::Trades, PushEventDetail::Trade(_)) => true,
+            (PushStreamKind::Candlestick(period), PushEventDetail::Candlestick(candlestick)) => {
+                candlestick.period == *period
+            }
+            _ => false,
+        }
+    }
+}
+
+struct StreamSender {
+    kind: PushStreamKind,
+    /// Empty means all symbols
+    symbols: HashSet<String>,
+    tx: PushSender<PushEvent>,
+}
+
+/// Sends the push events to the receiver returned by `QuoteContext::try_new`
+/// and to the typed push streams
+pub(crate) struct PushDispatcher {
+    push_tx: Option<PushSender<PushEvent>>,
+    streams: Vec<StreamSender>,
+}
+
+impl PushDispatcher {
+    pub(crate) fn new(push_tx: PushSender<PushEvent>) -> Self {
+        Self {
+            push_tx: Some(push_tx),
+            streams: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add_stream(
+        &mut self,
+        kind: PushStreamKind,
+        symbols: HashSet<String>,
+        tx: PushSender<PushEvent>,
+    ) {
+        self.streams.push(StreamSender { kind, symbols, tx });
+    }
+
+    pub(crate) fn send(&mut self, event: PushEvent) {
+        self.streams.retain(|stream| {
+            if !stream.kind.matches(&event.detail)
+                || (!stream.symbols.is_empty() && !stream.symbols.contains(&event.symbol))
+            {
+                return !stream.tx.is_closed();
+            }
+            stream.tx.send(event.clone())
+        });
+        if let Some(push_tx) = &self.push_tx {
+            push_tx.send(event);
+        }
+    }
+
+    /// Waits until the receiver and all the streams have room for new events
+    pub(crate) async fn ready(&self) {
+        if let Some(push_tx) = &self.push_tx {
+            push_tx.ready().await;
+        }
+        for stream in &self.streams {
+            stream.tx.ready().await;
+        }
+    }
+
+    /// Closes the receiver and all the streams
+    pub(crate) fn close(&mut self) {
+        self.push_tx = None;
+        self.streams.clear();
+    }
+}
+
+/// A stream of one kind of push events
+///
+/// Created by [`QuoteContext::quote_stream`](crate::quote::QuoteContext::quote_stream)
+/// and the similar methods. The stream ends when the `QuoteContext` is
+/// closed.
+///
+/// The stream is bounded by [`Config::push_channel`](crate::Config::push_channel)
+/// the same way as the receiver returned by `QuoteContext::try_new`.
+pub struct PushStream<T> {
+    rx: PushReceiver<PushEvent>,
+    map: Box<dyn FnMut(PushEvent) -> Option<T> + Send + Sync>,
+}
+
+impl<T> PushStream<T> {
+    pub(crate) fn new<F>(rx: PushReceiver<PushEvent>, map: F) -> Self
+    where
+        F: FnMut(PushEvent) -> Option<T> + Send + Sync + 'static,
+    {
+        Self {
+            rx,
+            map: Box::new(map),
+        }
+    }
+}
+
+impl<T> Stream for PushStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.rx.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(item) = (self.map)(event) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
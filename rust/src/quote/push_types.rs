@@ -101,7 +101,7 @@ impl longport_candlesticks::QuoteType for PushQuote {
 }
 
 /// Depth message
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PushDepth {
     /// Ask depth
     pub asks: Vec<Depth>,
@@ -110,7 +110,7 @@ pub struct PushDepth {
 }
 
 /// Brokers message
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PushBrokers {
     /// Ask brokers
     pub ask_brokers: Vec<Brokers>,
@@ -119,7 +119,7 @@ pub struct PushBrokers {
 }
 
 /// Trades message
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PushTrades {
     /// Trades data
     pub trades: Vec<Trade>,
@@ -137,7 +137,7 @@ pub struct PushCandlestick {
 }
 
 /// Push event detail
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum PushEventDetail {
     /// Quote
     Quote(PushQuote),
@@ -152,7 +152,7 @@ pub enum PushEventDetail {
 }
 
 /// Push event
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PushEvent {
     #[allow(dead_code)]
     pub(crate) sequence: i64,
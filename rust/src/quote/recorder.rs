@@ -0,0 +1,255 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    sync::mpsc::{self as std_mpsc, RecvTimeoutError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{Dispatch, instrument::WithSubscriber};
+
+use crate::quote::{Candlestick, SecurityBoard};
+
+const MAGIC: &[u8; 8] = b"LPQREC01";
+
+const KIND_PUSH: u8 = 0;
+const KIND_CONTROL: u8 = 1;
+
+/// The buffered records are flushed to the file at least once per interval
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of the records read ahead of the replay
+const READ_AHEAD: usize = 1024;
+
+/// A record of the quote push stream
+///
+/// Besides the raw push frames, the changes of the subscriptions and the
+/// initial candlesticks are recorded, so that the replay goes through the same
+/// filter and merge logic as the live session.
+#[derive(Debug)]
+pub(crate) enum Record {
+    Push { command_code: u8, body: Vec<u8> },
+    Control(ControlRecord),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ControlRecord {
+    Subscribe {
+        symbols: Vec<String>,
+        sub_types: u8,
+    },
+    Unsubscribe {
+        symbols: Vec<String>,
+        sub_types: u8,
+    },
+    SubscribeCandlesticks {
+        symbol: String,
+        period: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minutes: Option<u16>,
+        trade_sessions: i32,
+        board: SecurityBoard,
+        candlesticks: Vec<Candlestick>,
+    },
+    UnsubscribeCandlesticks {
+        symbol: String,
+        period: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minutes: Option<u16>,
+    },
+    TradingDays {
+        market: String,
+        normal_days: Vec<String>,
+        half_days: Vec<String>,
+    },
+}
+
+/// Writes records to a file
+///
+/// Each frame is `timestamp (u64, microseconds) | kind (u8) | length (u32) |
+/// payload`, all integers are big-endian.
+///
+/// The records are timestamped when they are queued and written by a
+/// blocking task, so the file I/O never stalls the core. The task flushes the
+/// file every [`FLUSH_INTERVAL`] and when the recorder is dropped.
+pub(crate) struct Recorder {
+    tx: std_mpsc::Sender<(u64, Record)>,
+}
+
+impl Recorder {
+    /// Creates the file and starts the writer task, must be called within a
+    /// Tokio runtime
+    pub(crate) fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.flush()?;
+
+        let (tx, rx) = std_mpsc::channel();
+        let dispatch = tracing::dispatcher::get_default(Dispatch::clone);
+        tokio::task::spawn_blocking(move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                if let Err(err) = write_records(writer, rx) {
+                    tracing::error!(error = %err, "failed to write quote recording, stop recording");
+                }
+            })
+        });
+        Ok(Self { tx })
+    }
+
+    /// Queues the record, returns `false` if the writer task has stopped
+    pub(crate) fn write(&self, record: Record) -> bool {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        self.tx.send((timestamp, record)).is_ok()
+    }
+}
+
+fn write_records(
+    mut writer: BufWriter<File>,
+    rx: std_mpsc::Receiver<(u64, Record)>,
+) -> io::Result<()> {
+    let mut flushed_at = Instant::now();
+
+    loop {
+        match rx.recv_timeout(FLUSH_INTERVAL) {
+            Ok((timestamp, record)) => {
+                write_record(&mut writer, timestamp, &record)?;
+                if flushed_at.elapsed() < FLUSH_INTERVAL {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return writer.flush(),
+        }
+        writer.flush()?;
+        flushed_at = Instant::now();
+    }
+}
+
+fn write_record(writer: &mut impl Write, timestamp: u64, record: &Record) -> io::Result<()> {
+    let (kind, payload) = match record {
+        Record::Push { command_code, body } => {
+            let mut payload = Vec::with_capacity(body.len() + 1);
+            payload.push(*command_code);
+            payload.extend_from_slice(body);
+            (KIND_PUSH, payload)
+        }
+        Record::Control(control) => (KIND_CONTROL, serde_json::to_vec(control)?),
+    };
+
+    writer.write_all(&timestamp.to_be_bytes())?;
+    writer.write_all(&[kind])?;
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)
+}
+
+/// Reads records from a file
+pub(crate) struct RecordReader {
+    reader: BufReader<File>,
+}
+
+impl RecordReader {
+    pub(crate) fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "not a quote recording file",
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Returns the next record and its timestamp in microseconds, or `None` at
+    /// the end of the file
+    pub(crate) fn read(&mut self) -> io::Result<Option<(u64, Record)>> {
+        let mut timestamp = [0; 8];
+        match self.reader.read_exact(&mut timestamp) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let mut kind = [0; 1];
+        self.reader.read_exact(&mut kind)?;
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len)?;
+        let mut payload = vec![0; u32::from_be_bytes(len) as usize];
+        self.reader.read_exact(&mut payload)?;
+
+        let record = match kind[0] {
+            KIND_PUSH if !payload.is_empty() => Record::Push {
+                command_code: payload[0],
+                body: payload.split_off(1),
+            },
+            KIND_CONTROL => Record::Control(serde_json::from_slice(&payload)?),
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid record kind: {}", kind[0]),
+                ));
+            }
+        };
+        Ok(Some((u64::from_be_bytes(timestamp), record)))
+    }
+}
+
+/// Reads all records from `reader` in a blocking task and sends them to `tx`
+/// from an async task, keeping the original intervals divided by `speed`
+///
+/// A `speed` that is not a positive number replays without any delay. Must be
+/// called within a Tokio runtime.
+pub(crate) fn replay(mut reader: RecordReader, speed: f64, tx: mpsc::UnboundedSender<Record>) {
+    let (read_tx, mut read_rx) = mpsc::channel(READ_AHEAD);
+    let dispatch = tracing::dispatcher::get_default(Dispatch::clone);
+
+    tokio::task::spawn_blocking({
+        let dispatch = dispatch.clone();
+        move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                loop {
+                    match reader.read() {
+                        Ok(Some(item)) => {
+                            if read_tx.blocking_send(item).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(err) => {
+                            tracing::error!(error = %err, "failed to read quote recording");
+                            break;
+                        }
+                    }
+                }
+            })
+        }
+    });
+
+    tokio::spawn(
+        async move {
+            let started_at = tokio::time::Instant::now();
+            let mut first_timestamp = None;
+
+            while let Some((timestamp, record)) = read_rx.recv().await {
+                if speed.is_finite() && speed > 0.0 {
+                    let first_timestamp = *first_timestamp.get_or_insert(timestamp);
+                    let offset = Duration::from_micros(timestamp.saturating_sub(first_timestamp));
+                    tokio::time::sleep_until(started_at + offset.div_f64(speed)).await;
+                }
+
+                if tx.send(record).is_err() {
+                    return;
+                }
+            }
+
+            tracing::info!("quote recording replayed");
+        }
+        .with_subscriber(dispatch),
+    );
+}
@@ -1,19 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-use longport_candlesticks::{Days, UpdateAction, UpdateFields};
+use longport_candlesticks::{Days, Period as CandlestickPeriod, UpdateAction, UpdateFields};
 use longport_proto::quote::Period;
 
 use crate::{
-    Market,
+    Config, Market,
     quote::{
-        Brokers, Candlestick, Depth, PushBrokers, PushDepth, PushEvent, PushTrades, SecurityBoard,
-        Trade, TradeDirection, TradeSession, TradeSessions,
+        AggregatePeriod, Brokers, Candlestick, OrderBook, PushBrokers, PushDepth, PushEvent,
+        PushTrades, SecurityBoard, Trade, TradeDirection, TradeSession, TradeSessions,
         push_types::{PushEventDetail, PushQuote},
     },
 };
 
-const MAX_TRADES: usize = 500;
-const MAX_CANDLESTICKS: usize = 500;
+const DEFAULT_TRADES_CAPACITY: usize = 500;
+const DEFAULT_CANDLESTICKS_CAPACITY: usize = 500;
 
 macro_rules! merge_decimal {
     ($prev:expr, $new:expr, $field:ident) => {
@@ -43,6 +43,7 @@ pub(crate) struct TailCandlestick {
 
 #[derive(Debug)]
 pub(crate) struct Candlesticks {
+    pub(crate) capacity: usize,
     pub(crate) trade_sessions: TradeSessions,
     pub(crate) candlesticks: Vec<Candlestick>,
     pub(crate) tails: HashMap<TradeSession, TailCandlestick>,
@@ -76,7 +77,7 @@ impl Candlesticks {
         market_type: Market,
         half_days: H,
         board: SecurityBoard,
-        period: Period,
+        period: CandlestickPeriod,
         trade: &Trade,
     ) -> UpdateAction<Candlestick>
     where
@@ -86,7 +87,6 @@ impl Candlesticks {
             return UpdateAction::None;
         };
         let ts = trade.trade_session;
-        let period = convert_period(period);
 
         let trade_type = trade.trade_type.as_str();
         let update_fields = match market_type {
@@ -156,7 +156,7 @@ impl Candlesticks {
         market_type: Market,
         half_days: H,
         board: SecurityBoard,
-        period: Period,
+        period: CandlestickPeriod,
         push_quote: &PushQuote,
     ) -> UpdateAction<Candlestick>
     where
@@ -166,7 +166,6 @@ impl Candlesticks {
             return UpdateAction::None;
         };
         let ts = push_quote.trade_session;
-        let period = convert_period(period);
         market.merge_trade(
             half_days,
             period,
@@ -184,11 +183,11 @@ impl Candlesticks {
     }
 
     pub(crate) fn check_and_remove(&mut self) {
-        if self.candlesticks.len() <= MAX_CANDLESTICKS * 2 {
+        if self.candlesticks.len() <= self.capacity {
             return;
         }
 
-        let remove_count = self.candlesticks.len() - MAX_CANDLESTICKS;
+        let remove_count = self.candlesticks.len() - self.capacity;
         let mut remove_tails = vec![];
 
         for (ts, tail) in &mut self.tails {
@@ -211,24 +210,37 @@ impl Candlesticks {
 pub(crate) struct SecuritiesData {
     pub(crate) quote: PushQuote,
 
-    pub(crate) asks: Vec<Depth>,
-    pub(crate) bids: Vec<Depth>,
+    pub(crate) order_book: OrderBook,
 
     pub(crate) ask_brokers: Vec<Brokers>,
     pub(crate) bid_brokers: Vec<Brokers>,
 
-    pub(crate) trades: Vec<Trade>,
+    pub(crate) trades: VecDeque<Trade>,
 
     pub(crate) board: SecurityBoard,
-    pub(crate) candlesticks: HashMap<Period, Candlesticks>,
+    pub(crate) candlesticks: HashMap<CandlestickPeriod, Candlesticks>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(crate) struct Store {
     pub(crate) securities: HashMap<String, SecuritiesData>,
+    pub(crate) trades_capacity: usize,
+    pub(crate) candlesticks_capacity: usize,
 }
 
 impl Store {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            securities: HashMap::new(),
+            trades_capacity: config
+                .realtime_trades_capacity
+                .unwrap_or(DEFAULT_TRADES_CAPACITY),
+            candlesticks_capacity: config
+                .realtime_candlesticks_capacity
+                .unwrap_or(DEFAULT_CANDLESTICKS_CAPACITY),
+        }
+    }
+
     pub(crate) fn handle_push(&mut self, event: &mut PushEvent) {
         let data = self.securities.entry(event.symbol.clone()).or_default();
 
@@ -236,7 +248,7 @@ impl Store {
             PushEventDetail::Quote(quote) => merge_quote(data, quote),
             PushEventDetail::Depth(depth) => merge_depth(data, depth),
             PushEventDetail::Brokers(brokers) => merge_brokers(data, brokers),
-            PushEventDetail::Trade(trade) => merge_trades(data, trade),
+            PushEventDetail::Trade(trade) => merge_trades(data, trade, self.trades_capacity),
             PushEventDetail::Candlestick(_) => unreachable!(),
         }
     }
@@ -262,8 +274,7 @@ fn merge_quote(data: &mut SecuritiesData, quote: &mut PushQuote) {
 }
 
 fn merge_depth(data: &mut SecuritiesData, depth: &PushDepth) {
-    replace(&mut data.asks, depth.asks.clone(), |v| v.position);
-    replace(&mut data.bids, depth.bids.clone(), |v| v.position);
+    data.order_book.merge(depth);
 }
 
 fn merge_brokers(data: &mut SecuritiesData, brokers: &PushBrokers) {
@@ -275,10 +286,10 @@ fn merge_brokers(data: &mut SecuritiesData, brokers: &PushBrokers) {
     });
 }
 
-fn merge_trades(data: &mut SecuritiesData, trades: &PushTrades) {
-    data.trades.extend(trades.trades.clone());
-    if data.trades.len() > MAX_TRADES * 2 {
-        data.trades.drain(..MAX_TRADES);
+fn merge_trades(data: &mut SecuritiesData, trades: &PushTrades, capacity: usize) {
+    data.trades.extend(trades.trades.iter().cloned());
+    if data.trades.len() > capacity {
+        data.trades.drain(..data.trades.len() - capacity);
     }
 }
 
@@ -311,7 +322,61 @@ pub(crate) fn get_market(
     })
 }
 
-fn convert_period(period: Period) -> longport_candlesticks::Period {
+impl From<AggregatePeriod> for CandlestickPeriod {
+    fn from(period: AggregatePeriod) -> Self {
+        use longport_candlesticks::Period::*;
+
+        match period {
+            AggregatePeriod::Period(period) => convert_period(period),
+            AggregatePeriod::Minutes(minutes) => match minutes {
+                0 | 1 => Min_1,
+                2 => Min_2,
+                3 => Min_3,
+                5 => Min_5,
+                10 => Min_10,
+                15 => Min_15,
+                20 => Min_20,
+                30 => Min_30,
+                45 => Min_45,
+                60 => Min_60,
+                120 => Min_120,
+                180 => Min_180,
+                240 => Min_240,
+                minutes => Minutes(minutes),
+            },
+        }
+    }
+}
+
+/// Returns the [`Period`] of the candlesticks period, `None` for the custom
+/// periods
+pub(crate) fn quote_period(period: CandlestickPeriod) -> Option<Period> {
+    use longport_candlesticks::Period::*;
+
+    Some(match period {
+        Min_1 => Period::OneMinute,
+        Min_2 => Period::TwoMinute,
+        Min_3 => Period::ThreeMinute,
+        Min_5 => Period::FiveMinute,
+        Min_10 => Period::TenMinute,
+        Min_15 => Period::FifteenMinute,
+        Min_20 => Period::TwentyMinute,
+        Min_30 => Period::ThirtyMinute,
+        Min_45 => Period::FortyFiveMinute,
+        Min_60 => Period::SixtyMinute,
+        Min_120 => Period::TwoHour,
+        Min_180 => Period::ThreeHour,
+        Min_240 => Period::FourHour,
+        Day => Period::Day,
+        Week => Period::Week,
+        Month => Period::Month,
+        Quarter => Period::Quarter,
+        Year => Period::Year,
+        Minutes(_) | Days(_) => return None,
+    })
+}
+
+pub(crate) fn convert_period(period: Period) -> CandlestickPeriod {
     use longport_candlesticks::Period::*;
 
     match period {
@@ -923,6 +923,27 @@ impl longport_candlesticks::CandlestickType for Candlestick {
     }
 }
 
+/// Period of the candlesticks built locally by
+/// [`QuoteContext::aggregate_candlesticks`](crate::quote::QuoteContext::aggregate_candlesticks)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AggregatePeriod {
+    /// A period of [`Period`]
+    Period(Period),
+    /// Any number of minutes (at least `1`), aligned to the start of each
+    /// trade session
+    ///
+    /// The numbers of minutes of [`Period`] are the same as the periods of
+    /// [`Period`], e.g. `Minutes(5)` is `Period::FiveMinute`.
+    Minutes(u16),
+}
+
+impl From<Period> for AggregatePeriod {
+    #[inline]
+    fn from(period: Period) -> Self {
+        AggregatePeriod::Period(period)
+    }
+}
+
 /// Strike price info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrikePriceInfo {
@@ -1148,21 +1169,21 @@ impl TryFrom<quote::FilterWarrant> for WarrantInfo {
                 turnover: info.turnover.parse().unwrap_or_default(),
                 expiry_date: parse_date(&info.expiry_date)
                     .map_err(|err| Error::parse_field_error("expiry_date", err))?,
-                strike_price: Some(info.last_done.parse().unwrap_or_default()),
+                strike_price: Some(info.strike_price.parse().unwrap_or_default()),
                 upper_strike_price: None,
                 lower_strike_price: None,
                 outstanding_qty: info.outstanding_qty.parse().unwrap_or_default(),
                 outstanding_ratio: info.outstanding_ratio.parse().unwrap_or_default(),
                 premium: info.premium.parse().unwrap_or_default(),
-                itm_otm: Some(info.last_done.parse().unwrap_or_default()),
-                implied_volatility: Some(info.last_done.parse().unwrap_or_default()),
-                delta: Some(info.last_done.parse().unwrap_or_default()),
+                itm_otm: Some(info.itm_otm.parse().unwrap_or_default()),
+                implied_volatility: Some(info.implied_volatility.parse().unwrap_or_default()),
+                delta: Some(info.delta.parse().unwrap_or_default()),
                 call_price: None,
                 to_call_price: None,
-                effective_leverage: Some(info.last_done.parse().unwrap_or_default()),
+                effective_leverage: Some(info.effective_leverage.parse().unwrap_or_default()),
                 leverage_ratio: info.leverage_ratio.parse().unwrap_or_default(),
-                conversion_ratio: Some(info.last_done.parse().unwrap_or_default()),
-                balance_point: Some(info.last_done.parse().unwrap_or_default()),
+                conversion_ratio: Some(info.conversion_ratio.parse().unwrap_or_default()),
+                balance_point: Some(info.balance_point.parse().unwrap_or_default()),
                 status: WarrantStatus::try_from(info.status)
                     .map_err(|err| Error::parse_field_error("state", err))?,
             }),
@@ -1177,21 +1198,21 @@ impl TryFrom<quote::FilterWarrant> for WarrantInfo {
                 turnover: info.turnover.parse().unwrap_or_default(),
                 expiry_date: parse_date(&info.expiry_date)
                     .map_err(|err| Error::parse_field_error("expiry_date", err))?,
-                strike_price: Some(info.last_done.parse().unwrap_or_default()),
+                strike_price: Some(info.strike_price.parse().unwrap_or_default()),
                 upper_strike_price: None,
                 lower_strike_price: None,
                 outstanding_qty: info.outstanding_qty.parse().unwrap_or_default(),
                 outstanding_ratio: info.outstanding_ratio.parse().unwrap_or_default(),
                 premium: info.premium.parse().unwrap_or_default(),
-                itm_otm: Some(info.last_done.parse().unwrap_or_default()),
+                itm_otm: Some(info.itm_otm.parse().unwrap_or_default()),
                 implied_volatility: None,
                 delta: None,
                 call_price: Some(info.call_price.parse().unwrap_or_default()),
                 to_call_price: Some(info.to_call_price.parse().unwrap_or_default()),
                 effective_leverage: None,
                 leverage_ratio: info.leverage_ratio.parse().unwrap_or_default(),
-                conversion_ratio: Some(info.last_done.parse().unwrap_or_default()),
-                balance_point: Some(info.last_done.parse().unwrap_or_default()),
+                conversion_ratio: Some(info.conversion_ratio.parse().unwrap_or_default()),
+                balance_point: Some(info.balance_point.parse().unwrap_or_default()),
                 status: WarrantStatus::try_from(info.status)
                     .map_err(|err| Error::parse_field_error("state", err))?,
             }),
@@ -1228,6 +1249,152 @@ impl TryFrom<quote::FilterWarrant> for WarrantInfo {
     }
 }
 
+/// Options for the warrant screener
+#[derive(Debug, Clone)]
+pub struct WarrantFilter {
+    pub(crate) symbol: String,
+    pub(crate) sort_by: WarrantSortBy,
+    pub(crate) sort_order: SortOrderType,
+    pub(crate) offset: i32,
+    pub(crate) count: i32,
+    pub(crate) warrant_type: Vec<WarrantType>,
+    pub(crate) issuer: Vec<i32>,
+    pub(crate) expiry_date: Vec<FilterWarrantExpiryDate>,
+    pub(crate) price_type: Vec<FilterWarrantInOutBoundsType>,
+    pub(crate) status: Vec<WarrantStatus>,
+}
+
+impl WarrantFilter {
+    /// Create a new `WarrantFilter` of the underlying security
+    pub fn new(
+        symbol: impl Into<String>,
+        sort_by: WarrantSortBy,
+        sort_order: SortOrderType,
+    ) -> Self {
+        Self {
+            symbol: symbol.into(),
+            sort_by,
+            sort_order,
+            offset: 0,
+            count: 0,
+            warrant_type: Vec::new(),
+            issuer: Vec::new(),
+            expiry_date: Vec::new(),
+            price_type: Vec::new(),
+            status: Vec::new(),
+        }
+    }
+
+    /// Set the offset of the first warrant
+    #[inline]
+    #[must_use]
+    pub fn offset(self, offset: i32) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// Set the number of warrants, `0` means the default page size of the
+    /// server
+    #[inline]
+    #[must_use]
+    pub fn count(self, count: i32) -> Self {
+        Self { count, ..self }
+    }
+
+    /// Set the warrant types
+    #[inline]
+    #[must_use]
+    pub fn warrant_type(self, warrant_type: impl IntoIterator<Item = WarrantType>) -> Self {
+        Self {
+            warrant_type: warrant_type.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the issuer IDs
+    #[inline]
+    #[must_use]
+    pub fn issuer(self, issuer: impl IntoIterator<Item = i32>) -> Self {
+        Self {
+            issuer: issuer.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the expiry date ranges
+    #[inline]
+    #[must_use]
+    pub fn expiry_date(
+        self,
+        expiry_date: impl IntoIterator<Item = FilterWarrantExpiryDate>,
+    ) -> Self {
+        Self {
+            expiry_date: expiry_date.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the in/out of the bounds types
+    #[inline]
+    #[must_use]
+    pub fn price_type(
+        self,
+        price_type: impl IntoIterator<Item = FilterWarrantInOutBoundsType>,
+    ) -> Self {
+        Self {
+            price_type: price_type.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the warrant status
+    #[inline]
+    #[must_use]
+    pub fn status(self, status: impl IntoIterator<Item = WarrantStatus>) -> Self {
+        Self {
+            status: status.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub(crate) fn to_config(&self) -> quote::FilterConfig {
+        quote::FilterConfig {
+            sort_by: self.sort_by.into(),
+            sort_order: self.sort_order.into(),
+            sort_offset: self.offset,
+            sort_count: self.count,
+            r#type: self.warrant_type.iter().map(|ty| (*ty).into()).collect(),
+            issuer: self.issuer.clone(),
+            expiry_date: self.expiry_date.iter().map(|e| (*e).into()).collect(),
+            price_type: self.price_type.iter().map(|ty| (*ty).into()).collect(),
+            status: self.status.iter().map(|status| (*status).into()).collect(),
+        }
+    }
+}
+
+/// A page of the warrant screener
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarrantList {
+    /// Warrants
+    pub warrants: Vec<WarrantInfo>,
+    /// Total number of the warrants matching the filter
+    pub total_count: i32,
+}
+
+impl TryFrom<quote::WarrantFilterListResponse> for WarrantList {
+    type Error = Error;
+
+    fn try_from(resp: quote::WarrantFilterListResponse) -> Result<Self> {
+        Ok(Self {
+            warrants: resp
+                .warrant_list
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>>>()?,
+            total_count: resp.total_count,
+        })
+    }
+}
+
 /// The information of trading session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingSessionInfo {
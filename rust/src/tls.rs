@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use rustls::{
+    ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    client::{
+        WebPkiServerVerifier,
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    },
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Result};
+
+/// TLS configuration of the HTTP requests and the websocket connections
+///
+/// # Examples
+///
+/// ```no_run
+/// use longport::{Config, TlsConfig};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let tls_config = TlsConfig::new()
+///     .add_root_certificates(std::fs::read("gateway-ca.pem")?)?
+///     .pin_certificate("9f:86:d0:81:88:4c:7d:65:9a:2f:ea:a0:c5:5a:d0:15:a3:bf:4f:1b:2b:0b:82:2c:d1:5d:6c:15:b0:f0:0a:08")?;
+/// let config = Config::from_env()?.tls_config(tls_config)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    builtin_roots: bool,
+    root_certificates: Vec<CertificateDer<'static>>,
+    pins: Vec<[u8; 32]>,
+    client_identity: Option<(Vec<CertificateDer<'static>>, Arc<PrivateKeyDer<'static>>)>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    /// Create a new `TlsConfig` which trusts the built-in root certificates
+    pub fn new() -> Self {
+        Self {
+            builtin_roots: true,
+            root_certificates: Vec::new(),
+            pins: Vec::new(),
+            client_identity: None,
+        }
+    }
+
+    /// Create a new `TlsConfig` from the given environment variables
+    ///
+    /// Returns `None` if none of the variables is set.
+    ///
+    /// # Variables
+    ///
+    /// - `LONGPORT_TLS_ROOT_CA` - Path of the PEM file of the extra root
+    ///   certificates
+    /// - `LONGPORT_TLS_PINS` - Comma separated SHA-256 fingerprints of the
+    ///   pinned certificates
+    /// - `LONGPORT_TLS_CLIENT_CERT` - Path of the PEM file of the client
+    ///   certificate chain
+    /// - `LONGPORT_TLS_CLIENT_KEY` - Path of the PEM file of the client
+    ///   private key
+    pub fn from_env() -> Result<Option<Self>> {
+        let root_ca = std::env::var("LONGPORT_TLS_ROOT_CA").ok();
+        let pins = std::env::var("LONGPORT_TLS_PINS").ok();
+        let client_cert = std::env::var("LONGPORT_TLS_CLIENT_CERT").ok();
+        let client_key = std::env::var("LONGPORT_TLS_CLIENT_KEY").ok();
+        if root_ca.is_none() && pins.is_none() && client_cert.is_none() && client_key.is_none() {
+            return Ok(None);
+        }
+
+        let mut config = Self::new();
+        if let Some(path) = root_ca {
+            config = config
+                .add_root_certificates(std::fs::read(path)?)
+                .map_err(|err| Error::parse_field_error("LONGPORT_TLS_ROOT_CA", err))?;
+        }
+        for pin in pins.iter().flat_map(|pins| pins.split(',')) {
+            config = config
+                .pin_certificate(pin.trim())
+                .map_err(|err| Error::parse_field_error("LONGPORT_TLS_PINS", err))?;
+        }
+        match (client_cert, client_key) {
+            (Some(cert), Some(key)) => {
+                config = config
+                    .client_identity(std::fs::read(cert)?, std::fs::read(key)?)
+                    .map_err(|err| Error::parse_field_error("LONGPORT_TLS_CLIENT_CERT", err))?;
+            }
+            (None, None) => {}
+            _ => {
+                return Err(Error::parse_field_error(
+                    "LONGPORT_TLS_CLIENT_CERT",
+                    "LONGPORT_TLS_CLIENT_CERT and LONGPORT_TLS_CLIENT_KEY must be set together",
+                ));
+            }
+        }
+        Ok(Some(config))
+    }
+
+    /// Trusts the root certificates in the PEM data, in addition to the
+    /// built-in root certificates
+    pub fn add_root_certificates(mut self, pem: impl AsRef<[u8]>) -> Result<Self> {
+        let certs = CertificateDer::pem_slice_iter(pem.as_ref())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|err| Error::Tls(err.to_string()))?;
+        if certs.is_empty() {
+            return Err(Error::Tls("no certificate found".to_string()));
+        }
+        self.root_certificates.extend(certs);
+        Ok(self)
+    }
+
+    /// Trusts only the root certificates added by
+    /// [`TlsConfig::add_root_certificates`]
+    pub fn disable_builtin_roots(self) -> Self {
+        Self {
+            builtin_roots: false,
+            ..self
+        }
+    }
+
+    /// Pins a certificate by the hex encoded SHA-256 fingerprint of its DER
+    /// data, with or without the `:` separators
+    ///
+    /// Once any certificate is pinned, the connection fails unless one of the
+    /// certificates presented by the server, the server certificate or an
+    /// intermediate, is pinned. Pin the intermediate to survive the renewal of
+    /// the server certificate.
+    pub fn pin_certificate(mut self, fingerprint: impl AsRef<str>) -> Result<Self> {
+        let hex = fingerprint.as_ref().replace(':', "");
+        let invalid = || Error::Tls(format!("invalid fingerprint: {}", fingerprint.as_ref()));
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut pin = [0; 32];
+        for (idx, byte) in pin.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        self.pins.push(pin);
+        Ok(self)
+    }
+
+    /// Authenticates with the client certificate chain and private key in the
+    /// PEM data
+    pub fn client_identity(
+        self,
+        cert_chain: impl AsRef<[u8]>,
+        private_key: impl AsRef<[u8]>,
+    ) -> Result<Self> {
+        let cert_chain = CertificateDer::pem_slice_iter(cert_chain.as_ref())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|err| Error::Tls(err.to_string()))?;
+        if cert_chain.is_empty() {
+            return Err(Error::Tls("no certificate found".to_string()));
+        }
+        let private_key = PrivateKeyDer::from_pem_slice(private_key.as_ref())
+            .map_err(|err| Error::Tls(err.to_string()))?;
+        Ok(Self {
+            client_identity: Some((cert_chain, Arc::new(private_key))),
+            ..self
+        })
+    }
+
+    pub(crate) fn build(&self) -> Result<Arc<ClientConfig>> {
+        let provider = Arc::new(ring::default_provider());
+        let mut roots = RootCertStore::empty();
+        if self.builtin_roots {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        for cert in &self.root_certificates {
+            roots
+                .add(cert.clone())
+                .map_err(|err| Error::Tls(err.to_string()))?;
+        }
+
+        let verifier =
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .map_err(|err| Error::Tls(err.to_string()))?;
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|err| Error::Tls(err.to_string()))?;
+        let builder = if self.pins.is_empty() {
+            builder.with_webpki_verifier(verifier)
+        } else {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+                    inner: verifier,
+                    pins: self.pins.clone(),
+                }))
+        };
+        let config = match &self.client_identity {
+            Some((cert_chain, private_key)) => builder
+                .with_client_auth_cert(cert_chain.clone(), private_key.clone_key())
+                .map_err(|err| Error::Tls(err.to_string()))?,
+            None => builder.with_no_client_auth(),
+        };
+        Ok(Arc::new(config))
+    }
+}
+
+/// Verifies the certificates with the root certificates, and then checks
+/// that one of them is pinned
+#[derive(Debug)]
+struct PinnedVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        if std::iter::once(end_entity)
+            .chain(intermediates)
+            .any(|cert| self.pins.contains(&Sha256::digest(cert).into()))
+        {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(
+                "no pinned certificate presented by the server".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use time::{Duration, OffsetDateTime, format_description::well_known::Rfc3339};
+use tokio::task::JoinHandle;
+
+use crate::Config;
+
+const DEFAULT_REFRESH_BEFORE: Duration = Duration::days(7);
+const DEFAULT_VALIDITY: Duration = Duration::days(90);
+const RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+const MIN_REFRESH_INTERVAL: Duration = Duration::minutes(1);
+
+/// Persists the refreshed access tokens
+pub trait TokenStore: Send + Sync + 'static {
+    /// Saves the new access token
+    fn save(&self, access_token: &str, expired_at: OffsetDateTime) -> io::Result<()>;
+}
+
+/// Writes the access token to a file, replacing its content
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Create a new `FileTokenStore`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn save(&self, access_token: &str, _expired_at: OffsetDateTime) -> io::Result<()> {
+        write_atomic(&self.path, access_token)
+    }
+}
+
+/// Updates `LONGPORT_ACCESS_TOKEN` and `LONGPORT_ACCESS_TOKEN_EXPIRED_AT` in a
+/// `.env` file, the other lines are kept
+#[derive(Debug, Clone)]
+pub struct DotEnvTokenStore {
+    path: PathBuf,
+}
+
+impl DotEnvTokenStore {
+    /// Create a new `DotEnvTokenStore`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl TokenStore for DotEnvTokenStore {
+    fn save(&self, access_token: &str, expired_at: OffsetDateTime) -> io::Result<()> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        let expired_at = expired_at
+            .format(&Rfc3339)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        write_atomic(
+            &self.path,
+            &update_dotenv(
+                &content,
+                &[
+                    ("LONGPORT_ACCESS_TOKEN", access_token),
+                    ("LONGPORT_ACCESS_TOKEN_EXPIRED_AT", &expired_at),
+                ],
+            ),
+        )
+    }
+}
+
+fn update_dotenv(content: &str, vars: &[(&str, &str)]) -> String {
+    let mut found = vec![false; vars.len()];
+    let mut res = String::new();
+
+    for line in content.lines() {
+        let key = line.split_once('=').map(|(key, _)| key.trim());
+        match vars.iter().position(|(name, _)| Some(*name) == key) {
+            Some(idx) => {
+                res.push_str(&format!("{}={}\n", vars[idx].0, vars[idx].1));
+                found[idx] = true;
+            }
+            None => {
+                res.push_str(line);
+                res.push('\n');
+            }
+        }
+    }
+
+    for ((name, value), found) in vars.iter().zip(found) {
+        if !found {
+            res.push_str(&format!("{name}={value}\n"));
+        }
+    }
+
+    res
+}
+
+fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)
+}
+
+type RefreshCallback = Box<dyn Fn(&str, OffsetDateTime) + Send + Sync>;
+
+/// Refreshes the access token of a [`Config`] in the background
+///
+/// The refresher requests a new token `refresh_before` the expiration time of
+/// the current one and swaps it into the `Config`, so the following HTTP
+/// requests and websocket reconnections of all the contexts created from the
+/// `Config` use the new token.
+///
+/// The expiration time of the initial token is read from
+/// [`Config::access_token_expired_at`], the token is refreshed immediately if
+/// it is unknown.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{Config, DotEnvTokenStore, TokenRefresher};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let _refresher = TokenRefresher::new(config.clone())
+///     .store(DotEnvTokenStore::new(".env"))
+///     .on_refresh(|_, expired_at| println!("access token refreshed, expires at {expired_at}"))
+///     .spawn();
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+pub struct TokenRefresher {
+    config: Arc<Config>,
+    refresh_before: Duration,
+    validity: Duration,
+    on_refresh: Option<RefreshCallback>,
+    store: Option<Box<dyn TokenStore>>,
+}
+
+impl TokenRefresher {
+    /// Create a new `TokenRefresher`
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            refresh_before: DEFAULT_REFRESH_BEFORE,
+            validity: DEFAULT_VALIDITY,
+            on_refresh: None,
+            store: None,
+        }
+    }
+
+    /// Specifies how long before the expiration time the token is refreshed
+    ///
+    /// It must be shorter than the [validity](TokenRefresher::validity) of
+    /// the new tokens, otherwise half of the validity is used. The tokens are
+    /// refreshed at most once per minute.
+    ///
+    /// Default: `7 days`
+    #[must_use]
+    pub fn refresh_before(self, duration: Duration) -> Self {
+        Self {
+            refresh_before: duration,
+            ..self
+        }
+    }
+
+    /// Specifies the validity of the new tokens
+    ///
+    /// Default: `90 days`
+    #[must_use]
+    pub fn validity(self, duration: Duration) -> Self {
+        Self {
+            validity: duration,
+            ..self
+        }
+    }
+
+    /// Sets the callback to be called with the new token and its expiration
+    /// time after each refresh
+    #[must_use]
+    pub fn on_refresh<F>(self, f: F) -> Self
+    where
+        F: Fn(&str, OffsetDateTime) + Send + Sync + 'static,
+    {
+        Self {
+            on_refresh: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Sets the store to persist the new tokens
+    #[must_use]
+    pub fn store(self, store: impl TokenStore) -> Self {
+        Self {
+            store: Some(Box::new(store)),
+            ..self
+        }
+    }
+
+    /// Starts the refresher, it stops when the returned handle is dropped
+    pub fn spawn(self) -> TokenRefresherHandle {
+        TokenRefresherHandle(tokio::spawn(self.run()))
+    }
+
+    async fn run(self) {
+        let refresh_before = refresh_before(self.refresh_before, self.validity);
+        let mut expired_at = self.config.access_token_expired_at;
+        let mut min_wait = Duration::ZERO;
+
+        loop {
+            if let Some(expired_at) = expired_at {
+                let wait = (expired_at - refresh_before - OffsetDateTime::now_utc()).max(min_wait);
+                if wait.is_positive() {
+                    tokio::time::sleep(wait.unsigned_abs()).await;
+                }
+            }
+
+            let new_expired_at = OffsetDateTime::now_utc() + self.validity;
+            let access_token = match self.config.refresh_access_token(Some(new_expired_at)).await {
+                Ok(access_token) => access_token,
+                Err(err) => {
+                    tracing::error!(error = %err, "failed to refresh access token");
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            self.config.set_access_token(access_token.clone());
+            tracing::info!(expired_at = %new_expired_at, "access token refreshed");
+
+            if let Some(store) = &self.store
+                && let Err(err) = store.save(&access_token, new_expired_at)
+            {
+                tracing::error!(error = %err, "failed to save access token");
+            }
+            if let Some(on_refresh) = &self.on_refresh {
+                on_refresh(&access_token, new_expired_at);
+            }
+            expired_at = Some(new_expired_at);
+            min_wait = MIN_REFRESH_INTERVAL;
+        }
+    }
+}
+
+/// Returns the `refresh_before` to use, half of the validity if it is not
+/// shorter than the validity, so the new token is not refreshed immediately
+fn refresh_before(refresh_before: Duration, validity: Duration) -> Duration {
+    if refresh_before < validity {
+        return refresh_before;
+    }
+    tracing::warn!(
+        refresh_before = %refresh_before,
+        validity = %validity,
+        "refresh_before is not shorter than validity, use half of the validity",
+    );
+    validity / 2
+}
+
+/// Handle of a running [`TokenRefresher`], the refresher stops when it is
+/// dropped
+pub struct TokenRefresherHandle(JoinHandle<()>);
+
+impl Drop for TokenRefresherHandle {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_dotenv_vars() {
+        let content = "LONGPORT_APP_KEY=key\nLONGPORT_ACCESS_TOKEN=old\n# comment\n";
+        assert_eq!(
+            update_dotenv(
+                content,
+                &[
+                    ("LONGPORT_ACCESS_TOKEN", "new"),
+                    ("LONGPORT_ACCESS_TOKEN_EXPIRED_AT", "2025-01-01T00:00:00Z"),
+                ]
+            ),
+            "LONGPORT_APP_KEY=key\nLONGPORT_ACCESS_TOKEN=new\n# comment\nLONGPORT_ACCESS_TOKEN_EXPIRED_AT=2025-01-01T00:00:00Z\n"
+        );
+    }
+
+    #[test]
+    fn clamp_refresh_before() {
+        assert_eq!(
+            refresh_before(Duration::days(7), Duration::days(90)),
+            Duration::days(7)
+        );
+        assert_eq!(
+            refresh_before(Duration::days(7), Duration::days(7)),
+            Duration::hours(84)
+        );
+        assert_eq!(
+            refresh_before(Duration::days(30), Duration::days(1)),
+            Duration::hours(12)
+        );
+    }
+}
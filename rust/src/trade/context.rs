@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 
 use longport_httpcli::{HttpClient, Json, Method};
 use longport_wscli::WsClientError;
@@ -8,7 +11,9 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{Subscriber, dispatcher, instrument::WithSubscriber};
 
 use crate::{
-    Config, Result, serde_utils,
+    Config, ConnectionEvent, Error, PushReceiver, QuoteContext, Result,
+    connection::ConnectionEvents,
+    serde_utils,
     trade::{
         AccountBalance, CashFlow, EstimateMaxPurchaseQuantityOptions, Execution,
         FundPositionsResponse, GetCashFlowOptions, GetFundPositionsOptions,
@@ -16,6 +21,7 @@ use crate::{
         GetTodayExecutionsOptions, GetTodayOrdersOptions, MarginRatio, Order, OrderDetail,
         PushEvent, ReplaceOrderOptions, StockPositionsResponse, SubmitOrderOptions, TopicType,
         core::{Command, Core},
+        paper::PaperTrading,
     },
 };
 
@@ -40,10 +46,19 @@ pub struct EstimateMaxPurchaseQuantityResponse {
     pub margin_max_qty: Decimal,
 }
 
+enum Backend {
+    Live {
+        command_tx: mpsc::UnboundedSender<Command>,
+        connection_events: ConnectionEvents,
+    },
+    Paper(PaperTrading),
+}
+
 struct InnerTradeContext {
-    command_tx: mpsc::UnboundedSender<Command>,
+    backend: Backend,
     http_cli: HttpClient,
     log_subscriber: Arc<dyn Subscriber + Send + Sync>,
+    dropped_push_events: Arc<AtomicU64>,
 }
 
 impl Drop for InnerTradeContext {
@@ -60,22 +75,94 @@ pub struct TradeContext(Arc<InnerTradeContext>);
 
 impl TradeContext {
     /// Create a `TradeContext`
-    pub async fn try_new(
+    ///
+    /// If [`Config::paper_trading`] is enabled, the orders are not sent to
+    /// the exchange. Instead, they are matched locally against the live
+    /// quotes of a dedicated [`QuoteContext`], which
+    /// opens another quote connection (use [`TradeContext::try_new_paper`] to
+    /// share an existing one), and the account balance, stock positions,
+    /// orders and executions are simulated in memory:
+    ///
+    /// - Market orders are filled at the next quote, limit orders when the last
+    ///   done price reaches the limit price.
+    /// - `LIT`/`MIT` orders are triggered when the last done price reaches the
+    ///   trigger price, trailing orders when the price moves back from its
+    ///   highest (sell) or lowest (buy) point by the trailing amount or
+    ///   percent.
+    /// - Orders are always filled completely, fees and trading sessions are not
+    ///   simulated.
+    ///
+    /// `cash_flow` is not supported in paper trading mode, `margin_ratio` is
+    /// still requested from the server.
+    pub async fn try_new(config: Arc<Config>) -> Result<(Self, PushReceiver<PushEvent>)> {
+        Self::create(config, None).await
+    }
+
+    /// Create a `TradeContext` in paper trading mode, the orders are matched
+    /// against the quotes of `quote_ctx` instead of a dedicated
+    /// [`QuoteContext`]
+    ///
+    /// [`Config::paper_trading`] does not need to be enabled, see
+    /// [`TradeContext::try_new`] for the details of the simulation. The
+    /// symbols of the orders are subscribed with
+    /// [`SubFlags::QUOTE`](crate::quote::SubFlags::QUOTE) on `quote_ctx` and
+    /// are not unsubscribed when the `TradeContext` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{Config, QuoteContext, TradeContext};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (quote_ctx, _) = QuoteContext::try_new(config.clone()).await?;
+    /// let (ctx, _) = TradeContext::try_new_paper(config, &quote_ctx).await?;
+    ///
+    /// let resp = ctx.account_balance(None).await?;
+    /// println!("{:?}", resp);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub async fn try_new_paper(
+        config: Arc<Config>,
+        quote_ctx: &QuoteContext,
+    ) -> Result<(Self, PushReceiver<PushEvent>)> {
+        Self::create(config, Some(quote_ctx.clone())).await
+    }
+
+    async fn create(
         config: Arc<Config>,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
+        paper_quote_ctx: Option<QuoteContext>,
+    ) -> Result<(Self, PushReceiver<PushEvent>)> {
         let log_subscriber = config.create_log_subscriber("trade");
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
             tracing::info!(language = ?config.language, "creating trade context");
         });
 
-        let http_cli = config.create_http_client();
-        let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let (push_tx, push_rx) = mpsc::unbounded_channel();
-        let core = Core::try_new(config, command_rx, push_tx)
-            .with_subscriber(log_subscriber.clone())
-            .await?;
-        tokio::spawn(core.run().with_subscriber(log_subscriber.clone()));
+        let http_cli = config.create_http_client()?;
+        let dropped_push_events = Arc::new(AtomicU64::new(0));
+        let (push_tx, push_rx) = config.create_push_channel(dropped_push_events.clone());
+        let backend = if config.paper_trading || paper_quote_ctx.is_some() {
+            Backend::Paper(
+                PaperTrading::try_new(config, paper_quote_ctx, push_tx, log_subscriber.clone())
+                    .with_subscriber(log_subscriber.clone())
+                    .await?,
+            )
+        } else {
+            let (command_tx, command_rx) = mpsc::unbounded_channel();
+            let connection_events = ConnectionEvents::default();
+            let core = Core::try_new(config, command_rx, push_tx, connection_events.clone())
+                .with_subscriber(log_subscriber.clone())
+                .await?;
+            tokio::spawn(core.run().with_subscriber(log_subscriber.clone()));
+            Backend::Live {
+                command_tx,
+                connection_events,
+            }
+        };
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
             tracing::info!("trade context created");
@@ -84,8 +171,9 @@ impl TradeContext {
         Ok((
             TradeContext(Arc::new(InnerTradeContext {
                 http_cli,
-                command_tx,
+                backend,
                 log_subscriber,
+                dropped_push_events,
             })),
             push_rx,
         ))
@@ -97,6 +185,29 @@ impl TradeContext {
         self.0.log_subscriber.clone()
     }
 
+    /// Returns the number of the push events dropped because the receiver was
+    /// full, see [`Config::push_channel`]
+    #[inline]
+    pub fn dropped_push_events(&self) -> u64 {
+        self.0.dropped_push_events.load(Ordering::Relaxed)
+    }
+
+    /// Returns a receiver of the connection state change events
+    ///
+    /// Each call returns a new receiver that first gets the latest event, the
+    /// current state of the connection, then the events occurred after it is
+    /// created, see [`ConnectionEvent`] for the sequence of the events.
+    /// In paper trading mode the events come from the quote connection used
+    /// to match the orders.
+    pub fn connection_events(&self) -> mpsc::UnboundedReceiver<ConnectionEvent> {
+        match &self.0.backend {
+            Backend::Live {
+                connection_events, ..
+            } => connection_events.subscribe(),
+            Backend::Paper(paper) => paper.connection_events(),
+        }
+    }
+
     /// Subscribe
     ///
     /// Reference: <https://open.longportapp.com/en/docs/trade/trade-push#subscribe>
@@ -137,9 +248,12 @@ impl TradeContext {
     where
         I: IntoIterator<Item = TopicType>,
     {
+        let command_tx = match &self.0.backend {
+            Backend::Live { command_tx, .. } => command_tx,
+            Backend::Paper(paper) => return paper.subscribe(topics.into_iter().collect()).await,
+        };
         let (reply_tx, reply_rx) = oneshot::channel();
-        self.0
-            .command_tx
+        command_tx
             .send(Command::Subscribe {
                 topics: topics.into_iter().collect(),
                 reply_tx,
@@ -155,9 +269,12 @@ impl TradeContext {
     where
         I: IntoIterator<Item = TopicType>,
     {
+        let command_tx = match &self.0.backend {
+            Backend::Live { command_tx, .. } => command_tx,
+            Backend::Paper(paper) => return paper.unsubscribe(topics.into_iter().collect()).await,
+        };
         let (reply_tx, reply_rx) = oneshot::channel();
-        self.0
-            .command_tx
+        command_tx
             .send(Command::Unsubscribe {
                 topics: topics.into_iter().collect(),
                 reply_tx,
@@ -198,6 +315,12 @@ impl TradeContext {
         &self,
         options: impl Into<Option<GetHistoryExecutionsOptions>>,
     ) -> Result<Vec<Execution>> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper
+                .history_executions(options.into().unwrap_or_default())
+                .await;
+        }
+
         #[derive(Deserialize)]
         struct Response {
             trades: Vec<Execution>,
@@ -244,6 +367,12 @@ impl TradeContext {
         &self,
         options: impl Into<Option<GetTodayExecutionsOptions>>,
     ) -> Result<Vec<Execution>> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper
+                .today_executions(options.into().unwrap_or_default())
+                .await;
+        }
+
         #[derive(Deserialize)]
         struct Response {
             trades: Vec<Execution>,
@@ -297,6 +426,12 @@ impl TradeContext {
         &self,
         options: impl Into<Option<GetHistoryOrdersOptions>>,
     ) -> Result<Vec<Order>> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper
+                .history_orders(options.into().unwrap_or_default())
+                .await;
+        }
+
         #[derive(Deserialize)]
         struct Response {
             orders: Vec<Order>,
@@ -347,6 +482,10 @@ impl TradeContext {
         &self,
         options: impl Into<Option<GetTodayOrdersOptions>>,
     ) -> Result<Vec<Order>> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper.today_orders(options.into().unwrap_or_default()).await;
+        }
+
         #[derive(Deserialize)]
         struct Response {
             orders: Vec<Order>,
@@ -391,6 +530,10 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper.replace_order(options).await;
+        }
+
         Ok(self
             .0
             .http_cli
@@ -435,6 +578,14 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn submit_order(&self, options: SubmitOrderOptions) -> Result<SubmitOrderResponse> {
+        let command_tx = match &self.0.backend {
+            Backend::Live { command_tx, .. } => command_tx,
+            Backend::Paper(paper) => {
+                return Ok(SubmitOrderResponse {
+                    order_id: paper.submit_order(options).await?,
+                });
+            }
+        };
         let resp: SubmitOrderResponse = self
             .0
             .http_cli
@@ -445,7 +596,7 @@ impl TradeContext {
             .with_subscriber(self.0.log_subscriber.clone())
             .await?
             .0;
-        _ = self.0.command_tx.send(Command::SubmittedOrder {
+        _ = command_tx.send(Command::SubmittedOrder {
             order_id: resp.order_id.clone(),
         });
         Ok(resp)
@@ -471,6 +622,10 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn cancel_order(&self, order_id: impl Into<String>) -> Result<()> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper.cancel_order(order_id.into()).await;
+        }
+
         #[derive(Debug, Serialize)]
         struct Request {
             order_id: String,
@@ -511,6 +666,12 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn account_balance(&self, currency: Option<&str>) -> Result<Vec<AccountBalance>> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper
+                .account_balance(currency.map(ToString::to_string))
+                .await;
+        }
+
         #[derive(Debug, Serialize)]
         struct Request<'a> {
             currency: Option<&'a str>,
@@ -560,6 +721,12 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn cash_flow(&self, options: GetCashFlowOptions) -> Result<Vec<CashFlow>> {
+        if let Backend::Paper(_) = &self.0.backend {
+            return Err(Error::PaperTrading(
+                "cash flow is not supported".to_string(),
+            ));
+        }
+
         #[derive(Debug, Deserialize)]
         struct Response {
             list: Vec<CashFlow>,
@@ -602,6 +769,12 @@ impl TradeContext {
         &self,
         opts: impl Into<Option<GetFundPositionsOptions>>,
     ) -> Result<FundPositionsResponse> {
+        if let Backend::Paper(_) = &self.0.backend {
+            return Ok(FundPositionsResponse {
+                channels: Vec::new(),
+            });
+        }
+
         Ok(self
             .0
             .http_cli
@@ -638,6 +811,12 @@ impl TradeContext {
         &self,
         opts: impl Into<Option<GetStockPositionsOptions>>,
     ) -> Result<StockPositionsResponse> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper
+                .stock_positions(opts.into().unwrap_or_default().symbols)
+                .await;
+        }
+
         Ok(self
             .0
             .http_cli
@@ -715,6 +894,10 @@ impl TradeContext {
     /// # });
     /// ```
     pub async fn order_detail(&self, order_id: impl Into<String>) -> Result<OrderDetail> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper.order_detail(order_id.into()).await;
+        }
+
         #[derive(Debug, Serialize)]
         struct Request {
             order_id: String,
@@ -770,6 +953,10 @@ impl TradeContext {
         &self,
         opts: EstimateMaxPurchaseQuantityOptions,
     ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        if let Backend::Paper(paper) = &self.0.backend {
+            return paper.estimate_max_purchase_quantity(opts).await;
+        }
+
         Ok(self
             .0
             .http_cli
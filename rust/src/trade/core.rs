@@ -16,11 +16,11 @@ use tokio::{
 
 use crate::{
     Config, Result,
+    connection::{ConnectionEvent, ConnectionEvents},
+    push_channel::PushSender,
     trade::{PushEvent, PushOrderChanged, TopicType, cmd_code},
 };
 
-const RECONNECT_DELAY: Duration = Duration::from_secs(2);
-
 pub(crate) enum Command {
     Subscribe {
         topics: Vec<TopicType>,
@@ -38,7 +38,7 @@ pub(crate) enum Command {
 pub(crate) struct Core {
     config: Arc<Config>,
     command_rx: mpsc::UnboundedReceiver<Command>,
-    push_tx: mpsc::UnboundedSender<PushEvent>,
+    push_tx: PushSender<PushEvent>,
     event_tx: mpsc::UnboundedSender<WsEvent>,
     event_rx: mpsc::UnboundedReceiver<WsEvent>,
     http_cli: HttpClient,
@@ -47,22 +47,26 @@ pub(crate) struct Core {
     close: bool,
     subscriptions: HashSet<String>,
     unknown_orders: VecDeque<(Instant, PushOrderChanged)>,
+    connection_events: ConnectionEvents,
 }
 
 impl Core {
     pub(crate) async fn try_new(
         config: Arc<Config>,
         command_rx: mpsc::UnboundedReceiver<Command>,
-        push_tx: mpsc::UnboundedSender<PushEvent>,
+        push_tx: PushSender<PushEvent>,
+        connection_events: ConnectionEvents,
     ) -> Result<Self> {
-        let http_cli = config.create_http_client();
+        let http_cli = config.create_http_client()?;
         let otp = http_cli.get_otp().await?;
 
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
         tracing::info!("connecting to trade server");
+        connection_events.send(ConnectionEvent::Connecting);
         let (url, res) = config.create_trade_ws_request().await;
         let request = res.map_err(WsClientError::from)?;
+        let proxy = config.create_ws_proxy()?;
         let ws_cli = WsClient::open(
             request,
             ProtocolVersion::Version1,
@@ -70,12 +74,16 @@ impl Core {
             Platform::OpenAPI,
             event_tx.clone(),
             vec![],
+            proxy.as_ref(),
+            config.tls_config.clone(),
         )
         .await?;
 
         tracing::info!(url = url, "trade server connected");
+        connection_events.send(ConnectionEvent::Connected);
 
         let session = ws_cli.request_auth(otp, Default::default()).await?;
+        connection_events.send(ConnectionEvent::Authenticated);
 
         Ok(Self {
             config,
@@ -89,94 +97,136 @@ impl Core {
             close: false,
             subscriptions: HashSet::new(),
             unknown_orders: VecDeque::new(),
+            connection_events,
         })
     }
 
     pub(crate) async fn run(mut self) {
+        let reconnect_policy = self.config.create_reconnect_policy();
+
         while !self.close {
-            match self.main_loop().await {
+            let mut err = match self.main_loop().await {
                 Ok(()) => return,
-                Err(err) => tracing::error!(error = %err, "trade disconnected"),
-            }
+                Err(err) => err,
+            };
+            tracing::error!(error = %err, "trade disconnected");
+            self.connection_events
+                .send(ConnectionEvent::disconnected(&err));
 
+            let mut attempt = 0;
             loop {
-                // reconnect
-                tokio::time::sleep(RECONNECT_DELAY).await;
+                let Some(delay) = reconnect_policy.delay(attempt) else {
+                    reconnect_policy.give_up(attempt, &err);
+                    self.connection_events.send(ConnectionEvent::GaveUp {
+                        attempts: attempt,
+                        error: err.to_string(),
+                    });
+                    return;
+                };
+                tokio::time::sleep(delay).await;
+                attempt += 1;
 
-                tracing::info!("connecting to trade server");
-                let (url, res) = self.config.create_trade_ws_request().await;
-                let request = res.expect("BUG: failed to create trade ws request");
-
-                match WsClient::open(
-                    request,
-                    ProtocolVersion::Version1,
-                    CodecType::Protobuf,
-                    Platform::OpenAPI,
-                    self.event_tx.clone(),
-                    vec![],
-                )
-                .await
-                {
-                    Ok(ws_cli) => self.ws_cli = ws_cli,
-                    Err(err) => {
-                        tracing::error!(error = %err, "failed to connect trade server");
-                        continue;
+                match self.reconnect().await {
+                    Ok(()) => break,
+                    Err(reconnect_err) => {
+                        self.connection_events
+                            .send(ConnectionEvent::disconnected(&reconnect_err));
+                        err = reconnect_err;
                     }
                 }
+            }
+        }
+    }
 
-                tracing::info!(url = url, "trade server connected");
+    async fn reconnect(&mut self) -> Result<()> {
+        tracing::info!("connecting to trade server");
+        self.connection_events.send(ConnectionEvent::Connecting);
+        let (url, res) = self.config.create_trade_ws_request().await;
+        let request = res.map_err(WsClientError::from)?;
+        let proxy = self.config.create_ws_proxy()?;
 
-                // request new session
-                match &self.session {
-                    Some(session) if !session.is_expired() => {
-                        match self
-                            .ws_cli
-                            .request_reconnect(&session.session_id, Default::default())
-                            .await
-                        {
-                            Ok(new_session) => self.session = Some(new_session),
-                            Err(err) => {
-                                self.session = None; // invalid session
-                                tracing::error!(error = %err, "failed to request session id");
-                                continue;
-                            }
-                        }
-                    }
-                    _ => {
-                        let otp = match self.http_cli.get_otp().await {
-                            Ok(otp) => otp,
-                            Err(err) => {
-                                tracing::error!(error = %err, "failed to request otp");
-                                continue;
-                            }
-                        };
+        match WsClient::open(
+            request,
+            ProtocolVersion::Version1,
+            CodecType::Protobuf,
+            Platform::OpenAPI,
+            self.event_tx.clone(),
+            vec![],
+            proxy.as_ref(),
+            self.config.tls_config.clone(),
+        )
+        .await
+        {
+            Ok(ws_cli) => self.ws_cli = ws_cli,
+            Err(err) => {
+                tracing::error!(error = %err, "failed to connect trade server");
+                return Err(err.into());
+            }
+        }
 
-                        match self.ws_cli.request_auth(otp, Default::default()).await {
-                            Ok(new_session) => self.session = Some(new_session),
-                            Err(err) => {
-                                tracing::error!(error = %err, "failed to request session id");
-                                continue;
-                            }
-                        }
+        tracing::info!(url = url, "trade server connected");
+        self.connection_events.send(ConnectionEvent::Connected);
+
+        // request new session
+        match &self.session {
+            Some(session) if !session.is_expired() => {
+                match self
+                    .ws_cli
+                    .request_reconnect(&session.session_id, Default::default())
+                    .await
+                {
+                    Ok(new_session) => {
+                        self.session = Some(new_session);
+                        self.connection_events.send(ConnectionEvent::Reconnected {
+                            session_reused: true,
+                        });
+                    }
+                    Err(err) => {
+                        self.session = None; // invalid session
+                        tracing::error!(error = %err, "failed to request session id");
+                        return Err(err.into());
                     }
                 }
+            }
+            _ => {
+                let otp = match self.http_cli.get_otp().await {
+                    Ok(otp) => otp,
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to request otp");
+                        return Err(err.into());
+                    }
+                };
 
-                // handle reconnect
-                match self.resubscribe().await {
-                    Ok(()) => break,
+                match self.ws_cli.request_auth(otp, Default::default()).await {
+                    Ok(new_session) => {
+                        self.session = Some(new_session);
+                        self.connection_events.send(ConnectionEvent::Reconnected {
+                            session_reused: false,
+                        });
+                    }
                     Err(err) => {
-                        tracing::error!(error = %err, "failed to subscribe topics");
-                        continue;
+                        tracing::error!(error = %err, "failed to request session id");
+                        return Err(err.into());
                     }
                 }
             }
         }
+
+        // handle reconnect
+        if let Err(err) = self.resubscribe().await {
+            tracing::error!(error = %err, "failed to subscribe topics");
+            return Err(err);
+        }
+        self.connection_events.send(ConnectionEvent::Resubscribed);
+        Ok(())
     }
 
     async fn main_loop(&mut self) -> Result<()> {
         let mut tick = tokio::time::interval(Duration::from_millis(500));
 
         loop {
+            self.push_tx.ready().await;
+
             tokio::select! {
                 item = self.event_rx.recv() => {
                     match item {
@@ -209,7 +259,7 @@ impl Core {
         match PushEvent::parse(command_code, &body) {
             Ok(Some(event)) => {
                 tracing::info!(event = ?event, "push event");
-                let _ = self.push_tx.send(event);
+                self.push_tx.send(event);
             }
             Ok(None) => {}
             Err(err) => {
@@ -223,7 +273,7 @@ impl Core {
         while let Some((t, _)) = self.unknown_orders.front() {
             if now - *t > Duration::from_secs(1) {
                 let (_, order_changed) = self.unknown_orders.pop_front().unwrap();
-                _ = self.push_tx.send(PushEvent::OrderChanged(order_changed));
+                self.push_tx.send(PushEvent::OrderChanged(order_changed));
             } else {
                 break;
             }
@@ -252,7 +302,7 @@ impl Core {
                     let Some((_, order_changed)) = self.unknown_orders.remove(idx) else {
                         unreachable!();
                     };
-                    let _ = self.push_tx.send(PushEvent::OrderChanged(order_changed));
+                    self.push_tx.send(PushEvent::OrderChanged(order_changed));
                 }
                 Ok(())
             }
@@ -3,6 +3,7 @@
 mod cmd_code;
 mod context;
 mod core;
+mod paper;
 mod push_types;
 mod requests;
 mod types;
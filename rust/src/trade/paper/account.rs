@@ -0,0 +1,974 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+
+use crate::{
+    Error, Market, Result,
+    quote::parse_market_from_symbol,
+    trade::{
+        AccountBalance, CashInfo, CommissionFreeStatus, DeductionStatus,
+        EstimateMaxPurchaseQuantityOptions, EstimateMaxPurchaseQuantityResponse, Execution, Order,
+        OrderChargeDetail, OrderDetail, OrderHistoryDetail, OrderSide, OrderStatus, OrderTag,
+        OrderType, PushOrderChanged, ReplaceOrderOptions, StockPosition, StockPositionChannel,
+        StockPositionsResponse, SubmitOrderOptions, TriggerStatus,
+    },
+};
+
+const ACCOUNT_CHANNEL: &str = "paper";
+
+/// How an order is matched
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum OrderKind {
+    Market,
+    Limit,
+    TouchedMarket,
+    TouchedLimit,
+    TrailingMarket,
+    TrailingLimit,
+}
+
+impl OrderKind {
+    fn of(order_type: OrderType) -> Option<Self> {
+        Some(match order_type {
+            OrderType::MO | OrderType::AO => OrderKind::Market,
+            OrderType::LO | OrderType::ELO | OrderType::ALO | OrderType::ODD | OrderType::SLO => {
+                OrderKind::Limit
+            }
+            OrderType::MIT => OrderKind::TouchedMarket,
+            OrderType::LIT => OrderKind::TouchedLimit,
+            OrderType::TSMAMT | OrderType::TSMPCT => OrderKind::TrailingMarket,
+            OrderType::TSLPAMT | OrderType::TSLPPCT => OrderKind::TrailingLimit,
+            OrderType::Unknown => return None,
+        })
+    }
+
+    fn is_conditional(self) -> bool {
+        !matches!(self, OrderKind::Market | OrderKind::Limit)
+    }
+
+    fn is_trailing(self) -> bool {
+        matches!(self, OrderKind::TrailingMarket | OrderKind::TrailingLimit)
+    }
+
+    fn is_market(self) -> bool {
+        matches!(
+            self,
+            OrderKind::Market | OrderKind::TouchedMarket | OrderKind::TrailingMarket
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Cash {
+    total: Decimal,
+    frozen: Decimal,
+}
+
+impl Cash {
+    fn available(&self) -> Decimal {
+        self.total - self.frozen
+    }
+}
+
+#[derive(Debug)]
+struct Position {
+    quantity: Decimal,
+    frozen: Decimal,
+    cost_price: Decimal,
+    currency: String,
+    market: Market,
+}
+
+#[derive(Debug)]
+struct PaperOrder {
+    order: Order,
+    kind: OrderKind,
+    /// The first price seen after the order was submitted, decides in which
+    /// direction the trigger price of `LIT`/`MIT` orders is touched
+    reference_price: Option<Decimal>,
+    /// Highest (sell) or lowest (buy) price seen by a trailing order
+    extreme_price: Option<Decimal>,
+    frozen_cash: Decimal,
+    frozen_quantity: Decimal,
+    history: Vec<OrderHistoryDetail>,
+    last_share: Option<Decimal>,
+    last_price: Option<Decimal>,
+}
+
+impl PaperOrder {
+    fn is_active(&self) -> bool {
+        matches!(
+            self.order.status,
+            OrderStatus::New | OrderStatus::Replaced | OrderStatus::PartialFilled
+        )
+    }
+
+    fn is_triggered(&self) -> bool {
+        !self.kind.is_conditional() || self.order.trigger_status == Some(TriggerStatus::Released)
+    }
+
+    fn set_status(&mut self, status: OrderStatus, msg: impl Into<String>, now: OffsetDateTime) {
+        self.order.status = status;
+        self.order.msg = msg.into();
+        self.order.updated_at = Some(now);
+        self.history.push(OrderHistoryDetail {
+            price: self
+                .order
+                .executed_price
+                .or(self.order.price)
+                .unwrap_or_default(),
+            quantity: self.order.quantity,
+            status,
+            msg: self.order.msg.clone(),
+            time: now,
+        });
+    }
+
+    fn to_push(&self) -> PushOrderChanged {
+        let order = &self.order;
+        PushOrderChanged {
+            side: order.side,
+            stock_name: order.stock_name.clone(),
+            submitted_quantity: order.quantity,
+            symbol: order.symbol.clone(),
+            order_type: order.order_type,
+            submitted_price: order.price.unwrap_or_default(),
+            executed_quantity: order.executed_quantity,
+            executed_price: order.executed_price,
+            order_id: order.order_id.clone(),
+            currency: order.currency.clone(),
+            status: order.status,
+            submitted_at: order.submitted_at,
+            updated_at: order.updated_at.unwrap_or(order.submitted_at),
+            trigger_price: order.trigger_price,
+            msg: order.msg.clone(),
+            tag: order.tag,
+            trigger_status: order.trigger_status,
+            trigger_at: order.trigger_at,
+            trailing_amount: order.trailing_amount,
+            trailing_percent: order.trailing_percent,
+            limit_offset: order.limit_offset,
+            account_no: ACCOUNT_CHANNEL.to_string(),
+            last_share: self.last_share,
+            last_price: self.last_price,
+            remark: order.remark.clone(),
+        }
+    }
+}
+
+/// In-memory state of a paper trading account
+///
+/// Orders are always filled completely at the last done price of the first
+/// quote that satisfies them, fees and trading sessions are not simulated.
+#[derive(Debug)]
+pub(crate) struct Account {
+    next_order_id: u64,
+    next_trade_id: u64,
+    cash: BTreeMap<String, Cash>,
+    positions: BTreeMap<String, Position>,
+    orders: Vec<PaperOrder>,
+    executions: Vec<Execution>,
+    last_prices: HashMap<String, Decimal>,
+}
+
+impl Account {
+    pub(crate) fn new(initial_cash: impl IntoIterator<Item = (String, Decimal)>) -> Self {
+        let mut cash = BTreeMap::<String, Cash>::new();
+        for (currency, amount) in initial_cash {
+            cash.entry(currency).or_default().total += amount;
+        }
+        Self {
+            next_order_id: 1,
+            next_trade_id: 1,
+            cash,
+            positions: BTreeMap::new(),
+            orders: Vec::new(),
+            executions: Vec::new(),
+            last_prices: HashMap::new(),
+        }
+    }
+
+    /// Returns the last done price of `symbol`
+    pub(crate) fn last_price(&self, symbol: &str) -> Option<Decimal> {
+        self.last_prices.get(symbol).copied()
+    }
+
+    pub(crate) fn orders(&self) -> impl Iterator<Item = &Order> {
+        self.orders.iter().map(|order| &order.order)
+    }
+
+    pub(crate) fn executions(&self) -> &[Execution] {
+        &self.executions
+    }
+
+    pub(crate) fn submit_order(
+        &mut self,
+        options: &SubmitOrderOptions,
+        now: OffsetDateTime,
+    ) -> Result<PushOrderChanged> {
+        let kind = OrderKind::of(options.order_type)
+            .ok_or_else(|| Error::PaperTrading("unsupported order type".to_string()))?;
+        let market = parse_market_from_symbol(&options.symbol).ok_or_else(|| {
+            Error::InvalidSecuritySymbol {
+                symbol: options.symbol.clone(),
+            }
+        })?;
+        if options.submitted_quantity <= Decimal::ZERO {
+            return Err(Error::PaperTrading("quantity must be positive".to_string()));
+        }
+        check_order_params(
+            kind,
+            options.order_type,
+            options.submitted_price,
+            options.trigger_price,
+            options.trailing_amount,
+            options.trailing_percent,
+        )?;
+
+        let order_id = self.next_order_id.to_string();
+        self.next_order_id += 1;
+
+        let order = PaperOrder {
+            order: Order {
+                order_id,
+                status: OrderStatus::New,
+                stock_name: String::new(),
+                quantity: options.submitted_quantity,
+                executed_quantity: Decimal::ZERO,
+                price: options.submitted_price,
+                executed_price: None,
+                submitted_at: now,
+                side: options.side,
+                symbol: options.symbol.clone(),
+                order_type: options.order_type,
+                last_done: self.last_price(&options.symbol),
+                trigger_price: options.trigger_price,
+                msg: String::new(),
+                tag: OrderTag::Normal,
+                time_in_force: options.time_in_force,
+                expire_date: options.expire_date,
+                updated_at: Some(now),
+                trigger_at: None,
+                trailing_amount: options.trailing_amount,
+                trailing_percent: options.trailing_percent,
+                limit_offset: options.limit_offset,
+                trigger_status: kind.is_conditional().then_some(TriggerStatus::Deactive),
+                currency: currency_of(market).to_string(),
+                outside_rth: options.outside_rth,
+                remark: options.remark.clone().unwrap_or_default(),
+            },
+            kind,
+            reference_price: None,
+            extreme_price: None,
+            frozen_cash: Decimal::ZERO,
+            frozen_quantity: Decimal::ZERO,
+            history: Vec::new(),
+            last_share: None,
+            last_price: None,
+        };
+
+        let res = self.freeze(&order, order.order.quantity, order.order.price);
+        let idx = self.orders.len();
+        self.orders.push(order);
+        match res {
+            Ok((frozen_cash, frozen_quantity)) => {
+                self.apply_freeze(idx, frozen_cash, frozen_quantity);
+                self.orders[idx].set_status(OrderStatus::New, "", now);
+            }
+            Err(msg) => self.orders[idx].set_status(OrderStatus::Rejected, msg, now),
+        }
+        Ok(self.orders[idx].to_push())
+    }
+
+    pub(crate) fn replace_order(
+        &mut self,
+        options: &ReplaceOrderOptions,
+        now: OffsetDateTime,
+    ) -> Result<PushOrderChanged> {
+        let idx = self.active_order_index(&options.order_id)?;
+        let order = &self.orders[idx];
+        if options.quantity <= Decimal::ZERO {
+            return Err(Error::PaperTrading("quantity must be positive".to_string()));
+        }
+        let price = options.price.or(order.order.price);
+        check_order_params(
+            order.kind,
+            order.order.order_type,
+            price,
+            options.trigger_price.or(order.order.trigger_price),
+            options.trailing_amount.or(order.order.trailing_amount),
+            options.trailing_percent.or(order.order.trailing_percent),
+        )?;
+
+        self.unfreeze(idx);
+        let (frozen_cash, frozen_quantity) =
+            match self.freeze(&self.orders[idx], options.quantity, price) {
+                Ok(frozen) => frozen,
+                Err(msg) => {
+                    let order = &self.orders[idx];
+                    let frozen = (order.frozen_cash, order.frozen_quantity);
+                    self.apply_freeze(idx, frozen.0, frozen.1);
+                    return Err(Error::PaperTrading(msg.to_string()));
+                }
+            };
+        self.apply_freeze(idx, frozen_cash, frozen_quantity);
+
+        let order = &mut self.orders[idx];
+        order.order.quantity = options.quantity;
+        order.order.price = price;
+        if let Some(trigger_price) = options.trigger_price {
+            order.order.trigger_price = Some(trigger_price);
+        }
+        if let Some(limit_offset) = options.limit_offset {
+            order.order.limit_offset = Some(limit_offset);
+        }
+        if let Some(trailing_amount) = options.trailing_amount {
+            order.order.trailing_amount = Some(trailing_amount);
+        }
+        if let Some(trailing_percent) = options.trailing_percent {
+            order.order.trailing_percent = Some(trailing_percent);
+        }
+        if let Some(remark) = &options.remark {
+            order.order.remark = remark.clone();
+        }
+        order.set_status(OrderStatus::Replaced, "", now);
+        Ok(order.to_push())
+    }
+
+    pub(crate) fn cancel_order(
+        &mut self,
+        order_id: &str,
+        now: OffsetDateTime,
+    ) -> Result<PushOrderChanged> {
+        let idx = self.active_order_index(order_id)?;
+        self.unfreeze(idx);
+        let order = &mut self.orders[idx];
+        order.set_status(OrderStatus::Canceled, "", now);
+        Ok(order.to_push())
+    }
+
+    /// Updates the last done price of `symbol` and matches the active orders
+    /// of it
+    pub(crate) fn update_price(
+        &mut self,
+        symbol: &str,
+        price: Decimal,
+        now: OffsetDateTime,
+    ) -> Vec<PushOrderChanged> {
+        self.last_prices.insert(symbol.to_string(), price);
+
+        let mut pushes = Vec::new();
+        for idx in 0..self.orders.len() {
+            let order = &mut self.orders[idx];
+            if order.order.symbol != symbol || !order.is_active() {
+                continue;
+            }
+            order.order.last_done = Some(price);
+
+            if !order.is_triggered() {
+                if !check_trigger(order, price) {
+                    continue;
+                }
+                order.order.trigger_status = Some(TriggerStatus::Released);
+                order.order.trigger_at = Some(now);
+                order.order.updated_at = Some(now);
+                pushes.push(order.to_push());
+            }
+
+            let matched = order.kind.is_market()
+                || order
+                    .order
+                    .price
+                    .is_some_and(|limit| match order.order.side {
+                        OrderSide::Sell => price >= limit,
+                        _ => price <= limit,
+                    });
+            if matched {
+                pushes.push(self.fill(idx, price, now));
+            }
+        }
+        pushes
+    }
+
+    pub(crate) fn order_detail(&self, order_id: &str) -> Result<OrderDetail> {
+        let order = self.find_order(order_id)?;
+        let Order {
+            order_id,
+            status,
+            stock_name,
+            quantity,
+            executed_quantity,
+            price,
+            executed_price,
+            submitted_at,
+            side,
+            symbol,
+            order_type,
+            last_done,
+            trigger_price,
+            msg,
+            tag,
+            time_in_force,
+            expire_date,
+            updated_at,
+            trigger_at,
+            trailing_amount,
+            trailing_percent,
+            limit_offset,
+            trigger_status,
+            currency,
+            outside_rth,
+            remark,
+        } = order.order.clone();
+
+        Ok(OrderDetail {
+            order_id,
+            status,
+            stock_name,
+            quantity,
+            executed_quantity,
+            price,
+            executed_price,
+            submitted_at,
+            side,
+            symbol,
+            order_type,
+            last_done,
+            trigger_price,
+            msg,
+            tag,
+            time_in_force,
+            expire_date,
+            updated_at,
+            trigger_at,
+            trailing_amount,
+            trailing_percent,
+            limit_offset,
+            trigger_status,
+            charge_detail: OrderChargeDetail {
+                total_amount: Decimal::ZERO,
+                currency: currency.clone(),
+                items: Vec::new(),
+            },
+            currency,
+            outside_rth,
+            remark,
+            free_status: CommissionFreeStatus::None,
+            free_amount: None,
+            free_currency: None,
+            deductions_status: DeductionStatus::NoData,
+            deductions_amount: None,
+            deductions_currency: None,
+            platform_deducted_status: DeductionStatus::NoData,
+            platform_deducted_amount: None,
+            platform_deducted_currency: None,
+            history: order.history.clone(),
+        })
+    }
+
+    pub(crate) fn account_balance(&self, currency: Option<&str>) -> Vec<AccountBalance> {
+        self.cash
+            .iter()
+            .filter(|(cash_currency, _)| currency.is_none_or(|currency| currency == *cash_currency))
+            .map(|(currency, cash)| {
+                let market_value = self
+                    .positions
+                    .iter()
+                    .filter(|(_, position)| &position.currency == currency)
+                    .map(|(symbol, position)| {
+                        position.quantity * self.last_price(symbol).unwrap_or(position.cost_price)
+                    })
+                    .sum::<Decimal>();
+                AccountBalance {
+                    total_cash: cash.total,
+                    max_finance_amount: Decimal::ZERO,
+                    remaining_finance_amount: Decimal::ZERO,
+                    risk_level: 0,
+                    margin_call: Decimal::ZERO,
+                    currency: currency.clone(),
+                    cash_infos: vec![CashInfo {
+                        withdraw_cash: cash.available(),
+                        available_cash: cash.available(),
+                        frozen_cash: cash.frozen,
+                        settling_cash: Decimal::ZERO,
+                        currency: currency.clone(),
+                    }],
+                    net_assets: cash.total + market_value,
+                    init_margin: Decimal::ZERO,
+                    maintenance_margin: Decimal::ZERO,
+                    buy_power: cash.available(),
+                    frozen_transaction_fees: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn stock_positions(&self, symbols: &[String]) -> StockPositionsResponse {
+        let positions = self
+            .positions
+            .iter()
+            .filter(|(symbol, _)| symbols.is_empty() || symbols.contains(symbol))
+            .map(|(symbol, position)| StockPosition {
+                symbol: symbol.clone(),
+                symbol_name: String::new(),
+                quantity: position.quantity,
+                available_quantity: position.quantity - position.frozen,
+                currency: position.currency.clone(),
+                cost_price: position.cost_price,
+                market: position.market,
+                init_quantity: None,
+            })
+            .collect();
+        StockPositionsResponse {
+            channels: vec![StockPositionChannel {
+                account_channel: ACCOUNT_CHANNEL.to_string(),
+                positions,
+            }],
+        }
+    }
+
+    pub(crate) fn estimate_max_purchase_quantity(
+        &self,
+        options: &EstimateMaxPurchaseQuantityOptions,
+    ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        let quantity = match options.side {
+            OrderSide::Sell => self
+                .positions
+                .get(&options.symbol)
+                .map(|position| position.quantity - position.frozen)
+                .unwrap_or_default(),
+            _ => {
+                let market = parse_market_from_symbol(&options.symbol).ok_or_else(|| {
+                    Error::InvalidSecuritySymbol {
+                        symbol: options.symbol.clone(),
+                    }
+                })?;
+                let price = options
+                    .price
+                    .or_else(|| self.last_price(&options.symbol))
+                    .filter(|price| *price > Decimal::ZERO)
+                    .ok_or_else(|| {
+                        Error::PaperTrading(format!("no price for {}", options.symbol))
+                    })?;
+                let available = self
+                    .cash
+                    .get(currency_of(market))
+                    .map(Cash::available)
+                    .unwrap_or_default();
+                let quantity = available / price;
+                if options.fractional_shares {
+                    quantity
+                } else {
+                    quantity.floor()
+                }
+            }
+        };
+        Ok(EstimateMaxPurchaseQuantityResponse {
+            cash_max_qty: quantity,
+            margin_max_qty: quantity,
+        })
+    }
+
+    fn find_order(&self, order_id: &str) -> Result<&PaperOrder> {
+        self.orders
+            .iter()
+            .find(|order| order.order.order_id == order_id)
+            .ok_or_else(|| Error::PaperTrading(format!("order not found: {order_id}")))
+    }
+
+    fn active_order_index(&self, order_id: &str) -> Result<usize> {
+        let idx = self
+            .orders
+            .iter()
+            .position(|order| order.order.order_id == order_id)
+            .ok_or_else(|| Error::PaperTrading(format!("order not found: {order_id}")))?;
+        if !self.orders[idx].is_active() {
+            return Err(Error::PaperTrading(format!(
+                "order is already finished: {order_id}"
+            )));
+        }
+        Ok(idx)
+    }
+
+    /// Checks the buying power or the available position for an order, and
+    /// returns the cash and quantity to freeze
+    fn freeze(
+        &self,
+        order: &PaperOrder,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> std::result::Result<(Decimal, Decimal), &'static str> {
+        match order.order.side {
+            OrderSide::Sell => {
+                let available = self
+                    .positions
+                    .get(&order.order.symbol)
+                    .map(|position| position.quantity - position.frozen)
+                    .unwrap_or_default();
+                if available < quantity {
+                    return Err("insufficient position");
+                }
+                Ok((Decimal::ZERO, quantity))
+            }
+            _ => {
+                // market orders are checked when filled
+                let Some(price) = price
+                    .or(order.order.trigger_price)
+                    .or_else(|| self.last_price(&order.order.symbol))
+                else {
+                    return Ok((Decimal::ZERO, Decimal::ZERO));
+                };
+                let amount = price * quantity;
+                let available = self
+                    .cash
+                    .get(&order.order.currency)
+                    .map(Cash::available)
+                    .unwrap_or_default();
+                if available < amount {
+                    return Err("insufficient buying power");
+                }
+                Ok((amount, Decimal::ZERO))
+            }
+        }
+    }
+
+    fn apply_freeze(&mut self, idx: usize, cash: Decimal, quantity: Decimal) {
+        let order = &mut self.orders[idx];
+        order.frozen_cash = cash;
+        order.frozen_quantity = quantity;
+        if !cash.is_zero() {
+            self.cash
+                .entry(order.order.currency.clone())
+                .or_default()
+                .frozen += cash;
+        }
+        if !quantity.is_zero()
+            && let Some(position) = self.positions.get_mut(&order.order.symbol)
+        {
+            position.frozen += quantity;
+        }
+    }
+
+    fn unfreeze(&mut self, idx: usize) {
+        let order = &self.orders[idx];
+        if let Some(cash) = self.cash.get_mut(&order.order.currency) {
+            cash.frozen -= order.frozen_cash;
+        }
+        if let Some(position) = self.positions.get_mut(&order.order.symbol) {
+            position.frozen -= order.frozen_quantity;
+        }
+    }
+
+    fn fill(&mut self, idx: usize, price: Decimal, now: OffsetDateTime) -> PushOrderChanged {
+        self.unfreeze(idx);
+        let order = &self.orders[idx];
+        let quantity = order.order.quantity;
+        let amount = price * quantity;
+        let currency = order.order.currency.clone();
+        let symbol = order.order.symbol.clone();
+
+        match order.order.side {
+            OrderSide::Sell => {
+                let position = self
+                    .positions
+                    .get_mut(&symbol)
+                    .expect("BUG: sell order without position");
+                position.quantity -= quantity;
+                if position.quantity.is_zero() {
+                    self.positions.remove(&symbol);
+                }
+                self.cash.entry(currency).or_default().total += amount;
+            }
+            _ => {
+                let cash = self.cash.entry(currency.clone()).or_default();
+                if cash.available() < amount {
+                    let order = &mut self.orders[idx];
+                    order.frozen_cash = Decimal::ZERO;
+                    order.frozen_quantity = Decimal::ZERO;
+                    order.set_status(OrderStatus::Rejected, "insufficient buying power", now);
+                    return order.to_push();
+                }
+                cash.total -= amount;
+                let position = self.positions.entry(symbol.clone()).or_insert(Position {
+                    quantity: Decimal::ZERO,
+                    frozen: Decimal::ZERO,
+                    cost_price: Decimal::ZERO,
+                    currency,
+                    market: parse_market_from_symbol(&symbol).unwrap_or_default(),
+                });
+                position.cost_price = (position.cost_price * position.quantity + amount)
+                    / (position.quantity + quantity);
+                position.quantity += quantity;
+            }
+        }
+
+        let trade_id = self.next_trade_id.to_string();
+        self.next_trade_id += 1;
+        let order = &mut self.orders[idx];
+        self.executions.push(Execution {
+            order_id: order.order.order_id.clone(),
+            trade_id,
+            symbol,
+            trade_done_at: now,
+            quantity,
+            price,
+        });
+
+        order.frozen_cash = Decimal::ZERO;
+        order.frozen_quantity = Decimal::ZERO;
+        order.order.executed_quantity = quantity;
+        order.order.executed_price = Some(price);
+        order.last_share = Some(quantity);
+        order.last_price = Some(price);
+        order.set_status(OrderStatus::Filled, "", now);
+        order.to_push()
+    }
+}
+
+fn check_order_params(
+    kind: OrderKind,
+    order_type: OrderType,
+    price: Option<Decimal>,
+    trigger_price: Option<Decimal>,
+    trailing_amount: Option<Decimal>,
+    trailing_percent: Option<Decimal>,
+) -> Result<()> {
+    let missing = |field: &str| Err(Error::PaperTrading(format!("missing {field}")));
+    match kind {
+        OrderKind::Limit | OrderKind::TouchedLimit if price.is_none() => missing("submitted_price"),
+        OrderKind::TouchedMarket | OrderKind::TouchedLimit if trigger_price.is_none() => {
+            missing("trigger_price")
+        }
+        OrderKind::TrailingMarket | OrderKind::TrailingLimit => match order_type {
+            OrderType::TSMAMT | OrderType::TSLPAMT if trailing_amount.is_none() => {
+                missing("trailing_amount")
+            }
+            OrderType::TSMPCT | OrderType::TSLPPCT if trailing_percent.is_none() => {
+                missing("trailing_percent")
+            }
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Returns `true` if the conditional order is triggered at `price`
+///
+/// `LIT`/`MIT` orders are triggered when the price reaches the trigger price
+/// from the side of the first price seen. Trailing orders follow the highest
+/// (sell) or lowest (buy) price and are triggered when the price moves back
+/// by the trailing amount or percent.
+fn check_trigger(order: &mut PaperOrder, price: Decimal) -> bool {
+    if !order.kind.is_trailing() {
+        let reference_price = *order.reference_price.get_or_insert(price);
+        let Some(trigger_price) = order.order.trigger_price else {
+            return false;
+        };
+        return if reference_price > trigger_price {
+            price <= trigger_price
+        } else {
+            price >= trigger_price
+        };
+    }
+
+    let is_sell = order.order.side == OrderSide::Sell;
+    let extreme_price = match order.extreme_price {
+        Some(extreme_price) if is_sell => extreme_price.max(price),
+        Some(extreme_price) => extreme_price.min(price),
+        None => price,
+    };
+    order.extreme_price = Some(extreme_price);
+
+    let offset = match (order.order.trailing_amount, order.order.trailing_percent) {
+        (Some(amount), _)
+            if matches!(
+                order.order.order_type,
+                OrderType::TSMAMT | OrderType::TSLPAMT
+            ) =>
+        {
+            amount
+        }
+        (_, Some(percent)) => extreme_price * percent / Decimal::ONE_HUNDRED,
+        _ => return false,
+    };
+    let limit_offset = order.order.limit_offset.unwrap_or_default();
+    let (trigger_price, limit_price) = if is_sell {
+        let trigger_price = extreme_price - offset;
+        (trigger_price, trigger_price - limit_offset)
+    } else {
+        let trigger_price = extreme_price + offset;
+        (trigger_price, trigger_price + limit_offset)
+    };
+    order.order.trigger_price = Some(trigger_price);
+    if order.kind == OrderKind::TrailingLimit {
+        order.order.price = Some(limit_price);
+    }
+
+    if is_sell {
+        price <= trigger_price
+    } else {
+        price >= trigger_price
+    }
+}
+
+fn currency_of(market: Market) -> &'static str {
+    match market {
+        Market::HK => "HKD",
+        Market::CN => "CNY",
+        Market::SG => "SGD",
+        _ => "USD",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trade::TimeInForceType;
+
+    fn account() -> Account {
+        Account::new([("USD".to_string(), decimal!(10000))])
+    }
+
+    fn now() -> OffsetDateTime {
+        OffsetDateTime::UNIX_EPOCH
+    }
+
+    fn buy(order_type: OrderType) -> SubmitOrderOptions {
+        SubmitOrderOptions::new(
+            "AAPL.US",
+            order_type,
+            OrderSide::Buy,
+            decimal!(10),
+            TimeInForceType::Day,
+        )
+    }
+
+    #[test]
+    fn limit_order() {
+        let mut account = account();
+        let opts = buy(OrderType::LO).submitted_price(decimal!(100));
+        let push = account.submit_order(&opts, now()).unwrap();
+        assert_eq!(push.status, OrderStatus::New);
+        assert_eq!(account.account_balance(None)[0].buy_power, decimal!(9000));
+
+        assert!(
+            account
+                .update_price("AAPL.US", decimal!(101), now())
+                .is_empty()
+        );
+        let pushes = account.update_price("AAPL.US", decimal!(99), now());
+        assert_eq!(pushes.len(), 1);
+        assert_eq!(pushes[0].status, OrderStatus::Filled);
+        assert_eq!(pushes[0].executed_price, Some(decimal!(99)));
+
+        let balance = &account.account_balance(None)[0];
+        assert_eq!(balance.total_cash, decimal!(9010));
+        assert_eq!(balance.net_assets, decimal!(10000));
+        let positions = account.stock_positions(&[]);
+        assert_eq!(positions.channels[0].positions[0].quantity, decimal!(10));
+        assert_eq!(positions.channels[0].positions[0].cost_price, decimal!(99));
+    }
+
+    #[test]
+    fn insufficient_buying_power() {
+        let mut account = account();
+        let opts = buy(OrderType::LO).submitted_price(decimal!(2000));
+        let push = account.submit_order(&opts, now()).unwrap();
+        assert_eq!(push.status, OrderStatus::Rejected);
+
+        let opts = SubmitOrderOptions::new(
+            "AAPL.US",
+            OrderType::MO,
+            OrderSide::Sell,
+            decimal!(1),
+            TimeInForceType::Day,
+        );
+        let push = account.submit_order(&opts, now()).unwrap();
+        assert_eq!(push.status, OrderStatus::Rejected);
+        assert_eq!(push.msg, "insufficient position");
+    }
+
+    #[test]
+    fn touched_order() {
+        let mut account = account();
+        let opts = buy(OrderType::MIT).trigger_price(decimal!(105));
+        account.submit_order(&opts, now()).unwrap();
+
+        assert!(
+            account
+                .update_price("AAPL.US", decimal!(100), now())
+                .is_empty()
+        );
+        let pushes = account.update_price("AAPL.US", decimal!(106), now());
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(pushes[0].trigger_status, Some(TriggerStatus::Released));
+        assert_eq!(pushes[1].status, OrderStatus::Filled);
+        assert_eq!(pushes[1].executed_price, Some(decimal!(106)));
+    }
+
+    #[test]
+    fn trailing_order() {
+        let mut account = account();
+        let opts = buy(OrderType::MO);
+        account.submit_order(&opts, now()).unwrap();
+        account.update_price("AAPL.US", decimal!(100), now());
+
+        let opts = SubmitOrderOptions::new(
+            "AAPL.US",
+            OrderType::TSMPCT,
+            OrderSide::Sell,
+            decimal!(10),
+            TimeInForceType::Day,
+        )
+        .trailing_percent(decimal!(10));
+        let push = account.submit_order(&opts, now()).unwrap();
+        assert_eq!(push.status, OrderStatus::New);
+
+        assert!(
+            account
+                .update_price("AAPL.US", decimal!(120), now())
+                .is_empty()
+        );
+        assert!(
+            account
+                .update_price("AAPL.US", decimal!(110), now())
+                .is_empty()
+        );
+        let pushes = account.update_price("AAPL.US", decimal!(108), now());
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(pushes[1].status, OrderStatus::Filled);
+        assert!(
+            account.stock_positions(&[]).channels[0]
+                .positions
+                .is_empty()
+        );
+        assert_eq!(account.account_balance(None)[0].total_cash, decimal!(10080));
+    }
+
+    #[test]
+    fn replace_and_cancel() {
+        let mut account = account();
+        let opts = buy(OrderType::LO).submitted_price(decimal!(100));
+        let order_id = account.submit_order(&opts, now()).unwrap().order_id;
+
+        let push = account
+            .replace_order(
+                &ReplaceOrderOptions::new(&order_id, decimal!(20)).price(decimal!(90)),
+                now(),
+            )
+            .unwrap();
+        assert_eq!(push.status, OrderStatus::Replaced);
+        assert_eq!(account.account_balance(None)[0].buy_power, decimal!(8200));
+        assert!(
+            account
+                .replace_order(&ReplaceOrderOptions::new(&order_id, decimal!(200)), now())
+                .is_err()
+        );
+
+        let push = account.cancel_order(&order_id, now()).unwrap();
+        assert_eq!(push.status, OrderStatus::Canceled);
+        assert_eq!(account.account_balance(None)[0].buy_power, decimal!(10000));
+        assert!(account.cancel_order(&order_id, now()).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use futures_util::StreamExt;
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    QuoteContext, Result,
+    push_channel::PushSender,
+    quote::{PushQuote, PushStream, SubFlags},
+    trade::{
+        AccountBalance, EstimateMaxPurchaseQuantityOptions, EstimateMaxPurchaseQuantityResponse,
+        Execution, Order, OrderDetail, PushEvent, PushOrderChanged, ReplaceOrderOptions,
+        StockPositionsResponse, SubmitOrderOptions, TopicType, paper::account::Account,
+    },
+};
+
+pub(crate) enum Command {
+    Subscribe {
+        topics: Vec<TopicType>,
+        reply_tx: oneshot::Sender<Result<()>>,
+    },
+    Unsubscribe {
+        topics: Vec<TopicType>,
+        reply_tx: oneshot::Sender<Result<()>>,
+    },
+    SubmitOrder {
+        options: SubmitOrderOptions,
+        reply_tx: oneshot::Sender<Result<String>>,
+    },
+    ReplaceOrder {
+        options: ReplaceOrderOptions,
+        reply_tx: oneshot::Sender<Result<()>>,
+    },
+    CancelOrder {
+        order_id: String,
+        reply_tx: oneshot::Sender<Result<()>>,
+    },
+    Orders {
+        reply_tx: oneshot::Sender<Vec<Order>>,
+    },
+    OrderDetail {
+        order_id: String,
+        reply_tx: oneshot::Sender<Result<OrderDetail>>,
+    },
+    Executions {
+        reply_tx: oneshot::Sender<Vec<Execution>>,
+    },
+    AccountBalance {
+        currency: Option<String>,
+        reply_tx: oneshot::Sender<Vec<AccountBalance>>,
+    },
+    StockPositions {
+        symbols: Vec<String>,
+        reply_tx: oneshot::Sender<StockPositionsResponse>,
+    },
+    EstimateMaxPurchaseQuantity {
+        options: EstimateMaxPurchaseQuantityOptions,
+        reply_tx: oneshot::Sender<Result<EstimateMaxPurchaseQuantityResponse>>,
+    },
+}
+
+pub(crate) struct Core {
+    command_rx: mpsc::UnboundedReceiver<Command>,
+    push_tx: PushSender<PushEvent>,
+    quote_ctx: QuoteContext,
+    quote_stream: Option<PushStream<(String, PushQuote)>>,
+    account: Account,
+    subscribed_quotes: HashSet<String>,
+    subscribed_private: bool,
+}
+
+impl Core {
+    pub(crate) fn new(
+        command_rx: mpsc::UnboundedReceiver<Command>,
+        push_tx: PushSender<PushEvent>,
+        quote_ctx: QuoteContext,
+        account: Account,
+    ) -> Self {
+        // the quotes of all the symbols, the symbols of the orders are
+        // subscribed by `watch_symbol`
+        let quote_stream = quote_ctx.quote_stream(Vec::<String>::new());
+        Self {
+            command_rx,
+            push_tx,
+            quote_ctx,
+            quote_stream: Some(quote_stream),
+            account,
+            subscribed_quotes: HashSet::new(),
+            subscribed_private: false,
+        }
+    }
+
+    pub(crate) async fn run(mut self) {
+        loop {
+            self.push_tx.ready().await;
+
+            tokio::select! {
+                item = self.command_rx.recv() => {
+                    match item {
+                        Some(command) => self.handle_command(command).await,
+                        None => return,
+                    }
+                }
+                item = recv_quote(&mut self.quote_stream) => {
+                    match item {
+                        Some((symbol, quote)) => self.handle_quote(&symbol, quote),
+                        None => {
+                            tracing::error!("quote context closed, paper orders will not be matched");
+                            self.quote_stream = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, command: Command) {
+        match command {
+            Command::Subscribe { topics, reply_tx } => {
+                if topics.contains(&TopicType::Private) {
+                    self.subscribed_private = true;
+                }
+                let _ = reply_tx.send(Ok(()));
+            }
+            Command::Unsubscribe { topics, reply_tx } => {
+                if topics.contains(&TopicType::Private) {
+                    self.subscribed_private = false;
+                }
+                let _ = reply_tx.send(Ok(()));
+            }
+            Command::SubmitOrder { options, reply_tx } => {
+                let res = self
+                    .account
+                    .submit_order(&options, OffsetDateTime::now_utc());
+                let res = match res {
+                    Ok(push) => {
+                        let order_id = push.order_id.clone();
+                        self.send_push(push);
+                        self.watch_symbol(&options.symbol).await;
+                        Ok(order_id)
+                    }
+                    Err(err) => Err(err),
+                };
+                let _ = reply_tx.send(res);
+            }
+            Command::ReplaceOrder { options, reply_tx } => {
+                let res = self
+                    .account
+                    .replace_order(&options, OffsetDateTime::now_utc())
+                    .map(|push| {
+                        let symbol = push.symbol.clone();
+                        self.send_push(push);
+                        self.match_orders(&symbol);
+                    });
+                let _ = reply_tx.send(res);
+            }
+            Command::CancelOrder { order_id, reply_tx } => {
+                let res = self
+                    .account
+                    .cancel_order(&order_id, OffsetDateTime::now_utc())
+                    .map(|push| self.send_push(push));
+                let _ = reply_tx.send(res);
+            }
+            Command::Orders { reply_tx } => {
+                let _ = reply_tx.send(self.account.orders().cloned().collect());
+            }
+            Command::OrderDetail { order_id, reply_tx } => {
+                let _ = reply_tx.send(self.account.order_detail(&order_id));
+            }
+            Command::Executions { reply_tx } => {
+                let _ = reply_tx.send(self.account.executions().to_vec());
+            }
+            Command::AccountBalance { currency, reply_tx } => {
+                let _ = reply_tx.send(self.account.account_balance(currency.as_deref()));
+            }
+            Command::StockPositions { symbols, reply_tx } => {
+                let _ = reply_tx.send(self.account.stock_positions(&symbols));
+            }
+            Command::EstimateMaxPurchaseQuantity { options, reply_tx } => {
+                let _ = reply_tx.send(self.account.estimate_max_purchase_quantity(&options));
+            }
+        }
+    }
+
+    fn handle_quote(&mut self, symbol: &str, quote: PushQuote) {
+        for push in self
+            .account
+            .update_price(symbol, quote.last_done, OffsetDateTime::now_utc())
+        {
+            self.send_push(push);
+        }
+    }
+
+    /// Subscribes the quote of `symbol` if it is not subscribed yet, or
+    /// matches the orders with the last known price
+    async fn watch_symbol(&mut self, symbol: &str) {
+        if self.subscribed_quotes.contains(symbol) {
+            self.match_orders(symbol);
+            return;
+        }
+
+        match self
+            .quote_ctx
+            .subscribe([symbol], SubFlags::QUOTE, true)
+            .await
+        {
+            Ok(()) => {
+                self.subscribed_quotes.insert(symbol.to_string());
+            }
+            Err(err) => tracing::error!(symbol = symbol, error = %err, "failed to subscribe quote"),
+        }
+    }
+
+    fn match_orders(&mut self, symbol: &str) {
+        if let Some(price) = self.account.last_price(symbol) {
+            for push in self
+                .account
+                .update_price(symbol, price, OffsetDateTime::now_utc())
+            {
+                self.send_push(push);
+            }
+        }
+    }
+
+    fn send_push(&self, push: PushOrderChanged) {
+        if self.subscribed_private {
+            self.push_tx.send(PushEvent::OrderChanged(push));
+        }
+    }
+}
+
+async fn recv_quote(
+    stream: &mut Option<PushStream<(String, PushQuote)>>,
+) -> Option<(String, PushQuote)> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => std::future::pending().await,
+    }
+}
@@ -0,0 +1,250 @@
+//! Paper trading, simulates the orders locally against the live quotes
+
+mod account;
+mod core;
+
+use std::sync::Arc;
+
+use longport_wscli::WsClientError;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{Subscriber, instrument::WithSubscriber};
+
+use self::{
+    account::Account,
+    core::{Command, Core},
+};
+use crate::{
+    Config, ConnectionEvent, Market, QuoteContext, Result,
+    push_channel::PushSender,
+    quote::parse_market_from_symbol,
+    trade::{
+        AccountBalance, EstimateMaxPurchaseQuantityOptions, EstimateMaxPurchaseQuantityResponse,
+        Execution, GetHistoryExecutionsOptions, GetHistoryOrdersOptions, GetTodayExecutionsOptions,
+        GetTodayOrdersOptions, Order, OrderDetail, OrderSide, OrderStatus, PushEvent,
+        ReplaceOrderOptions, StockPositionsResponse, SubmitOrderOptions, TopicType,
+    },
+};
+
+const DEFAULT_INITIAL_CASH: i64 = 1_000_000;
+
+pub(crate) struct PaperTrading {
+    command_tx: mpsc::UnboundedSender<Command>,
+    quote_ctx: QuoteContext,
+}
+
+impl PaperTrading {
+    /// Creates the paper trading backend, the orders are matched against the
+    /// quotes of `quote_ctx`, or of a dedicated `QuoteContext` if it is `None`
+    pub(crate) async fn try_new(
+        config: Arc<Config>,
+        quote_ctx: Option<QuoteContext>,
+        push_tx: PushSender<PushEvent>,
+        log_subscriber: Arc<dyn Subscriber + Send + Sync>,
+    ) -> Result<Self> {
+        let mut initial_cash = config.paper_initial_cash.clone();
+        if initial_cash.is_empty() {
+            initial_cash = ["HKD", "USD"]
+                .into_iter()
+                .map(|currency| (currency.to_string(), Decimal::from(DEFAULT_INITIAL_CASH)))
+                .collect();
+        }
+
+        let quote_ctx = match quote_ctx {
+            Some(quote_ctx) => quote_ctx,
+            None => {
+                // the quotes used for matching must not be written to the
+                // recording of the user's quote context
+                let quote_config = Arc::new(Config {
+                    quote_record_path: None,
+                    ..(*config).clone()
+                });
+                QuoteContext::try_new(quote_config).await?.0
+            }
+        };
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let core = Core::new(
+            command_rx,
+            push_tx,
+            quote_ctx.clone(),
+            Account::new(initial_cash),
+        );
+        tokio::spawn(core.run().with_subscriber(log_subscriber));
+        Ok(Self {
+            command_tx,
+            quote_ctx,
+        })
+    }
+
+    pub(crate) fn connection_events(&self) -> mpsc::UnboundedReceiver<ConnectionEvent> {
+        self.quote_ctx.connection_events()
+    }
+
+    async fn request<T>(&self, f: impl FnOnce(oneshot::Sender<T>) -> Command) -> Result<T> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.command_tx
+            .send(f(reply_tx))
+            .map_err(|_| WsClientError::ClientClosed)?;
+        Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
+    }
+
+    pub(crate) async fn subscribe(&self, topics: Vec<TopicType>) -> Result<()> {
+        self.request(|reply_tx| Command::Subscribe { topics, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn unsubscribe(&self, topics: Vec<TopicType>) -> Result<()> {
+        self.request(|reply_tx| Command::Unsubscribe { topics, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn submit_order(&self, options: SubmitOrderOptions) -> Result<String> {
+        self.request(|reply_tx| Command::SubmitOrder { options, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
+        self.request(|reply_tx| Command::ReplaceOrder { options, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn cancel_order(&self, order_id: String) -> Result<()> {
+        self.request(|reply_tx| Command::CancelOrder { order_id, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn today_orders(&self, options: GetTodayOrdersOptions) -> Result<Vec<Order>> {
+        let orders = self
+            .request(|reply_tx| Command::Orders { reply_tx })
+            .await?;
+        Ok(orders
+            .into_iter()
+            .filter(|order| {
+                matches_order(
+                    order,
+                    options.symbol.as_deref(),
+                    &options.status,
+                    options.side,
+                    options.market,
+                ) && options
+                    .order_id
+                    .as_ref()
+                    .is_none_or(|order_id| &order.order_id == order_id)
+            })
+            .collect())
+    }
+
+    pub(crate) async fn history_orders(
+        &self,
+        options: GetHistoryOrdersOptions,
+    ) -> Result<Vec<Order>> {
+        let orders = self
+            .request(|reply_tx| Command::Orders { reply_tx })
+            .await?;
+        Ok(orders
+            .into_iter()
+            .filter(|order| {
+                matches_order(
+                    order,
+                    options.symbol.as_deref(),
+                    &options.status,
+                    options.side,
+                    options.market,
+                ) && matches_time(order.submitted_at, options.start_at, options.end_at)
+            })
+            .collect())
+    }
+
+    pub(crate) async fn order_detail(&self, order_id: String) -> Result<OrderDetail> {
+        self.request(|reply_tx| Command::OrderDetail { order_id, reply_tx })
+            .await?
+    }
+
+    pub(crate) async fn today_executions(
+        &self,
+        options: GetTodayExecutionsOptions,
+    ) -> Result<Vec<Execution>> {
+        let executions = self
+            .request(|reply_tx| Command::Executions { reply_tx })
+            .await?;
+        Ok(executions
+            .into_iter()
+            .filter(|execution| {
+                options
+                    .symbol
+                    .as_ref()
+                    .is_none_or(|symbol| &execution.symbol == symbol)
+                    && options
+                        .order_id
+                        .as_ref()
+                        .is_none_or(|order_id| &execution.order_id == order_id)
+            })
+            .collect())
+    }
+
+    pub(crate) async fn history_executions(
+        &self,
+        options: GetHistoryExecutionsOptions,
+    ) -> Result<Vec<Execution>> {
+        let executions = self
+            .request(|reply_tx| Command::Executions { reply_tx })
+            .await?;
+        Ok(executions
+            .into_iter()
+            .filter(|execution| {
+                options
+                    .symbol
+                    .as_ref()
+                    .is_none_or(|symbol| &execution.symbol == symbol)
+                    && matches_time(execution.trade_done_at, options.start_at, options.end_at)
+            })
+            .collect())
+    }
+
+    pub(crate) async fn account_balance(
+        &self,
+        currency: Option<String>,
+    ) -> Result<Vec<AccountBalance>> {
+        self.request(|reply_tx| Command::AccountBalance { currency, reply_tx })
+            .await
+    }
+
+    pub(crate) async fn stock_positions(
+        &self,
+        symbols: Vec<String>,
+    ) -> Result<StockPositionsResponse> {
+        self.request(|reply_tx| Command::StockPositions { symbols, reply_tx })
+            .await
+    }
+
+    pub(crate) async fn estimate_max_purchase_quantity(
+        &self,
+        options: EstimateMaxPurchaseQuantityOptions,
+    ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        self.request(|reply_tx| Command::EstimateMaxPurchaseQuantity { options, reply_tx })
+            .await?
+    }
+}
+
+fn matches_order(
+    order: &Order,
+    symbol: Option<&str>,
+    status: &[OrderStatus],
+    side: Option<OrderSide>,
+    market: Option<Market>,
+) -> bool {
+    symbol.is_none_or(|symbol| order.symbol == symbol)
+        && (status.is_empty() || status.contains(&order.status))
+        && side.is_none_or(|side| order.side == side)
+        && market.is_none_or(|market| parse_market_from_symbol(&order.symbol) == Some(market))
+}
+
+fn matches_time(
+    time: OffsetDateTime,
+    start_at: Option<OffsetDateTime>,
+    end_at: Option<OffsetDateTime>,
+) -> bool {
+    start_at.is_none_or(|start_at| time >= start_at) && end_at.is_none_or(|end_at| time <= end_at)
+}
@@ -6,16 +6,16 @@ use crate::trade::{OrderSide, OrderType};
 /// Options for estimate maximum purchase quantity
 #[derive(Debug, Serialize, Clone)]
 pub struct EstimateMaxPurchaseQuantityOptions {
-    symbol: String,
-    order_type: OrderType,
+    pub(crate) symbol: String,
+    pub(crate) order_type: OrderType,
     #[serde(skip_serializing_if = "Option::is_none")]
-    price: Option<Decimal>,
-    side: OrderSide,
+    pub(crate) price: Option<Decimal>,
+    pub(crate) side: OrderSide,
     #[serde(skip_serializing_if = "Option::is_none")]
-    currency: Option<String>,
+    pub(crate) currency: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    order_id: Option<String>,
-    fractional_shares: bool,
+    pub(crate) order_id: Option<String>,
+    pub(crate) fractional_shares: bool,
 }
 
 impl EstimateMaxPurchaseQuantityOptions {
@@ -7,17 +7,17 @@ use crate::serde_utils;
 #[derive(Debug, Serialize, Default, Clone)]
 pub struct GetHistoryExecutionsOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
-    symbol: Option<String>,
+    pub(crate) symbol: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "serde_utils::timestamp_opt"
     )]
-    start_at: Option<OffsetDateTime>,
+    pub(crate) start_at: Option<OffsetDateTime>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "serde_utils::timestamp_opt"
     )]
-    end_at: Option<OffsetDateTime>,
+    pub(crate) end_at: Option<OffsetDateTime>,
 }
 
 impl GetHistoryExecutionsOptions {
@@ -10,23 +10,23 @@ use crate::{
 #[derive(Debug, Default, Serialize, Clone)]
 pub struct GetHistoryOrdersOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
-    symbol: Option<String>,
+    pub(crate) symbol: Option<String>,
     #[serde(skip_serializing_if = "<[_]>::is_empty")]
-    status: Vec<OrderStatus>,
+    pub(crate) status: Vec<OrderStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    side: Option<OrderSide>,
+    pub(crate) side: Option<OrderSide>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    market: Option<Market>,
+    pub(crate) market: Option<Market>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "serde_utils::timestamp_opt"
     )]
-    start_at: Option<OffsetDateTime>,
+    pub(crate) start_at: Option<OffsetDateTime>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "serde_utils::timestamp_opt"
     )]
-    end_at: Option<OffsetDateTime>,
+    pub(crate) end_at: Option<OffsetDateTime>,
 }
 
 impl GetHistoryOrdersOptions {
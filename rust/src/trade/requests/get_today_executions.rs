@@ -4,9 +4,9 @@ use serde::Serialize;
 #[derive(Debug, Default, Serialize, Clone)]
 pub struct GetTodayExecutionsOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
-    symbol: Option<String>,
+    pub(crate) symbol: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    order_id: Option<String>,
+    pub(crate) order_id: Option<String>,
 }
 
 impl GetTodayExecutionsOptions {
@@ -9,15 +9,15 @@ use crate::{
 #[derive(Debug, Default, Serialize, Clone)]
 pub struct GetTodayOrdersOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
-    symbol: Option<String>,
+    pub(crate) symbol: Option<String>,
     #[serde(skip_serializing_if = "<[_]>::is_empty")]
-    status: Vec<OrderStatus>,
+    pub(crate) status: Vec<OrderStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    side: Option<OrderSide>,
+    pub(crate) side: Option<OrderSide>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    market: Option<Market>,
+    pub(crate) market: Option<Market>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    order_id: Option<String>,
+    pub(crate) order_id: Option<String>,
 }
 
 impl GetTodayOrdersOptions {
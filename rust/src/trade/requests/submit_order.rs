@@ -10,27 +10,27 @@ use crate::{
 /// Options for submit order request
 #[derive(Debug, Serialize, Clone)]
 pub struct SubmitOrderOptions {
-    symbol: String,
-    order_type: OrderType,
-    side: OrderSide,
-    submitted_quantity: Decimal,
-    time_in_force: TimeInForceType,
+    pub(crate) symbol: String,
+    pub(crate) order_type: OrderType,
+    pub(crate) side: OrderSide,
+    pub(crate) submitted_quantity: Decimal,
+    pub(crate) time_in_force: TimeInForceType,
     #[serde(skip_serializing_if = "Option::is_none")]
-    submitted_price: Option<Decimal>,
+    pub(crate) submitted_price: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    trigger_price: Option<Decimal>,
+    pub(crate) trigger_price: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit_offset: Option<Decimal>,
+    pub(crate) limit_offset: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    trailing_amount: Option<Decimal>,
+    pub(crate) trailing_amount: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    trailing_percent: Option<Decimal>,
+    pub(crate) trailing_percent: Option<Decimal>,
     #[serde(with = "serde_utils::date_opt")]
-    expire_date: Option<Date>,
+    pub(crate) expire_date: Option<Date>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    outside_rth: Option<OutsideRTH>,
+    pub(crate) outside_rth: Option<OutsideRTH>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    remark: Option<String>,
+    pub(crate) remark: Option<String>,
 }
 
 impl SubmitOrderOptions {
@@ -0,0 +1,1464 @@
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicI64, AtomicU32, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use longport::{
+    AccountManager, Config, ConnectionEvent, PushOverflowPolicy, PushReceiver, RetryPolicy,
+    TlsConfig, TokenRefresher,
+    portfolio::{Portfolio, PortfolioEvent},
+    quote::{
+        AdjustType, AggregatePeriod, BrokerSide, Period, PushEventDetail, QuoteContext,
+        SortOrderType, SubFlags, TradeSessions, WarrantFilter, WarrantSortBy, WarrantType,
+    },
+    trade::{
+        OrderSide, OrderStatus, OrderType, PushEvent, SubmitOrderOptions, TimeInForceType,
+        TopicType, TradeContext,
+    },
+};
+use longport_mock::{Channel, Method, MockServer, cmd_code};
+use longport_proto::quote::{
+    self as proto, PushDepth, PushQuote, SecurityCandlestickResponse,
+    SecurityHistoryCandlestickRequest, SubType,
+};
+use rust_decimal::Decimal;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+use serde_json::json;
+use time::macros::datetime;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    time::timeout,
+};
+use tokio_rustls::TlsAcceptor;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn create_config(server: &MockServer) -> Arc<Config> {
+    Arc::new(
+        Config::new("app_key", "app_secret", "access_token")
+            .http_url(server.http_url())
+            .quote_ws_url(server.quote_ws_url())
+            .trade_ws_url(server.trade_ws_url())
+            .dont_print_quote_packages(),
+    )
+}
+
+async fn recv<T>(rx: &mut PushReceiver<T>) -> T {
+    timeout(TIMEOUT, rx.recv())
+        .await
+        .expect("timeout")
+        .expect("channel closed")
+}
+
+async fn recv_connection_event(
+    rx: &mut mpsc::UnboundedReceiver<ConnectionEvent>,
+) -> ConnectionEvent {
+    timeout(TIMEOUT, rx.recv())
+        .await
+        .expect("timeout")
+        .expect("channel closed")
+}
+
+async fn next<S: Stream + Unpin>(stream: &mut S) -> S::Item {
+    timeout(TIMEOUT, stream.next())
+        .await
+        .expect("timeout")
+        .expect("stream closed")
+}
+
+fn push_quote(last_done: &str) -> PushQuote {
+    PushQuote {
+        symbol: "700.HK".to_string(),
+        sequence: 1,
+        last_done: last_done.to_string(),
+        timestamp: 1700000000,
+        volume: 100,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn quote_push() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, mut receiver) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    assert_eq!(ctx.member_id(), 1);
+
+    ctx.subscribe(["700.HK"], SubFlags::QUOTE, false)
+        .await
+        .unwrap();
+    assert_eq!(
+        server.subscriptions().get("700.HK"),
+        Some(&vec![SubType::Quote])
+    );
+
+    assert_eq!(server.push_quote(push_quote("380.5")), 1);
+    let event = recv(&mut receiver).await;
+    assert_eq!(event.symbol, "700.HK");
+    match event.detail {
+        PushEventDetail::Quote(quote) => assert_eq!(quote.last_done, "380.5".parse().unwrap()),
+        detail => panic!("unexpected push event: {detail:?}"),
+    }
+
+    ctx.unsubscribe(["700.HK"], SubFlags::QUOTE).await.unwrap();
+    assert!(server.subscriptions().is_empty());
+}
+
+#[tokio::test]
+async fn quote_push_conflate() {
+    let server = MockServer::start().await.unwrap();
+    let config = (*create_config(&server))
+        .clone()
+        .push_channel(1, PushOverflowPolicy::ConflatePerSymbol);
+    let (ctx, mut receiver) = QuoteContext::try_new(Arc::new(config)).await.unwrap();
+
+    ctx.subscribe(["700.HK"], SubFlags::QUOTE, false)
+        .await
+        .unwrap();
+    for last_done in ["380", "381", "382"] {
+        server.push_quote(push_quote(last_done));
+    }
+    timeout(TIMEOUT, async {
+        while ctx.dropped_push_events() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("timeout");
+
+    assert_eq!(receiver.len(), 1);
+    match recv(&mut receiver).await.detail {
+        PushEventDetail::Quote(quote) => assert_eq!(quote.last_done, "382".parse().unwrap()),
+        detail => panic!("unexpected push event: {detail:?}"),
+    }
+}
+
+#[tokio::test]
+async fn quote_push_streams() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, receiver) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    drop(receiver);
+
+    let mut quote_700 = ctx.quote_stream(["700.HK"]);
+    let mut quote_all = ctx.quote_stream(Vec::<String>::new());
+    let mut depth = ctx.depth_stream(["700.HK"]);
+    ctx.subscribe(
+        ["700.HK", "AAPL.US"],
+        SubFlags::QUOTE | SubFlags::DEPTH,
+        false,
+    )
+    .await
+    .unwrap();
+
+    server.push_quote(PushQuote {
+        symbol: "AAPL.US".to_string(),
+        ..push_quote("230")
+    });
+    server.push_quote(push_quote("380"));
+    server.push_depth(PushDepth {
+        symbol: "700.HK".to_string(),
+        ..Default::default()
+    });
+
+    let (symbol, quote) = next(&mut quote_700).await;
+    assert_eq!(symbol, "700.HK");
+    assert_eq!(quote.last_done, "380".parse().unwrap());
+    assert_eq!(next(&mut quote_all).await.0, "AAPL.US");
+    assert_eq!(next(&mut quote_all).await.0, "700.HK");
+    assert_eq!(next(&mut depth).await.0, "700.HK");
+
+    drop(ctx);
+    assert!(timeout(TIMEOUT, quote_700.next()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn quote_order_book() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, _) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+
+    let mut changes = ctx.book_changed_stream(["700.HK"]);
+    ctx.subscribe(["700.HK"], SubFlags::DEPTH, false)
+        .await
+        .unwrap();
+
+    let depth = |position, price: &str, volume| proto::Depth {
+        position,
+        price: price.to_string(),
+        volume,
+        order_num: 1,
+    };
+    server.push_depth(PushDepth {
+        symbol: "700.HK".to_string(),
+        ask: vec![depth(1, "380.2", 100), depth(2, "380.4", 200)],
+        bid: vec![depth(1, "380", 300)],
+        ..Default::default()
+    });
+    server.push_depth(PushDepth {
+        symbol: "700.HK".to_string(),
+        ask: vec![depth(2, "380.4", 500)],
+        ..Default::default()
+    });
+
+    let (symbol, changed) = next(&mut changes).await;
+    assert_eq!(symbol, "700.HK");
+    assert_eq!((changed.asks.len(), changed.bids.len()), (2, 1));
+    let (_, changed) = next(&mut changes).await;
+    assert!(changed.bids.is_empty());
+    assert_eq!(changed.asks.len(), 1);
+    assert_eq!(changed.asks[0].price, "380.4".parse().unwrap());
+    assert_eq!(changed.asks[0].volume, 500);
+
+    let book = ctx.realtime_order_book("700.HK").await.unwrap();
+    assert_eq!(book.spread(), Some("0.2".parse().unwrap()));
+    assert_eq!(book.ask_volume(5), 600);
+    assert_eq!(book.imbalance(1), Some(Decimal::new(5, 1)));
+}
+
+#[tokio::test]
+async fn quote_broker_queue_view() {
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(cmd_code::GET_BROKER_IDS, |_: ()| {
+        Ok(proto::ParticipantBrokerIdsResponse {
+            participant_broker_numbers: vec![proto::ParticipantInfo {
+                broker_ids: vec![1, 2],
+                participant_name_cn: "经纪A".to_string(),
+                participant_name_en: "Broker A".to_string(),
+                participant_name_hk: "經紀A".to_string(),
+            }],
+        })
+    });
+    let (ctx, _) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+
+    let mut brokers = ctx.brokers_stream(["700.HK"]);
+    ctx.subscribe(["700.HK"], SubFlags::BROKER, false)
+        .await
+        .unwrap();
+
+    let brokers_at = |position, broker_ids: &[i32]| proto::Brokers {
+        position,
+        broker_ids: broker_ids.to_vec(),
+    };
+    server.push_brokers(proto::PushBrokers {
+        symbol: "700.HK".to_string(),
+        ask_brokers: vec![brokers_at(1, &[1, 3])],
+        bid_brokers: vec![brokers_at(1, &[2])],
+        ..Default::default()
+    });
+    next(&mut brokers).await;
+
+    let mut view = ctx.broker_queue_view("700.HK").await.unwrap();
+    let queue = view.queue(BrokerSide::Ask);
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue[0].participant.unwrap().name_en, "Broker A");
+    assert!(queue[1].participant.is_none());
+
+    server.push_brokers(proto::PushBrokers {
+        symbol: "700.HK".to_string(),
+        ask_brokers: vec![brokers_at(1, &[3]), brokers_at(2, &[1])],
+        ..Default::default()
+    });
+    let (_, push) = next(&mut brokers).await;
+    let moved = view.apply(&push);
+    assert_eq!(moved.len(), 1);
+    assert_eq!(
+        (moved[0].broker_id, moved[0].from, moved[0].to),
+        (1, Some(1), Some(2))
+    );
+    assert_eq!(view.activity(1).unwrap().ask.moves, 1);
+}
+
+#[tokio::test]
+async fn quote_resubscribe_after_disconnect() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, mut receiver) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    ctx.subscribe(["700.HK"], SubFlags::QUOTE, false)
+        .await
+        .unwrap();
+
+    server.expire_sessions();
+    assert_eq!(server.disconnect(Channel::Quote), 1);
+
+    // wait until the subscriptions are restored on the new connection
+    timeout(TIMEOUT, async {
+        while server.push_quote(push_quote("381")) == 0 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("timeout");
+
+    let event = recv(&mut receiver).await;
+    assert_eq!(event.symbol, "700.HK");
+}
+
+/// Starts a SOCKS5 proxy without authentication, returns its address and the
+/// number of the connections through it
+async fn start_socks5_proxy() -> (SocketAddr, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+
+    tokio::spawn(async move {
+        while let Ok((mut client, _)) = listener.accept().await {
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let mut greeting = [0; 2];
+                client.read_exact(&mut greeting).await?;
+                let mut methods = vec![0; greeting[1] as usize];
+                client.read_exact(&mut methods).await?;
+                client.write_all(&[5, 0]).await?;
+
+                let mut request = [0; 4];
+                client.read_exact(&mut request).await?;
+                let host = match request[3] {
+                    1 => {
+                        let mut ip = [0; 4];
+                        client.read_exact(&mut ip).await?;
+                        std::net::Ipv4Addr::from(ip).to_string()
+                    }
+                    3 => {
+                        let mut host = vec![0; client.read_u8().await? as usize];
+                        client.read_exact(&mut host).await?;
+                        String::from_utf8_lossy(&host).into_owned()
+                    }
+                    _ => unimplemented!(),
+                };
+                let port = client.read_u16().await?;
+                let mut upstream = TcpStream::connect((host.as_str(), port)).await?;
+                client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+                Ok::<_, std::io::Error>(())
+            });
+        }
+    });
+    (addr, connections)
+}
+
+#[tokio::test]
+async fn connect_through_proxy() {
+    let server = MockServer::start().await.unwrap();
+    let (proxy_addr, connections) = start_socks5_proxy().await;
+    let config = Config::clone(&create_config(&server)).proxy(format!("socks5h://{proxy_addr}"));
+    let (ctx, mut receiver) = QuoteContext::try_new(Arc::new(config)).await.unwrap();
+
+    // the socket token request and the websocket connection
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+    ctx.subscribe(["700.HK"], SubFlags::QUOTE, false)
+        .await
+        .unwrap();
+    server.push_quote(push_quote("380.5"));
+    assert_eq!(recv(&mut receiver).await.symbol, "700.HK");
+
+    let config = Config::clone(&create_config(&server)).proxy("ftp://127.0.0.1:21");
+    assert!(QuoteContext::try_new(Arc::new(config)).await.is_err());
+}
+
+/// Starts a TLS server for `localhost` in front of the mock server, returns
+/// its address
+async fn start_tls_server(server: &MockServer) -> SocketAddr {
+    let cert_chain = CertificateDer::pem_slice_iter(include_bytes!("fixtures/server.pem"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let private_key = PrivateKeyDer::from_pem_slice(include_bytes!("fixtures/server.key")).unwrap();
+    let tls_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(cert_chain, private_key)
+    .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(tls_config));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let upstream_addr = server.addr();
+
+    tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let mut client = acceptor.accept(client).await?;
+                let mut upstream = TcpStream::connect(upstream_addr).await?;
+                tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+                Ok::<_, std::io::Error>(())
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn connect_with_tls_config() {
+    const SERVER_FINGERPRINT: &str = "C1:0B:2B:8B:C4:97:FD:1F:72:91:64:57:11:A1:9D:F6:B2:FB:D2:8A:14:3E:E4:AA:03:72:AE:AA:B0:4F:15:A0";
+
+    let server = MockServer::start().await.unwrap();
+    let addr = start_tls_server(&server).await;
+    let create_config = |tls_config: TlsConfig| {
+        Config::new("app_key", "app_secret", "access_token")
+            .http_url(format!("https://localhost:{}", addr.port()))
+            .quote_ws_url(server.quote_ws_url().replace("ws://", "wss://").replace(
+                &server.addr().to_string(),
+                &format!("localhost:{}", addr.port()),
+            ))
+            .dont_print_quote_packages()
+            .tls_config(tls_config)
+            .map(Arc::new)
+            .unwrap()
+    };
+    let tls_config = TlsConfig::new()
+        .add_root_certificates(include_bytes!("fixtures/ca.pem"))
+        .unwrap();
+
+    // the server certificate is not trusted by the built-in root certificates
+    let config = create_config(TlsConfig::new());
+    assert!(QuoteContext::try_new(config).await.is_err());
+
+    let config = create_config(tls_config.clone());
+    QuoteContext::try_new(config).await.unwrap();
+
+    let config = create_config(
+        tls_config
+            .clone()
+            .pin_certificate(SERVER_FINGERPRINT)
+            .unwrap(),
+    );
+    QuoteContext::try_new(config).await.unwrap();
+
+    let config = create_config(tls_config.pin_certificate("00".repeat(32)).unwrap());
+    assert!(QuoteContext::try_new(config).await.is_err());
+
+    assert!(TlsConfig::new().pin_certificate("71:B3").is_err());
+    assert!(TlsConfig::new().add_root_certificates("").is_err());
+}
+
+#[tokio::test]
+async fn trade_connection_events() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, _) = TradeContext::try_new(create_config(&server)).await.unwrap();
+    ctx.subscribe([TopicType::Private]).await.unwrap();
+    let mut events = ctx.connection_events();
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Authenticated
+    ));
+
+    assert_eq!(server.disconnect(Channel::Trade), 1);
+
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Disconnected { .. }
+    ));
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Connecting
+    ));
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Connected
+    ));
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Reconnected {
+            session_reused: true
+        }
+    ));
+    assert!(matches!(
+        recv_connection_event(&mut events).await,
+        ConnectionEvent::Resubscribed
+    ));
+}
+
+#[tokio::test]
+async fn trade_reconnect_give_up() {
+    let server = MockServer::start().await.unwrap();
+    let gave_up = Arc::new(AtomicU32::new(0));
+    let config = Arc::unwrap_or_clone(create_config(&server)).reconnect_policy(
+        RetryPolicy::new()
+            .initial_delay(Duration::from_millis(10))
+            .max_attempts(Some(2))
+            .on_give_up({
+                let gave_up = gave_up.clone();
+                move |attempts, _| gave_up.store(attempts, Ordering::SeqCst)
+            }),
+    );
+    let (ctx, mut receiver) = TradeContext::try_new(Arc::new(config)).await.unwrap();
+    let mut events = ctx.connection_events();
+
+    drop(server);
+
+    loop {
+        if let ConnectionEvent::GaveUp { attempts, .. } = recv_connection_event(&mut events).await {
+            assert_eq!(attempts, 2);
+            break;
+        }
+    }
+    assert_eq!(gave_up.load(Ordering::SeqCst), 2);
+    assert!(matches!(
+        recv_connection_event(&mut ctx.connection_events()).await,
+        ConnectionEvent::GaveUp { attempts: 2, .. }
+    ));
+    assert!(
+        timeout(TIMEOUT, receiver.recv())
+            .await
+            .expect("timeout")
+            .is_none()
+    );
+    assert!(ctx.subscribe([TopicType::Private]).await.is_err());
+}
+
+#[tokio::test]
+async fn refresh_access_token() {
+    let server = MockServer::start().await.unwrap();
+    let config = create_config(&server);
+    let (ctx, _) = TradeContext::try_new(config.clone()).await.unwrap();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let _refresher = TokenRefresher::new(config.clone())
+        .on_refresh(move |access_token, _| {
+            let _ = tx.send(access_token.to_string());
+        })
+        .spawn();
+    let access_token = timeout(TIMEOUT, rx.recv()).await.unwrap().unwrap();
+    assert_ne!(access_token, "access_token");
+    assert_eq!(config.access_token(), access_token);
+
+    ctx.today_orders(None).await.unwrap();
+    assert_eq!(server.last_access_token(), Some(access_token));
+}
+
+#[tokio::test]
+async fn trade_order_lifecycle() {
+    let server = MockServer::start().await.unwrap();
+    let (ctx, mut receiver) = TradeContext::try_new(create_config(&server)).await.unwrap();
+    ctx.subscribe([TopicType::Private]).await.unwrap();
+
+    let resp = ctx
+        .submit_order(
+            SubmitOrderOptions::new(
+                "700.HK",
+                OrderType::LO,
+                OrderSide::Buy,
+                Decimal::from(200),
+                TimeInForceType::Day,
+            )
+            .submitted_price("380".parse().unwrap()),
+        )
+        .await
+        .unwrap();
+
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.order_id, resp.order_id);
+    assert_eq!(changed.status, OrderStatus::New);
+
+    assert!(server.fill_order(&resp.order_id, Decimal::from(100), "379.8".parse().unwrap()));
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.status, OrderStatus::PartialFilled);
+    assert_eq!(changed.executed_quantity, Decimal::from(100));
+
+    let orders = ctx.today_orders(None).await.unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].executed_price, Some("379.8".parse().unwrap()));
+
+    let executions = ctx.today_executions(None).await.unwrap();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].quantity, Decimal::from(100));
+
+    ctx.cancel_order(&resp.order_id).await.unwrap();
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.status, OrderStatus::Canceled);
+    assert!(ctx.cancel_order(&resp.order_id).await.is_err());
+}
+
+#[tokio::test]
+async fn account_manager_routes_accounts() {
+    let quote_server = MockServer::start().await.unwrap();
+    let server1 = MockServer::start().await.unwrap();
+    let server2 = MockServer::start().await.unwrap();
+    let (manager, _, mut receiver) = AccountManager::try_new(
+        create_config(&quote_server),
+        [
+            ("sub2", create_config(&server2)),
+            ("sub1", create_config(&server1)),
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(manager.account_ids().collect::<Vec<_>>(), ["sub1", "sub2"]);
+    assert!(manager.trade("sub3").is_err());
+    manager.subscribe([TopicType::Private]).await.unwrap();
+
+    let resp = manager
+        .trade("sub2")
+        .unwrap()
+        .submit_order(SubmitOrderOptions::new(
+            "700.HK",
+            OrderType::MO,
+            OrderSide::Buy,
+            Decimal::from(100),
+            TimeInForceType::Day,
+        ))
+        .await
+        .unwrap();
+    assert!(server1.orders().is_empty());
+    assert_eq!(server2.orders().len(), 1);
+
+    let event = recv(&mut receiver).await;
+    assert_eq!(event.account_id, "sub2");
+    let PushEvent::OrderChanged(changed) = event.event;
+    assert_eq!(changed.order_id, resp.order_id);
+
+    let resp = manager
+        .trade("sub1")
+        .unwrap()
+        .submit_order(SubmitOrderOptions::new(
+            "700.HK",
+            OrderType::MO,
+            OrderSide::Sell,
+            Decimal::from(100),
+            TimeInForceType::Day,
+        ))
+        .await
+        .unwrap();
+    assert!(server1.fill_order(&resp.order_id, Decimal::from(100), Decimal::from(380)));
+    let event = recv(&mut receiver).await;
+    assert_eq!(event.account_id, "sub1");
+    let PushEvent::OrderChanged(changed) = event.event;
+    assert_eq!(changed.status, OrderStatus::New);
+    let event = recv(&mut receiver).await;
+    assert_eq!(event.account_id, "sub1");
+    let PushEvent::OrderChanged(changed) = event.event;
+    assert_eq!(changed.status, OrderStatus::Filled);
+
+    assert!(
+        AccountManager::try_new(
+            create_config(&quote_server),
+            [
+                ("sub1", create_config(&server1)),
+                ("sub1", create_config(&server2)),
+            ],
+        )
+        .await
+        .is_err()
+    );
+}
+
+#[tokio::test]
+async fn portfolio_valuation() {
+    let server = MockServer::start().await.unwrap();
+    server.set_stock_positions(vec![json!({
+        "account_channel": "lb",
+        "stock_info": [{
+            "symbol": "700.HK",
+            "symbol_name": "Tencent",
+            "quantity": "100",
+            "available_quantity": "100",
+            "currency": "HKD",
+            "cost_price": "300",
+            "market": "HK",
+            "init_quantity": "100",
+        }],
+    })]);
+    server.on_http(Method::GET, "/v1/asset/fund", |_| Ok(json!({ "list": [] })));
+    server.on_quote(
+        cmd_code::GET_REALTIME_QUOTE,
+        |req: proto::MultiSecurityRequest| {
+            Ok(proto::SecurityQuoteResponse {
+                secu_quote: req
+                    .symbol
+                    .into_iter()
+                    .map(|symbol| proto::SecurityQuote {
+                        symbol,
+                        last_done: "320".to_string(),
+                        prev_close: "310".to_string(),
+                        open: "310".to_string(),
+                        high: "320".to_string(),
+                        low: "310".to_string(),
+                        turnover: "0".to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        },
+    );
+
+    let config = create_config(&server);
+    let (quote_ctx, mut quote_receiver) = QuoteContext::try_new(config.clone()).await.unwrap();
+    let (trade_ctx, mut trade_receiver) = TradeContext::try_new(config).await.unwrap();
+    trade_ctx.subscribe([TopicType::Private]).await.unwrap();
+
+    let mut portfolio = Portfolio::load(&trade_ctx, &quote_ctx).await.unwrap();
+    assert_eq!(
+        server.subscriptions().get("700.HK"),
+        Some(&vec![SubType::Quote])
+    );
+    let holding = portfolio.holding("700.HK").unwrap();
+    assert_eq!(holding.unrealized_pnl(), Decimal::from(2000));
+    assert_eq!(holding.day_pnl(), Decimal::from(1000));
+
+    server.push_quote(push_quote("330"));
+    let event = recv(&mut quote_receiver).await;
+    let PushEventDetail::Quote(quote) = event.detail else {
+        panic!("unexpected push event: {:?}", event.detail);
+    };
+    assert_eq!(
+        portfolio.apply_quote(&event.symbol, &quote),
+        Some(PortfolioEvent::PriceChanged {
+            symbol: "700.HK".to_string(),
+            last_done: Decimal::from(330),
+        })
+    );
+
+    let resp = trade_ctx
+        .submit_order(
+            SubmitOrderOptions::new(
+                "700.HK",
+                OrderType::LO,
+                OrderSide::Sell,
+                Decimal::from(40),
+                TimeInForceType::Day,
+            )
+            .submitted_price(Decimal::from(335)),
+        )
+        .await
+        .unwrap();
+    let PushEvent::OrderChanged(changed) = recv(&mut trade_receiver).await;
+    assert_eq!(portfolio.apply_order_changed(&changed), None);
+
+    assert!(server.fill_order(&resp.order_id, Decimal::from(40), Decimal::from(335)));
+    let PushEvent::OrderChanged(changed) = recv(&mut trade_receiver).await;
+    assert_eq!(
+        portfolio.apply_order_changed(&changed),
+        Some(PortfolioEvent::PositionChanged {
+            symbol: "700.HK".to_string(),
+            quantity: Decimal::from(60),
+            executed_quantity: Decimal::from(-40),
+            executed_price: Decimal::from(335),
+        })
+    );
+
+    let exposure = portfolio.exposure_by_currency()["HKD"];
+    assert_eq!(exposure.market_value, Decimal::from(19800));
+    assert_eq!(exposure.unrealized_pnl, Decimal::from(1800));
+    // (330 - 310) * 60 + (335 - 310) * 40
+    assert_eq!(exposure.day_pnl, Decimal::from(2200));
+}
+
+#[tokio::test]
+async fn quote_history_candlesticks_range() {
+    // one bar per minute from 2024-01-02 09:30 HKT
+    const FIRST_BAR: i64 = 1704159000;
+    const TOTAL_BARS: i64 = 2500;
+
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(
+        cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
+        |req: SecurityHistoryCandlestickRequest| {
+            let query = req.offset_request.unwrap();
+            let cursor = time::PrimitiveDateTime::parse(
+                &format!("{}{}", query.date, query.minute),
+                time::macros::format_description!("[year][month][day][hour][minute]"),
+            )
+            .unwrap()
+            .assume_offset(time::macros::offset!(+8))
+            .unix_timestamp();
+            // the page starts at the cursor, so it overlaps the previous page
+            let first = ((cursor - FIRST_BAR) / 60).max(0);
+            let candlesticks = (first..TOTAL_BARS)
+                .take(query.count as usize)
+                .map(|idx| proto::Candlestick {
+                    close: idx.to_string(),
+                    timestamp: FIRST_BAR + idx * 60,
+                    ..Default::default()
+                })
+                .collect();
+            Ok(SecurityCandlestickResponse {
+                symbol: req.symbol,
+                candlesticks,
+            })
+        },
+    );
+
+    let (ctx, _) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    let candlesticks: Vec<_> = ctx
+        .history_candlesticks_range(
+            "700.HK",
+            Period::OneMinute,
+            AdjustType::NoAdjust,
+            TradeSessions::Intraday,
+            datetime!(2024-01-02 09:30),
+            datetime!(2024-01-03 12:00),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+    // 2024-01-02 09:30 to 2024-01-03 12:00 inclusive
+    assert_eq!(candlesticks.len(), 26 * 60 + 31);
+    for (idx, candlestick) in candlesticks.iter().enumerate() {
+        assert_eq!(candlestick.close, Decimal::from(idx));
+    }
+}
+
+#[tokio::test]
+async fn quote_candlestick_cache() {
+    // one daily bar per weekday of 2020-01, the close is the day of the month
+    // plus the forward adjustment
+    let server = MockServer::start().await.unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let adjustment = Arc::new(AtomicI64::new(0));
+    server.on_quote(cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS, {
+        let requests = requests.clone();
+        let adjustment = adjustment.clone();
+        move |req: SecurityHistoryCandlestickRequest| {
+            requests.fetch_add(1, Ordering::SeqCst);
+            let query = req.offset_request.unwrap();
+            let cursor = time::Date::parse(
+                &query.date,
+                time::macros::format_description!("[year][month][day]"),
+            )
+            .unwrap();
+            let adjustment = if req.adjust_type == proto::AdjustType::ForwardAdjust as i32 {
+                adjustment.load(Ordering::SeqCst)
+            } else {
+                0
+            };
+            let candlesticks = (cursor.day()..=31)
+                .map(|day| time::Date::from_calendar_date(2020, time::Month::January, day).unwrap())
+                .filter(|date| date.weekday().number_from_monday() <= 5)
+                .take(query.count as usize)
+                .map(|date| proto::Candlestick {
+                    close: (date.day() as i64 + adjustment).to_string(),
+                    timestamp: date
+                        .midnight()
+                        .assume_offset(time::macros::offset!(+8))
+                        .unix_timestamp(),
+                    ..Default::default()
+                })
+                .collect();
+            Ok(SecurityCandlestickResponse {
+                symbol: req.symbol,
+                candlesticks,
+            })
+        }
+    });
+
+    let dir = std::env::temp_dir().join(format!("longport-candlesticks-{}", std::process::id()));
+    let config = Arc::unwrap_or_clone(create_config(&server)).candlestick_cache_dir(&dir);
+    let (ctx, _) = QuoteContext::try_new(Arc::new(config)).await.unwrap();
+    let closes = |adjust_type, start, end| {
+        ctx.history_candlesticks_range(
+            "700.HK",
+            Period::Day,
+            adjust_type,
+            TradeSessions::Intraday,
+            start,
+            end,
+        )
+        .map_ok(|candlestick| candlestick.close.to_string())
+        .try_collect::<Vec<_>>()
+    };
+
+    let week1 = closes(
+        AdjustType::NoAdjust,
+        datetime!(2020-01-06 00:00),
+        datetime!(2020-01-10 23:59),
+    )
+    .await
+    .unwrap();
+    assert_eq!(week1, ["6", "7", "8", "9", "10"]);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // served from the cache
+    let cached = closes(
+        AdjustType::NoAdjust,
+        datetime!(2020-01-07 00:00),
+        datetime!(2020-01-09 23:59),
+    )
+    .await
+    .unwrap();
+    assert_eq!(cached, ["7", "8", "9"]);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // only the second week is requested
+    let weeks = closes(
+        AdjustType::NoAdjust,
+        datetime!(2020-01-06 00:00),
+        datetime!(2020-01-17 23:59),
+    )
+    .await
+    .unwrap();
+    assert_eq!(weeks.len(), 10);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    // the adjustment is checked with the request of the missing days
+    let range = (datetime!(2020-01-06 00:00), datetime!(2020-01-10 23:59));
+    closes(AdjustType::ForwardAdjust, range.0, range.1)
+        .await
+        .unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    let weeks = closes(
+        AdjustType::ForwardAdjust,
+        datetime!(2020-01-06 00:00),
+        datetime!(2020-01-17 23:59),
+    )
+    .await
+    .unwrap();
+    assert_eq!(weeks.len(), 10);
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+    // the forward-adjusted series is invalidated when the adjustment changes
+    adjustment.store(100, Ordering::SeqCst);
+    let adjusted = closes(AdjustType::ForwardAdjust, range.0, range.1)
+        .await
+        .unwrap();
+    assert_eq!(adjusted, ["106", "107", "108", "109", "110"]);
+    assert!(dir.join("700.HK").is_dir());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn quote_warrant_screener() {
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(
+        cmd_code::GET_FILTERED_WARRANT,
+        |req: proto::WarrantFilterListRequest| {
+            let config = req.filter_config.unwrap_or_default();
+            assert_eq!(config.r#type, vec![0]);
+            let total = 5;
+            let warrants = (config.sort_offset
+                ..(config.sort_offset + config.sort_count).min(total))
+                .map(|idx| proto::FilterWarrant {
+                    symbol: format!("{idx}.HK"),
+                    last_done: "0.1".to_string(),
+                    strike_price: "400".to_string(),
+                    expiry_date: "20251231".to_string(),
+                    r#type: 2,
+                    status: 4,
+                    ..Default::default()
+                })
+                .collect();
+            Ok(proto::WarrantFilterListResponse {
+                warrant_list: warrants,
+                total_count: total,
+            })
+        },
+    );
+    let (ctx, _) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+
+    let filter = WarrantFilter::new("700.HK", WarrantSortBy::Volume, SortOrderType::Descending)
+        .warrant_type([WarrantType::Call]);
+    let page = ctx
+        .warrant_screener(filter.clone().offset(2).count(2))
+        .await
+        .unwrap();
+    assert_eq!(page.total_count, 5);
+    assert_eq!(
+        page.warrants
+            .iter()
+            .map(|warrant| warrant.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["2.HK", "3.HK"]
+    );
+    assert_eq!(page.warrants[0].strike_price, Some(Decimal::from(400)));
+
+    let warrants: Vec<_> = ctx
+        .warrant_screener_stream(filter.offset(1).count(2))
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        warrants
+            .iter()
+            .map(|warrant| warrant.symbol.as_str())
+            .collect::<Vec<_>>(),
+        vec!["1.HK", "2.HK", "3.HK", "4.HK"]
+    );
+}
+
+#[tokio::test]
+async fn quote_option_chain_snapshot_without_underlying_quote() {
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(
+        cmd_code::GET_OPTION_CHAIN_INFO_BY_DATE,
+        |_: proto::OptionChainDateStrikeInfoRequest| {
+            Ok(proto::OptionChainDateStrikeInfoResponse {
+                strike_price_info: vec![],
+            })
+        },
+    );
+    server.on_quote(
+        cmd_code::GET_REALTIME_QUOTE,
+        |_: proto::MultiSecurityRequest| Ok(proto::SecurityQuoteResponse { secu_quote: vec![] }),
+    );
+
+    let (ctx, _) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    let err = ctx
+        .option_chain_snapshot("AAPL.US", datetime!(2024-01-19 0:00).date())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        longport::Error::InvalidSecuritySymbol { symbol } if symbol == "AAPL.US"
+    ));
+}
+
+#[tokio::test]
+async fn quote_aggregate_candlesticks() {
+    // 2024-01-02 09:30 HKT
+    const OPEN_TIME: i64 = 1704159000;
+
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(
+        cmd_code::GET_BASIC_INFO,
+        |req: proto::MultiSecurityRequest| {
+            Ok(proto::SecurityStaticInfoResponse {
+                secu_static_info: req
+                    .symbol
+                    .into_iter()
+                    .map(|symbol| proto::StaticInfo {
+                        symbol,
+                        board: "HKEquity".to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        },
+    );
+    server.on_quote(
+        cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
+        |req: SecurityHistoryCandlestickRequest| {
+            let minutes = match proto::Period::try_from(req.period).unwrap() {
+                proto::Period::OneMinute => 1,
+                proto::Period::FiveMinute => 5,
+                period => panic!("unexpected period: {period:?}"),
+            };
+            let count = req.offset_request.unwrap().count as i64;
+            // the last history candlestick is the one before 09:35
+            let candlesticks = (0..count)
+                .map(|idx| proto::Candlestick {
+                    close: "1".to_string(),
+                    timestamp: OPEN_TIME + 300 - (count - idx) * minutes * 60,
+                    ..Default::default()
+                })
+                .collect();
+            Ok(SecurityCandlestickResponse {
+                symbol: req.symbol,
+                candlesticks,
+            })
+        },
+    );
+
+    let (ctx, mut receiver) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    ctx.aggregate_candlesticks(
+        "700.HK",
+        [Period::OneMinute, Period::FiveMinute],
+        TradeSessions::Intraday,
+        2,
+    )
+    .await
+    .unwrap();
+    ctx.aggregate_candlesticks("700.HK", [Period::TenMinute], TradeSessions::Intraday, 0)
+        .await
+        .unwrap();
+    let sub_types = server.subscriptions().remove("700.HK").unwrap();
+    assert!(sub_types.contains(&SubType::Quote) && sub_types.contains(&SubType::Trade));
+
+    let trade = |price: &str, timestamp: i64| proto::Trade {
+        price: price.to_string(),
+        volume: 100,
+        timestamp,
+        ..Default::default()
+    };
+    server.push_trades(proto::PushTrade {
+        symbol: "700.HK".to_string(),
+        sequence: 1,
+        trade: vec![trade("10", OPEN_TIME + 370), trade("11", OPEN_TIME + 425)],
+    });
+    loop {
+        let event = recv(&mut receiver).await;
+        if let PushEventDetail::Candlestick(push) = event.detail
+            && push.period == Period::OneMinute
+            && push.candlestick.timestamp.unix_timestamp() == OPEN_TIME + 420
+        {
+            break;
+        }
+    }
+
+    let candlesticks = |period| {
+        let ctx = ctx.clone();
+        async move {
+            ctx.realtime_candlesticks("700.HK", period, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|candlestick| {
+                    (
+                        candlestick.timestamp.unix_timestamp() - OPEN_TIME,
+                        candlestick.close,
+                        candlestick.volume,
+                    )
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(
+        candlesticks(Period::OneMinute).await,
+        vec![
+            (180, Decimal::ONE, 0),
+            (240, Decimal::ONE, 0),
+            (360, Decimal::TEN, 100),
+            (420, Decimal::from(11), 100),
+        ]
+    );
+    assert_eq!(
+        candlesticks(Period::FiveMinute).await,
+        vec![
+            (-300, Decimal::ONE, 0),
+            (0, Decimal::ONE, 0),
+            (300, Decimal::from(11), 200),
+        ]
+    );
+    assert_eq!(
+        candlesticks(Period::TenMinute).await,
+        vec![(0, Decimal::from(11), 200)]
+    );
+    assert!(
+        ctx.realtime_candlesticks("700.HK", Period::SixtyMinute, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // the same sessions keep the buffer, other sessions rebuild it
+    ctx.aggregate_candlesticks("700.HK", [Period::TenMinute], TradeSessions::Intraday, 0)
+        .await
+        .unwrap();
+    assert_eq!(candlesticks(Period::TenMinute).await.len(), 1);
+    ctx.aggregate_candlesticks("700.HK", [Period::TenMinute], TradeSessions::All, 0)
+        .await
+        .unwrap();
+    assert!(candlesticks(Period::TenMinute).await.is_empty());
+}
+
+#[tokio::test]
+async fn quote_aggregate_custom_minute_candlesticks() {
+    // 2024-01-02 09:30 HKT
+    const OPEN_TIME: i64 = 1704159000;
+
+    let server = MockServer::start().await.unwrap();
+    server.on_quote(
+        cmd_code::GET_BASIC_INFO,
+        |req: proto::MultiSecurityRequest| {
+            Ok(proto::SecurityStaticInfoResponse {
+                secu_static_info: req
+                    .symbol
+                    .into_iter()
+                    .map(|symbol| proto::StaticInfo {
+                        symbol,
+                        board: "HKEquity".to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        },
+    );
+    server.on_quote(
+        cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
+        |req: SecurityHistoryCandlestickRequest| {
+            assert_eq!(req.period, proto::Period::OneMinute as i32);
+            assert_eq!(req.offset_request.unwrap().count, 14);
+            // the one-minute candlesticks from 09:30 to 09:39
+            let candlesticks = (0..10)
+                .map(|idx| proto::Candlestick {
+                    close: "1".to_string(),
+                    volume: 10,
+                    timestamp: OPEN_TIME + idx * 60,
+                    ..Default::default()
+                })
+                .collect();
+            Ok(SecurityCandlestickResponse {
+                symbol: req.symbol,
+                candlesticks,
+            })
+        },
+    );
+
+    let (ctx, mut receiver) = QuoteContext::try_new(create_config(&server)).await.unwrap();
+    ctx.aggregate_candlesticks("700.HK", [Period::OneMinute], TradeSessions::Intraday, 0)
+        .await
+        .unwrap();
+    ctx.aggregate_candlesticks(
+        "700.HK",
+        [AggregatePeriod::Minutes(7)],
+        TradeSessions::Intraday,
+        2,
+    )
+    .await
+    .unwrap();
+
+    let trade = |price: &str, timestamp: i64| proto::Trade {
+        price: price.to_string(),
+        volume: 100,
+        timestamp,
+        ..Default::default()
+    };
+    server.push_trades(proto::PushTrade {
+        symbol: "700.HK".to_string(),
+        sequence: 1,
+        trade: vec![trade("10", OPEN_TIME + 610), trade("11", OPEN_TIME + 900)],
+    });
+    loop {
+        let event = recv(&mut receiver).await;
+        if let PushEventDetail::Candlestick(push) = event.detail {
+            // the custom periods are not pushed
+            assert_eq!(push.period, Period::OneMinute);
+            if push.candlestick.timestamp.unix_timestamp() == OPEN_TIME + 900 {
+                break;
+            }
+        }
+    }
+
+    let candlesticks = ctx
+        .realtime_candlesticks("700.HK", AggregatePeriod::Minutes(7), 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|candlestick| {
+            (
+                candlestick.timestamp.unix_timestamp() - OPEN_TIME,
+                candlestick.close,
+                candlestick.volume,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        candlesticks,
+        vec![
+            (0, Decimal::ONE, 70),
+            (420, Decimal::TEN, 130),
+            (840, Decimal::from(11), 100),
+        ]
+    );
+
+    ctx.unsubscribe_candlesticks("700.HK", AggregatePeriod::Minutes(7))
+        .await
+        .unwrap();
+    assert!(
+        ctx.realtime_candlesticks("700.HK", AggregatePeriod::Minutes(7), 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn quote_realtime_trades_capacity() {
+    let server = MockServer::start().await.unwrap();
+    let config = Arc::unwrap_or_clone(create_config(&server)).realtime_trades_capacity(3);
+    let (ctx, mut receiver) = QuoteContext::try_new(Arc::new(config)).await.unwrap();
+    ctx.subscribe(["700.HK"], SubFlags::TRADE, false)
+        .await
+        .unwrap();
+
+    let push_trades = |sequence, volumes: std::ops::RangeInclusive<i64>| {
+        server.push_trades(proto::PushTrade {
+            symbol: "700.HK".to_string(),
+            sequence,
+            trade: volumes
+                .map(|volume| proto::Trade {
+                    price: "10".to_string(),
+                    volume,
+                    timestamp: 1700000000 + volume,
+                    ..Default::default()
+                })
+                .collect(),
+        });
+    };
+    let realtime_volumes = || async {
+        ctx.realtime_trades("700.HK", 100)
+            .await
+            .unwrap()
+            .iter()
+            .map(|trade| trade.volume)
+            .collect::<Vec<_>>()
+    };
+
+    // more than the capacity but less than twice of it
+    push_trades(1, 1..=5);
+    assert!(matches!(
+        recv(&mut receiver).await.detail,
+        PushEventDetail::Trade(_)
+    ));
+    assert_eq!(realtime_volumes().await, vec![3, 4, 5]);
+
+    push_trades(2, 6..=10);
+    assert!(matches!(
+        recv(&mut receiver).await.detail,
+        PushEventDetail::Trade(_)
+    ));
+    let volumes = ctx
+        .with_realtime_trades("700.HK", |trades| {
+            trades.iter().map(|trade| trade.volume).collect::<Vec<_>>()
+        })
+        .await
+        .unwrap();
+    assert_eq!(volumes, vec![8, 9, 10]);
+    assert_eq!(realtime_volumes().await, vec![8, 9, 10]);
+    assert_eq!(
+        ctx.with_realtime_candlesticks("700.HK", Period::OneMinute, |candlesticks| candlesticks
+            .len())
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn quote_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("longport-quote-{}.rec", std::process::id()));
+    let server = MockServer::start().await.unwrap();
+    let config = Arc::new(
+        Config::new("app_key", "app_secret", "access_token")
+            .http_url(server.http_url())
+            .quote_ws_url(server.quote_ws_url())
+            .dont_print_quote_packages()
+            .record_quote_pushes(&path),
+    );
+
+    let (ctx, mut receiver) = QuoteContext::try_new(config).await.unwrap();
+    ctx.subscribe(["700.HK"], SubFlags::QUOTE, false)
+        .await
+        .unwrap();
+    for last_done in ["380", "381", "382"] {
+        server.push_quote(push_quote(last_done));
+        recv(&mut receiver).await;
+    }
+    drop(ctx);
+
+    // the recording is complete once the writer task has flushed it after the
+    // context is closed
+    let expected = ["380", "381", "382"].map(|s| s.parse().unwrap());
+    let ctx = timeout(TIMEOUT, async {
+        loop {
+            let (ctx, mut receiver) = QuoteContext::replay(create_config(&server), &path, 0.0)
+                .await
+                .unwrap();
+            let mut prices = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                match event.detail {
+                    PushEventDetail::Quote(quote) => prices.push(quote.last_done),
+                    detail => panic!("unexpected push event: {detail:?}"),
+                }
+            }
+            if prices == expected {
+                break ctx;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("timeout");
+    assert_eq!(ctx.subscriptions().await.unwrap().len(), 1);
+    assert!(ctx.quote(["700.HK"]).await.is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn paper_trading() {
+    let server = MockServer::start().await.unwrap();
+    let config = Arc::new(
+        Config::new("app_key", "app_secret", "access_token")
+            .http_url(server.http_url())
+            .quote_ws_url(server.quote_ws_url())
+            .trade_ws_url(server.trade_ws_url())
+            .dont_print_quote_packages()
+            .paper_trading()
+            .paper_initial_cash("HKD", Decimal::from(100000)),
+    );
+    let (ctx, mut receiver) = TradeContext::try_new(config).await.unwrap();
+    ctx.subscribe([TopicType::Private]).await.unwrap();
+
+    let resp = ctx
+        .submit_order(
+            SubmitOrderOptions::new(
+                "700.HK",
+                OrderType::LO,
+                OrderSide::Buy,
+                Decimal::from(100),
+                TimeInForceType::Day,
+            )
+            .submitted_price("380".parse().unwrap()),
+        )
+        .await
+        .unwrap();
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.order_id, resp.order_id);
+    assert_eq!(changed.status, OrderStatus::New);
+    assert!(server.orders().is_empty());
+
+    server.push_quote(push_quote("381"));
+    server.push_quote(push_quote("379.5"));
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.status, OrderStatus::Filled);
+    assert_eq!(changed.executed_price, Some("379.5".parse().unwrap()));
+
+    let positions = ctx.stock_positions(None).await.unwrap();
+    assert_eq!(positions.channels[0].positions[0].symbol, "700.HK");
+    assert_eq!(
+        positions.channels[0].positions[0].quantity,
+        Decimal::from(100)
+    );
+    let balance = ctx.account_balance(Some("HKD")).await.unwrap();
+    assert_eq!(balance[0].total_cash, Decimal::from(62050));
+    assert_eq!(ctx.today_executions(None).await.unwrap().len(), 1);
+    assert!(ctx.cancel_order(&resp.order_id).await.is_err());
+}
+
+#[tokio::test]
+async fn paper_trading_with_quote_context() {
+    let server = MockServer::start().await.unwrap();
+    let config = create_config(&server);
+    let (quote_ctx, mut quote_receiver) = QuoteContext::try_new(config.clone()).await.unwrap();
+    let (ctx, mut receiver) = TradeContext::try_new_paper(config, &quote_ctx)
+        .await
+        .unwrap();
+    ctx.subscribe([TopicType::Private]).await.unwrap();
+
+    let resp = ctx
+        .submit_order(
+            SubmitOrderOptions::new(
+                "700.HK",
+                OrderType::LO,
+                OrderSide::Buy,
+                Decimal::from(100),
+                TimeInForceType::Day,
+            )
+            .submitted_price("380".parse().unwrap()),
+        )
+        .await
+        .unwrap();
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.order_id, resp.order_id);
+    assert!(server.orders().is_empty());
+
+    // the symbol is subscribed on the shared quote context
+    let subscriptions = quote_ctx.subscriptions().await.unwrap();
+    assert_eq!(subscriptions[0].symbol, "700.HK");
+    assert!(subscriptions[0].sub_types.contains(SubFlags::QUOTE));
+
+    server.push_quote(push_quote("379.5"));
+    let PushEvent::OrderChanged(changed) = recv(&mut receiver).await;
+    assert_eq!(changed.status, OrderStatus::Filled);
+    assert_eq!(changed.executed_price, Some("379.5".parse().unwrap()));
+    assert_eq!(recv(&mut quote_receiver).await.symbol, "700.HK");
+}